@@ -28,6 +28,21 @@ pub enum GameMessage {
     Kicked {
         target: String,
     },
+    History {
+        entries: Vec<game_state::HistoryEntry>,
+    },
+    Summary {
+        summary: game_state::GameSummary,
+    },
+    ChatBacklog {
+        messages: Vec<ChatMessage>,
+    },
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ChatMessage {
+    pub from: String,
+    pub message: String,
 }
 
 /// zstd dictionary, compressed with zstd.
@@ -1,12 +1,14 @@
 #![deny(warnings)]
 
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet, VecDeque};
 use std::env;
 use std::io::{self, ErrorKind};
+use std::net::{IpAddr, SocketAddr};
 use std::sync::{
     atomic::{AtomicUsize, Ordering},
     Arc,
 };
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
 
 use futures::SinkExt;
 use futures::StreamExt;
@@ -18,13 +20,22 @@ use warp::ws::{Message, WebSocket};
 use warp::Filter;
 
 use shengji_core::{game_state, interactive, settings, types};
-use shengji_types::{GameMessage, ZSTD_ZSTD_DICT};
+use shengji_types::{ChatMessage, GameMessage, ZSTD_ZSTD_DICT};
 
 use storage::{HashMapStorage, State, Storage};
 
 /// Our global unique user id counter.
 static NEXT_USER_ID: AtomicUsize = AtomicUsize::new(1);
 
+/// The number of recent chat messages kept for users who join a room later.
+const MAX_CHAT_BACKLOG: usize = 100;
+
+/// How often rooms are checked for players who have run out of time to move.
+const DEADLINE_CHECK_INTERVAL_SECS: u64 = 5;
+
+/// How often the state of every room is dumped to disk.
+const DUMP_INTERVAL_SECS: u64 = 60;
+
 lazy_static::lazy_static! {
     static ref CARDS_JSON: CardsBlob = CardsBlob {
         cards: types::FULL_DECK.iter().map(|c| c.as_info()).collect()
@@ -61,6 +72,20 @@ lazy_static::lazy_static! {
     static ref MESSAGE_PATH: String = {
         std::env::var("MESSAGE_PATH").unwrap_or_else(|_| "/tmp/shengji_messages.json".to_string())
     };
+    static ref OBSERVER_IDLE_TIMEOUT_SECS: Option<u64> = {
+        std::env::var("OBSERVER_IDLE_TIMEOUT_SECS").ok().and_then(|s| s.parse().ok())
+    };
+    /// The most rooms which can be active at once after being created from a
+    /// single IP address. Note that behind a proxy, all connections share the
+    /// proxy's address.
+    static ref MAX_ROOMS_PER_IP: Option<usize> = {
+        std::env::var("MAX_ROOMS_PER_IP").ok().and_then(|s| s.parse().ok())
+    };
+    /// The number of actions, and separately chat messages, which a single
+    /// connection may send per second before the rest are dropped.
+    static ref MAX_MESSAGES_PER_SEC: usize = {
+        std::env::var("MAX_MESSAGES_PER_SEC").ok().and_then(|s| s.parse().ok()).unwrap_or(20)
+    };
 
 }
 
@@ -73,6 +98,62 @@ struct CardsBlob {
 struct InMemoryStats {
     num_games_created: usize,
     header_messages: Vec<String>,
+    /// Every room which has been joined since it was last cleaned up.
+    #[serde(skip)]
+    active_rooms: HashSet<Vec<u8>>,
+    /// The active rooms which were created from each IP address.
+    #[serde(skip)]
+    rooms_by_ip: HashMap<IpAddr, HashSet<Vec<u8>>>,
+    /// The most recent chat messages in each room, oldest first. These are
+    /// kept out of the game state so that chatting doesn't change its
+    /// version.
+    #[serde(skip)]
+    chat_backlogs: HashMap<Vec<u8>, VecDeque<ChatMessage>>,
+}
+
+impl InMemoryStats {
+    /// Records that someone from `ip` joined `room`. If that creates the room,
+    /// it counts against `ip`, unless `ip` already has `limit` active rooms.
+    fn try_join_room(&mut self, ip: Option<IpAddr>, room: &[u8], limit: usize) -> bool {
+        if self.active_rooms.contains(room) {
+            return true;
+        }
+        if let Some(ip) = ip {
+            if self.num_rooms_created_by(ip) >= limit {
+                return false;
+            }
+            self.rooms_by_ip
+                .entry(ip)
+                .or_default()
+                .insert(room.to_vec());
+        }
+        self.active_rooms.insert(room.to_vec());
+        true
+    }
+
+    /// Forgets about rooms which are no longer active, so that they no
+    /// longer count against the IP which created them, and drops their chat.
+    fn retain_active_rooms(&mut self, active_rooms: &[Vec<u8>]) {
+        self.active_rooms.retain(|room| active_rooms.contains(room));
+        for rooms in self.rooms_by_ip.values_mut() {
+            rooms.retain(|room| active_rooms.contains(room));
+        }
+        self.rooms_by_ip.retain(|_, rooms| !rooms.is_empty());
+        self.chat_backlogs
+            .retain(|room, _| active_rooms.contains(room));
+    }
+
+    fn record_chat_message(&mut self, room: &[u8], message: ChatMessage) {
+        let backlog = self.chat_backlogs.entry(room.to_vec()).or_default();
+        backlog.push_back(message);
+        while backlog.len() > MAX_CHAT_BACKLOG {
+            backlog.pop_front();
+        }
+    }
+
+    fn num_rooms_created_by(&self, ip: IpAddr) -> usize {
+        self.rooms_by_ip.get(&ip).map(|r| r.len()).unwrap_or(0)
+    }
 }
 
 #[derive(Clone, Debug, Default, Serialize, Deserialize)]
@@ -114,6 +195,90 @@ impl State for VersionedGame {
     }
 }
 
+/// Per-connection bookkeeping for a user who has joined a room.
+#[derive(Clone, Copy, Debug)]
+struct UserState {
+    /// When the user last sent anything over the websocket, in seconds since
+    /// the epoch.
+    last_activity: u64,
+}
+
+impl UserState {
+    fn new(now: u64) -> Self {
+        UserState { last_activity: now }
+    }
+
+    fn record_activity(&mut self, now: u64) {
+        self.last_activity = now;
+    }
+
+    /// The number of seconds left before the user has been idle for
+    /// `timeout` seconds.
+    fn time_until_idle(&self, now: u64, timeout: u64) -> u64 {
+        (self.last_activity + timeout).saturating_sub(now)
+    }
+
+    fn is_idle(&self, now: u64, timeout: u64) -> bool {
+        self.time_until_idle(now, timeout) == 0
+    }
+}
+
+/// A token bucket which lets a connection send bursts of up to `rate`
+/// messages, refilling at `rate` messages per second. Rather than counting
+/// tokens, it tracks when the bucket will next be full, which keeps the
+/// arithmetic exact.
+#[derive(Clone, Copy, Debug)]
+struct RateLimiter {
+    interval: Duration,
+    burst: Duration,
+    full_at: Instant,
+    reported_at: Option<Instant>,
+}
+
+impl RateLimiter {
+    fn new(rate: usize, now: Instant) -> Self {
+        let rate = rate.max(1) as u32;
+        let interval = Duration::from_secs(1) / rate;
+        RateLimiter {
+            interval,
+            burst: interval * (rate - 1),
+            full_at: now,
+            reported_at: None,
+        }
+    }
+
+    /// Whether a message dropped at `now` should be reported to the sender,
+    /// which happens at most once each time the bucket could have refilled.
+    fn should_report_drop(&mut self, now: Instant) -> bool {
+        let refill = self.burst + self.interval;
+        match self.reported_at {
+            Some(t) if now.saturating_duration_since(t) < refill => false,
+            _ => {
+                self.reported_at = Some(now);
+                true
+            }
+        }
+    }
+
+    /// Takes a token for a message received at `now`, if there are any left.
+    fn try_acquire(&mut self, now: Instant) -> bool {
+        let full_at = self.full_at.max(now);
+        if full_at - now > self.burst {
+            false
+        } else {
+            self.full_at = full_at + self.interval;
+            true
+        }
+    }
+}
+
+fn unix_now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
 async fn send_to_user(tx: &'_ mpsc::UnboundedSender<Message>, msg: &GameMessage) -> bool {
     if let Ok(j) = serde_json::to_vec(&msg) {
         if let Ok(s) = ZSTD_COMPRESSOR.lock().unwrap().compress(&j, 0) {
@@ -127,6 +292,8 @@ async fn send_to_user(tx: &'_ mpsc::UnboundedSender<Message>, msg: &GameMessage)
 pub struct JoinRoom {
     room_name: String,
     name: String,
+    #[serde(default)]
+    reconnect_token: Option<String>,
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
@@ -181,7 +348,12 @@ async fn main() {
 
     info!(init_logger, "Loaded games from state dump"; "num_games" => num_games_loaded);
 
-    let stats = Arc::new(Mutex::new(InMemoryStats::default()));
+    let mut stats = InMemoryStats::default();
+    // Rooms restored from the dump don't count against anyone.
+    if let Ok(keys) = backend_storage.clone().get_all_keys().await {
+        stats.active_rooms.extend(keys);
+    }
+    let stats = Arc::new(Mutex::new(stats));
 
     match try_read_file::<Vec<String>>(&*MESSAGE_PATH).await {
         Ok(messages) => {
@@ -195,16 +367,20 @@ async fn main() {
         }
     }
 
-    let periodic_task = periodically_dump_state(backend_storage.clone(), stats.clone());
+    let (shutdown_storage, shutdown_stats) = (backend_storage.clone(), stats.clone());
+    tokio::task::spawn(periodically_enforce_deadlines(backend_storage.clone()));
 
     let games_filter = warp::any().map(move || (backend_storage.clone(), stats.clone()));
 
     let api = warp::path("api")
         .and(warp::ws())
+        .and(warp::addr::remote())
         .and(games_filter.clone())
-        .map(|ws: warp::ws::Ws, (backend_storage, stats)| {
-            ws.on_upgrade(move |socket| user_connected(socket, backend_storage, stats))
-        });
+        .map(
+            |ws: warp::ws::Ws, remote: Option<SocketAddr>, (backend_storage, stats)| {
+                ws.on_upgrade(move |socket| user_connected(socket, remote, backend_storage, stats))
+            },
+        );
 
     let cards = warp::path("cards.json").map(|| warp::reply::json(&*CARDS_JSON));
 
@@ -226,10 +402,14 @@ async fn main() {
 
     let dump_state = warp::path("full_state.json")
         .and(games_filter.clone())
-        .and_then(|(backend_storage, stats)| dump_state(backend_storage, stats));
+        .and_then(|(backend_storage, stats)| dump_state(backend_storage, stats, &DUMP_PATH));
     let game_stats = warp::path("stats")
-        .and(games_filter)
+        .and(games_filter.clone())
         .and_then(|(backend_storage, _)| get_stats(backend_storage));
+    let game_snapshot = warp::path!("game" / String / "state")
+        .and(warp::get())
+        .and(games_filter)
+        .and_then(|room, (backend_storage, _)| game_snapshot(room, backend_storage));
 
     #[cfg(feature = "dynamic")]
     let static_routes = warp::fs::dir("../frontend/dist").or(warp::fs::dir("../favicon"));
@@ -250,18 +430,51 @@ async fn main() {
         .or(api)
         .or(dump_state)
         .or(game_stats)
+        .or(game_snapshot)
         .or(default_settings)
         .or(static_routes)
         .or(rules);
 
-    let serve_task = warp::serve(routes).run(([0, 0, 0, 0], 3030));
+    let shutdown_logger = init_logger.clone();
+    let (_, serve_task) =
+        warp::serve(routes).bind_with_graceful_shutdown(([0, 0, 0, 0], 3030), async move {
+            let _ = tokio::signal::ctrl_c().await;
+            info!(shutdown_logger, "Received shutdown signal");
+        });
 
-    tokio::select! {
-        () = periodic_task => unreachable!(),
-        () = serve_task => {
-            info!(init_logger, "Shutting down")
-        },
+    serve_then_dump_state(
+        serve_task,
+        shutdown_storage,
+        shutdown_stats,
+        &DUMP_PATH,
+        Some(Duration::from_secs(DUMP_INTERVAL_SECS)),
+    )
+    .await;
+    info!(init_logger, "Shutting down");
+}
+
+/// Runs the server, dumping the state to `dump_path` every `dump_interval`,
+/// until it has shut down. The state is dumped once more at the end, since
+/// otherwise the changes since the last periodic dump would be lost.
+async fn serve_then_dump_state<S: Storage<VersionedGame, E>, E>(
+    serve_task: impl std::future::Future<Output = ()>,
+    backend_storage: S,
+    stats: Arc<Mutex<InMemoryStats>>,
+    dump_path: &str,
+    dump_interval: Option<Duration>,
+) {
+    match dump_interval {
+        Some(period) => {
+            let periodic_task =
+                periodically_dump_state(backend_storage.clone(), stats.clone(), dump_path, period);
+            tokio::select! {
+                () = periodic_task => unreachable!(),
+                () = serve_task => (),
+            }
+        }
+        None => serve_task.await,
     }
+    let _ = dump_state(backend_storage, stats, dump_path).await;
 }
 
 async fn try_read_file<M: serde::de::DeserializeOwned>(path: &'_ str) -> Result<M, io::Error> {
@@ -274,17 +487,109 @@ async fn try_read_file<M: serde::de::DeserializeOwned>(path: &'_ str) -> Result<
 async fn periodically_dump_state<S: Storage<VersionedGame, E>, E>(
     backend_storage: S,
     stats: Arc<Mutex<InMemoryStats>>,
+    dump_path: &str,
+    period: Duration,
 ) {
-    let mut interval = tokio::time::interval(tokio::time::Duration::from_secs(60));
+    let mut interval = tokio::time::interval(period);
     loop {
         interval.tick().await;
-        let _ = dump_state(backend_storage.clone(), stats.clone()).await;
+        let _ = dump_state(backend_storage.clone(), stats.clone(), dump_path).await;
+    }
+}
+
+/// Announces players who have run out of time to move, once per turn, and
+/// finishes the exchange for a landlord who has disconnected. This runs on
+/// its own, since an idle room would otherwise never time out.
+async fn periodically_enforce_deadlines<S: Storage<VersionedGame, E>, E>(backend_storage: S) {
+    let logger = ROOT_LOGGER.new(o!("component" => "deadlines"));
+    // The time of the last action in each room when its timed-out player was
+    // last announced.
+    let mut announced: HashMap<Vec<u8>, u64> = HashMap::new();
+    let mut interval = tokio::time::interval(tokio::time::Duration::from_secs(
+        DEADLINE_CHECK_INTERVAL_SECS,
+    ));
+    loop {
+        interval.tick().await;
+        let keys = match backend_storage.clone().get_all_keys().await {
+            Ok(keys) => keys,
+            Err(_) => continue,
+        };
+        announced.retain(|room, _| keys.contains(room));
+
+        for room in keys {
+            let now = unix_now();
+            let versioned_game = match backend_storage.clone().get(room.clone()).await {
+                Ok(versioned_game) => versioned_game,
+                Err(_) => continue,
+            };
+            let game = &versioned_game.game;
+            let last_action_at =
+                match (game.timed_out_player(now), game.time_since_last_action(now)) {
+                    (Some(_), Some(elapsed)) => now - elapsed,
+                    _ => continue,
+                };
+            let landlord_absent = match game {
+                game_state::GameState::Exchange(exchange) => !versioned_game
+                    .associated_websockets
+                    .get(&exchange.landlord())
+                    .map(|ws| !ws.is_empty())
+                    .unwrap_or(false),
+                _ => false,
+            };
+
+            if announced.insert(room.clone(), last_action_at) != Some(last_action_at) {
+                let g = interactive::InteractiveGame::new_from_state(versioned_game.game);
+                for (data, message) in g.check_move_deadline(now).unwrap_or_default() {
+                    let _ = backend_storage
+                        .clone()
+                        .publish(room.clone(), GameMessage::Broadcast { data, message })
+                        .await;
+                }
+            }
+            if !landlord_absent {
+                continue;
+            }
+
+            // The room may have changed in the meantime, in which case the
+            // operation fails and leaves the room as it was.
+            let logger = logger.new(o!("room" => String::from_utf8_lossy(&room).into_owned()));
+            let _ = apply_operation(
+                room.clone(),
+                backend_storage.clone(),
+                move |game, _, associated_websockets| {
+                    let landlord = match game.dump_state()? {
+                        game_state::GameState::Exchange(exchange) => exchange.landlord(),
+                        _ => return Err(anyhow::anyhow!("not exchanging")),
+                    };
+                    let landlord_connected = associated_websockets
+                        .get(&landlord)
+                        .map(|ws| !ws.is_empty())
+                        .unwrap_or(false);
+                    let msgs = if landlord_connected {
+                        vec![]
+                    } else {
+                        game.auto_finalize_exchange(now, &logger)?
+                    };
+                    if msgs.is_empty() {
+                        return Err(anyhow::anyhow!("nothing to finish"));
+                    }
+                    // Everyone gets a full turn after the exchange ends.
+                    game.record_action_at(now);
+                    Ok(msgs
+                        .into_iter()
+                        .map(|(data, message)| GameMessage::Broadcast { data, message })
+                        .collect())
+                },
+            )
+            .await;
+        }
     }
 }
 
 async fn dump_state<S: Storage<VersionedGame, E>, E>(
     backend_storage: S,
     stats: Arc<Mutex<InMemoryStats>>,
+    dump_path: &str,
 ) -> Result<impl warp::Reply, warp::Rejection> {
     let mut state_dump: HashMap<String, game_state::GameState> = HashMap::new();
 
@@ -313,9 +618,12 @@ async fn dump_state<S: Storage<VersionedGame, E>, E>(
         .get_all_keys()
         .await
         .map_err(|_| warp::reject())?;
+    stats.lock().await.retain_active_rooms(&keys);
 
     let mut num_players = 0;
     let mut num_observers = 0;
+    let mut num_games_finished = 0;
+    let mut num_games_abandoned = 0;
     let mut num_skipped_games = 0usize;
     let mut num_processed_games = 0usize;
 
@@ -323,6 +631,8 @@ async fn dump_state<S: Storage<VersionedGame, E>, E>(
         if let Ok(versioned_game) = backend_storage.clone().get(room_name.clone()).await {
             num_players += versioned_game.game.players().len();
             num_observers += versioned_game.game.observers().len();
+            num_games_finished += versioned_game.game.num_games_finished();
+            num_games_abandoned += versioned_game.game.num_games_abandoned();
             if let Ok(name) = String::from_utf8(room_name.clone()) {
                 state_dump.insert(name, versioned_game.game);
             }
@@ -345,17 +655,19 @@ async fn dump_state<S: Storage<VersionedGame, E>, E>(
     }
 
     let logger = ROOT_LOGGER.new(o!(
-        "dump_path" => &*DUMP_PATH,
+        "dump_path" => dump_path.to_owned(),
         "num_games" => num_games,
         "num_processed_games" => num_processed_games,
         "num_skipped_games" => num_skipped_games,
         "num_players" => num_players,
         "num_observers" => num_observers,
+        "num_games_finished" => num_games_finished,
+        "num_games_abandoned" => num_games_abandoned,
         "num_online_players" => num_players_online_now,
     ));
 
     // Best-effort attempt to write the full state to disk, for fun.
-    match write_state_to_disk(&state_dump).await {
+    match write_state_to_disk(dump_path, &state_dump).await {
         Ok(()) => {
             info!(logger, "Dumped state to disk");
         }
@@ -364,14 +676,20 @@ async fn dump_state<S: Storage<VersionedGame, E>, E>(
         }
     }
 
+    // The dump on disk keeps the reconnect tokens so that players can rejoin
+    // after a restart, but anyone can fetch this one.
+    for game in state_dump.values_mut() {
+        game.redact_reconnect_tokens();
+    }
     Ok(warp::reply::json(&state_dump))
 }
 
 #[allow(unused)]
 async fn write_state_to_disk(
+    path: &str,
     state: &HashMap<String, game_state::GameState>,
 ) -> std::io::Result<()> {
-    let mut f = tokio::fs::File::create(&*DUMP_PATH).await?;
+    let mut f = tokio::fs::File::create(path).await?;
     let json = serde_json::to_vec(state)?;
     f.write_all(&json).await?;
     f.sync_all().await?;
@@ -400,6 +718,44 @@ async fn get_stats<S: Storage<VersionedGame, E>, E>(
     }))
 }
 
+/// Serves the state of a single game, redacted as it would be for a spectator,
+/// so that it can be polled without joining the room.
+async fn game_snapshot<S: Storage<VersionedGame, E>, E>(
+    room: String,
+    backend_storage: S,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    // Nobody is assigned this ID, so the state is redacted as it would be for
+    // an observer.
+    const SPECTATOR_ID: types::PlayerID = types::PlayerID(usize::MAX);
+
+    let room = room.into_bytes();
+    let keys = backend_storage
+        .clone()
+        .get_all_keys()
+        .await
+        .map_err(|_| warp::reject())?;
+    if !keys.contains(&room) {
+        return Ok(warp::reply::with_status(
+            warp::reply::json(&"room not found"),
+            warp::http::StatusCode::NOT_FOUND,
+        ));
+    }
+    let versioned_game = backend_storage
+        .get(room)
+        .await
+        .map_err(|_| warp::reject())?;
+    if let game_state::GameState::Initialize(_) = versioned_game.game {
+        return Ok(warp::reply::with_status(
+            warp::reply::json(&"game has not started"),
+            warp::http::StatusCode::CONFLICT,
+        ));
+    }
+    Ok(warp::reply::with_status(
+        warp::reply::json(&versioned_game.game.for_player(SPECTATOR_ID)),
+        warp::http::StatusCode::OK,
+    ))
+}
+
 async fn default_propagated() -> Result<impl warp::Reply, warp::Rejection> {
     Ok(warp::reply::json(&settings::PropagatedState::default()))
 }
@@ -407,6 +763,7 @@ async fn default_propagated() -> Result<impl warp::Reply, warp::Rejection> {
 #[allow(clippy::cognitive_complexity)]
 async fn user_connected<S: Storage<VersionedGame, E>, E: std::fmt::Debug>(
     ws: WebSocket,
+    remote: Option<SocketAddr>,
     backend_storage: S,
     stats: Arc<Mutex<InMemoryStats>>,
 ) {
@@ -433,7 +790,7 @@ async fn user_connected<S: Storage<VersionedGame, E>, E: std::fmt::Debug>(
         if let Ok(msg) = result {
             match serde_json::from_slice::<JoinRoom>(msg.as_bytes()) {
                 Ok(msg) if msg.room_name.len() == 16 && msg.name.len() < 32 => {
-                    val = Some((msg.room_name, msg.name));
+                    val = Some((msg.room_name, msg.name, msg.reconnect_token));
                     break;
                 }
                 Ok(_) => {
@@ -455,9 +812,29 @@ async fn user_connected<S: Storage<VersionedGame, E>, E: std::fmt::Debug>(
         }
     }
 
-    if let Some((room, name)) = val {
+    if let Some((room, name, reconnect_token)) = val {
         let logger = logger.new(o!("room" => room.clone(), "name" => name.clone()));
 
+        if let Some(limit) = *MAX_ROOMS_PER_IP {
+            let ip = remote.map(|r| r.ip());
+            let mut locked_stats = stats.lock().await;
+            if !locked_stats.try_join_room(ip, room.as_bytes(), limit) {
+                info!(logger, "Too many rooms created from this address";
+                    "ip" => ip.map(|ip| ip.to_string()),
+                    "num_rooms" => ip.map(|ip| locked_stats.num_rooms_created_by(ip))
+                );
+                let _ = send_to_user(
+                    &tx,
+                    &GameMessage::Error(
+                        "Too many games have been created from your address; try joining an existing game"
+                            .to_string(),
+                    ),
+                )
+                .await;
+                return;
+            }
+        }
+
         let mut subscription = match backend_storage
             .clone()
             .subscribe(room.as_bytes().to_vec(), ws_id)
@@ -474,12 +851,21 @@ async fn user_connected<S: Storage<VersionedGame, E>, E: std::fmt::Debug>(
             }
         };
 
+        // Rate-limit errors go straight to this connection, rather than
+        // through the backend storage.
+        let rate_limit_tx = tx.clone();
+
         // Subscribe to messages for the room. After this point, we should
         // no longer use tx! It's owned by the backend storage.
         let logger_ = logger.clone();
         let name_ = name.clone();
         let (subscribe_player_id_tx, subscribe_player_id_rx) =
             oneshot::channel::<types::PlayerID>();
+        // The player ID this connection acts as. Promoting an observer into a
+        // vacated seat moves their name to the seat's ID, so the connection
+        // follows its name whenever the state changes.
+        let bound_player_id = Arc::new(AtomicUsize::new(0));
+        let bound_player_id_ = bound_player_id.clone();
         tokio::task::spawn(async move {
             debug!(logger_, "Subscribed to messages");
             if let Ok(player_id) = subscribe_player_id_rx.await {
@@ -491,7 +877,10 @@ async fn user_connected<S: Storage<VersionedGame, E>, E: std::fmt::Debug>(
                         | GameMessage::Broadcast { .. }
                         | GameMessage::Message { .. }
                         | GameMessage::Error(_)
-                        | GameMessage::Header { .. } => true,
+                        | GameMessage::Header { .. }
+                        | GameMessage::History { .. }
+                        | GameMessage::Summary { .. }
+                        | GameMessage::ChatBacklog { .. } => true,
                         GameMessage::Beep { target } | GameMessage::Kicked { target } => {
                             *target == name_
                         }
@@ -500,6 +889,13 @@ async fn user_connected<S: Storage<VersionedGame, E>, E: std::fmt::Debug>(
                     let v = if should_send {
                         if let GameMessage::State { state } = v {
                             let g = interactive::InteractiveGame::new_from_state(state);
+                            let player_id = match g.player_id(&name_) {
+                                Ok(id) => {
+                                    bound_player_id_.store(id.0, Ordering::SeqCst);
+                                    id
+                                }
+                                Err(_) => player_id,
+                            };
                             g.dump_state_for_player(player_id)
                                 .ok()
                                 .map(|state| GameMessage::State { state })
@@ -528,7 +924,14 @@ async fn user_connected<S: Storage<VersionedGame, E>, E: std::fmt::Debug>(
             &room,
             backend_storage.clone(),
             move |g, version, associated_websockets| {
-                let (assigned_player_id, register_msgs) = g.register(name_)?;
+                // A stale or invalid token falls back to joining by name.
+                let (assigned_player_id, register_msgs) = match reconnect_token
+                    .as_deref()
+                    .and_then(|token| g.register_with_token(token).ok())
+                {
+                    Some(registered) => registered,
+                    None => g.register(name_)?,
+                };
                 info!(logger_, "Joining room"; "player_id" => assigned_player_id.0);
                 let mut clients_to_disconnect = vec![];
                 let clients = associated_websockets
@@ -571,6 +974,7 @@ async fn user_connected<S: Storage<VersionedGame, E>, E: std::fmt::Debug>(
         if let Ok((player_id, join_span, websockets_to_disconnect)) = player_id_rx.await {
             let logger = logger.new(o!("player_id" => player_id.0));
             info!(logger, "Successfully registered user");
+            bound_player_id.store(player_id.0, Ordering::SeqCst);
             let _ = subscribe_player_id_tx.send(player_id);
 
             for id in websockets_to_disconnect {
@@ -587,8 +991,71 @@ async fn user_connected<S: Storage<VersionedGame, E>, E: std::fmt::Debug>(
                     .await;
             }
 
+            // Spectators who join partway through a game get a replay of the
+            // moves made so far.
+            if let Ok(versioned_game) = backend_storage.clone().get(room.as_bytes().to_vec()).await
+            {
+                if versioned_game.game.is_observer(player_id) {
+                    let state = versioned_game.game.for_player(player_id);
+                    if !state.history().is_empty() {
+                        let _ = backend_storage
+                            .clone()
+                            .publish_to_single_subscriber(
+                                room.as_bytes().to_vec(),
+                                ws_id,
+                                GameMessage::History {
+                                    entries: state.history().to_vec(),
+                                },
+                            )
+                            .await;
+                    }
+                }
+            }
+            send_chat_backlog(&room, ws_id, backend_storage.clone(), stats.clone()).await;
+
             // Handle the main game loop
-            while let Some(result) = user_ws_rx.next().await {
+            let mut user_state = UserState::new(unix_now());
+            let mut action_limiter = RateLimiter::new(*MAX_MESSAGES_PER_SEC, Instant::now());
+            let mut chat_limiter = RateLimiter::new(*MAX_MESSAGES_PER_SEC, Instant::now());
+            loop {
+                let next = match *OBSERVER_IDLE_TIMEOUT_SECS {
+                    Some(timeout) => {
+                        let wait = user_state.time_until_idle(unix_now(), timeout);
+                        match tokio::time::timeout(
+                            tokio::time::Duration::from_secs(wait),
+                            user_ws_rx.next(),
+                        )
+                        .await
+                        {
+                            Ok(next) => next,
+                            Err(_) => {
+                                if user_state.is_idle(unix_now(), timeout)
+                                    && evict_idle_observer(
+                                        &logger,
+                                        &room,
+                                        ws_id,
+                                        types::PlayerID(bound_player_id.load(Ordering::SeqCst)),
+                                        name.clone(),
+                                        backend_storage.clone(),
+                                    )
+                                    .await
+                                {
+                                    break;
+                                }
+                                // Only observers are evicted, so everyone
+                                // else gets a fresh timeout.
+                                user_state.record_activity(unix_now());
+                                continue;
+                            }
+                        }
+                    }
+                    None => user_ws_rx.next().await,
+                };
+                let result = match next {
+                    Some(result) => result,
+                    None => break,
+                };
+                user_state.record_activity(unix_now());
                 let result = match result {
                     Ok(r) => r,
                     Err(e) => {
@@ -601,13 +1068,33 @@ async fn user_connected<S: Storage<VersionedGame, E>, E: std::fmt::Debug>(
                 }
                 match serde_json::from_slice::<UserMessage>(result.as_bytes()) {
                     Ok(msg) => {
+                        let limiter = match msg {
+                            UserMessage::Action(_) => Some(&mut action_limiter),
+                            UserMessage::Message(_) => Some(&mut chat_limiter),
+                            _ => None,
+                        };
+                        if let Some(limiter) = limiter {
+                            let now = Instant::now();
+                            if !limiter.try_acquire(now) {
+                                debug!(logger, "Dropping rate-limited message");
+                                if limiter.should_report_drop(now) {
+                                    let _ = send_to_user(
+                                        &rate_limit_tx,
+                                        &GameMessage::Error("rate limited".to_string()),
+                                    )
+                                    .await;
+                                }
+                                continue;
+                            }
+                        }
                         if let Err(e) = handle_user_action(
                             logger.clone(),
                             ws_id,
-                            player_id,
+                            types::PlayerID(bound_player_id.load(Ordering::SeqCst)),
                             &room,
                             name.clone(),
                             backend_storage.clone(),
+                            stats.clone(),
                             msg,
                         )
                         .await
@@ -672,35 +1159,7 @@ where
 {
     let room_name_ = room_name.as_bytes().to_vec();
 
-    let res = backend_storage
-        .clone()
-        .execute_operation_with_messages::<EitherError<E>, _>(
-            room_name_.clone(),
-            move |versioned_game| {
-                let mut g = interactive::InteractiveGame::new_from_state(versioned_game.game);
-                let mut associated_websockets = versioned_game.associated_websockets;
-                let mut msgs = operation(
-                    &mut g,
-                    versioned_game.monotonic_id,
-                    &mut associated_websockets,
-                )
-                .map_err(EitherError::E2)?;
-                let game = g.into_state();
-                msgs.push(GameMessage::State {
-                    state: game.clone(),
-                });
-                Ok((
-                    VersionedGame {
-                        room_name: versioned_game.room_name,
-                        game,
-                        associated_websockets,
-                        monotonic_id: versioned_game.monotonic_id + 1,
-                    },
-                    msgs,
-                ))
-            },
-        )
-        .await;
+    let res = apply_operation(room_name_.clone(), backend_storage.clone(), operation).await;
     match res {
         Ok(_) => true,
         Err(EitherError::E(_)) => {
@@ -720,6 +1179,50 @@ where
     }
 }
 
+/// Applies `operation` to the game in `room_name` and publishes the messages
+/// it returns, followed by the new state.
+async fn apply_operation<S, E, F>(
+    room_name: Vec<u8>,
+    backend_storage: S,
+    operation: F,
+) -> Result<u64, EitherError<E>>
+where
+    S: Storage<VersionedGame, E>,
+    F: FnOnce(
+            &mut interactive::InteractiveGame,
+            u64,
+            &mut HashMap<types::PlayerID, Vec<usize>>,
+        ) -> Result<Vec<GameMessage>, anyhow::Error>
+        + Send
+        + 'static,
+{
+    backend_storage
+        .execute_operation_with_messages::<EitherError<E>, _>(room_name, move |versioned_game| {
+            let mut g = interactive::InteractiveGame::new_from_state(versioned_game.game);
+            let mut associated_websockets = versioned_game.associated_websockets;
+            let mut msgs = operation(
+                &mut g,
+                versioned_game.monotonic_id,
+                &mut associated_websockets,
+            )
+            .map_err(EitherError::E2)?;
+            let game = g.into_state();
+            msgs.push(GameMessage::State {
+                state: game.clone(),
+            });
+            Ok((
+                VersionedGame {
+                    room_name: versioned_game.room_name,
+                    game,
+                    associated_websockets,
+                    monotonic_id: versioned_game.monotonic_id + 1,
+                },
+                msgs,
+            ))
+        })
+        .await
+}
+
 async fn execute_immutable_operation<S, E, F>(
     ws_id: usize,
     room_name: &str,
@@ -773,6 +1276,7 @@ where
     }
 }
 
+#[allow(clippy::too_many_arguments)]
 async fn handle_user_action<S: Storage<VersionedGame, E>, E>(
     logger: Logger,
     ws_id: usize,
@@ -780,6 +1284,7 @@ async fn handle_user_action<S: Storage<VersionedGame, E>, E>(
     room_name: &str,
     name: String,
     backend_storage: S,
+    stats: Arc<Mutex<InMemoryStats>>,
     msg: UserMessage,
 ) -> Result<(), E> {
     match msg {
@@ -787,7 +1292,7 @@ async fn handle_user_action<S: Storage<VersionedGame, E>, E>(
             execute_immutable_operation(
                 ws_id,
                 room_name,
-                backend_storage,
+                backend_storage.clone(),
                 move |game, _| {
                     let next_player_id = game.next_player()?;
                     let beeped_player_name = game.player_name(next_player_id)?.to_owned();
@@ -806,27 +1311,17 @@ async fn handle_user_action<S: Storage<VersionedGame, E>, E>(
             .await;
         }
         UserMessage::Message(m) => {
-            backend_storage
-                .publish(
-                    room_name.as_bytes().to_vec(),
-                    GameMessage::Message {
-                        from: name,
-                        message: m,
-                    },
-                )
-                .await?;
+            post_chat_message(room_name, name, m, backend_storage, stats).await?;
         }
         UserMessage::ReadyCheck => {
-            backend_storage
-                .clone()
-                .publish(
-                    room_name.as_bytes().to_vec(),
-                    GameMessage::Message {
-                        from: name.clone(),
-                        message: "Is everyone ready?".to_owned(),
-                    },
-                )
-                .await?;
+            post_chat_message(
+                room_name,
+                name.clone(),
+                "Is everyone ready?".to_owned(),
+                backend_storage.clone(),
+                stats,
+            )
+            .await?;
             backend_storage
                 .publish(
                     room_name.as_bytes().to_vec(),
@@ -835,15 +1330,14 @@ async fn handle_user_action<S: Storage<VersionedGame, E>, E>(
                 .await?;
         }
         UserMessage::Ready => {
-            backend_storage
-                .publish(
-                    room_name.as_bytes().to_vec(),
-                    GameMessage::Message {
-                        from: name,
-                        message: "I'm ready!".to_owned(),
-                    },
-                )
-                .await?;
+            post_chat_message(
+                room_name,
+                name,
+                "I'm ready!".to_owned(),
+                backend_storage,
+                stats,
+            )
+            .await?;
         }
         UserMessage::Kick(id) => {
             info!(logger, "Kicking user"; "other" => id.0);
@@ -863,16 +1357,39 @@ async fn handle_user_action<S: Storage<VersionedGame, E>, E>(
             .await;
         }
         UserMessage::Action(action) => {
+            let promoted = match action {
+                interactive::Action::PromoteObserver(observer, _) => Some(observer),
+                _ => None,
+            };
             execute_operation(
                 ws_id,
                 room_name,
                 backend_storage,
-                move |game, _, _| {
-                    Ok(game
-                        .interact(action, caller, &logger)?
+                move |game, _, associated_websockets| {
+                    let promoted_name = promoted
+                        .and_then(|observer| game.player_name(observer).ok())
+                        .map(|name| name.to_owned());
+                    let msgs = game.interact(action, caller, &logger)?;
+                    game.record_action_at(unix_now());
+                    // The promoted observer's connections now belong to the
+                    // seat they took over.
+                    if let (Some(observer), Some(name)) = (promoted, promoted_name) {
+                        let seat = game.player_id(&name)?;
+                        if let Some(clients) = associated_websockets.remove(&observer) {
+                            associated_websockets
+                                .entry(seat)
+                                .or_insert_with(Vec::new)
+                                .extend(clients);
+                        }
+                    }
+                    let mut msgs = msgs
                         .into_iter()
                         .map(|(data, message)| GameMessage::Broadcast { data, message })
-                        .collect())
+                        .collect::<Vec<_>>();
+                    if let Some(summary) = game.take_summary() {
+                        msgs.push(GameMessage::Summary { summary });
+                    }
+                    Ok(msgs)
                 },
                 "handle user action",
             )
@@ -882,6 +1399,104 @@ async fn handle_user_action<S: Storage<VersionedGame, E>, E>(
     Ok(())
 }
 
+/// Sends a chat message to everyone in the room, and remembers it so that it
+/// can be replayed to anyone who joins later.
+async fn post_chat_message<S: Storage<VersionedGame, E>, E>(
+    room_name: &str,
+    from: String,
+    message: String,
+    backend_storage: S,
+    stats: Arc<Mutex<InMemoryStats>>,
+) -> Result<(), E> {
+    stats.lock().await.record_chat_message(
+        room_name.as_bytes(),
+        ChatMessage {
+            from: from.clone(),
+            message: message.clone(),
+        },
+    );
+    backend_storage
+        .publish(
+            room_name.as_bytes().to_vec(),
+            GameMessage::Message { from, message },
+        )
+        .await
+}
+
+/// Sends the room's recent chat messages to a single websocket, so that users
+/// joining partway through can catch up on the conversation.
+async fn send_chat_backlog<S: Storage<VersionedGame, E>, E>(
+    room: &str,
+    ws_id: usize,
+    backend_storage: S,
+    stats: Arc<Mutex<InMemoryStats>>,
+) {
+    let messages = match stats.lock().await.chat_backlogs.get(room.as_bytes()) {
+        Some(backlog) if !backlog.is_empty() => backlog.iter().cloned().collect(),
+        _ => return,
+    };
+    let _ = backend_storage
+        .publish_to_single_subscriber(
+            room.as_bytes().to_vec(),
+            ws_id,
+            GameMessage::ChatBacklog { messages },
+        )
+        .await;
+}
+
+/// Removes an idle observer from the room and tells their client to close the
+/// connection. Players, and observers with other open sessions, are left
+/// alone. Returns whether the observer was removed.
+async fn evict_idle_observer<S: Storage<VersionedGame, E>, E>(
+    logger: &Logger,
+    room: &str,
+    ws_id: usize,
+    player_id: types::PlayerID,
+    name: String,
+    backend_storage: S,
+) -> bool {
+    let should_evict = match backend_storage.clone().get(room.as_bytes().to_vec()).await {
+        Ok(versioned_game) => {
+            versioned_game.game.is_observer(player_id)
+                && versioned_game
+                    .associated_websockets
+                    .get(&player_id)
+                    .map(|ws| ws.iter().all(|w| *w == ws_id))
+                    .unwrap_or(true)
+        }
+        Err(_) => false,
+    };
+    if !should_evict {
+        return false;
+    }
+
+    info!(logger, "Disconnecting idle observer");
+    let removed = execute_operation(
+        ws_id,
+        room,
+        backend_storage.clone(),
+        move |game, _, _| {
+            Ok(game
+                .remove_idle_observer(player_id)?
+                .into_iter()
+                .map(|(data, message)| GameMessage::Broadcast { data, message })
+                .collect())
+        },
+        "remove idle observer",
+    )
+    .await;
+    if removed {
+        let _ = backend_storage
+            .publish_to_single_subscriber(
+                room.as_bytes().to_vec(),
+                ws_id,
+                GameMessage::Kicked { target: name },
+            )
+            .await;
+    }
+    removed
+}
+
 async fn user_disconnected<S: Storage<VersionedGame, E>, E>(
     room: String,
     ws_id: usize,
@@ -914,7 +1529,23 @@ async fn user_disconnected<S: Storage<VersionedGame, E>, E>(
 
 #[cfg(test)]
 mod tests {
-    use super::CARDS_JSON;
+    use std::collections::HashMap;
+    use std::sync::Arc;
+    use std::time::{Duration, Instant};
+
+    use shengji_core::game_state::{GameState, InitializePhase};
+    use shengji_types::GameMessage;
+    use slog::o;
+    use storage::{HashMapStorage, Storage};
+    use tokio::sync::{oneshot, Mutex};
+    use warp::http::StatusCode;
+    use warp::{Filter, Reply};
+
+    use super::{
+        game_snapshot, post_chat_message, send_chat_backlog, serve_then_dump_state, try_read_file,
+        types, InMemoryStats, RateLimiter, UserState, VersionedGame, CARDS_JSON, MAX_CHAT_BACKLOG,
+        ROOT_LOGGER,
+    };
 
     static CARDS_JSON_FROM_FILE: &str = include_str!("../../frontend/src/generated/cards.json");
 
@@ -929,4 +1560,260 @@ mod tests {
             "Run `yarn download-cards-json` with the backend running to sync the generated cards.json file"
         );
     }
+
+    #[test]
+    fn test_idle_timing() {
+        let mut user_state = UserState::new(100);
+        assert_eq!(user_state.time_until_idle(100, 30), 30);
+        assert_eq!(user_state.time_until_idle(120, 30), 10);
+        assert!(!user_state.is_idle(129, 30));
+        assert!(user_state.is_idle(130, 30));
+        assert_eq!(user_state.time_until_idle(200, 30), 0);
+
+        // Any activity restarts the clock.
+        user_state.record_activity(125);
+        assert!(!user_state.is_idle(130, 30));
+        assert_eq!(user_state.time_until_idle(130, 30), 25);
+        assert!(user_state.is_idle(155, 30));
+    }
+
+    #[test]
+    fn test_rate_limiter() {
+        let start = Instant::now();
+        let mut limiter = RateLimiter::new(20, start);
+
+        // A full bucket allows a burst of 20 messages at once...
+        for _ in 0..20 {
+            assert!(limiter.try_acquire(start));
+        }
+        assert!(!limiter.try_acquire(start));
+
+        // ...after which messages are let through at 20 per second.
+        let t = |ms| start + Duration::from_millis(ms);
+        assert!(!limiter.try_acquire(t(25)));
+        assert!(limiter.try_acquire(t(50)));
+        assert!(!limiter.try_acquire(t(60)));
+        assert!(limiter.try_acquire(t(100)));
+        let allowed = (1..=100)
+            .filter(|i| limiter.try_acquire(t(100 + 10 * i)))
+            .count();
+        assert_eq!(allowed, 20);
+
+        // The bucket refills after a quiet period, but never beyond a burst.
+        for _ in 0..20 {
+            assert!(limiter.try_acquire(t(60_000)));
+        }
+        assert!(!limiter.try_acquire(t(60_000)));
+
+        // Dropped messages are reported at most once per refill.
+        assert!(limiter.should_report_drop(t(60_000)));
+        assert!(!limiter.should_report_drop(t(60_010)));
+        assert!(!limiter.should_report_drop(t(60_999)));
+        assert!(limiter.should_report_drop(t(61_000)));
+    }
+
+    #[test]
+    fn test_rooms_per_ip() {
+        let mut stats = InMemoryStats::default();
+        let ip = "10.0.0.1".parse().unwrap();
+        let other_ip = "10.0.0.2".parse().unwrap();
+
+        assert!(stats.try_join_room(Some(ip), b"room1", 2));
+        assert!(stats.try_join_room(Some(ip), b"room2", 2));
+        assert!(!stats.try_join_room(Some(ip), b"room3", 2));
+        // Rejoining an existing room isn't creating a new one.
+        assert!(stats.try_join_room(Some(ip), b"room1", 2));
+        assert!(stats.try_join_room(Some(other_ip), b"room3", 2));
+        assert_eq!(stats.num_rooms_created_by(ip), 2);
+        // Neither is joining a room which someone else created.
+        assert!(stats.try_join_room(Some(ip), b"room3", 2));
+        assert_eq!(stats.num_rooms_created_by(ip), 2);
+        // Rooms created without a known address don't count against anyone.
+        assert!(stats.try_join_room(None, b"room4", 2));
+        assert!(stats.try_join_room(Some(ip), b"room4", 2));
+
+        // Once a room is cleaned up, another can be created in its place.
+        stats.retain_active_rooms(&[b"room2".to_vec(), b"room3".to_vec()]);
+        assert_eq!(stats.num_rooms_created_by(ip), 1);
+        assert!(stats.try_join_room(Some(ip), b"room5", 2));
+        assert!(!stats.try_join_room(Some(ip), b"room6", 2));
+
+        stats.retain_active_rooms(&[]);
+        assert_eq!(stats.num_rooms_created_by(ip), 0);
+        assert_eq!(stats.num_rooms_created_by(other_ip), 0);
+        assert!(stats.rooms_by_ip.is_empty());
+        assert!(stats.active_rooms.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_chat_backlog() {
+        let backend_storage = HashMapStorage::new(ROOT_LOGGER.new(o!("component" => "storage")));
+        let stats = Arc::new(Mutex::new(InMemoryStats::default()));
+        let room = "chat_backlog_rm";
+        let key = room.as_bytes().to_vec();
+        let mut early = backend_storage
+            .clone()
+            .subscribe(key.clone(), 1)
+            .await
+            .unwrap();
+
+        for i in 0..MAX_CHAT_BACKLOG + 5 {
+            post_chat_message(
+                room,
+                "p1".to_string(),
+                format!("message {}", i),
+                backend_storage.clone(),
+                stats.clone(),
+            )
+            .await
+            .unwrap();
+        }
+        // Chatting doesn't touch the game state.
+        assert_eq!(
+            backend_storage
+                .clone()
+                .get(key.clone())
+                .await
+                .unwrap()
+                .monotonic_id,
+            0
+        );
+        // Everyone already in the room sees the messages as they're sent.
+        for i in 0..MAX_CHAT_BACKLOG + 5 {
+            match early.recv().await.unwrap() {
+                GameMessage::Message { from, message } => {
+                    assert_eq!(from, "p1");
+                    assert_eq!(message, format!("message {}", i));
+                }
+                _ => panic!("expected a chat message"),
+            }
+        }
+
+        // Someone joining later gets the most recent messages in one batch.
+        let mut late = backend_storage
+            .clone()
+            .subscribe(key.clone(), 2)
+            .await
+            .unwrap();
+        send_chat_backlog(room, 2, backend_storage.clone(), stats.clone()).await;
+        match late.recv().await.unwrap() {
+            GameMessage::ChatBacklog { messages } => {
+                assert_eq!(messages.len(), MAX_CHAT_BACKLOG);
+                assert_eq!(messages[0].message, "message 5");
+                assert_eq!(
+                    messages.last().unwrap().message,
+                    format!("message {}", MAX_CHAT_BACKLOG + 4)
+                );
+                assert!(messages.iter().all(|m| m.from == "p1"));
+            }
+            _ => panic!("expected the chat backlog"),
+        }
+        // The backlog only goes to the user who joined.
+        assert!(early.try_recv().is_err());
+
+        // Rooms without any chat don't send an empty backlog.
+        let mut other = backend_storage
+            .clone()
+            .subscribe(b"quiet_room".to_vec(), 3)
+            .await
+            .unwrap();
+        send_chat_backlog("quiet_room", 3, backend_storage.clone(), stats.clone()).await;
+        assert!(other.try_recv().is_err());
+
+        // The backlog is dropped along with the room.
+        stats.lock().await.retain_active_rooms(&[]);
+        assert!(stats.lock().await.chat_backlogs.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_dump_state_on_shutdown() {
+        let backend_storage = HashMapStorage::new(ROOT_LOGGER.new(o!("component" => "storage")));
+        let stats = Arc::new(Mutex::new(InMemoryStats::default()));
+        let mut init = InitializePhase::new();
+        init.add_player("p1".to_string()).unwrap();
+        backend_storage
+            .clone()
+            .put(VersionedGame {
+                room_name: b"shutdown_room".to_vec(),
+                game: GameState::Initialize(init),
+                associated_websockets: HashMap::new(),
+                monotonic_id: 1,
+            })
+            .await
+            .unwrap();
+        let dump_path =
+            std::env::temp_dir().join(format!("shengji_state_test_{}.json", std::process::id()));
+        let dump_path = dump_path.to_str().unwrap();
+
+        let (tx, rx) = oneshot::channel::<()>();
+        let (_, serve_task) = warp::serve(warp::any().map(warp::reply))
+            .bind_with_graceful_shutdown(([127, 0, 0, 1], 0), async move {
+                let _ = rx.await;
+            });
+        tx.send(()).unwrap();
+        // Without the periodic task, only the final dump writes the file.
+        serve_then_dump_state(serve_task, backend_storage, stats, dump_path, None).await;
+
+        let dump: HashMap<String, serde_json::Value> = try_read_file(dump_path).await.unwrap();
+        std::fs::remove_file(dump_path).unwrap();
+        assert!(dump.contains_key("shutdown_room"));
+    }
+
+    #[tokio::test]
+    async fn test_game_snapshot() {
+        let backend_storage = HashMapStorage::new(ROOT_LOGGER.new(o!("component" => "storage")));
+        let snapshot = |room: &str| {
+            let backend_storage = backend_storage.clone();
+            let room = room.to_string();
+            async move {
+                let response = game_snapshot(room, backend_storage)
+                    .await
+                    .unwrap()
+                    .into_response();
+                let status = response.status();
+                let body = warp::hyper::body::to_bytes(response.into_body())
+                    .await
+                    .unwrap();
+                (
+                    status,
+                    serde_json::from_slice::<serde_json::Value>(&body).unwrap(),
+                )
+            }
+        };
+        let put = |game: GameState| {
+            backend_storage.clone().put(VersionedGame {
+                room_name: b"room".to_vec(),
+                game,
+                associated_websockets: HashMap::new(),
+                monotonic_id: 1,
+            })
+        };
+
+        assert_eq!(snapshot("room").await.0, StatusCode::NOT_FOUND);
+
+        let mut init = InitializePhase::new();
+        for name in &["p1", "p2", "p3", "p4"] {
+            init.add_player(name.to_string()).unwrap();
+        }
+        put(GameState::Initialize(init.clone())).await.unwrap();
+        assert_eq!(snapshot("room").await.0, StatusCode::CONFLICT);
+
+        let mut draw = init.start(types::PlayerID(0)).unwrap();
+        while let Ok(id) = draw.next_player() {
+            if draw.draw_card(id).is_err() {
+                break;
+            }
+        }
+        put(GameState::Draw(draw)).await.unwrap();
+        let (status, state) = snapshot("room").await;
+        assert_eq!(status, StatusCode::OK);
+        let hands = state["Draw"]["hands"]["hands"].as_object().unwrap();
+        assert_eq!(hands.len(), 4);
+        let unknown = types::Card::Unknown.as_char().to_string();
+        for hand in hands.values() {
+            let hand = hand.as_object().unwrap();
+            assert_eq!(hand.keys().collect::<Vec<_>>(), vec![&unknown]);
+            assert!(hand[&unknown].as_u64().unwrap() > 0);
+        }
+    }
 }
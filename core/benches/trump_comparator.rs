@@ -0,0 +1,35 @@
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use rand::{rngs::StdRng, seq::SliceRandom, SeedableRng};
+
+use shengji_core::types::{Number, PermanentTrumps, Suit, Trump, FULL_DECK};
+
+fn sort_six_deck_hand(c: &mut Criterion) {
+    let trump = Trump::Standard {
+        suit: Suit::Hearts,
+        number: Number::Seven,
+        permanent: PermanentTrumps::NONE,
+    };
+    let mut hand = (0..6)
+        .flat_map(|_| FULL_DECK.iter().copied())
+        .collect::<Vec<_>>();
+    hand.shuffle(&mut StdRng::seed_from_u64(0));
+
+    c.bench_function("sort six decks with Trump::compare", |b| {
+        b.iter(|| {
+            let mut cards = black_box(hand.clone());
+            cards.sort_by(|a, b| trump.compare(*a, *b));
+            cards
+        })
+    });
+    c.bench_function("sort six decks with TrumpComparator", |b| {
+        b.iter(|| {
+            let comparator = trump.comparator();
+            let mut cards = black_box(hand.clone());
+            cards.sort_by(|a, b| comparator.compare(*a, *b));
+            cards
+        })
+    });
+}
+
+criterion_group!(benches, sort_six_deck_hand);
+criterion_main!(benches);
@@ -10,6 +10,9 @@ pub enum BidPolicy {
     JokerOrHigherSuit,
     JokerOrGreaterLength,
     GreaterLength,
+    /// Like `GreaterLength`, but a bid can't be reinforced up to the same
+    /// number of cards as the winning bid either.
+    StrictlyIncreasing,
 }
 
 impl Default for BidPolicy {
@@ -72,6 +75,9 @@ pub struct Bid {
     pub(crate) count: usize,
     #[serde(default)]
     pub(crate) epoch: usize,
+    /// Whether this bid was made before the bidder had drawn their full hand.
+    #[serde(default)]
+    pub(crate) blind: bool,
 }
 
 impl Bid {
@@ -132,6 +138,7 @@ impl Bid {
                         card: *card,
                         count: inner_count,
                         epoch,
+                        blind: false,
                     };
                     if let Some(existing_bid) = bids.last() {
                         if new_bid.count > existing_bid.count {
@@ -185,6 +192,7 @@ impl Bid {
                         count,
                         id,
                         epoch,
+                        blind: false,
                     })
                     .collect())
             }
@@ -201,12 +209,13 @@ impl Bid {
                     .unwrap_or(0);
 
                 if let Some(last_bid) = bids.last() {
-                    if last_bid.count <= available {
+                    if last_bid.count <= available && bid_policy != BidPolicy::StrictlyIncreasing {
                         let new_bid = Bid {
                             card: most_recent_bid.card,
                             count: last_bid.count,
                             id,
                             epoch,
+                            blind: false,
                         };
                         if new_bid == *last_bid || !last_bid.card.is_joker() {
                             valid_bids.push(new_bid);
@@ -252,6 +261,7 @@ impl Bid {
             card,
             count,
             epoch,
+            blind: false,
         };
         if Self::valid_bids(
             id,
@@ -275,6 +285,17 @@ impl Bid {
         }
     }
 
+    /// Whether `take_back_bid` would succeed for this player.
+    pub fn can_take_back_bid(
+        id: PlayerID,
+        bid_takeback_policy: BidTakebackPolicy,
+        bids: &'_ [Bid],
+        epoch: usize,
+    ) -> bool {
+        bid_takeback_policy != BidTakebackPolicy::NoBidTakeback
+            && bids.last().map(|b| (b.id, b.epoch)) == Some((id, epoch))
+    }
+
     pub fn take_back_bid(
         id: PlayerID,
         bid_takeback_policy: BidTakebackPolicy,
@@ -292,7 +313,31 @@ impl Bid {
         }
     }
 
+    /// Whether this bid has the same count as `other`, and neither could
+    /// overturn the other under `bid_policy`.
+    pub fn matches_strength(&self, other: &Bid, bid_policy: BidPolicy) -> bool {
+        if self.count != other.count {
+            return false;
+        }
+        match bid_policy {
+            BidPolicy::JokerOrHigherSuit | BidPolicy::JokerOrGreaterLength => {
+                if self.card.is_joker() || other.card.is_joker() {
+                    self.card == other.card
+                } else if bid_policy == BidPolicy::JokerOrHigherSuit && self.count > 1 {
+                    self.card.suit() == other.card.suit()
+                } else {
+                    true
+                }
+            }
+            BidPolicy::GreaterLength | BidPolicy::StrictlyIncreasing => true,
+        }
+    }
+
     /// Returns the player IDs for the first player to bid, and for the player who won the bid.
+    ///
+    /// The winning bid is always the most recent one, so a bid which only
+    /// matches the previous winner (see `KittyTheftBidPolicy::MustMatch`)
+    /// still takes over.
     pub fn first_and_winner(bids: &'_ [Bid], autobid: Option<Bid>) -> Result<(Bid, Bid), Error> {
         if bids.is_empty() && autobid.is_none() {
             bail!("nobody has bid yet")
@@ -330,6 +375,7 @@ mod tests {
                 card: $card,
                 count: $count,
                 epoch: 0,
+                blind: false,
             }
         };
     }
@@ -534,4 +580,124 @@ mod tests {
             );
         }
     }
+
+    #[test]
+    fn test_valid_bids_strictly_increasing() {
+        let p = PlayerID(0);
+        let mut h = Hands::new(vec![p]);
+        h.add(
+            p,
+            vec![
+                C_2,
+                C_2,
+                C_2,
+                S_2,
+                S_2,
+                Card::SmallJoker,
+                Card::SmallJoker,
+                Card::BigJoker,
+                Card::BigJoker,
+            ],
+        )
+        .unwrap();
+        let players = vec![Player::new(p, "p0".into())];
+
+        let test_cases = vec![
+            // A pair of spades can outbid a pair of diamonds by suit...
+            (
+                vec![b!(PlayerID(1), D_2, 2)],
+                BidPolicy::JokerOrHigherSuit,
+                BidReinforcementPolicy::ReinforceWhileWinning,
+                b!(p, S_2, 2),
+                true,
+            ),
+            // ... and jokers can outbid a pair of the same length ...
+            (
+                vec![b!(PlayerID(1), D_2, 2)],
+                BidPolicy::JokerOrGreaterLength,
+                BidReinforcementPolicy::ReinforceWhileWinning,
+                b!(p, Card::SmallJoker, 2),
+                true,
+            ),
+            // ... and a bid can be reinforced to match the winning bid ...
+            (
+                vec![b!(p, C_2, 1), b!(PlayerID(1), S_2, 2)],
+                BidPolicy::GreaterLength,
+                BidReinforcementPolicy::ReinforceWhileEquivalent,
+                b!(p, C_2, 2),
+                true,
+            ),
+            // ... but none of these are allowed when bids must strictly
+            // increase.
+            (
+                vec![b!(PlayerID(1), D_2, 2)],
+                BidPolicy::StrictlyIncreasing,
+                BidReinforcementPolicy::ReinforceWhileWinning,
+                b!(p, S_2, 2),
+                false,
+            ),
+            (
+                vec![b!(PlayerID(1), D_2, 2)],
+                BidPolicy::StrictlyIncreasing,
+                BidReinforcementPolicy::ReinforceWhileWinning,
+                b!(p, Card::SmallJoker, 2),
+                false,
+            ),
+            (
+                vec![b!(p, C_2, 1), b!(PlayerID(1), S_2, 2)],
+                BidPolicy::StrictlyIncreasing,
+                BidReinforcementPolicy::ReinforceWhileEquivalent,
+                b!(p, C_2, 2),
+                false,
+            ),
+            // Larger bids, including jokers, are still fine.
+            (
+                vec![b!(p, C_2, 1), b!(PlayerID(1), S_2, 2)],
+                BidPolicy::StrictlyIncreasing,
+                BidReinforcementPolicy::ReinforceWhileEquivalent,
+                b!(p, C_2, 3),
+                true,
+            ),
+            (
+                vec![b!(PlayerID(1), Card::SmallJoker, 1)],
+                BidPolicy::StrictlyIncreasing,
+                BidReinforcementPolicy::ReinforceWhileWinning,
+                b!(p, Card::BigJoker, 2),
+                true,
+            ),
+            // Reinforcing our own winning bid is still allowed.
+            (
+                vec![b!(p, C_2, 1)],
+                BidPolicy::StrictlyIncreasing,
+                BidReinforcementPolicy::ReinforceWhileEquivalent,
+                b!(p, C_2, 2),
+                true,
+            ),
+        ];
+
+        for (mut bids, policy, rpol, bid, allowed) in test_cases {
+            assert_eq!(
+                Bid::bid(
+                    p,
+                    bid.card,
+                    bid.count,
+                    &mut bids,
+                    None,
+                    &h,
+                    &players,
+                    None,
+                    policy,
+                    rpol,
+                    JokerBidPolicy::BothTwoOrMore,
+                    3,
+                    0,
+                ),
+                allowed,
+                "{:?} under {:?}",
+                bid,
+                policy
+            );
+            assert_eq!(bids.last() == Some(&bid), allowed);
+        }
+    }
 }
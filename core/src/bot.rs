@@ -0,0 +1,340 @@
+//! A simple computer player, which can fill empty seats in a game.
+//!
+//! The bot doesn't try to play well; it only picks a move which is always
+//! legal, using a few cheap heuristics.
+
+use std::collections::HashMap;
+
+use crate::bidding::Bid;
+use crate::game_state::{DrawPhase, ExchangePhase, GameState, PlayPhase};
+use crate::interactive::Action;
+use crate::settings::{FriendSelection, KittyTheftPolicy};
+use crate::trick::Trick;
+use crate::types::{Card, EffectiveSuit, Number, PlayerID, Suit, Trump, ALL_SUITS};
+
+/// The most actions that bots may take in response to a single action by a
+/// person, in case the bots somehow get stuck in a loop.
+pub const MAX_BOT_ACTIONS: usize = 1000;
+
+/// Picks the next action for the bot `id`, or `None` if it's waiting for
+/// someone else.
+pub fn next_action(state: &GameState, id: PlayerID) -> Option<Action> {
+    if state.paused || state.is_observer(id) {
+        return None;
+    }
+    match state {
+        GameState::Initialize(_) => None,
+        GameState::Draw(draw) => draw_action(draw, id),
+        GameState::Exchange(exchange) => exchange_action(exchange, id),
+        GameState::Play(play) => play_action(play, id),
+    }
+}
+
+/// Always draws, and bids as strongly as possible whenever it can.
+fn draw_action(draw: &DrawPhase, id: PlayerID) -> Option<Action> {
+    let strongest = draw.valid_bids(id).into_iter().max_by_key(|bid| {
+        let joker_rank = match bid.card {
+            Card::BigJoker => 2,
+            Card::SmallJoker => 1,
+            _ => 0,
+        };
+        (bid.count, joker_rank)
+    });
+    if let Some(Bid { card, count, .. }) = strongest {
+        return Some(Action::Bid(card, count));
+    }
+    if draw.next_player().ok()? != id {
+        None
+    } else if draw.done_drawing() {
+        Some(Action::PickUpKitty)
+    } else {
+        Some(Action::DrawCard)
+    }
+}
+
+/// Buries the weakest off-suit cards, picks friends, and starts the game.
+fn exchange_action(exchange: &ExchangePhase, id: PlayerID) -> Option<Action> {
+    let trump = exchange.trump();
+    if exchange.exchanger() == id && !exchange.is_finalized() {
+        let hand = exchange.hands().get(id).ok()?;
+        if exchange.is_trump_suit_pending() {
+            return Some(Action::SetTrumpSuit(longest_suit(hand)?));
+        }
+
+        // A sealed bottom has to be kept as it is.
+        let mut desired = if exchange
+            .propagated()
+            .has_experimental_feature("sealed_kitty")
+        {
+            Card::count(exchange.kitty().iter().copied())
+        } else {
+            Card::count(burial_order(
+                trump,
+                Card::cards(hand.iter())
+                    .chain(exchange.kitty().iter())
+                    .copied()
+                    .collect(),
+                exchange.kitty_size(),
+            ))
+        };
+        let mut to_hand = None;
+        for card in exchange.kitty() {
+            match desired.get_mut(card) {
+                Some(count) if *count > 0 => *count -= 1,
+                _ => to_hand = to_hand.or(Some(*card)),
+            }
+        }
+        if let Some(card) = to_hand {
+            return Some(Action::MoveCardToHand(card));
+        }
+        if let Some((card, _)) = desired.into_iter().find(|(_, count)| *count > 0) {
+            return Some(Action::MoveCardToKitty(card));
+        }
+    }
+
+    if exchange.landlord() == id && !exchange.friends_picked() {
+        return Some(Action::SetFriends(pick_friends(exchange, id)));
+    }
+
+    let theft_allowed =
+        exchange.propagated().kitty_theft_policy == KittyTheftPolicy::AllowKittyTheft;
+    if exchange.exchanger() == id && theft_allowed && !exchange.is_finalized() {
+        Some(Action::PutDownKitty)
+    } else if exchange.landlord() == id
+        && exchange.friends_picked()
+        && (!theft_allowed || exchange.is_finalized())
+    {
+        Some(Action::BeginPlay)
+    } else {
+        None
+    }
+}
+
+/// Finishes the exchange on behalf of the landlord `id`, keeping the cards
+/// which they have already put in the bottom. If they were in the middle of
+/// exchanging cards, the bottom is topped up or trimmed to the right size.
+pub(crate) fn finish_exchange_action(exchange: &ExchangePhase, id: PlayerID) -> Option<Action> {
+    if exchange.landlord() != id {
+        return None;
+    }
+    let trump = exchange.trump();
+    let theft_allowed =
+        exchange.propagated().kitty_theft_policy == KittyTheftPolicy::AllowKittyTheft;
+    if exchange.exchanger() == id && !exchange.is_finalized() {
+        let hand = exchange.hands().get(id).ok()?;
+        if exchange.is_trump_suit_pending() {
+            return Some(Action::SetTrumpSuit(longest_suit(hand)?));
+        }
+        let kitty = exchange.kitty();
+        if kitty.len() < exchange.kitty_size() {
+            let card =
+                *burial_order(trump, Card::cards(hand.iter()).copied().collect(), 1).first()?;
+            return Some(Action::MoveCardToKitty(card));
+        }
+        if kitty.len() > exchange.kitty_size() {
+            let card = *burial_order(trump, kitty.to_vec(), kitty.len()).last()?;
+            return Some(Action::MoveCardToHand(card));
+        }
+    }
+
+    if !exchange.friends_picked() {
+        Some(Action::SetFriends(pick_friends(exchange, id)))
+    } else if exchange.exchanger() == id && theft_allowed && !exchange.is_finalized() {
+        Some(Action::PutDownKitty)
+    } else if !theft_allowed || exchange.is_finalized() {
+        Some(Action::BeginPlay)
+    } else {
+        None
+    }
+}
+
+/// The suit which the most cards in `hand` belong to.
+fn longest_suit(hand: &HashMap<Card, usize>) -> Option<Suit> {
+    ALL_SUITS.iter().copied().max_by_key(|suit| {
+        Card::cards(hand.iter())
+            .filter(|c| c.suit() == Some(*suit))
+            .count()
+    })
+}
+
+/// Orders `cards` from the most to the least suitable to be buried, and keeps
+/// the first `kitty_size` of them. Low off-suit cards without points are
+/// buried first.
+fn burial_order(trump: Trump, mut cards: Vec<Card>, kitty_size: usize) -> Vec<Card> {
+    let key = |c: &Card| {
+        (
+            trump.effective_suit(*c) == EffectiveSuit::Trump,
+            c.points().is_some(),
+        )
+    };
+    cards.sort_by(|a, b| key(a).cmp(&key(b)).then(trump.compare(*a, *b)));
+    cards.truncate(kitty_size);
+    cards
+}
+
+/// Picks the highest off-suit cards that the leader doesn't hold.
+fn pick_friends(exchange: &ExchangePhase, id: PlayerID) -> Vec<FriendSelection> {
+    let hand = exchange.hands().get(id).ok();
+    let numbers = [
+        Number::Ace,
+        Number::King,
+        Number::Queen,
+        Number::Jack,
+        Number::Ten,
+        Number::Nine,
+        Number::Eight,
+        Number::Seven,
+        Number::Six,
+        Number::Five,
+        Number::Four,
+        Number::Three,
+        Number::Two,
+    ];
+    numbers
+        .iter()
+        .flat_map(|number| {
+            ALL_SUITS.iter().map(move |suit| Card::Suited {
+                suit: *suit,
+                number: *number,
+            })
+        })
+        .filter(|card| hand.map(|h| !h.contains_key(card)).unwrap_or(true))
+        .map(|card| FriendSelection {
+            card,
+            initial_skip: 0,
+        })
+        .filter(|friend| exchange.check_friend(friend).is_ok())
+        .take(exchange.num_friends())
+        .collect()
+}
+
+/// Follows with the fewest points possible, and leads the strongest single
+/// card.
+fn play_action(play: &PlayPhase, id: PlayerID) -> Option<Action> {
+    if play.game_finished() {
+        return None;
+    }
+    let trick = play.trick();
+    match trick.next_player() {
+        None if trick.current_winner() == Some(id) => Some(Action::EndTrick),
+        Some(next) if next == id => {
+            let hand = play.hands().get(id).ok()?;
+            let trump = trick.trump();
+            let cards = match trick.trick_format() {
+                Some(tf) => {
+                    Trick::analyze_defensive_discard(hand, tf, play.propagated().trick_draw_policy)?
+                }
+                None => {
+                    let best = Card::cards(hand.iter()).copied().max_by(|a, b| {
+                        let is_trump = |c: Card| trump.effective_suit(c) == EffectiveSuit::Trump;
+                        // Prefer side suits, since leading trump helps the
+                        // other team draw out our trumps.
+                        is_trump(*b).cmp(&is_trump(*a)).then(trump.compare(*a, *b))
+                    })?;
+                    vec![best]
+                }
+            };
+            Some(Action::PlayCards(cards))
+        }
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use slog::{o, Discard, Logger};
+
+    use crate::game_state::GameState;
+    use crate::interactive::{Action, InteractiveGame};
+    use crate::message::MessageVariant;
+    use crate::trick::Trick;
+    use crate::types::Card;
+
+    #[test]
+    fn test_bot_plays_full_game() {
+        let logger = Logger::root(Discard, o!());
+        let mut game = InteractiveGame::new();
+        let humans = (0..3)
+            .map(|i| game.register(format!("p{}", i)).unwrap().0)
+            .collect::<Vec<_>>();
+        game.interact(Action::AddBot, humans[0], &logger).unwrap();
+        let bot = game.player_id("Bot 1").unwrap();
+        // Nobody can take over the bot's seat by joining with its name.
+        assert!(game.register("Bot 1".into()).is_err());
+
+        let draw = match game.into_state() {
+            GameState::Initialize(init) => init.start_with_seed(humans[0], 42).unwrap(),
+            _ => panic!("expected to be initializing"),
+        };
+        let mut game = InteractiveGame::new_from_state(GameState::Draw(draw));
+        game.run_bots(&logger);
+
+        // The people always draw, bid if nobody else has, play the first
+        // legal cards, and end the tricks that they win.
+        let mut num_actions = 0;
+        loop {
+            num_actions += 1;
+            assert!(num_actions < 1000, "game didn't finish");
+            let state = game.dump_state().unwrap();
+            let no_bids = !state
+                .history()
+                .iter()
+                .any(|entry| matches!(entry.message, MessageVariant::MadeBid { .. }));
+            let (id, action) = match state {
+                GameState::Initialize(_) => panic!("game was reset"),
+                GameState::Draw(draw) => {
+                    let next = draw.next_player().unwrap();
+                    assert!(next != bot, "bot didn't take its turn");
+                    let bids = draw.valid_bids(next);
+                    if no_bids && !bids.is_empty() {
+                        (next, Action::Bid(bids[0].card, bids[0].count))
+                    } else if draw.done_drawing() {
+                        (next, Action::PickUpKitty)
+                    } else {
+                        (next, Action::DrawCard)
+                    }
+                }
+                GameState::Exchange(exchange) => {
+                    assert!(exchange.landlord() != bot, "bot didn't start the game");
+                    (exchange.landlord(), Action::BeginPlay)
+                }
+                GameState::Play(play) => {
+                    if play.game_finished() {
+                        break;
+                    }
+                    let trick = play.trick();
+                    match trick.next_player() {
+                        Some(next) => {
+                            assert!(next != bot, "bot didn't play");
+                            let hand = play.hands().get(next).unwrap();
+                            let cards = match trick.trick_format() {
+                                Some(tf) => Trick::all_legal_follows(
+                                    hand,
+                                    tf,
+                                    play.propagated().trick_draw_policy,
+                                )
+                                .remove(0),
+                                None => vec![*Card::cards(hand.iter()).next().unwrap()],
+                            };
+                            (next, Action::PlayCards(cards))
+                        }
+                        None => {
+                            let winner = trick.current_winner().unwrap();
+                            assert!(winner != bot, "bot didn't end its trick");
+                            (winner, Action::EndTrick)
+                        }
+                    }
+                }
+            };
+            game.interact(action, id, &logger).unwrap();
+        }
+
+        let state = game.into_state();
+        assert!(state.history().iter().any(|entry| entry.actor == bot
+            && matches!(entry.message, MessageVariant::PlayedCards { .. })));
+        match state {
+            GameState::Play(play) => assert!(play.hands().get(bot).unwrap().is_empty()),
+            _ => panic!("expected the game to finish"),
+        }
+    }
+}
@@ -1,13 +1,30 @@
 use serde::{Deserialize, Serialize};
 use slog_derive::KV;
 
-use crate::types::{Card, Number, FULL_DECK};
+use crate::types::{Card, Number, ALL_SUITS, FULL_DECK};
 
-#[derive(Clone, Debug, Serialize, Deserialize, KV)]
+/// The most copies of each joker that a single deck may contain.
+pub const MAX_JOKERS_PER_DECK: usize = 4;
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, KV)]
 pub struct Deck {
     pub exclude_small_joker: bool,
     pub exclude_big_joker: bool,
+    /// The number of small jokers in the deck, unless `exclude_small_joker` is set.
+    #[serde(default = "default_num_jokers")]
+    pub num_small_jokers: usize,
+    /// The number of big jokers in the deck, unless `exclude_big_joker` is set.
+    #[serde(default = "default_num_jokers")]
+    pub num_big_jokers: usize,
     pub min: Number,
+    /// Cards which are left out of the deck entirely.
+    #[slog(skip)]
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub excluded: Vec<Card>,
+}
+
+fn default_num_jokers() -> usize {
+    1
 }
 
 impl slog::Value for Deck {
@@ -26,69 +43,96 @@ impl Default for Deck {
         Deck {
             exclude_small_joker: false,
             exclude_big_joker: false,
+            num_small_jokers: 1,
+            num_big_jokers: 1,
             min: Number::Two,
+            excluded: vec![],
         }
     }
 }
 
 impl Deck {
+    /// Leaves every copy of each of the `excluded` cards out of the deck.
+    pub fn with_excluded(mut self, excluded: &[Card]) -> Self {
+        for card in excluded {
+            if !self.excluded.contains(card) {
+                self.excluded.push(*card);
+            }
+        }
+        self
+    }
+
+    /// Whether the deck has any cards of `number`, after leaving out the
+    /// excluded cards.
     pub fn includes_number(&self, number: Number) -> bool {
         number >= self.min
+            && ALL_SUITS.iter().any(|suit| {
+                !self.excluded.contains(&Card::Suited {
+                    suit: *suit,
+                    number,
+                })
+            })
     }
 
-    pub fn includes_card(&self, card: Card) -> bool {
-        match card {
-            Card::BigJoker if self.exclude_big_joker => false,
-            Card::SmallJoker if self.exclude_small_joker => false,
-            Card::Suited { number, .. } if !self.includes_number(number) => false,
-            _ => true,
+    /// Whether the deck has any cards of `number`, which players at that
+    /// level need in order to bid.
+    pub fn can_bid(&self, number: Number) -> bool {
+        self.includes_number(number)
+    }
+
+    pub fn num_small_jokers(&self) -> usize {
+        if self.exclude_small_joker {
+            0
+        } else {
+            self.num_small_jokers
         }
     }
 
-    pub fn points(&self) -> usize {
-        let mut pts = 0;
-        if self.includes_number(Number::Five) {
-            pts += 5 * 4;
+    pub fn num_big_jokers(&self) -> usize {
+        if self.exclude_big_joker {
+            0
+        } else {
+            self.num_big_jokers
         }
-        if self.includes_number(Number::Ten) {
-            pts += 10 * 4;
+    }
+
+    /// The number of copies of `card` in this deck.
+    pub fn num_copies(&self, card: Card) -> usize {
+        if self.excluded.contains(&card) {
+            return 0;
         }
-        if self.includes_number(Number::King) {
-            pts += 10 * 4;
+        match card {
+            Card::BigJoker => self.num_big_jokers(),
+            Card::SmallJoker => self.num_small_jokers(),
+            Card::Suited { number, .. } if number >= self.min => 1,
+            Card::Suited { .. } | Card::Unknown => 0,
         }
-        pts
     }
 
-    pub fn is_empty(&self) -> bool {
-        self.exclude_big_joker && self.exclude_small_joker && self.min == Number::Ace
+    pub fn includes_card(&self, card: Card) -> bool {
+        self.num_copies(card) > 0
     }
 
-    pub fn len(&self) -> usize {
-        let mut cards = 54;
-        if self.exclude_big_joker {
-            cards -= 1;
-        }
-        if self.exclude_small_joker {
-            cards -= 1;
-        }
+    pub fn points(&self) -> usize {
+        FULL_DECK
+            .iter()
+            .map(|card| card.points().unwrap_or(0) * self.num_copies(*card))
+            .sum()
+    }
 
-        let mut n = Number::Two;
-        while n < self.min {
-            cards -= 4;
-            if let Some(nn) = n.successor() {
-                n = nn;
-            } else {
-                break;
-            }
-        }
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
 
-        cards
+    pub fn len(&self) -> usize {
+        FULL_DECK.iter().map(|card| self.num_copies(*card)).sum()
     }
 
     pub fn cards(&'_ self) -> impl Iterator<Item = Card> + '_ {
         DeckIterator {
             deck: self,
             index: 0,
+            emitted: 0,
         }
     }
 }
@@ -96,6 +140,7 @@ impl Deck {
 pub struct DeckIterator<'d> {
     deck: &'d Deck,
     index: usize,
+    emitted: usize,
 }
 
 impl<'d> Iterator for DeckIterator<'d> {
@@ -106,18 +151,19 @@ impl<'d> Iterator for DeckIterator<'d> {
                 break None;
             }
             let card = FULL_DECK[self.index];
-            self.index += 1;
-
-            if self.deck.includes_card(card) {
+            if self.emitted < self.deck.num_copies(card) {
+                self.emitted += 1;
                 break Some(card);
             }
+            self.index += 1;
+            self.emitted = 0;
         }
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::types::Number;
+    use crate::types::{cards, Card, Number, FULL_DECK};
 
     use super::Deck;
 
@@ -134,6 +180,33 @@ mod tests {
                 52,
                 100,
             ),
+            (
+                Deck {
+                    num_big_jokers: 0,
+                    num_small_jokers: 0,
+                    ..Default::default()
+                },
+                52,
+                100,
+            ),
+            (
+                Deck {
+                    num_big_jokers: 2,
+                    num_small_jokers: 3,
+                    ..Default::default()
+                },
+                57,
+                100,
+            ),
+            (
+                Deck {
+                    exclude_big_joker: true,
+                    num_big_jokers: 2,
+                    ..Default::default()
+                },
+                53,
+                100,
+            ),
             (
                 Deck {
                     min: Number::Five,
@@ -160,4 +233,51 @@ mod tests {
             assert_eq!(deck.cards().flat_map(|c| c.points()).sum::<usize>(), points);
         }
     }
+
+    #[test]
+    fn test_jokerless_deck() {
+        let deck = Deck {
+            num_big_jokers: 0,
+            num_small_jokers: 0,
+            ..Default::default()
+        };
+        assert!(!deck.includes_card(Card::BigJoker));
+        assert!(!deck.includes_card(Card::SmallJoker));
+        assert_eq!(
+            deck.len(),
+            FULL_DECK.iter().filter(|c| !c.is_joker()).count()
+        );
+        assert!(deck.cards().all(|c| !c.is_joker()));
+        assert!(!deck.is_empty());
+
+        let deck = Deck {
+            num_big_jokers: 2,
+            num_small_jokers: 0,
+            ..Default::default()
+        };
+        assert_eq!(deck.cards().filter(|c| *c == Card::BigJoker).count(), 2);
+        assert_eq!(deck.cards().filter(|c| *c == Card::SmallJoker).count(), 0);
+    }
+
+    #[test]
+    fn test_excluded_cards() {
+        let excluded = [cards::S_5, cards::S_10, cards::S_K, Card::BigJoker];
+        let deck = Deck::default().with_excluded(&excluded);
+        assert_eq!(deck.len(), 50);
+        assert_eq!(deck.points(), 75);
+        assert_eq!(deck.cards().count(), 50);
+        assert!(deck.cards().all(|c| !excluded.contains(&c)));
+        assert!(deck.can_bid(Number::Five));
+
+        // Excluding a card twice doesn't change anything.
+        assert_eq!(deck.clone().with_excluded(&[cards::S_5]), deck);
+
+        assert!(deck.includes_number(Number::Five));
+
+        let deck = deck.with_excluded(&[cards::H_5, cards::C_5, cards::D_5]);
+        assert!(!deck.can_bid(Number::Five));
+        assert!(!deck.includes_number(Number::Five));
+        assert!(deck.includes_number(Number::Six));
+        assert_eq!(deck.points(), 60);
+    }
 }
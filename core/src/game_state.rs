@@ -1,8 +1,8 @@
 use std::collections::{HashMap, HashSet};
-use std::ops::{Deref, DerefMut};
+use std::ops::{Deref, DerefMut, Range};
 
 use anyhow::{anyhow, bail, Error};
-use rand::{seq::SliceRandom, RngCore};
+use rand::{rngs::StdRng, seq::SliceRandom, RngCore, SeedableRng};
 use serde::{Deserialize, Serialize};
 
 use crate::bidding::Bid;
@@ -10,15 +10,24 @@ use crate::deck::Deck;
 use crate::hands::Hands;
 use crate::message::MessageVariant;
 use crate::player::Player;
-use crate::scoring::{compute_level_deltas, next_threshold_reachable, GameScoreResult};
+use crate::scoring::{
+    compute_level_deltas, next_threshold_reachable, points_needed_for_next_level,
+    progress_to_next_threshold, GameScoreResult,
+};
 use crate::settings::{
-    AdvancementPolicy, FirstLandlordSelectionPolicy, Friend, FriendSelection,
-    FriendSelectionPolicy, GameMode, GameModeSettings, GameStartPolicy, KittyBidPolicy,
-    KittyPenalty, KittyTheftPolicy, MultipleJoinPolicy, PlayTakebackPolicy, PropagatedState,
-    ThrowPenalty,
+    AdvancementPolicy, FirstLandlordSelectionPolicy, FirstLeadPolicy, Friend,
+    FriendPointTransferPolicy, FriendSelection, FriendSelectionPolicy, GameMode, GameModeSettings,
+    GameModifier, GameStartPolicy, JokerBidSuitPolicy, KittyBidPolicy, KittyOnLossPolicy,
+    KittyPenalty, KittyRevealPolicy, KittyTheftBidPolicy, KittyTheftPolicy, KittyTheftRevealPolicy,
+    MultipleJoinPolicy, OutOfTurnPolicy, PastGame, PlayTakebackPolicy, PropagatedState,
+    RemainderPolicy, SpectatorCardVisibility, ThrowPenalty,
+};
+use crate::trick::{
+    PlayCards, ThrowEvaluationPolicy, Trick, TrickDrawPolicy, TrickEnded, TrickFormat, TrickUnit,
+};
+use crate::types::{
+    Card, EffectiveSuit, Number, PermanentTrumps, PlayerID, Suit, Trump, ALL_SUITS,
 };
-use crate::trick::{PlayCards, Trick, TrickEnded, TrickUnit};
-use crate::types::{Card, Number, PlayerID, Trump, ALL_SUITS};
 
 macro_rules! bail_unwrap {
     ($opt:expr) => {
@@ -48,6 +57,56 @@ impl GameState {
         }
     }
 
+    pub fn can_take_back_bid(&self, id: PlayerID) -> bool {
+        match self {
+            GameState::Draw(p) => p.can_take_back_bid(id),
+            GameState::Exchange(p) => p.can_take_back_bid(id),
+            _ => false,
+        }
+    }
+
+    /// Records that a player acted at `now`, in seconds since the Unix epoch.
+    pub fn record_action_at(&mut self, now: u64) {
+        match self {
+            GameState::Initialize(_) => (),
+            GameState::Draw(p) => p.last_action_at = Some(now),
+            GameState::Exchange(p) => p.last_action_at = Some(now),
+            GameState::Play(p) => p.last_action_at = Some(now),
+        }
+    }
+
+    /// The moves made so far this game, oldest first.
+    pub fn history(&self) -> &[HistoryEntry] {
+        match self {
+            GameState::Initialize(_) => &[],
+            GameState::Draw(p) => &p.history,
+            GameState::Exchange(p) => &p.history,
+            GameState::Play(p) => &p.history,
+        }
+    }
+
+    /// The number of seconds between the last recorded action and `now`, if
+    /// an action has been recorded in the current phase.
+    pub fn time_since_last_action(&self, now: u64) -> Option<u64> {
+        let last_action_at = match self {
+            GameState::Initialize(_) => None,
+            GameState::Draw(p) => p.last_action_at,
+            GameState::Exchange(p) => p.last_action_at,
+            GameState::Play(p) => p.last_action_at,
+        }?;
+        Some(now.saturating_sub(last_action_at))
+    }
+
+    /// The player whose turn it is, if they have taken longer than the move
+    /// deadline to act.
+    pub fn timed_out_player(&self, now: u64) -> Option<PlayerID> {
+        let deadline = self.move_deadline_secs?;
+        if self.paused || self.time_since_last_action(now)? <= deadline {
+            return None;
+        }
+        self.next_player().ok()
+    }
+
     pub fn propagated(&self) -> &'_ PropagatedState {
         match self {
             GameState::Initialize(p) => &p.propagated,
@@ -61,6 +120,22 @@ impl GameState {
         self.propagated().players.iter().any(|p| p.id == id)
     }
 
+    pub fn is_observer(&self, id: PlayerID) -> bool {
+        self.propagated().observers.iter().any(|p| p.id == id)
+    }
+
+    pub fn remove_observer(&mut self, id: PlayerID) -> Result<(), Error> {
+        if !self.is_observer(id) {
+            bail!("not an observer")
+        }
+        match self {
+            GameState::Initialize(ref mut p) => p.propagated.remove_observer(id),
+            GameState::Draw(ref mut p) => p.remove_observer(id),
+            GameState::Exchange(ref mut p) => p.remove_observer(id),
+            GameState::Play(ref mut p) => p.remove_observer(id),
+        }
+    }
+
     pub fn player_name(&self, id: PlayerID) -> Result<&'_ str, Error> {
         for p in &self.propagated().players {
             if p.id == id {
@@ -89,8 +164,52 @@ impl GameState {
         bail!("Couldn't find player id")
     }
 
+    /// Rejoins as the player or observer who was given `token` when they first
+    /// registered, even if somebody else has since joined with their name.
+    pub fn register_with_token(
+        &mut self,
+        token: &str,
+    ) -> Result<(PlayerID, Vec<MessageVariant>), Error> {
+        let propagated = self.propagated();
+        let pid = propagated
+            .players
+            .iter()
+            .chain(propagated.observers.iter())
+            .find(|p| !p.reconnect_token.is_empty() && p.reconnect_token == token)
+            .map(|p| p.id);
+        match pid {
+            Some(pid) => Ok((
+                pid,
+                vec![MessageVariant::JoinedGameAgain {
+                    player: pid,
+                    game_shadowing_policy: self.game_shadowing_policy,
+                }],
+            )),
+            None => bail!("invalid reconnect token"),
+        }
+    }
+
+    pub fn reconnect_token(&self, id: PlayerID) -> Result<&'_ str, Error> {
+        let propagated = self.propagated();
+        match propagated
+            .players
+            .iter()
+            .chain(propagated.observers.iter())
+            .find(|p| p.id == id)
+        {
+            Some(p) => Ok(&p.reconnect_token),
+            None => bail!("Couldn't find player"),
+        }
+    }
+
     pub fn register(&mut self, name: String) -> Result<(PlayerID, Vec<MessageVariant>), Error> {
         if let Ok(pid) = self.player_id(&name) {
+            if self.propagated().is_vacated(pid) {
+                bail!("that player was kicked from the game")
+            }
+            if self.propagated().is_bot(pid) {
+                bail!("that seat is played by a bot")
+            }
             return Ok((
                 pid,
                 vec![MessageVariant::JoinedGameAgain {
@@ -111,14 +230,38 @@ impl GameState {
     // https://github.com/rust-lang/rust-clippy/issues/7224
     #[allow(clippy::redundant_closure)]
     pub fn kick(&mut self, id: PlayerID) -> Result<Vec<MessageVariant>, Error> {
+        // Players can't be removed partway through a game, so their seat is
+        // vacated instead.
+        let is_player = self.is_player(id);
         match self {
             GameState::Initialize(ref mut p) => p.remove_player(id),
+            GameState::Draw(ref mut p) if is_player => p.propagated.vacate_seat(id),
+            GameState::Exchange(ref mut p) if is_player => p.propagated.vacate_seat(id),
+            GameState::Play(ref mut p) if is_player => p.propagated.vacate_seat(id),
             GameState::Draw(ref mut p) => p.remove_observer(id).map(|()| vec![]),
             GameState::Exchange(ref mut p) => p.remove_observer(id).map(|()| vec![]),
             GameState::Play(ref mut p) => p.remove_observer(id).map(|()| vec![]),
         }
     }
 
+    /// Fills a seat which was vacated partway through a game with an
+    /// observer, if the room's `VacatedSeatPolicy` allows it. Before the game
+    /// starts, observers can simply be made into players.
+    pub fn promote_observer(
+        &mut self,
+        observer_id: PlayerID,
+        seat_index: usize,
+    ) -> Result<Vec<MessageVariant>, Error> {
+        match self {
+            GameState::Initialize(_) => bail!("game has not started yet"),
+            GameState::Draw(ref mut p) => p.propagated.promote_observer(observer_id, seat_index),
+            GameState::Exchange(ref mut p) => {
+                p.propagated.promote_observer(observer_id, seat_index)
+            }
+            GameState::Play(ref mut p) => p.propagated.promote_observer(observer_id, seat_index),
+        }
+    }
+
     pub fn set_chat_link(&mut self, chat_link: Option<String>) -> Result<(), Error> {
         match self {
             GameState::Initialize(ref mut p) => p.propagated.set_chat_link(chat_link),
@@ -129,28 +272,65 @@ impl GameState {
     }
 
     pub fn reset(&mut self) -> Result<Vec<MessageVariant>, Error> {
+        let (mut s, m) = match self {
+            GameState::Initialize(_) => bail!("Game has not started yet!"),
+            GameState::Draw(ref mut p) => p.return_to_initialize()?,
+            GameState::Exchange(ref mut p) => p.return_to_initialize()?,
+            GameState::Play(ref mut p) => p.return_to_initialize()?,
+        };
+        // A reset game shouldn't stay paused.
+        s.propagated.paused = false;
+        *self = GameState::Initialize(s);
+        Ok(m)
+    }
+
+    pub fn set_paused(&mut self, id: PlayerID, paused: bool) -> Result<Vec<MessageVariant>, Error> {
+        if !self.is_player(id) {
+            bail!("Only players can pause or resume the game")
+        }
         match self {
             GameState::Initialize(_) => bail!("Game has not started yet!"),
-            GameState::Draw(ref mut p) => {
-                let (s, m) = p.return_to_initialize()?;
-                *self = GameState::Initialize(s);
-                Ok(m)
-            }
-            GameState::Exchange(ref mut p) => {
-                let (s, m) = p.return_to_initialize()?;
-                *self = GameState::Initialize(s);
-                Ok(m)
-            }
-            GameState::Play(ref mut p) => {
-                let (s, m) = p.return_to_initialize()?;
-                *self = GameState::Initialize(s);
-                Ok(m)
-            }
+            GameState::Draw(ref mut p) => p.propagated.set_paused(paused),
+            GameState::Exchange(ref mut p) => p.propagated.set_paused(paused),
+            GameState::Play(ref mut p) => p.propagated.set_paused(paused),
+        }
+    }
+
+    /// Removes every player's reconnect token, so that the state can be
+    /// shown to people who aren't in the game.
+    pub fn redact_reconnect_tokens(&mut self) {
+        match self {
+            GameState::Initialize(InitializePhase {
+                ref mut propagated, ..
+            })
+            | GameState::Draw(DrawPhase {
+                ref mut propagated, ..
+            })
+            | GameState::Exchange(ExchangePhase {
+                ref mut propagated, ..
+            })
+            | GameState::Play(PlayPhase {
+                ref mut propagated, ..
+            }) => propagated.redact_reconnect_tokens(),
         }
     }
 
     pub fn for_player(&self, id: PlayerID) -> GameState {
         let mut s = self.clone();
+        match s {
+            GameState::Initialize(InitializePhase {
+                ref mut propagated, ..
+            })
+            | GameState::Draw(DrawPhase {
+                ref mut propagated, ..
+            })
+            | GameState::Exchange(ExchangePhase {
+                ref mut propagated, ..
+            })
+            | GameState::Play(PlayPhase {
+                ref mut propagated, ..
+            }) => propagated.redact_reconnect_tokens_except(id),
+        }
         match s {
             GameState::Initialize { .. } => (),
             GameState::Draw(DrawPhase {
@@ -172,13 +352,22 @@ impl GameState {
                 ref mut hands,
                 ref mut kitty,
                 ref mut game_mode,
+                ref mut kitty_point_count,
+                ref mut original_kitty,
+                ref propagated,
                 exchanger,
                 landlord,
                 finalized,
                 ..
             }) => {
                 hands.redact_except(id);
+                for card in original_kitty {
+                    *card = Card::Unknown;
+                }
                 if id != exchanger.unwrap_or(landlord) || finalized {
+                    if propagated.reveal_kitty_point_count {
+                        *kitty_point_count = Some(count_point_cards(kitty));
+                    }
                     for card in kitty {
                         *card = Card::Unknown;
                     }
@@ -196,6 +385,9 @@ impl GameState {
                 ref mut hands,
                 ref mut kitty,
                 ref mut points,
+                ref mut trick_points,
+                ref mut history,
+                ref mut kitty_point_count,
                 ref trick,
                 ref landlords_team,
                 ref propagated,
@@ -210,18 +402,58 @@ impl GameState {
                             v.clear();
                         }
                     }
+                    for (winner, pts) in trick_points.iter_mut() {
+                        if landlords_team.contains(winner) {
+                            *pts = 0;
+                        }
+                    }
                 }
                 // Don't redact at the end of the game.
                 let game_ongoing =
                     !game_ended_early && (!hands.is_empty() || !trick.played_cards().is_empty());
-                if game_ongoing {
+                let is_spectator = !propagated.players.iter().any(|p| p.id == id);
+                let redact_hands = match propagated.spectator_card_visibility {
+                    _ if !is_spectator => game_ongoing,
+                    SpectatorCardVisibility::Hidden => true,
+                    SpectatorCardVisibility::AfterGameEnd => game_ongoing,
+                    SpectatorCardVisibility::AlwaysVisible => false,
+                };
+                if redact_hands {
                     hands.redact_except(id);
                 }
                 if game_ongoing && id != exchanger.unwrap_or(landlord) {
+                    if propagated.reveal_kitty_point_count {
+                        *kitty_point_count = Some(count_point_cards(kitty));
+                    }
                     for card in kitty {
                         *card = Card::Unknown;
                     }
                 }
+                if game_ongoing && propagated.hide_played_cards {
+                    for entry in history {
+                        match entry.message {
+                            MessageVariant::PlayedCards { ref mut cards } => {
+                                for card in cards {
+                                    *card = Card::Unknown;
+                                }
+                            }
+                            MessageVariant::ThrowFailed {
+                                ref mut original_cards,
+                                ..
+                            } => {
+                                for card in original_cards {
+                                    *card = Card::Unknown;
+                                }
+                            }
+                            MessageVariant::JoinedTeam {
+                                ref mut via_card, ..
+                            } => {
+                                *via_card = Card::Unknown;
+                            }
+                            _ => (),
+                        }
+                    }
+                }
             }
         }
         s
@@ -245,6 +477,58 @@ pub struct PlayerGameFinishedResult {
     pub confetti: bool,
 }
 
+/// The outcome of a finished game, with the same information as the messages
+/// from `PlayPhase::finish_game`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GameSummary {
+    pub landlord: PlayerID,
+    pub landlord_won: bool,
+    pub non_landlord_points: isize,
+    pub bonus_level: bool,
+    pub ranks_before: HashMap<PlayerID, Number>,
+    pub ranks_after: HashMap<PlayerID, Number>,
+    pub results: HashMap<String, PlayerGameFinishedResult>,
+}
+
+/// A move made during a game, in the order that it was made.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HistoryEntry {
+    pub seq: usize,
+    pub actor: PlayerID,
+    pub message: MessageVariant,
+}
+
+/// Appends the moves among `msgs` to `history`. Friends joining the team are
+/// attributed to the friend rather than to `actor`.
+fn record_history(history: &mut Vec<HistoryEntry>, actor: PlayerID, msgs: &[MessageVariant]) {
+    for msg in msgs {
+        let actor = match msg {
+            MessageVariant::JoinedTeam { player, .. } => *player,
+            MessageVariant::PlayedCards { .. }
+            | MessageVariant::ThrowFailed { .. }
+            | MessageVariant::TookBackPlay
+            | MessageVariant::TrickWon { .. }
+            | MessageVariant::MadeBid { .. }
+            | MessageVariant::MadeBlindBid { .. }
+            | MessageVariant::TookBackBid
+            | MessageVariant::DirectorCorrection {
+                correction: Correction::ReassignedPoints { .. },
+                ..
+            } => actor,
+            _ => continue,
+        };
+        history.push(HistoryEntry {
+            seq: history.len(),
+            actor,
+            message: msg.clone(),
+        });
+    }
+}
+
+fn count_point_cards(cards: &[Card]) -> usize {
+    cards.iter().filter(|c| c.points().is_some()).count()
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PlayPhase {
     num_decks: usize,
@@ -265,6 +549,120 @@ pub struct PlayPhase {
     removed_cards: Vec<Card>,
     #[serde(default)]
     decks: Vec<Deck>,
+    #[serde(default)]
+    blind_bidder: Option<PlayerID>,
+    /// Bonus levels earned by each player from `capture_bonus_rules`.
+    #[serde(default)]
+    capture_bonuses: HashMap<PlayerID, isize>,
+    #[serde(default)]
+    winning_bid: Option<Bid>,
+    /// The winner of each finished trick, and the points that they took.
+    #[serde(default)]
+    trick_points: Vec<(PlayerID, usize)>,
+    #[serde(default)]
+    pending_take_back: Option<PendingTakeBack>,
+    /// When a player last acted, in seconds since the Unix epoch.
+    #[serde(default)]
+    last_action_at: Option<u64>,
+    #[serde(default)]
+    history: Vec<HistoryEntry>,
+    /// The number of point cards in the bottom, filled in for players who
+    /// can't see the bottom if `reveal_kitty_point_count` is set.
+    #[serde(default)]
+    kitty_point_count: Option<usize>,
+    /// How many times each player has tried to play out of turn.
+    #[serde(default)]
+    out_of_turn_attempts: HashMap<PlayerID, usize>,
+    /// The number of tricks which had finished before each friend joined the
+    /// landlord's team.
+    #[serde(default)]
+    joined_team_at: HashMap<PlayerID, usize>,
+    /// The multiplier applied to the kitty's points, once they have been
+    /// given to the winner of the last trick.
+    #[serde(default)]
+    kitty_multiplier: Option<usize>,
+}
+
+/// A request to take back a play, which is waiting on the opponents of the
+/// requester to approve it.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PendingTakeBack {
+    pub requester: PlayerID,
+    pub approvals: Vec<PlayerID>,
+}
+
+/// A correction made by the director on behalf of another player.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Correction {
+    TookBackCards,
+    ReassignedPoints { to: PlayerID, cards: Vec<Card> },
+}
+
+/// The points held by each team after a trick. The defenders' points are
+/// omitted if the landlord team's points are hidden.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PointFlowEntry {
+    pub trick_index: usize,
+    pub attackers_cumulative: usize,
+    pub defenders_cumulative: Option<usize>,
+}
+
+/// Where the attacking team's points came from.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ScoreBreakdown {
+    /// The points in the tricks won by each attacker, not counting the kitty.
+    pub trick_points: HashMap<PlayerID, usize>,
+    /// The points which friends captured before joining the landlord's team,
+    /// if they still count for the attackers.
+    pub transferred_points: usize,
+    /// The points in the kitty, if the attackers won it in the last trick, or
+    /// were given it because the landlord's team lost.
+    pub kitty_points: usize,
+    pub kitty_multiplier: usize,
+    /// The points added for the landlord team's penalties, less the points
+    /// taken away for the attackers' penalties.
+    pub penalty_points: isize,
+    /// Whether the total is doubled by `GameModifier::DoublePoints`.
+    pub double_points: bool,
+}
+
+impl ScoreBreakdown {
+    /// The attacking team's final points, as used to compute the score.
+    pub fn total(&self) -> isize {
+        let total = self.trick_points.values().sum::<usize>() as isize
+            + self.transferred_points as isize
+            + (self.kitty_points * self.kitty_multiplier) as isize
+            + self.penalty_points;
+        if self.double_points {
+            total * 2
+        } else {
+            total
+        }
+    }
+}
+
+/// A position from partway through the play phase, with every hand face-up,
+/// which can be shared and played out from as an exercise.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Puzzle {
+    pub num_decks: usize,
+    pub decks: Vec<Deck>,
+    pub game_mode: GameMode,
+    pub players: Vec<Player>,
+    pub hands: Hands,
+    pub points: HashMap<PlayerID, Vec<Card>>,
+    pub kitty: Vec<Card>,
+    pub landlord: PlayerID,
+    pub landlords_team: Vec<PlayerID>,
+    pub trump: Trump,
+    pub trick: Trick,
+    pub objective: PuzzleObjective,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum PuzzleObjective {
+    /// Win as many of the remaining points as possible for `player`'s team.
+    MaximizePoints { player: PlayerID },
 }
 
 impl PlayPhase {
@@ -280,6 +678,12 @@ impl PlayPhase {
         Ok(bail_unwrap!(self.trick.next_player()))
     }
 
+    /// The number of seconds between the last recorded action and `now`.
+    pub fn time_since_last_action(&self, now: u64) -> Option<u64> {
+        self.last_action_at
+            .map(|last_action_at| now.saturating_sub(last_action_at))
+    }
+
     pub fn trick(&self) -> &Trick {
         &self.trick
     }
@@ -301,6 +705,18 @@ impl PlayPhase {
             .can_play_cards(id, &self.hands, cards, self.propagated.trick_draw_policy)?)
     }
 
+    /// Returns the only legal play for `id` if they are following the current
+    /// trick and have exactly one way to do so, so that it can be submitted
+    /// automatically.
+    pub fn sole_legal_play(&self, id: PlayerID) -> Option<Vec<Card>> {
+        if self.game_ended_early || self.trick.next_player() != Some(id) {
+            return None;
+        }
+        let tf = self.trick.trick_format()?;
+        let hand = self.hands.get(id).ok()?;
+        Trick::sole_legal_follow(hand, tf, self.propagated.trick_draw_policy)
+    }
+
     pub fn play_cards(
         &mut self,
         id: PlayerID,
@@ -318,17 +734,19 @@ impl PlayPhase {
         if self.game_ended_early {
             bail!("Game has already ended; cards can't be played");
         }
-
         let mut msgs = self.trick.play_cards(PlayCards {
             id,
             hands: &mut self.hands,
             cards,
             trick_draw_policy: self.propagated.trick_draw_policy,
             throw_eval_policy: self.propagated.throw_evaluation_policy,
+            throw_tie_break_policy: self.propagated.throw_tie_break_policy,
             format_hint,
             hide_throw_halting_player: self.propagated.hide_throw_halting_player,
             tractor_requirements: self.propagated.tractor_requirements,
+            throws_allowed: !self.propagated.has_game_modifier(GameModifier::NoThrows),
         })?;
+        record_history(&mut self.history, id, &msgs);
         if self.propagated.hide_played_cards {
             for msg in &mut msgs {
                 match msg {
@@ -349,6 +767,7 @@ impl PlayPhase {
                 }
             }
         }
+        self.pending_take_back = None;
         Ok(msgs)
     }
 
@@ -356,12 +775,219 @@ impl PlayPhase {
         if self.game_ended_early {
             bail!("Game has already ended; cards can't be taken back");
         }
-        if self.propagated.play_takeback_policy == PlayTakebackPolicy::NoPlayTakeback {
-            bail!("Taking back played cards is not allowed")
+        match self.propagated.play_takeback_policy {
+            PlayTakebackPolicy::AllowPlayTakeback => (),
+            PlayTakebackPolicy::NoPlayTakeback => bail!("Taking back played cards is not allowed"),
+            PlayTakebackPolicy::RequiresApproval => {
+                bail!("Taking back played cards requires approval from the other team")
+            }
         }
-        Ok(self
-            .trick
-            .take_back(id, &mut self.hands, self.propagated.throw_evaluation_policy)?)
+        self.trick.take_back(
+            id,
+            &mut self.hands,
+            self.propagated.throw_evaluation_policy,
+            self.propagated.throw_tie_break_policy,
+        )?;
+        record_history(&mut self.history, id, &[MessageVariant::TookBackPlay]);
+        Ok(())
+    }
+
+    fn check_director(&self, id: PlayerID) -> Result<(), Error> {
+        if self.propagated.director != Some(id) {
+            bail!("only the director can correct mistakes")
+        }
+        Ok(())
+    }
+
+    /// Takes back `player`'s play on the director's behalf, regardless of the
+    /// takeback policy.
+    pub fn director_take_back(
+        &mut self,
+        director: PlayerID,
+        player: PlayerID,
+    ) -> Result<Vec<MessageVariant>, Error> {
+        self.check_director(director)?;
+        if self.game_ended_early {
+            bail!("Game has already ended; cards can't be taken back");
+        }
+        self.trick.take_back(
+            player,
+            &mut self.hands,
+            self.propagated.throw_evaluation_policy,
+            self.propagated.throw_tie_break_policy,
+        )?;
+        self.pending_take_back = None;
+        record_history(&mut self.history, player, &[MessageVariant::TookBackPlay]);
+        Ok(vec![MessageVariant::DirectorCorrection {
+            player,
+            correction: Correction::TookBackCards,
+        }])
+    }
+
+    /// Moves point cards which were credited to the wrong player. The points
+    /// recorded for past tricks are left as they were.
+    pub fn director_reassign_points(
+        &mut self,
+        director: PlayerID,
+        from: PlayerID,
+        to: PlayerID,
+        cards: Vec<Card>,
+    ) -> Result<Vec<MessageVariant>, Error> {
+        self.check_director(director)?;
+        if !self.propagated.players.iter().any(|p| p.id == to) {
+            bail!("player ID not found")
+        }
+        let mut remaining = self.points.get(&from).cloned().unwrap_or_default();
+        for card in &cards {
+            match remaining.iter().position(|c| c == card) {
+                Some(idx) => {
+                    remaining.remove(idx);
+                }
+                None => bail!("those points weren't won by that player"),
+            }
+        }
+        self.points.insert(from, remaining);
+        let trump = self.trump;
+        let to_points = self.points.entry(to).or_default();
+        to_points.extend(cards.iter().copied());
+        to_points.sort_by(|a, b| trump.compare(*a, *b));
+        let msgs = vec![MessageVariant::DirectorCorrection {
+            player: from,
+            correction: Correction::ReassignedPoints { to, cards },
+        }];
+        record_history(&mut self.history, director, &msgs);
+        Ok(msgs)
+    }
+
+    /// Records that `id` tried to play out of turn, and warns or penalizes
+    /// them according to the `out_of_turn_policy`. Attempts made after the
+    /// trick is complete, when the policy is `Ignore`, or by anyone who isn't
+    /// seated in the game, aren't counted.
+    pub fn record_out_of_turn(&mut self, id: PlayerID) -> Vec<MessageVariant> {
+        let (warnings, points) = match self.propagated.out_of_turn_policy {
+            OutOfTurnPolicy::Ignore => return vec![],
+            OutOfTurnPolicy::WarnThenPenalize { warnings, points } => (warnings, points),
+        };
+        // Penalties count against the player's own team, so observers can't
+        // be penalized.
+        if !self.propagated.players.iter().any(|p| p.id == id) {
+            return vec![];
+        }
+        match self.trick.next_player() {
+            Some(next) if next != id => (),
+            _ => return vec![],
+        }
+        let attempts = self.out_of_turn_attempts.entry(id).or_insert(0);
+        *attempts += 1;
+        if *attempts <= warnings {
+            vec![MessageVariant::OutOfTurnWarning {
+                player: id,
+                attempts: *attempts,
+            }]
+        } else {
+            *self.penalties.entry(id).or_insert(0) += points;
+            vec![MessageVariant::OutOfTurnPenalty { player: id, points }]
+        }
+    }
+
+    pub fn pending_take_back(&self) -> Option<&PendingTakeBack> {
+        self.pending_take_back.as_ref()
+    }
+
+    /// The players who must approve a takeback requested by `requester`: their
+    /// opponents who still have cards in hand.
+    fn take_back_approvers(&self, requester: PlayerID) -> Vec<PlayerID> {
+        let requester_is_landlord = self.landlords_team.contains(&requester);
+        self.propagated
+            .players
+            .iter()
+            .map(|p| p.id)
+            .filter(|id| self.landlords_team.contains(id) != requester_is_landlord)
+            .filter(|id| self.hands.get(*id).map(|h| !h.is_empty()).unwrap_or(false))
+            .collect()
+    }
+
+    /// Takes back the requester's play immediately if the policy allows it,
+    /// and otherwise asks their opponents to approve doing so.
+    pub fn request_take_back(&mut self, id: PlayerID) -> Result<Vec<MessageVariant>, Error> {
+        if self.propagated.play_takeback_policy != PlayTakebackPolicy::RequiresApproval {
+            self.take_back_cards(id)?;
+            return Ok(vec![MessageVariant::TookBackPlay]);
+        }
+        if self.game_ended_early {
+            bail!("Game has already ended; cards can't be taken back");
+        }
+        if self.pending_take_back.is_some() {
+            bail!("There is already a pending request to take back a play");
+        }
+        if self.trick.played_cards().last().map(|p| p.id) != Some(id) {
+            bail!("You can only take back the most recent play");
+        }
+        self.pending_take_back = Some(PendingTakeBack {
+            requester: id,
+            approvals: vec![],
+        });
+        let mut msgs = vec![MessageVariant::TakeBackRequested];
+        msgs.extend(self.apply_approved_take_back()?);
+        Ok(msgs)
+    }
+
+    pub fn approve_take_back(&mut self, id: PlayerID) -> Result<Vec<MessageVariant>, Error> {
+        let pending = match self.pending_take_back {
+            Some(ref pending) => pending,
+            None => bail!("There is no pending request to take back a play"),
+        };
+        if !self.take_back_approvers(pending.requester).contains(&id) {
+            bail!("Only the requester's opponents can approve taking back a play");
+        }
+        if pending.approvals.contains(&id) {
+            bail!("You have already approved taking back this play");
+        }
+        if let Some(ref mut pending) = self.pending_take_back {
+            pending.approvals.push(id);
+        }
+        let mut msgs = vec![MessageVariant::TakeBackApproved];
+        msgs.extend(self.apply_approved_take_back()?);
+        Ok(msgs)
+    }
+
+    pub fn cancel_take_back(&mut self, id: PlayerID) -> Result<Vec<MessageVariant>, Error> {
+        let pending = match self.pending_take_back {
+            Some(ref pending) => pending,
+            None => bail!("There is no pending request to take back a play"),
+        };
+        if pending.requester != id && !self.take_back_approvers(pending.requester).contains(&id) {
+            bail!("Only the requester and their opponents can cancel taking back a play");
+        }
+        self.pending_take_back = None;
+        Ok(vec![MessageVariant::TakeBackCancelled])
+    }
+
+    fn apply_approved_take_back(&mut self) -> Result<Vec<MessageVariant>, Error> {
+        let pending = match self.pending_take_back {
+            Some(ref pending) => pending,
+            None => return Ok(vec![]),
+        };
+        let approvers = self.take_back_approvers(pending.requester);
+        if !approvers.iter().all(|id| pending.approvals.contains(id)) {
+            return Ok(vec![]);
+        }
+        let requester = pending.requester;
+        self.pending_take_back = None;
+        self.trick.take_back(
+            requester,
+            &mut self.hands,
+            self.propagated.throw_evaluation_policy,
+            self.propagated.throw_tie_break_policy,
+        )?;
+        record_history(
+            &mut self.history,
+            requester,
+            &[MessageVariant::TookBackPlay],
+        );
+        Ok(vec![MessageVariant::TakeBackCompleted {
+            player: requester,
+        }])
     }
 
     pub fn finish_trick(&mut self) -> Result<Vec<MessageVariant>, Error> {
@@ -372,13 +998,26 @@ impl PlayPhase {
             winner,
             points: mut new_points,
             largest_trick_unit_size,
+            has_tractor,
             failed_throw_size,
         } = self.trick.complete()?;
+        self.pending_take_back = None;
 
-        let kitty_multipler = match self.propagated.kitty_penalty {
+        let mut kitty_multipler = match self.propagated.kitty_penalty {
             KittyPenalty::Times => 2 * largest_trick_unit_size,
             KittyPenalty::Power => 2usize.pow(largest_trick_unit_size as u32),
+            KittyPenalty::None => 1,
+            KittyPenalty::Fixed(multiplier) => multiplier,
+            KittyPenalty::ByUnitType { tractor, .. } if has_tractor => tractor,
+            KittyPenalty::ByUnitType { pair, .. } if largest_trick_unit_size > 1 => pair,
+            KittyPenalty::ByUnitType { single, .. } => single,
         };
+        if self
+            .propagated
+            .has_game_modifier(GameModifier::NoKittyMultiplier)
+        {
+            kitty_multipler = 1;
+        }
 
         if failed_throw_size > 0 {
             match self.propagated.throw_penalty {
@@ -396,6 +1035,11 @@ impl PlayPhase {
             ref mut friends, ..
         } = self.game_mode
         {
+            // Friend slots are claimed in the order that the cards were played,
+            // but players who join in the same trick are added to the team in
+            // seat order, so that the team order doesn't depend on who led.
+            let mut new_members = vec![];
+            let mut double_joins = vec![];
             for played in self.trick.played_cards() {
                 for card in played.cards.iter() {
                     for friend in friends.iter_mut() {
@@ -403,27 +1047,21 @@ impl PlayPhase {
                             if friend.skip == 0 {
                                 if friend.player_id.is_none() {
                                     let already_on_the_team =
-                                        self.landlords_team.contains(&played.id);
+                                        self.landlords_team.contains(&played.id)
+                                            || new_members.iter().any(|(id, _)| *id == played.id);
 
                                     match self.propagated.multiple_join_policy {
                                         MultipleJoinPolicy::Unrestricted if already_on_the_team => {
                                             // double-join!
                                             friend.player_id = Some(played.id);
-                                            msgs.push(MessageVariant::JoinedTeam {
-                                                player: played.id,
-                                                already_joined: true,
-                                            });
+                                            double_joins.push((played.id, *card));
                                         }
                                         MultipleJoinPolicy::NoDoubleJoin if already_on_the_team => {
                                         }
                                         MultipleJoinPolicy::Unrestricted
                                         | MultipleJoinPolicy::NoDoubleJoin => {
                                             friend.player_id = Some(played.id);
-                                            self.landlords_team.push(played.id);
-                                            msgs.push(MessageVariant::JoinedTeam {
-                                                player: played.id,
-                                                already_joined: false,
-                                            });
+                                            new_members.push((played.id, *card));
                                         }
                                     }
                                 }
@@ -434,6 +1072,50 @@ impl PlayPhase {
                     }
                 }
             }
+            let remaining_friends = friends.iter().filter(|f| f.player_id.is_none()).count();
+            let players = &self.propagated.players;
+            new_members.sort_by_key(|(id, _)| players.iter().position(|p| p.id == *id));
+            for (id, card) in new_members {
+                self.landlords_team.push(id);
+                self.joined_team_at.insert(id, self.trick_points.len());
+                msgs.push(MessageVariant::JoinedTeam {
+                    player: id,
+                    already_joined: false,
+                    via_card: card,
+                    remaining_friends,
+                });
+            }
+            for (id, card) in double_joins {
+                msgs.push(MessageVariant::JoinedTeam {
+                    player: id,
+                    already_joined: true,
+                    via_card: card,
+                    remaining_friends,
+                });
+            }
+        }
+        if !self.propagated.capture_bonus_rules.is_empty() {
+            let mut captured = HashMap::new();
+            for card in self.trick.played_cards().iter().flat_map(|pc| &pc.cards) {
+                *captured.entry(*card).or_insert(0) += 1;
+            }
+            for (cards, bonus) in &self.propagated.capture_bonus_rules {
+                let mut required = HashMap::new();
+                for card in cards {
+                    *required.entry(*card).or_insert(0) += 1;
+                }
+                if required
+                    .iter()
+                    .all(|(card, ct)| captured.get(card).copied().unwrap_or(0) >= *ct)
+                {
+                    *self.capture_bonuses.entry(winner).or_insert(0) += *bonus;
+                    msgs.push(MessageVariant::CaptureBonusEarned {
+                        player: winner,
+                        cards: cards.clone(),
+                        bonus: *bonus,
+                    });
+                }
+            }
         }
         let points = bail_unwrap!(self.points.get_mut(&winner));
         let kitty_points = self
@@ -444,7 +1126,16 @@ impl PlayPhase {
             .collect::<Vec<_>>();
 
         if self.hands.is_empty() {
-            if self.propagated.should_reveal_kitty_at_end_of_game {
+            let reveal_kitty = match self.propagated.kitty_reveal_policy() {
+                KittyRevealPolicy::Never => false,
+                KittyRevealPolicy::Always => true,
+                KittyRevealPolicy::OnlyIfContestedPoints => {
+                    !kitty_points.is_empty()
+                        && kitty_multipler > 0
+                        && !self.landlords_team.contains(&winner)
+                }
+            };
+            if reveal_kitty {
                 msgs.push(MessageVariant::EndOfGameKittyReveal {
                     cards: self.kitty.clone(),
                 });
@@ -452,6 +1143,7 @@ impl PlayPhase {
             for _ in 0..kitty_multipler {
                 new_points.extend(kitty_points.iter().copied());
             }
+            self.kitty_multiplier = Some(kitty_multipler);
             if !kitty_points.is_empty() && kitty_multipler > 0 {
                 msgs.push(MessageVariant::PointsInKitty {
                     points: kitty_points.iter().flat_map(|c| c.points()).sum::<usize>(),
@@ -460,9 +1152,10 @@ impl PlayPhase {
             }
         }
         let winner_idx = bail_unwrap!(self.propagated.players.iter().position(|p| p.id == winner));
+        let num_points = new_points.iter().flat_map(|c| c.points()).sum::<usize>();
+        self.trick_points.push((winner, num_points));
         if !new_points.is_empty() {
             let trump = self.trump;
-            let num_points = new_points.iter().flat_map(|c| c.points()).sum::<usize>();
             points.extend(new_points);
             points.sort_by(|a, b| trump.compare(*a, *b));
             msgs.push(MessageVariant::TrickWon {
@@ -483,38 +1176,259 @@ impl PlayPhase {
             }),
         );
         self.last_trick = Some(std::mem::replace(&mut self.trick, new_trick));
+        record_history(&mut self.history, winner, &msgs);
+        if self.propagated.hide_played_cards {
+            for msg in &mut msgs {
+                if let MessageVariant::JoinedTeam {
+                    ref mut via_card, ..
+                } = msg
+                {
+                    *via_card = Card::Unknown;
+                }
+            }
+        }
 
         Ok(msgs)
     }
 
-    pub fn compute_player_level_deltas<'a, 'b: 'a>(
-        players: impl Iterator<Item = &'b mut Player>,
-        non_landlord_level_bump: usize,
-        landlord_level_bump: usize,
-        landlords_team: &'a [PlayerID],
-        landlord_won: bool,
-        landlord: (PlayerID, Number),
-        advancement_policy: AdvancementPolicy,
-    ) -> Vec<MessageVariant> {
-        let mut msgs = vec![];
-
-        let result = players
-            .map(|player| {
-                let is_defending = landlords_team.contains(&player.id);
-                let bump = if is_defending {
-                    landlord_level_bump
+    /// The cumulative points held by each team after every finished trick.
+    /// Points are attributed using the current teams, so friends who join
+    /// later bring their earlier points along with them.
+    pub fn point_flow(&self) -> Vec<PointFlowEntry> {
+        let mut attackers = 0;
+        let mut defenders = 0;
+        self.trick_points
+            .iter()
+            .enumerate()
+            .map(|(trick_index, (winner, points))| {
+                if self.landlords_team.contains(winner) {
+                    defenders += points;
                 } else {
-                    non_landlord_level_bump
-                };
-                let mut num_advances = 0;
-                let mut was_blocked = false;
-                let initial_rank = player.rank();
-                let landlord_successfully_defended_a: bool =
-                    landlord.1 == Number::Ace && landlord_won;
+                    attackers += points;
+                }
+                PointFlowEntry {
+                    trick_index,
+                    attackers_cumulative: attackers,
+                    defenders_cumulative: if self.propagated.hide_landlord_points {
+                        None
+                    } else {
+                        Some(defenders)
+                    },
+                }
+            })
+            .collect()
+    }
+
+    /// The number of trumps played so far, and the players who are known to
+    /// be void in trump because they didn't follow a trump lead. Both are
+    /// derived from the public history of plays.
+    fn trump_plays(&self) -> (usize, HashSet<PlayerID>) {
+        let mut num_played = 0;
+        let mut voids = HashSet::new();
+        let mut count_trick = |plays: &[(PlayerID, &[Card])]| {
+            for (_, cards) in plays {
+                num_played += cards
+                    .iter()
+                    .filter(|c| self.trump.effective_suit(**c) == EffectiveSuit::Trump)
+                    .count();
+            }
+            if let Some((_, lead)) = plays.first() {
+                let trump_lead = !lead.is_empty()
+                    && lead
+                        .iter()
+                        .all(|c| self.trump.effective_suit(*c) == EffectiveSuit::Trump);
+                if trump_lead {
+                    for (id, cards) in &plays[1..] {
+                        if cards.iter().any(|c| {
+                            *c != Card::Unknown
+                                && self.trump.effective_suit(*c) != EffectiveSuit::Trump
+                        }) {
+                            voids.insert(*id);
+                        }
+                    }
+                }
+            }
+        };
+
+        let mut plays: Vec<(PlayerID, &[Card])> = vec![];
+        for entry in &self.history {
+            match entry.message {
+                MessageVariant::PlayedCards { ref cards } => plays.push((entry.actor, cards)),
+                MessageVariant::TookBackPlay => {
+                    if let Some(idx) = plays.iter().rposition(|(id, _)| *id == entry.actor) {
+                        plays.remove(idx);
+                    }
+                }
+                MessageVariant::TrickWon { .. } => {
+                    count_trick(&plays);
+                    plays.clear();
+                }
+                _ => (),
+            }
+        }
+        count_trick(&plays);
+        (num_played, voids)
+    }
+
+    /// Estimates how many trumps each player still holds, using only public
+    /// information. The trumps which haven't been played yet are split among
+    /// the players who aren't known to be void in trump, and the bottom, in
+    /// proportion to the number of cards that each of them holds.
+    pub fn estimated_trump_remaining(&self) -> HashMap<PlayerID, usize> {
+        let is_trump = |c: &Card| self.trump.effective_suit(*c) == EffectiveSuit::Trump;
+        let total = self
+            .decks
+            .iter()
+            .flat_map(|d| d.cards())
+            .filter(is_trump)
+            .count()
+            .saturating_sub(self.removed_cards.iter().filter(|c| is_trump(c)).count());
+        let (num_played, voids) = self.trump_plays();
+        let unseen = total.saturating_sub(num_played);
+
+        let hand_sizes = self
+            .propagated
+            .players
+            .iter()
+            .map(|p| {
+                let size = self
+                    .hands
+                    .get(p.id)
+                    .map(|h| h.values().sum::<usize>())
+                    .unwrap_or(0);
+                (p.id, if voids.contains(&p.id) { 0 } else { size })
+            })
+            .collect::<Vec<_>>();
+        let num_holding = self.kitty.len() + hand_sizes.iter().map(|(_, s)| s).sum::<usize>();
+
+        hand_sizes
+            .into_iter()
+            .map(|(id, size)| {
+                let estimate = if num_holding == 0 {
+                    0
+                } else {
+                    ((unseen * size) as f64 / num_holding as f64).round() as usize
+                };
+                (id, estimate.min(size))
+            })
+            .collect()
+    }
+
+    /// For each suit in `id`'s hand, finds the largest throw which can't be
+    /// beaten no matter how the cards that `id` hasn't seen are distributed.
+    /// Suits without a safe throw of more than one unit are left out.
+    pub fn safe_throws(&self, id: PlayerID) -> HashMap<EffectiveSuit, Vec<Card>> {
+        let hand = match self.hands.get(id) {
+            Ok(hand) => hand,
+            Err(_) => return HashMap::new(),
+        };
+
+        // Every card which hasn't been played is either in someone else's
+        // hand, or in the bottom if `id` didn't exchange it.
+        let mut unseen = HashMap::new();
+        for p in &self.propagated.players {
+            if p.id != id {
+                for (card, count) in self.hands.get(p.id).into_iter().flatten() {
+                    *unseen.entry(*card).or_insert(0) += count;
+                }
+            }
+        }
+        if self.exchanger.unwrap_or(self.landlord) != id {
+            for card in &self.kitty {
+                *unseen.entry(*card).or_insert(0) += 1;
+            }
+        }
+
+        let mut by_suit: HashMap<EffectiveSuit, Vec<Card>> = HashMap::new();
+        for card in Card::cards(hand.iter()) {
+            by_suit
+                .entry(self.trump.effective_suit(*card))
+                .or_default()
+                .push(*card);
+        }
+
+        let mut safe = HashMap::new();
+        for (suit, mut cards) in by_suit {
+            // Drop the units that could be beaten until only safe ones remain,
+            // since the remaining cards may group differently.
+            let tf = loop {
+                let tf = match TrickFormat::from_cards(
+                    self.trump,
+                    self.propagated.tractor_requirements,
+                    &cards,
+                    None,
+                ) {
+                    Ok(tf) => tf,
+                    Err(_) => break None,
+                };
+                let beaten = tf
+                    .units()
+                    .iter()
+                    .filter(|u| u.is_beaten_by(self.trump, &unseen))
+                    .flat_map(|u| u.cards())
+                    .collect::<Vec<_>>();
+                if beaten.is_empty() {
+                    break Some(tf);
+                }
+                for card in beaten {
+                    if let Some(idx) = cards.iter().position(|c| *c == card) {
+                        cards.remove(idx);
+                    }
+                }
+                if cards.is_empty() {
+                    break None;
+                }
+            };
+            if let Some(tf) = tf {
+                if tf.is_throw() {
+                    cards.sort_by(|a, b| self.trump.compare(*a, *b));
+                    safe.insert(suit, cards);
+                }
+            }
+        }
+        safe
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    pub fn compute_player_level_deltas<'a, 'b: 'a>(
+        players: impl Iterator<Item = &'b mut Player>,
+        non_landlord_level_bump: usize,
+        landlord_level_bump: usize,
+        landlords_team: &'a [PlayerID],
+        landlord_won: bool,
+        landlord: (PlayerID, Number),
+        advancement_policy: AdvancementPolicy,
+        capture_bonuses: &HashMap<PlayerID, isize>,
+    ) -> Vec<MessageVariant> {
+        let mut msgs = vec![];
+
+        let result = players
+            .map(|player| {
+                let is_defending = landlords_team.contains(&player.id);
+                let bump = if is_defending {
+                    landlord_level_bump
+                } else {
+                    non_landlord_level_bump
+                };
+                let bump = match capture_bonuses.get(&player.id) {
+                    Some(bonus) => (bump as isize + bonus).max(0) as usize,
+                    None => bump,
+                };
+                let mut num_advances = 0;
+                let mut was_blocked = false;
+                let initial_rank = player.rank();
+                let landlord_successfully_defended_a: bool =
+                    landlord.1 == Number::Ace && landlord_won;
 
                 for bump_idx in 0..bump {
                     match advancement_policy {
                         AdvancementPolicy::FullyUnrestricted => (),
+                        AdvancementPolicy::DefendPointsThenFreeze { ceiling }
+                            if player.rank() >= ceiling =>
+                        {
+                            was_blocked = true;
+                            break;
+                        }
 
                         // Player *must* defend on Ace and win to advance.
                         _ if player.rank() == Number::Ace
@@ -526,14 +1440,17 @@ impl PlayPhase {
                             break;
                         }
                         AdvancementPolicy::Unrestricted => (),
-                        AdvancementPolicy::DefendPoints => match player.rank().points() {
-                            None => (),
-                            Some(_) if is_defending && bump_idx == 0 => (),
-                            Some(_) => {
-                                was_blocked = true;
-                                break;
+                        AdvancementPolicy::DefendPoints
+                        | AdvancementPolicy::DefendPointsThenFreeze { .. } => {
+                            match player.rank().points() {
+                                None => (),
+                                Some(_) if is_defending && bump_idx == 0 => (),
+                                Some(_) => {
+                                    was_blocked = true;
+                                    break;
+                                }
                             }
-                        },
+                        }
                     }
 
                     player.advance();
@@ -572,7 +1489,194 @@ impl PlayPhase {
         msgs
     }
 
+    /// Itemizes the attacking team's points by where they came from.
+    pub fn score_breakdown(&self) -> ScoreBreakdown {
+        let sum_points = |cards: &[Card]| cards.iter().flat_map(|c| c.points()).sum::<usize>();
+
+        let mut trick_points = self
+            .points
+            .iter()
+            .filter(|(id, _)| !self.landlords_team.contains(id))
+            .map(|(id, cards)| (*id, sum_points(cards)))
+            .collect::<HashMap<_, _>>();
+
+        // The kitty's points were given to the winner of the last trick. The
+        // director may have moved some of them to another attacker since, so
+        // whatever the winner no longer holds is taken from the others.
+        let (kitty_points, kitty_multiplier) =
+            match (self.kitty_multiplier, self.trick_points.last()) {
+                (Some(multiplier), Some((winner, _))) if trick_points.contains_key(winner) => {
+                    let kitty_points = sum_points(&self.kitty);
+                    let mut holders = trick_points.keys().copied().collect::<Vec<_>>();
+                    holders.sort_by_key(|id| (id != winner, id.0));
+                    let mut remaining = kitty_points * multiplier;
+                    for id in holders {
+                        let points = trick_points.entry(id).or_insert(0);
+                        let taken = remaining.min(*points);
+                        *points -= taken;
+                        remaining -= taken;
+                    }
+                    (kitty_points, multiplier)
+                }
+                _ => (0, 0),
+            };
+        // Otherwise, the attackers may still get them because the landlord's
+        // team lost.
+        let (kitty_points, kitty_multiplier) = if kitty_multiplier == 0 {
+            match self.kitty_points_on_loss() {
+                0 => (0, 0),
+                kitty_points => (kitty_points, 1),
+            }
+        } else {
+            (kitty_points, kitty_multiplier)
+        };
+
+        let transferred_points = self.points_kept_with_captors();
+
+        let penalty_points = self
+            .penalties
+            .iter()
+            .map(|(id, penalty)| {
+                if self.landlords_team.contains(id) {
+                    *penalty as isize
+                } else {
+                    -(*penalty as isize)
+                }
+            })
+            .sum();
+
+        ScoreBreakdown {
+            trick_points,
+            transferred_points,
+            kitty_points,
+            kitty_multiplier,
+            penalty_points,
+            double_points: self
+                .propagated
+                .has_game_modifier(GameModifier::DoublePoints),
+        }
+    }
+
+    /// Captures the current position as a puzzle for whoever is next to play.
+    pub fn to_puzzle(&self) -> Result<Puzzle, Error> {
+        let player = match self.trick.next_player() {
+            Some(player) => player,
+            None => bail!("the trick needs to be finished first"),
+        };
+        Ok(Puzzle {
+            num_decks: self.num_decks,
+            decks: self.decks.clone(),
+            game_mode: self.game_mode.clone(),
+            players: self
+                .propagated
+                .players
+                .iter()
+                .map(|p| Player {
+                    reconnect_token: String::new(),
+                    ..p.clone()
+                })
+                .collect(),
+            hands: self.hands.clone(),
+            points: self.points.clone(),
+            kitty: self.kitty.clone(),
+            landlord: self.landlord,
+            landlords_team: self.landlords_team.clone(),
+            trump: self.trump,
+            trick: self.trick.clone(),
+            objective: PuzzleObjective::MaximizePoints { player },
+        })
+    }
+
+    /// Sets up a game in the position described by `puzzle`, with the default
+    /// settings.
+    pub fn from_puzzle(puzzle: Puzzle) -> Result<PlayPhase, Error> {
+        for id in puzzle
+            .trick
+            .player_queue()
+            .chain(puzzle.trick.played_cards().iter().map(|played| played.id))
+        {
+            if !puzzle.players.iter().any(|p| p.id == id) {
+                bail!("trick includes a player who isn't seated")
+            }
+        }
+        for p in &puzzle.players {
+            puzzle.hands.get(p.id)?;
+        }
+        if puzzle.trick.trump() != puzzle.trump {
+            bail!("trick has a different trump")
+        }
+
+        Ok(PlayPhase {
+            num_decks: puzzle.num_decks,
+            game_mode: puzzle.game_mode,
+            penalties: puzzle.players.iter().map(|p| (p.id, 0)).collect(),
+            propagated: PropagatedState {
+                max_player_id: puzzle.players.iter().map(|p| p.id.0 + 1).max().unwrap_or(0),
+                players: puzzle.players,
+                ..Default::default()
+            },
+            hands: puzzle.hands,
+            points: puzzle.points,
+            kitty: puzzle.kitty,
+            landlord: puzzle.landlord,
+            landlords_team: puzzle.landlords_team,
+            trump: puzzle.trump,
+            trick: puzzle.trick,
+            last_trick: None,
+            exchanger: None,
+            game_ended_early: false,
+            removed_cards: vec![],
+            decks: puzzle.decks,
+            blind_bidder: None,
+            capture_bonuses: HashMap::new(),
+            winning_bid: None,
+            trick_points: vec![],
+            pending_take_back: None,
+            last_action_at: None,
+            history: vec![],
+            kitty_point_count: None,
+            out_of_turn_attempts: HashMap::new(),
+            joined_team_at: HashMap::new(),
+            kitty_multiplier: None,
+        })
+    }
+
+    /// The points which friends won before joining the landlord's team. Under
+    /// `KeepWithCaptor`, these were captured for the attackers, so they still
+    /// count for them.
+    fn points_kept_with_captors(&self) -> usize {
+        if self.propagated.friend_point_transfer_policy != FriendPointTransferPolicy::KeepWithCaptor
+        {
+            return 0;
+        }
+        self.trick_points
+            .iter()
+            .enumerate()
+            .filter(|(idx, (winner, _))| {
+                self.joined_team_at
+                    .get(winner)
+                    .map(|joined_at| idx < joined_at)
+                    .unwrap_or(false)
+            })
+            .map(|(_, (_, points))| *points)
+            .sum()
+    }
+
+    /// The attacking team's points, and the points which everyone can see
+    /// have been captured, including the kitty if it was given to the
+    /// attackers because the landlord's team lost.
     pub fn calculate_points(&self) -> (isize, isize) {
+        let (non_landlords_points, observed_points) = self.captured_points();
+        let kitty_points = self.kitty_points_on_loss() as isize;
+        (
+            non_landlords_points + kitty_points,
+            observed_points + kitty_points,
+        )
+    }
+
+    /// Like `calculate_points`, but only counting the points captured in
+    /// tricks.
+    fn captured_points(&self) -> (isize, isize) {
         let mut non_landlords_points: isize = self
             .points
             .iter()
@@ -581,7 +1685,7 @@ impl PlayPhase {
             .flat_map(|c| c.points())
             .sum::<usize>() as isize;
 
-        let observed_points = self
+        let mut observed_points = self
             .points
             .iter()
             .filter(|(id, _)| {
@@ -591,6 +1695,12 @@ impl PlayPhase {
             .flat_map(|c| c.points())
             .sum::<usize>() as isize;
 
+        let transferred = self.points_kept_with_captors() as isize;
+        non_landlords_points += transferred;
+        if self.propagated.hide_landlord_points {
+            observed_points += transferred;
+        }
+
         for (id, penalty) in &self.penalties {
             if *penalty > 0 {
                 if self.landlords_team.contains(&id) {
@@ -603,21 +1713,69 @@ impl PlayPhase {
         (non_landlords_points, observed_points)
     }
 
-    pub fn game_finished(&self) -> bool {
-        self.game_ended_early || self.hands.is_empty() && self.trick.played_cards().is_empty()
+    /// How many more points the defending or attacking team needs in order to
+    /// reach its next scoring threshold, if it can reach it at all.
+    pub fn points_needed_for_next_level(&self, is_defending: bool) -> Result<Option<usize>, Error> {
+        let (non_landlords_points, observed_points) = self.calculate_points();
+        points_needed_for_next_level(
+            &self.propagated.game_scoring_parameters,
+            &self.decks,
+            non_landlords_points,
+            observed_points,
+            is_defending,
+        )
     }
 
-    pub fn finish_game_early(&mut self) -> Result<MessageVariant, Error> {
+    /// How far (from 0 to 1) the attacking team is toward its next scoring
+    /// threshold, for showing as a progress bar. This is 0 once the game is
+    /// over, or if the next threshold can no longer be reached.
+    pub fn progress_to_next_threshold(&self) -> Result<f32, Error> {
         if self.game_finished() {
-            bail!("Game has already ended");
+            return Ok(0.0);
         }
         let (non_landlords_points, observed_points) = self.calculate_points();
-        let can_end_early = !next_threshold_reachable(
+        progress_to_next_threshold(
             &self.propagated.game_scoring_parameters,
             &self.decks,
             non_landlords_points,
             observed_points,
-        )?;
+        )
+    }
+
+    pub fn game_finished(&self) -> bool {
+        self.game_ended_early || self.hands.is_empty() && self.trick.played_cards().is_empty()
+    }
+
+    pub fn finish_game_early(&mut self) -> Result<MessageVariant, Error> {
+        if self.game_finished() {
+            bail!("Game has already ended");
+        }
+        let (non_landlords_points, observed_points) = self.calculate_points();
+        let can_end_early = if self
+            .propagated
+            .has_game_modifier(GameModifier::DoublePoints)
+        {
+            // The scoring thresholds apply to the doubled points, so check
+            // that the outcome is the same even if the attackers capture
+            // everything that's left.
+            let remaining_points = self
+                .propagated
+                .game_scoring_parameters
+                .materialize(&self.decks)?
+                .total_points()
+                - observed_points;
+            self.score_points(non_landlords_points)?.1
+                == self
+                    .score_points(non_landlords_points + remaining_points)?
+                    .1
+        } else {
+            !next_threshold_reachable(
+                &self.propagated.game_scoring_parameters,
+                &self.decks,
+                non_landlords_points,
+                observed_points,
+            )?
+        };
 
         if can_end_early {
             self.game_ended_early = true;
@@ -627,13 +1785,24 @@ impl PlayPhase {
         }
     }
 
-    pub fn finish_game(&self) -> Result<(InitializePhase, bool, Vec<MessageVariant>), Error> {
-        let mut msgs = vec![];
-        if !self.game_finished() {
-            bail!("not done playing yet!")
-        }
-
+    /// The final number of points for the attacking team, and the resulting
+    /// change in levels, as if the game ended now.
+    pub fn final_score(&self) -> Result<(isize, GameScoreResult), Error> {
         let (non_landlords_points, _) = self.calculate_points();
+        self.score_points(non_landlords_points)
+    }
+
+    /// Scores the game as if the attackers had `non_landlords_points`, before
+    /// any game modifiers are applied.
+    fn score_points(&self, non_landlords_points: isize) -> Result<(isize, GameScoreResult), Error> {
+        let non_landlords_points = if self
+            .propagated
+            .has_game_modifier(GameModifier::DoublePoints)
+        {
+            non_landlords_points * 2
+        } else {
+            non_landlords_points
+        };
 
         let mut smaller_landlord_team = false;
 
@@ -649,29 +1818,145 @@ impl PlayPhase {
             smaller_landlord_team = actual_team_size < setting_team_size;
         }
 
-        let mut propagated = self.propagated.clone();
-
-        let GameScoreResult {
-            non_landlord_delta: non_landlord_level_bump,
-            landlord_delta: landlord_level_bump,
-            landlord_won,
-            landlord_bonus: bonus_level_earned,
-        } = compute_level_deltas(
-            &propagated.game_scoring_parameters,
+        let result = compute_level_deltas(
+            &self.propagated.game_scoring_parameters,
             &self.decks,
             non_landlords_points,
             smaller_landlord_team,
         )?;
+        Ok((non_landlords_points, result))
+    }
+
+    /// The points in the kitty which the attackers get under
+    /// `KittyOnLossPolicy::AttackersOnLoss`, because the landlord's team lost
+    /// without the attackers having won the kitty in the last trick.
+    fn kitty_points_on_loss(&self) -> usize {
+        // The kitty is only given out once the last trick has been played.
+        if self.kitty_multiplier.is_none()
+            || self.propagated.kitty_on_loss_policy != KittyOnLossPolicy::AttackersOnLoss
+        {
+            return 0;
+        }
+        let attackers_won_kitty = self
+            .trick_points
+            .last()
+            .map(|(winner, _)| !self.landlords_team.contains(winner))
+            .unwrap_or(false);
+        // The kitty only adds to the attackers' points, so whether the
+        // landlord's team lost doesn't depend on it.
+        let landlord_won = self
+            .score_points(self.captured_points().0)
+            .map(|(_, result)| result.landlord_won)
+            .unwrap_or(true);
+        if landlord_won || attackers_won_kitty {
+            return 0;
+        }
+        self.kitty.iter().flat_map(|c| c.points()).sum()
+    }
+
+    /// The most valuable player on the winning team: the attacker who
+    /// captured the most points, or the member of the landlord's team who won
+    /// the most tricks. Ties go to whoever did better by the other measure,
+    /// and then to whoever sits earliest after the landlord.
+    pub fn compute_mvp(&self) -> Option<PlayerID> {
+        let (_, result) = self.final_score().ok()?;
+        let points = |id: PlayerID| {
+            self.points
+                .get(&id)
+                .map(|cards| cards.iter().flat_map(|c| c.points()).sum::<usize>())
+                .unwrap_or(0)
+        };
+        let tricks_won = |id: PlayerID| self.trick_points.iter().filter(|(w, _)| *w == id).count();
+
+        let players = &self.propagated.players;
+        let landlord_idx = players.iter().position(|p| p.id == self.landlord)?;
+        let mut mvp: Option<((usize, usize), PlayerID)> = None;
+        for offset in 0..players.len() {
+            let id = players[(landlord_idx + offset) % players.len()].id;
+            if self.landlords_team.contains(&id) != result.landlord_won {
+                continue;
+            }
+            let key = if result.landlord_won {
+                (tricks_won(id), points(id))
+            } else {
+                (points(id), tricks_won(id))
+            };
+            if mvp.map(|(best, _)| key > best).unwrap_or(true) {
+                mvp = Some((key, id));
+            }
+        }
+        mvp.map(|(_, id)| id)
+    }
+
+    /// Previews who `finish_game` will pick as the next landlord, as if the
+    /// game ended now.
+    pub fn next_landlord(&self) -> Result<PlayerID, Error> {
+        let (_, result) = self.final_score()?;
+        self.next_landlord_given(result.landlord_won)
+    }
+
+    /// The next landlord is the first player on the winning team, going
+    /// around the table in seat order starting after the current landlord.
+    /// This only depends on the seating and on who is on the landlord's team,
+    /// and not on the order in which the landlord's friends joined.
+    fn next_landlord_given(&self, landlord_won: bool) -> Result<PlayerID, Error> {
+        let players = &self.propagated.players;
+        let landlord_idx = bail_unwrap!(players.iter().position(|p| p.id == self.landlord));
+        let next_landlord = (1..=players.len())
+            .map(|offset| &players[(landlord_idx + offset) % players.len()])
+            .find(|p| landlord_won == self.landlords_team.contains(&p.id));
+        Ok(bail_unwrap!(next_landlord).id)
+    }
+
+    pub fn finish_game(
+        &self,
+    ) -> Result<(InitializePhase, GameSummary, Vec<MessageVariant>), Error> {
+        let mut msgs = vec![];
+        if !self.game_finished() {
+            bail!("not done playing yet!")
+        }
+
+        let (
+            non_landlords_points,
+            GameScoreResult {
+                non_landlord_delta: non_landlord_level_bump,
+                landlord_delta: mut landlord_level_bump,
+                landlord_won,
+                landlord_bonus: bonus_level_earned,
+            },
+        ) = self.final_score()?;
+
+        let mut propagated = self.propagated.clone();
 
         msgs.push(MessageVariant::EndOfGameSummary {
             landlord_won,
             non_landlords_points,
         });
 
+        let kitty_points = self.kitty_points_on_loss();
+        if kitty_points > 0 {
+            msgs.push(MessageVariant::KittyAwardedOnLoss {
+                points: kitty_points,
+            });
+        }
+
         if bonus_level_earned {
             msgs.push(MessageVariant::BonusLevelEarned);
         };
 
+        if let Some(player) = self.compute_mvp() {
+            msgs.push(MessageVariant::GameMvp { player });
+        }
+
+        if let (true, Some(blind_bidder), Some(bonus)) =
+            (landlord_won, self.blind_bidder, propagated.blind_bid_bonus)
+        {
+            if self.landlords_team.contains(&blind_bidder) {
+                landlord_level_bump += bonus;
+                msgs.push(MessageVariant::BlindBidBonusEarned { bonus });
+            }
+        }
+
         let landlord_idx = bail_unwrap!(propagated
             .players
             .iter()
@@ -685,36 +1970,87 @@ impl PlayPhase {
             landlord_won,
             (self.landlord, self.propagated.players[landlord_idx].level),
             propagated.advancement_policy,
+            &self.capture_bonuses,
         ));
 
-        let mut idx = (landlord_idx + 1) % propagated.players.len();
-        let (next_landlord, next_landlord_idx) = loop {
-            if landlord_won == self.landlords_team.contains(&propagated.players[idx].id) {
-                break (propagated.players[idx].id, idx);
+        if let Some(max_metalevel) = propagated.max_metalevel {
+            // Only players who reached the maximum in this game have just won
+            // the match.
+            for (player, before) in propagated.players.iter().zip(&self.propagated.players) {
+                if player.metalevel >= max_metalevel && before.metalevel < max_metalevel {
+                    msgs.push(MessageVariant::MatchWon { player: player.id });
+                    propagated.match_winners.push(player.id);
+                }
             }
-            idx = (idx + 1) % propagated.players.len()
+        }
+
+        let next_landlord = self.next_landlord_given(landlord_won)?;
+
+        let summary = GameSummary {
+            landlord: self.landlord,
+            landlord_won,
+            non_landlord_points: non_landlords_points,
+            bonus_level: bonus_level_earned,
+            ranks_before: self
+                .propagated
+                .players
+                .iter()
+                .map(|p| (p.id, p.rank()))
+                .collect(),
+            ranks_after: propagated
+                .players
+                .iter()
+                .map(|p| (p.id, p.rank()))
+                .collect(),
+            results: msgs
+                .iter()
+                .find_map(|m| match m {
+                    MessageVariant::GameFinished { result } => Some(result.clone()),
+                    _ => None,
+                })
+                .unwrap_or_default(),
         };
 
         msgs.push(MessageVariant::NewLandlordForNextGame {
-            landlord: propagated.players[next_landlord_idx].id,
+            landlord: next_landlord,
         });
         propagated.set_landlord(Some(next_landlord))?;
         propagated.num_games_finished += 1;
+        propagated.game_modifiers.clear();
+        if let Some(winning_bid) = self.winning_bid {
+            propagated.record_past_game(PastGame {
+                winning_bid,
+                trump: self.trump,
+            });
+        }
         msgs.extend(propagated.make_all_observers_into_players()?);
 
-        Ok((InitializePhase { propagated }, landlord_won, msgs))
+        Ok((InitializePhase { propagated }, summary, msgs))
     }
 
     pub fn return_to_initialize(&self) -> Result<(InitializePhase, Vec<MessageVariant>), Error> {
         let mut msgs = vec![MessageVariant::ResettingGame];
 
         let mut propagated = self.propagated.clone();
+        propagated.record_reset();
         msgs.extend(propagated.make_all_observers_into_players()?);
 
         Ok((InitializePhase { propagated }, msgs))
     }
 }
 
+/// Formats `n` as an English ordinal, e.g. "2nd".
+fn ordinal(n: usize) -> String {
+    let suffix = match (n % 10, n % 100) {
+        (_, 11..=13) => "th",
+        (1, _) => "st",
+        (2, _) => "nd",
+        (3, _) => "rd",
+        _ => "th",
+    };
+    format!("{}{}", n, suffix)
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ExchangePhase {
     propagated: PropagatedState,
@@ -739,6 +2075,22 @@ pub struct ExchangePhase {
     removed_cards: Vec<Card>,
     #[serde(default)]
     decks: Vec<Deck>,
+    /// When a player last acted, in seconds since the Unix epoch.
+    #[serde(default)]
+    last_action_at: Option<u64>,
+    #[serde(default)]
+    history: Vec<HistoryEntry>,
+    /// The number of point cards in the bottom, filled in for players who
+    /// can't see the bottom if `reveal_kitty_point_count` is set.
+    #[serde(default)]
+    kitty_point_count: Option<usize>,
+    /// Whether the exchanger still has to choose the trump suit after
+    /// winning with a joker bid.
+    #[serde(default)]
+    trump_suit_pending: bool,
+    /// The bottom cards as they were dealt, before anyone exchanged them.
+    #[serde(default)]
+    original_kitty: Vec<Card>,
 }
 
 impl ExchangePhase {
@@ -757,6 +2109,9 @@ impl ExchangePhase {
         if self.finalized {
             bail!("cards already finalized")
         }
+        if self.propagated.has_experimental_feature("sealed_kitty") {
+            bail!("the bottom cards are sealed")
+        }
         self.hands
             .remove(self.exchanger.unwrap_or(self.landlord), Some(card))?;
         self.kitty.push(card);
@@ -770,6 +2125,9 @@ impl ExchangePhase {
         if self.finalized {
             bail!("cards already finalized")
         }
+        if self.propagated.has_experimental_feature("sealed_kitty") {
+            bail!("the bottom cards are sealed")
+        }
         if let Some(index) = self.kitty.iter().position(|c| *c == card) {
             self.kitty.swap_remove(index);
             self.hands
@@ -780,6 +2138,33 @@ impl ExchangePhase {
         }
     }
 
+    /// Computes what the exchanger's hand would be after moving `to_kitty`
+    /// from their hand into the kitty and `from_kitty` from the kitty into
+    /// their hand, without actually moving any cards.
+    pub fn preview_hand_after(
+        &self,
+        id: PlayerID,
+        to_kitty: &[Card],
+        from_kitty: &[Card],
+    ) -> Result<Vec<Card>, Error> {
+        if self.exchanger.unwrap_or(self.landlord) != id {
+            bail!("not the exchanger")
+        }
+        let mut kitty = self.kitty.clone();
+        for card in from_kitty {
+            match kitty.iter().position(|c| c == card) {
+                Some(index) => {
+                    kitty.swap_remove(index);
+                }
+                None => bail!("card not in the kitty"),
+            }
+        }
+        let mut hands = self.hands.clone();
+        hands.remove(id, to_kitty.iter().copied())?;
+        hands.add(id, from_kitty.iter().copied())?;
+        Ok(hands.cards(id, self.trump.number())?)
+    }
+
     pub fn num_friends(&self) -> usize {
         match self.game_mode {
             GameMode::FindingFriends { num_friends, .. } => num_friends,
@@ -795,73 +2180,28 @@ impl ExchangePhase {
         if self.landlord != id {
             bail!("not the landlord")
         }
-        if let GameMode::FindingFriends {
-            num_friends,
-            ref mut friends,
-        } = self.game_mode
-        {
+        if let GameMode::FindingFriends { num_friends, .. } = self.game_mode {
             let friend_set = iter.into_iter().collect::<HashSet<_>>();
             if num_friends != friend_set.len() {
                 bail!("incorrect number of friends")
             }
-
-            friends.clear();
-
             for friend in friend_set.iter() {
-                if FriendSelectionPolicy::TrumpsIncluded != self.propagated.friend_selection_policy
-                {
-                    if friend.card.is_joker() || friend.card.number() == Some(self.trump.number()) {
-                        bail!(
-                            "you can't pick a joker or a {} as your friend",
-                            self.trump.number().as_str()
-                        )
-                    }
-                    if self.trump.suit() != None && friend.card.suit() == self.trump.suit() {
-                        bail!("you can't pick a trump suit as your friend")
-                    }
-                }
-                if friend.initial_skip >= self.num_decks {
-                    bail!("need to pick a card that exists!")
-                }
-
-                if let FriendSelectionPolicy::HighestCardNotAllowed =
-                    self.propagated.friend_selection_policy
-                {
-                    match (self.trump.number(), friend.card.number()) {
-                        (Number::Ace, Some(Number::King)) | (_, Some(Number::Ace)) => {
-                            bail!("you can't pick the highest card as your friend")
-                        }
-                        _ => (),
-                    }
-                }
+                self.check_friend(friend)?;
+            }
 
-                if let FriendSelectionPolicy::PointCardNotAllowed =
-                    self.propagated.friend_selection_policy
-                {
-                    let landlord = self.landlord;
-                    let landlord_level = self
-                        .propagated
-                        .players
-                        .iter()
-                        .find(|p| p.id == landlord)
-                        .ok_or_else(|| anyhow!("Couldn't find landlord level?"))?
-                        .rank();
-
-                    match (landlord_level, friend.card.points(), friend.card.number()) {
-                        (Number::Ace, _, Some(Number::King)) => (),
-                        (_, Some(_), _) => {
-                            bail!("you can't pick a point card as your friend");
-                        }
-                        (_, _, _) => (),
-                    }
+            if let GameMode::FindingFriends {
+                ref mut friends, ..
+            } = self.game_mode
+            {
+                friends.clear();
+                for friend in friend_set.iter() {
+                    friends.push(Friend {
+                        card: friend.card,
+                        initial_skip: friend.initial_skip,
+                        skip: friend.initial_skip,
+                        player_id: None,
+                    });
                 }
-
-                friends.push(Friend {
-                    card: friend.card,
-                    initial_skip: friend.initial_skip,
-                    skip: friend.initial_skip,
-                    player_id: None,
-                });
             }
 
             Ok(())
@@ -870,6 +2210,86 @@ impl ExchangePhase {
         }
     }
 
+    /// Checks whether `friend` may be picked under the friend selection
+    /// policy.
+    pub fn check_friend(&self, friend: &FriendSelection) -> Result<(), Error> {
+        if FriendSelectionPolicy::TrumpsIncluded != self.propagated.friend_selection_policy {
+            if friend.card.is_joker() || friend.card.number() == Some(self.trump.number()) {
+                bail!(
+                    "you can't pick a joker or a {} as your friend",
+                    self.trump.number()
+                )
+            }
+            if self.trump.suit() != None && friend.card.suit() == self.trump.suit() {
+                bail!("you can't pick a trump suit as your friend")
+            }
+        }
+        let skip_range = self.valid_skip_range(friend.card);
+        if skip_range.is_empty() {
+            bail!("need to pick a card that exists!")
+        }
+        if !skip_range.contains(&friend.initial_skip) {
+            if skip_range.end == 1 {
+                bail!("there is only 1 copy of that card, so you can only pick the 1st one played")
+            }
+            bail!(
+                "there are only {} copies of that card, so you can pick at most the {} one played",
+                skip_range.end,
+                ordinal(skip_range.end)
+            )
+        }
+
+        if let FriendSelectionPolicy::HighestCardNotAllowed =
+            self.propagated.friend_selection_policy
+        {
+            match (self.trump.number(), friend.card.number()) {
+                (Number::Ace, Some(Number::King)) | (_, Some(Number::Ace)) => {
+                    bail!("you can't pick the highest card as your friend")
+                }
+                _ => (),
+            }
+        }
+
+        if let FriendSelectionPolicy::PointCardNotAllowed = self.propagated.friend_selection_policy
+        {
+            let landlord = self.landlord;
+            let landlord_level = self
+                .propagated
+                .players
+                .iter()
+                .find(|p| p.id == landlord)
+                .ok_or_else(|| anyhow!("Couldn't find landlord level?"))?
+                .rank();
+
+            match (landlord_level, friend.card.points(), friend.card.number()) {
+                (Number::Ace, _, Some(Number::King)) => (),
+                (_, Some(_), _) => {
+                    bail!("you can't pick a point card as your friend");
+                }
+                (_, _, _) => (),
+            }
+        }
+        Ok(())
+    }
+
+    /// The values of `initial_skip` which pick out a copy of `card` that is
+    /// actually in the decks being played.
+    pub fn valid_skip_range(&self, card: Card) -> Range<usize> {
+        0..self.decks.iter().map(|d| d.num_copies(card)).sum()
+    }
+
+    /// Describes which card will reveal the friend, e.g. "the 2nd King of
+    /// clubs played".
+    pub fn describe_friend_selection(&self, friend: &FriendSelection) -> String {
+        let card = match friend.card {
+            Card::Suited { number, suit } => format!("{} of {}", number.name(), suit.name()),
+            Card::BigJoker => "big joker".to_string(),
+            Card::SmallJoker => "small joker".to_string(),
+            Card::Unknown => "unknown card".to_string(),
+        };
+        format!("the {} {} played", ordinal(friend.initial_skip + 1), card)
+    }
+
     pub fn finalize(&mut self, id: PlayerID) -> Result<(), Error> {
         if id != self.exchanger.unwrap_or(self.landlord) {
             bail!("only the exchanger can finalize their cards")
@@ -880,10 +2300,39 @@ impl ExchangePhase {
         if self.kitty.len() != self.kitty_size {
             bail!("incorrect number of cards in the bottom")
         }
+        if self.trump_suit_pending {
+            bail!("must choose the trump suit first")
+        }
         self.finalized = true;
         Ok(())
     }
 
+    /// Chooses the trump suit after a joker bid, if the leader is allowed to.
+    pub fn set_trump_suit(&mut self, id: PlayerID, suit: Suit) -> Result<(), Error> {
+        if id != self.exchanger.unwrap_or(self.landlord) {
+            bail!("only the exchanger can choose the trump suit")
+        }
+        if self.finalized {
+            bail!("the trump suit can't be changed once the bottom is finalized")
+        }
+        if self.propagated.joker_bid_suit_policy != JokerBidSuitPolicy::LandlordChoosesSuit {
+            bail!("the trump suit is determined by the bid")
+        }
+        let (_, winning_bid) = Bid::first_and_winner(&self.bids, self.autobid)?;
+        match winning_bid.card {
+            Card::SmallJoker | Card::BigJoker => (),
+            _ => bail!("the trump suit can only be chosen after a joker bid"),
+        }
+        self.trump = Trump::Standard {
+            suit,
+            number: self.trump.number(),
+            permanent: self.trump.permanent(),
+        };
+        self.hands.set_trump(self.trump);
+        self.trump_suit_pending = false;
+        Ok(())
+    }
+
     pub fn pick_up_cards(&mut self, id: PlayerID) -> Result<(), Error> {
         if !self.finalized {
             bail!("Current exchanger is still exchanging cards!")
@@ -898,16 +2347,31 @@ impl ExchangePhase {
         if id != winning_bid.id {
             bail!("Only the winner of the bid can pick up the cards")
         }
+        if self.propagated.kitty_theft_reveal_policy == KittyTheftRevealPolicy::ResetToOriginalKitty
+            && !self.original_kitty.is_empty()
+        {
+            // Undo the previous exchange, so that the buried cards aren't
+            // revealed to the new exchanger.
+            let previous = self.exchanger.unwrap_or(self.landlord);
+            self.hands.add(previous, self.kitty.clone())?;
+            self.hands
+                .remove(previous, self.original_kitty.iter().copied())?;
+            self.kitty = self.original_kitty.clone();
+        }
         self.trump = match winning_bid.card {
             Card::Unknown => bail!("can't bid with unknown cards!"),
             Card::SmallJoker | Card::BigJoker => Trump::NoTrump {
                 number: self.trump.number(),
+                permanent: self.trump.permanent(),
             },
             Card::Suited { suit, .. } => Trump::Standard {
                 suit,
                 number: self.trump.number(),
+                permanent: self.trump.permanent(),
             },
         };
+        self.trump_suit_pending = winning_bid.card.is_joker()
+            && self.propagated.joker_bid_suit_policy == JokerBidSuitPolicy::LandlordChoosesSuit;
         self.finalized = false;
         self.epoch += 1;
         self.exchanger = Some(winning_bid.id);
@@ -919,7 +2383,7 @@ impl ExchangePhase {
         if !self.finalized || self.autobid.is_some() {
             return false;
         }
-        Bid::bid(
+        let made_bid = Bid::bid(
             id,
             card,
             count,
@@ -933,7 +2397,64 @@ impl ExchangePhase {
             self.propagated.joker_bid_policy,
             self.num_decks,
             self.epoch,
-        )
+        ) || (self.propagated.kitty_theft_bid_policy
+            == KittyTheftBidPolicy::MustMatch
+            && self.match_winning_bid(id, card, count));
+        if made_bid {
+            record_history(
+                &mut self.history,
+                id,
+                &[MessageVariant::MadeBid { card, count }],
+            );
+        }
+        made_bid
+    }
+
+    /// Lets a player other than the winning bidder make a bid of the same
+    /// strength, which then becomes the winning bid.
+    fn match_winning_bid(&mut self, id: PlayerID, card: Card, count: usize) -> bool {
+        let winning_bid = match self.bids.last() {
+            Some(bid) if bid.id != id => *bid,
+            _ => return false,
+        };
+        let bid_level = self
+            .propagated
+            .players
+            .iter()
+            .find(|p| p.id == self.propagated.landlord.unwrap_or(id))
+            .map(|p| p.rank());
+        let available = self
+            .hands
+            .counts(id)
+            .and_then(|c| c.get(&card).cloned())
+            .unwrap_or(0);
+        let new_bid = Bid {
+            id,
+            card,
+            count,
+            epoch: self.epoch,
+            blind: false,
+        };
+        if count == 0
+            || available < count
+            || (!card.is_joker() && card.number() != bid_level)
+            || !new_bid.matches_strength(&winning_bid, self.propagated.bid_policy)
+        {
+            return false;
+        }
+        self.bids.push(new_bid);
+        true
+    }
+
+    pub fn can_take_back_bid(&self, id: PlayerID) -> bool {
+        self.finalized
+            && self.autobid.is_none()
+            && Bid::can_take_back_bid(
+                id,
+                self.propagated.bid_takeback_policy,
+                &self.bids,
+                self.epoch,
+            )
     }
 
     pub fn take_back_bid(&mut self, id: PlayerID) -> Result<(), Error> {
@@ -948,7 +2469,9 @@ impl ExchangePhase {
             self.propagated.bid_takeback_policy,
             &mut self.bids,
             self.epoch,
-        )
+        )?;
+        record_history(&mut self.history, id, &[MessageVariant::TookBackBid]);
+        Ok(())
     }
 
     pub fn landlord(&self) -> PlayerID {
@@ -963,6 +2486,43 @@ impl ExchangePhase {
         self.trump
     }
 
+    /// The player who is currently exchanging cards with the bottom.
+    pub fn exchanger(&self) -> PlayerID {
+        self.exchanger.unwrap_or(self.landlord)
+    }
+
+    pub fn kitty(&self) -> &[Card] {
+        &self.kitty
+    }
+
+    pub fn kitty_size(&self) -> usize {
+        self.kitty_size
+    }
+
+    pub fn is_finalized(&self) -> bool {
+        self.finalized
+    }
+
+    pub fn propagated(&self) -> &PropagatedState {
+        &self.propagated
+    }
+
+    pub fn is_trump_suit_pending(&self) -> bool {
+        self.trump_suit_pending
+    }
+
+    /// Whether the leader has picked all of their friends, if there are any
+    /// to pick.
+    pub fn friends_picked(&self) -> bool {
+        match self.game_mode {
+            GameMode::FindingFriends {
+                num_friends,
+                ref friends,
+            } => friends.len() == num_friends,
+            GameMode::Tractor => true,
+        }
+    }
+
     pub fn next_player(&self) -> Result<PlayerID, Error> {
         if self.propagated.kitty_theft_policy == KittyTheftPolicy::AllowKittyTheft
             && self.autobid.is_none()
@@ -990,6 +2550,9 @@ impl ExchangePhase {
                 bail!("need to pick friends")
             }
         }
+        if self.trump_suit_pending {
+            bail!("must choose the trump suit first")
+        }
 
         if self.propagated.kitty_theft_policy == KittyTheftPolicy::AllowKittyTheft
             && self.autobid.is_none()
@@ -1024,6 +2587,16 @@ impl ExchangePhase {
             .players
             .iter()
             .position(|p| p.id == self.landlord));
+        let (_, winning_bid) = Bid::first_and_winner(&self.bids, self.autobid)?;
+        let leader_idx = match self.propagated.first_lead_policy {
+            FirstLeadPolicy::Landlord => landlord_idx,
+            FirstLeadPolicy::LeftOfLandlord => (landlord_idx + 1) % self.propagated.players.len(),
+            FirstLeadPolicy::BidWinner => bail_unwrap!(self
+                .propagated
+                .players
+                .iter()
+                .position(|p| p.id == winning_bid.id)),
+        };
 
         Ok(PlayPhase {
             num_decks: self.num_decks,
@@ -1033,7 +2606,7 @@ impl ExchangePhase {
             trick: Trick::new(
                 self.trump,
                 (0..self.propagated.players.len()).map(|offset| {
-                    let idx = (landlord_idx + offset) % self.propagated.players.len();
+                    let idx = (leader_idx + offset) % self.propagated.players.len();
                     self.propagated.players[idx].id
                 }),
             ),
@@ -1053,6 +2626,21 @@ impl ExchangePhase {
             game_ended_early: false,
             removed_cards: self.removed_cards.clone(),
             decks: self.decks.clone(),
+            blind_bidder: if winning_bid.blind {
+                Some(winning_bid.id)
+            } else {
+                None
+            },
+            capture_bonuses: HashMap::new(),
+            winning_bid: Some(winning_bid),
+            trick_points: vec![],
+            pending_take_back: None,
+            last_action_at: self.last_action_at,
+            history: self.history.clone(),
+            kitty_point_count: None,
+            out_of_turn_attempts: HashMap::new(),
+            joined_team_at: HashMap::new(),
+            kitty_multiplier: None,
         })
     }
 
@@ -1060,6 +2648,7 @@ impl ExchangePhase {
         let mut msgs = vec![MessageVariant::ResettingGame];
 
         let mut propagated = self.propagated.clone();
+        propagated.record_reset();
         msgs.extend(propagated.make_all_observers_into_players()?);
 
         Ok((InitializePhase { propagated }, msgs))
@@ -1085,6 +2674,14 @@ pub struct DrawPhase {
     removed_cards: Vec<Card>,
     #[serde(default)]
     decks: Vec<Deck>,
+    /// Players who have already forced a redeal this game.
+    #[serde(default)]
+    weak_hand_declarations: Vec<PlayerID>,
+    /// When a player last acted, in seconds since the Unix epoch.
+    #[serde(default)]
+    last_action_at: Option<u64>,
+    #[serde(default)]
+    history: Vec<HistoryEntry>,
 }
 
 impl DrawPhase {
@@ -1112,19 +2709,56 @@ impl DrawPhase {
         }
     }
 
-    pub fn draw_card(&mut self, id: PlayerID) -> Result<(), Error> {
+    pub fn draw_card(&mut self, id: PlayerID) -> Result<Vec<MessageVariant>, Error> {
         if id != self.propagated.players[self.position].id {
             bail!("not your turn!");
         }
         if let Some(next_card) = self.deck.pop() {
             self.hands.add(id, Some(next_card))?;
             self.position = (self.position + 1) % self.propagated.players.len();
-            Ok(())
+            if self.remaining_draws(id) == 0 {
+                Ok(self.revoke_unfulfilled_blind_bids(id)?)
+            } else {
+                Ok(vec![])
+            }
         } else {
             bail!("no cards left in deck")
         }
     }
 
+    /// The number of cards that the player has yet to draw from the deck.
+    fn remaining_draws(&self, id: PlayerID) -> usize {
+        let num_players = self.propagated.players.len();
+        match self.propagated.players.iter().position(|p| p.id == id) {
+            Some(idx) => (0..self.deck.len())
+                .filter(|offset| (self.position + offset) % num_players == idx)
+                .count(),
+            None => 0,
+        }
+    }
+
+    /// Once a player has drawn their full hand, any blind bids they made which
+    /// they can't actually back with cards are removed.
+    fn revoke_unfulfilled_blind_bids(
+        &mut self,
+        id: PlayerID,
+    ) -> Result<Vec<MessageVariant>, Error> {
+        let counts = self.hands.counts(id).cloned().unwrap_or_default();
+        let mut msgs = vec![];
+        self.bids.retain(|b| {
+            let unfulfilled =
+                b.id == id && b.blind && counts.get(&b.card).copied().unwrap_or(0) < b.count;
+            if unfulfilled {
+                msgs.push(MessageVariant::BlindBidRevoked {
+                    card: b.card,
+                    count: b.count,
+                });
+            }
+            !unfulfilled
+        });
+        Ok(msgs)
+    }
+
     pub fn reveal_card(&mut self) -> Result<MessageVariant, Error> {
         if !self.deck.is_empty() {
             bail!("can't reveal card until deck is fully drawn")
@@ -1157,6 +2791,7 @@ impl DrawPhase {
                     id,
                     card,
                     epoch: 0,
+                    blind: false,
                 });
             }
             KittyBidPolicy::FirstCardOfLevelOrHighest
@@ -1167,19 +2802,25 @@ impl DrawPhase {
                     id,
                     card,
                     epoch: 0,
+                    blind: false,
                 });
             }
             KittyBidPolicy::FirstCardOfLevelOrHighest
                 if self.revealed_cards >= self.kitty.len() - 1 =>
             {
                 let mut sorted_kitty = self.kitty.clone();
-                sorted_kitty.sort_by(|a, b| Trump::NoTrump { number: level }.compare(*a, *b));
+                let trump = Trump::NoTrump {
+                    number: level,
+                    permanent: PermanentTrumps::NONE,
+                };
+                sorted_kitty.sort_by(|a, b| trump.compare(*a, *b));
                 if let Some(highest_card) = sorted_kitty.last() {
                     self.autobid = Some(Bid {
                         count: 1,
                         id,
                         card: *highest_card,
                         epoch: 0,
+                        blind: false,
                     });
                 }
             }
@@ -1194,7 +2835,7 @@ impl DrawPhase {
         if self.revealed_cards > 0 {
             return false;
         }
-        Bid::bid(
+        let made_bid = Bid::bid(
             id,
             card,
             count,
@@ -1208,17 +2849,170 @@ impl DrawPhase {
             self.propagated.joker_bid_policy,
             self.num_decks,
             0,
+        );
+        if made_bid {
+            record_history(
+                &mut self.history,
+                id,
+                &[MessageVariant::MadeBid { card, count }],
+            );
+        }
+        made_bid
+    }
+
+    /// The bids that `id` could make right now.
+    pub fn valid_bids(&self, id: PlayerID) -> Vec<Bid> {
+        if self.revealed_cards > 0 || self.autobid.is_some() {
+            return vec![];
+        }
+        Bid::valid_bids(
+            id,
+            &self.bids,
+            &self.hands,
+            &self.propagated.players,
+            self.propagated.landlord,
+            0,
+            self.propagated.bid_policy,
+            self.propagated.bid_reinforcement_policy,
+            self.propagated.joker_bid_policy,
+            self.num_decks,
         )
+        .unwrap_or_default()
+    }
+
+    pub fn blind_bid(&mut self, id: PlayerID, card: Card, count: usize) -> Result<(), Error> {
+        if self.propagated.blind_bid_bonus.is_none() {
+            bail!("blind bids are not allowed")
+        }
+        if self.revealed_cards > 0 || self.autobid.is_some() {
+            bail!("can't bid after cards have been revealed")
+        }
+        if self.remaining_draws(id) == 0 {
+            bail!("blind bids must be made before drawing your full hand")
+        }
+        let held = self
+            .hands
+            .counts(id)
+            .and_then(|c| c.get(&card).copied())
+            .unwrap_or(0);
+        if count <= held {
+            bail!("you already have those cards, so the bid isn't blind")
+        }
+
+        // Check the bid as though the player had already drawn the cards.
+        let mut hands = self.hands.clone();
+        hands.add(id, (held..count).map(|_| card))?;
+        let valid_bids = Bid::valid_bids(
+            id,
+            &self.bids,
+            &hands,
+            &self.propagated.players,
+            self.propagated.landlord,
+            0,
+            self.propagated.bid_policy,
+            self.propagated.bid_reinforcement_policy,
+            self.propagated.joker_bid_policy,
+            self.num_decks,
+        )?;
+        let mut new_bid = Bid {
+            id,
+            card,
+            count,
+            epoch: 0,
+            blind: false,
+        };
+        if !valid_bids.contains(&new_bid) {
+            bail!("bid was invalid")
+        }
+        new_bid.blind = true;
+        self.bids.push(new_bid);
+        record_history(
+            &mut self.history,
+            id,
+            &[MessageVariant::MadeBlindBid { card, count }],
+        );
+        Ok(())
+    }
+
+    pub fn can_take_back_bid(&self, id: PlayerID) -> bool {
+        Bid::can_take_back_bid(id, self.propagated.bid_takeback_policy, &self.bids, 0)
     }
 
     pub fn take_back_bid(&mut self, id: PlayerID) -> Result<(), Error> {
-        Bid::take_back_bid(id, self.propagated.bid_takeback_policy, &mut self.bids, 0)
+        Bid::take_back_bid(id, self.propagated.bid_takeback_policy, &mut self.bids, 0)?;
+        record_history(&mut self.history, id, &[MessageVariant::TookBackBid]);
+        Ok(())
+    }
+
+    /// Declares the player's hand weak under the `weak_hand_redeal` rule, and
+    /// deals a fresh set of hands. Each player may only force one redeal per
+    /// game.
+    pub fn declare_weak_hand(&self, id: PlayerID) -> Result<DrawPhase, Error> {
+        let rule = match self.propagated.weak_hand_redeal {
+            Some(rule) => rule,
+            None => bail!("weak hands can't be redealt"),
+        };
+        if self.weak_hand_declarations.contains(&id) {
+            bail!("you've already forced a redeal this game")
+        }
+        let hand = self.hands.get(id)?;
+        if self.remaining_draws(id) > 0 {
+            bail!("you must draw your full hand before declaring it weak")
+        }
+
+        let level = match self.level {
+            Some(level) => level,
+            None => self
+                .propagated
+                .players
+                .iter()
+                .find(|p| p.id == id)
+                .ok_or_else(|| anyhow!("player not found"))?
+                .rank(),
+        };
+        let points = hand
+            .iter()
+            .map(|(card, count)| card.points().unwrap_or(0) * count)
+            .sum::<usize>();
+        let trumps = hand
+            .iter()
+            .filter(|(card, _)| card.is_joker() || card.number() == Some(level))
+            .map(|(_, count)| count)
+            .sum::<usize>();
+        if points >= rule.point_threshold {
+            bail!("hand has too many points to be declared weak")
+        }
+        if trumps > rule.trump_threshold {
+            bail!("hand has too many trumps to be declared weak")
+        }
+
+        let init = InitializePhase {
+            propagated: self.propagated.clone(),
+        };
+        let mut redealt = init.start(self.propagated.landlord.unwrap_or(id))?;
+        redealt.weak_hand_declarations = self.weak_hand_declarations.clone();
+        redealt.weak_hand_declarations.push(id);
+        Ok(redealt)
     }
 
     pub fn done_drawing(&self) -> bool {
         self.deck.is_empty()
     }
 
+    pub fn hands(&self) -> &Hands {
+        &self.hands
+    }
+
+    /// The card that will be dealt by the next call to `draw_card`. This is
+    /// `Card::Unknown` if the deck has been redacted for a player.
+    pub fn peek_next_card(&self) -> Option<Card> {
+        self.deck.last().copied()
+    }
+
+    pub fn cards_remaining(&self) -> usize {
+        self.deck.len()
+    }
+
     pub fn advance(&self, id: PlayerID) -> Result<ExchangePhase, Error> {
         if !self.deck.is_empty() {
             bail!("deck has cards remaining")
@@ -1245,12 +3039,15 @@ impl DrawPhase {
                 Card::Unknown => bail!("can't bid with unknown cards!"),
                 Card::SmallJoker | Card::BigJoker => Trump::NoTrump {
                     number: landlord_level,
+                    permanent: PermanentTrumps::NONE,
                 },
                 Card::Suited { suit, .. } => Trump::Standard {
                     suit,
                     number: landlord_level,
+                    permanent: PermanentTrumps::NONE,
                 },
-            };
+            }
+            .with_permanent(self.propagated.permanent_trump_numbers.clone().into());
             let mut hands = self.hands.clone();
             hands.set_trump(trump);
             Ok(ExchangePhase {
@@ -1258,6 +3055,7 @@ impl DrawPhase {
                 game_mode: self.game_mode.clone(),
                 kitty_size: self.kitty.len(),
                 kitty: self.kitty.clone(),
+                original_kitty: self.kitty.clone(),
                 propagated: self.propagated.clone(),
                 landlord,
                 hands,
@@ -1269,20 +3067,110 @@ impl DrawPhase {
                 autobid: self.autobid,
                 removed_cards: self.removed_cards.clone(),
                 decks: self.decks.clone(),
+                last_action_at: self.last_action_at,
+                history: self.history.clone(),
+                kitty_point_count: None,
+                trump_suit_pending: winning_bid.card.is_joker()
+                    && self.propagated.joker_bid_suit_policy
+                        == JokerBidSuitPolicy::LandlordChoosesSuit,
             })
         }
     }
 
+    pub fn skips_exchange(&self) -> bool {
+        self.propagated.skip_exchange
+    }
+
+    /// Moves straight from drawing to playing, leaving the kitty untouched.
+    pub fn advance_to_play(&self, id: PlayerID) -> Result<PlayPhase, Error> {
+        if !self.propagated.skip_exchange {
+            bail!("the exchange phase can't be skipped")
+        }
+        self.advance(id)?.advance(id)
+    }
+
     pub fn return_to_initialize(&self) -> Result<(InitializePhase, Vec<MessageVariant>), Error> {
         let mut msgs = vec![MessageVariant::ResettingGame];
 
         let mut propagated = self.propagated.clone();
+        propagated.record_reset();
         msgs.extend(propagated.make_all_observers_into_players()?);
 
         Ok((InitializePhase { propagated }, msgs))
     }
 }
 
+/// How the cards are dealt out: the size of the bottom, and how many cards must
+/// be removed from the deck so that every player draws the same number of
+/// cards.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct KittyArrangement {
+    pub kitty_size: usize,
+    pub num_removed_cards: usize,
+}
+
+/// Computes the kitty arrangement for dealing `decks` to `num_players`, or
+/// fails if there's no way to deal them out.
+pub fn kitty_arrangement(
+    decks: &[Deck],
+    num_players: usize,
+    kitty_size: Option<usize>,
+    remainder_policy: RemainderPolicy,
+) -> Result<KittyArrangement, Error> {
+    if num_players == 0 {
+        bail!("need at least one player to deal to")
+    }
+    let deck_len = decks.iter().map(|d| d.len()).sum::<usize>();
+    let kitty_size = match (kitty_size, remainder_policy) {
+        (Some(size), _) => size,
+        (None, RemainderPolicy::GrowKitty) => {
+            let mut kitty_size = deck_len % num_players;
+            if kitty_size == 0 {
+                kitty_size = num_players;
+            }
+            if kitty_size < 5 {
+                kitty_size += num_players;
+            }
+            kitty_size
+        }
+        (None, RemainderPolicy::RemoveCards) => {
+            // Use the smallest kitty that doesn't require a partial round of drawing; the
+            // leftover cards are removed from the deck.
+            let mut kitty_size = num_players;
+            while kitty_size < 5 {
+                kitty_size += num_players;
+            }
+            kitty_size
+        }
+        (None, RemainderPolicy::SmallerLastRound) => match deck_len % num_players {
+            0 => num_players,
+            remainder => remainder,
+        },
+    };
+
+    // Remove cards from the deck until the deck and kitty together work out to the appropriate
+    // number of cards.
+    let num_removed_cards =
+        (deck_len % num_players + num_players - kitty_size % num_players) % num_players;
+
+    // Cards are removed one number at a time, from each suit in each deck.
+    if num_removed_cards > decks.len() * ALL_SUITS.len() {
+        bail!(
+            "can't remove {} cards from {} decks",
+            num_removed_cards,
+            decks.len()
+        )
+    }
+    if deck_len < kitty_size + num_removed_cards + num_players {
+        bail!("not enough cards to deal to {} players", num_players)
+    }
+
+    Ok(KittyArrangement {
+        kitty_size,
+        num_removed_cards,
+    })
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct InitializePhase {
     propagated: PropagatedState,
@@ -1295,27 +3183,236 @@ impl InitializePhase {
         }
     }
 
+    /// Starts the next game immediately after the previous one finishes,
+    /// keeping the settings, seats, and landlord determined by the last game.
+    pub fn rematch(&self, id: PlayerID) -> Result<DrawPhase, Error> {
+        if !self.propagated.quick_rematch {
+            bail!("quick rematches are not enabled")
+        }
+        if self.propagated.num_games_finished == 0 {
+            bail!("can only rematch after a game has finished")
+        }
+        self.start(id)
+    }
+
     pub fn start(&self, id: PlayerID) -> Result<DrawPhase, Error> {
+        self.start_with_rng(id, &mut rand::thread_rng())
+    }
+
+    /// Starts the game like `start`, but shuffles the deck (and picks the
+    /// first landlord, if there isn't one) deterministically from `seed`, so
+    /// that a deal can be reproduced.
+    pub fn start_with_seed(&self, id: PlayerID, seed: u64) -> Result<DrawPhase, Error> {
+        self.start_with_rng(id, &mut StdRng::seed_from_u64(seed))
+    }
+
+    fn start_with_rng(&self, id: PlayerID, rng: &mut impl RngCore) -> Result<DrawPhase, Error> {
         if self.propagated.players.len() < 4 {
             bail!("not enough players")
         }
 
+        if !self.propagated.match_winners.is_empty() {
+            bail!("the match is over; start a new match to keep playing")
+        }
+
         if self.propagated.game_start_policy == GameStartPolicy::AllowLandlordOnly
             && self.propagated.landlord.map(|l| l != id).unwrap_or(false)
         {
             bail!("Only the landlord can start the game")
         }
 
-        let game_mode = match self.propagated.game_mode {
-            GameModeSettings::FindingFriends {
-                num_friends: Some(num_friends),
+        let game_mode = self.game_mode()?;
+
+        let landlord_position = self.landlord_position();
+        let position =
+            landlord_position.unwrap_or(rng.next_u32() as usize % self.propagated.players.len());
+        let level = landlord_position.map(|p| self.propagated.players[p].rank());
+
+        let num_decks = self.propagated.num_decks();
+        let decks = self.playable_decks(level)?;
+        let mut deck = Vec::with_capacity(decks.iter().map(|d| d.len()).sum::<usize>());
+        for deck_ in &decks {
+            deck.extend(deck_.cards());
+        }
+
+        deck.shuffle(rng);
+
+        let (KittyArrangement { kitty_size, .. }, cards_to_remove) =
+            self.kitty_plan(&decks, level, self.propagated.kitty_size)?;
+
+        // Remove cards from the deck, until the deck and kitty together work out to the
+        // appropriate number of cards.
+        let mut removed_cards = vec![];
+        for card_to_remove in cards_to_remove {
+            match deck.iter().position(|c| *c == card_to_remove) {
+                Some(idx) => {
+                    deck.remove(idx);
+                    removed_cards.push(card_to_remove);
+                }
+                None => bail!(format!(
+                    "Couldn't find {:?} in the deck to remove",
+                    card_to_remove
+                )),
+            }
+        }
+
+        let propagated = self.propagated.clone();
+
+        Ok(DrawPhase {
+            deck: (&deck[0..deck.len() - kitty_size]).to_vec(),
+            kitty: (&deck[deck.len() - kitty_size..]).to_vec(),
+            hands: Hands::new(self.propagated.players.iter().map(|p| p.id)),
+            bids: Vec::new(),
+            revealed_cards: 0,
+            autobid: None,
+            propagated,
+            position,
+            num_decks,
+            decks,
+            game_mode,
+            level,
+            removed_cards,
+            weak_hand_declarations: vec![],
+            last_action_at: None,
+            history: vec![],
+        })
+    }
+
+    /// Lists the kitty sizes that the game could be started with, given the
+    /// current players and decks. This runs the same checks as `start`, so
+    /// every size listed here will start successfully, and every other size
+    /// will fail.
+    pub fn valid_kitty_sizes(&self) -> Result<Vec<usize>, Error> {
+        if self.propagated.players.len() < 4 {
+            bail!("not enough players")
+        }
+        self.game_mode()?;
+        let level = self
+            .landlord_position()
+            .map(|p| self.propagated.players[p].rank());
+        let decks = self.playable_decks(level)?;
+        let deck_len = decks.iter().map(|d| d.len()).sum::<usize>();
+
+        Ok((1..deck_len)
+            .filter(|size| self.kitty_plan(&decks, level, Some(*size)).is_ok())
+            .collect())
+    }
+
+    /// Picks the game mode, the number of decks, and the main scoring and
+    /// trick policies at random ("dealer's choice"), deterministically from
+    /// `seed`. Only settings which can be started with are chosen; if none of
+    /// them work out, the settings are left unchanged.
+    pub fn randomize_settings(&mut self, seed: u64) -> Result<Vec<MessageVariant>, Error> {
+        let num_players = self.propagated.players.len();
+        if num_players < 4 {
+            bail!("not enough players")
+        }
+        if !self.propagated.match_winners.is_empty() {
+            bail!("the match is over; start a new match to keep playing")
+        }
+        let mut rng = StdRng::seed_from_u64(seed);
+        let mut state = self.clone();
+        let mut msgs = vec![];
+
+        msgs.extend(state.set_advancement_policy(*bail_unwrap!([
+            AdvancementPolicy::Unrestricted,
+            AdvancementPolicy::FullyUnrestricted,
+            AdvancementPolicy::DefendPoints,
+        ]
+        .choose(&mut rng)))?);
+        msgs.extend(state.set_kitty_penalty(*bail_unwrap!(
+            [KittyPenalty::Times, KittyPenalty::Power, KittyPenalty::None].choose(&mut rng)
+        ))?);
+        msgs.extend(state.set_kitty_bid_policy(*bail_unwrap!([
+            KittyBidPolicy::FirstCard,
+            KittyBidPolicy::FirstCardOfLevelOrHighest,
+        ]
+        .choose(&mut rng)))?);
+        msgs.extend(state.set_trick_draw_policy(*bail_unwrap!([
+            TrickDrawPolicy::NoProtections,
+            TrickDrawPolicy::LongerTuplesProtected,
+            TrickDrawPolicy::OnlyDrawTractorOnTractor,
+            TrickDrawPolicy::NoFormatBasedDraw,
+        ]
+        .choose(&mut rng)))?);
+        msgs.extend(state.set_throw_evaluation_policy(*bail_unwrap!([
+            ThrowEvaluationPolicy::All,
+            ThrowEvaluationPolicy::Highest,
+            ThrowEvaluationPolicy::TrickUnitLength,
+        ]
+        .choose(&mut rng)))?);
+
+        // Tractor only works with some numbers of players, so try the game
+        // modes and deck counts in a random order until one of them can be
+        // started. The kitty size is picked automatically.
+        let mut candidates = vec![];
+        for game_mode in &[
+            GameModeSettings::Tractor,
+            GameModeSettings::FindingFriends { num_friends: None },
+        ] {
+            for num_decks in &[num_players / 2, num_players / 2 + 1] {
+                candidates.push((*game_mode, *num_decks));
+            }
+        }
+        candidates.shuffle(&mut rng);
+        for (game_mode, num_decks) in candidates {
+            let mut state_ = state.clone();
+            let mut msgs_ = vec![];
+            if game_mode != GameModeSettings::Tractor {
+                // Friends are picked during the exchange, and the number of
+                // friends is chosen automatically.
+                msgs_.extend(state_.set_skip_exchange(false)?);
+                msgs_.extend(state_.set_friend_bounds(None, None)?);
+            }
+            msgs_.extend(state_.set_game_mode(game_mode)?);
+            msgs_.extend(state_.set_num_decks(Some(num_decks))?);
+            if state_.check_startable().is_ok() {
+                *self = state_;
+                msgs.extend(msgs_);
+                return Ok(msgs);
+            }
+        }
+        bail!("couldn't find settings that the game can be started with")
+    }
+
+    /// Runs the checks in `start` which don't depend on who is starting the
+    /// game.
+    fn check_startable(&self) -> Result<(), Error> {
+        self.game_mode()?;
+        let level = self
+            .landlord_position()
+            .map(|p| self.propagated.players[p].rank());
+        let decks = self.playable_decks(level)?;
+        self.kitty_plan(&decks, level, self.propagated.kitty_size)?;
+        Ok(())
+    }
+
+    fn game_mode(&self) -> Result<GameMode, Error> {
+        let min_friends = self.propagated.min_friends.unwrap_or(0);
+        let max_friends = self.propagated.max_friends.unwrap_or(usize::MAX);
+        Ok(match self.propagated.game_mode {
+            GameModeSettings::FindingFriends { .. }
+                if min_friends >= self.propagated.players.len().saturating_sub(1) =>
+            {
+                bail!("not enough players for the minimum number of friends")
+            }
+            GameModeSettings::FindingFriends {
+                num_friends: Some(num_friends),
+                ..
+            } if num_friends < min_friends || num_friends > max_friends => {
+                bail!("the number of friends is outside of the allowed bounds")
+            }
+            GameModeSettings::FindingFriends {
+                num_friends: Some(num_friends),
                 ..
             } if num_friends + 1 < self.propagated.players.len() => GameMode::FindingFriends {
                 num_friends,
                 friends: vec![],
             },
             GameModeSettings::FindingFriends { .. } => GameMode::FindingFriends {
-                num_friends: (self.propagated.players.len() / 2) - 1,
+                num_friends: ((self.propagated.players.len() / 2) - 1)
+                    .max(min_friends)
+                    .min(max_friends),
                 friends: vec![],
             },
             GameModeSettings::Tractor if self.propagated.players.len() % 2 == 0 => {
@@ -1324,161 +3421,137 @@ impl InitializePhase {
             GameModeSettings::Tractor => {
                 bail!("can only play tractor with an even number of players")
             }
-        };
-
-        let mut rng = rand::thread_rng();
-
-        let position = self
-            .propagated
-            .landlord
-            .and_then(|landlord| {
-                self.propagated
-                    .players
-                    .iter()
-                    .position(|p| p.id == landlord)
-            })
-            .unwrap_or(rng.next_u32() as usize % self.propagated.players.len());
+        })
+    }
 
-        let level = if self.propagated.landlord.is_some() {
-            Some(self.propagated.players[position].rank())
-        } else {
-            None
-        };
+    fn landlord_position(&self) -> Option<usize> {
+        self.propagated.landlord.and_then(|landlord| {
+            self.propagated
+                .players
+                .iter()
+                .position(|p| p.id == landlord)
+        })
+    }
 
-        let num_decks = self.propagated.num_decks();
-        if num_decks == 0 {
+    /// Returns the decks to deal from, if they can be bid on.
+    fn playable_decks(&self, level: Option<Number>) -> Result<Vec<Deck>, Error> {
+        if self.propagated.num_decks() == 0 {
             bail!("need at least one deck to start the game");
         }
         let decks = self.propagated.decks()?;
-        let mut deck = Vec::with_capacity(decks.iter().map(|d| d.len()).sum::<usize>());
-        for deck_ in &decks {
-            deck.extend(deck_.cards());
-        }
         // Ensure that it is possible to bid for the landlord, if set, or all players, if not.
         match level {
-            Some(level) if decks.iter().any(|d| d.includes_number(level)) => (),
+            Some(level) if decks.iter().any(|d| d.can_bid(level)) => (),
             None if self
                 .players
                 .iter()
-                .all(|p| decks.iter().any(|d| d.includes_number(p.level))) => {}
+                .all(|p| decks.iter().any(|d| d.can_bid(p.level))) => {}
             _ => bail!("deck configuration is missing cards needed to bid"),
         }
+        Ok(decks)
+    }
 
-        deck.shuffle(&mut rng);
+    /// Determines how large the kitty will be, and which cards have to be
+    /// removed from the deck to deal it out evenly.
+    fn kitty_plan(
+        &self,
+        decks: &[Deck],
+        level: Option<Number>,
+        kitty_size: Option<usize>,
+    ) -> Result<(KittyArrangement, Vec<Card>), Error> {
+        let arrangement = kitty_arrangement(
+            decks,
+            self.propagated.players.len(),
+            kitty_size,
+            self.propagated.remainder_policy,
+        )?;
+        if arrangement.num_removed_cards == 0 {
+            return Ok((arrangement, vec![]));
+        }
 
-        let mut removed_cards = vec![];
+        let min_number: Number = decks
+            .iter()
+            .map(|d| d.min)
+            .min()
+            .ok_or_else(|| anyhow!("no minimum value in deck?"))?;
+
+        // Choose a card to remove that doesn't unfairly disadvantage a particular player,
+        // and ideally isn't points either.
+        let bad_levels = match level {
+            // If the minimum value is an A, this will fall back to removing a
+            // trump card from the deck...
+            Some(level) => vec![level].into_iter().collect::<HashSet<Number>>(),
+            None => {
+                let mut bad_levels = self
+                    .propagated
+                    .players
+                    .iter()
+                    .map(|p| p.level)
+                    .collect::<HashSet<Number>>();
+                bad_levels.insert(Number::Five);
+                bad_levels.insert(Number::Ten);
+                bad_levels.insert(Number::King);
+                bad_levels
+            }
+        };
+        let mut n = min_number;
+        let removed_card_number = loop {
+            // Ranks which were excluded from every deck can't be removed.
+            if !bad_levels.contains(&n) && decks.iter().any(|d| d.includes_number(n)) {
+                break n;
+            }
+            n = match n.successor() {
+                Some(nn) => nn,
+                // If we somehow have enough players that we can't remove cards
+                // without disadvantaging _someone_, or choosing points,
+                // arbitrarily choose to remove twos.
+                None => break min_number,
+            };
+        };
 
-        let kitty_size = match self.propagated.kitty_size {
-            Some(size)
-                if deck.len() % self.propagated.players.len()
-                    == size % self.propagated.players.len() =>
-            {
-                size
+        let mut available = HashMap::new();
+        for deck in decks {
+            for card in deck.cards() {
+                *available.entry(card).or_insert(0) += 1;
             }
-            Some(size) => {
-                // Remove cards from the deck, until the deck and kitty together work out to the
-                // appropriate number of cards.
-                let num_players = self.propagated.players.len();
+        }
 
-                let min_number: Number = decks
-                    .iter()
-                    .map(|d| d.min)
-                    .min()
-                    .ok_or_else(|| anyhow!("no minimum value in deck?"))?;
-
-                // Choose a card to remove that doesn't unfairly disadvantage a particular player,
-                // and ideally isn't points either.
-                let removed_card_number = match level {
-                    Some(level) if level == min_number => {
-                        // If the minimum value isn't an A, this will be reasonable, otherwise
-                        // it'll remove a trump card from the deck...
-                        min_number.successor().unwrap_or(min_number)
-                    }
-                    Some(_) => min_number,
-                    None => {
-                        let mut bad_levels = self
-                            .propagated
-                            .players
-                            .iter()
-                            .map(|p| p.level)
-                            .collect::<HashSet<Number>>();
-                        bad_levels.insert(Number::Five);
-                        bad_levels.insert(Number::Ten);
-                        bad_levels.insert(Number::King);
-                        let mut n = min_number;
-                        loop {
-                            if !bad_levels.contains(&n) {
-                                break n;
-                            }
-                            n = match n.successor() {
-                                Some(nn) => nn,
-                                // If we somehow have enough players that we can't remove cards
-                                // without disadvantaging _someone_, or choosing points,
-                                // arbitrarily choose to remove twos.
-                                None => break min_number,
-                            };
-                        }
-                    }
+        let mut cards_to_remove = vec![];
+        let mut suit_idx = ALL_SUITS.len() - 1;
+        for _ in 0..arrangement.num_removed_cards {
+            // Skip over suits which have run out of the card, e.g. because it
+            // was excluded from the decks.
+            let mut card_to_remove = None;
+            for _ in 0..ALL_SUITS.len() {
+                let card = Card::Suited {
+                    suit: ALL_SUITS[suit_idx],
+                    number: removed_card_number,
                 };
-
-                let mut suit_idx = ALL_SUITS.len() - 1;
-
-                while deck.len() % num_players != size % num_players {
-                    let card_to_remove = Card::Suited {
-                        suit: ALL_SUITS[suit_idx],
-                        number: removed_card_number,
-                    };
-                    suit_idx = if suit_idx == 0 {
-                        ALL_SUITS.len() - 1
-                    } else {
-                        suit_idx - 1
-                    };
-
-                    // Attempt to remove the card from the deck.
-                    match deck.iter().position(|c| *c == card_to_remove) {
-                        Some(idx) => {
-                            deck.remove(idx);
-                            removed_cards.push(card_to_remove);
-                        }
-                        // Note: we would only hit this case if there are fewer decks than players,
-                        // which should be prevented in the settings layer.
-                        None => bail!(format!(
-                            "Couldn't find {:?} in the deck to remove",
-                            card_to_remove
-                        )),
-                    }
+                suit_idx = if suit_idx == 0 {
+                    ALL_SUITS.len() - 1
+                } else {
+                    suit_idx - 1
+                };
+                if available.get(&card).copied().unwrap_or(0) > 0 {
+                    card_to_remove = Some(card);
+                    break;
                 }
-                size
             }
-            None => {
-                let mut kitty_size = deck.len() % self.propagated.players.len();
-                if kitty_size == 0 {
-                    kitty_size = self.propagated.players.len();
-                }
-                if kitty_size < 5 {
-                    kitty_size += self.propagated.players.len();
+
+            // Note: we would only run out of cards if there are fewer decks than players,
+            // which should be prevented in the settings layer.
+            match card_to_remove {
+                Some(card) => {
+                    *available.entry(card).or_insert(0) -= 1;
+                    cards_to_remove.push(card);
                 }
-                kitty_size
+                None => bail!(format!(
+                    "Couldn't find any {:?}s in the deck to remove",
+                    removed_card_number
+                )),
             }
-        };
-
-        let propagated = self.propagated.clone();
-
-        Ok(DrawPhase {
-            deck: (&deck[0..deck.len() - kitty_size]).to_vec(),
-            kitty: (&deck[deck.len() - kitty_size..]).to_vec(),
-            hands: Hands::new(self.propagated.players.iter().map(|p| p.id)),
-            bids: Vec::new(),
-            revealed_cards: 0,
-            autobid: None,
-            propagated,
-            position,
-            num_decks,
-            decks,
-            game_mode,
-            level,
-            removed_cards,
-        })
+        }
+        Ok((arrangement, cards_to_remove))
     }
 }
 
@@ -1499,12 +3572,79 @@ impl DerefMut for InitializePhase {
 #[cfg(test)]
 mod tests {
     use super::{
-        AdvancementPolicy, FriendSelection, GameMode, GameModeSettings, InitializePhase,
-        KittyTheftPolicy, MessageVariant, PlayPhase, Player,
+        compute_level_deltas, kitty_arrangement, ordinal, AdvancementPolicy, Correction, DrawPhase,
+        ExchangePhase, FriendSelection, GameMode, GameModeSettings, GameState, Hands,
+        InitializePhase, KittyArrangement, KittyTheftPolicy, KittyTheftRevealPolicy,
+        MessageVariant, PendingTakeBack, PlayPhase, Player, PointFlowEntry, PropagatedState,
+        Puzzle, PuzzleObjective, RemainderPolicy,
+    };
+
+    use std::collections::{HashMap, HashSet};
+
+    use slog::{o, Discard, Logger};
+
+    use crate::bidding::BidTakebackPolicy;
+    use crate::deck::Deck;
+    use crate::interactive::{Action, InteractiveGame};
+    use crate::settings::{
+        AbandonedGamePolicy, FirstLeadPolicy, FriendPointTransferPolicy, FriendSelectionPolicy,
+        GameModifier, JokerBidSuitPolicy, KittyOnLossPolicy, KittyPenalty, KittyRevealPolicy,
+        KittyTheftBidPolicy, OutOfTurnPolicy, PlayTakebackPolicy, SpectatorCardVisibility,
+        VacatedSeatPolicy, WeakHandRedeal, MAX_PAST_GAMES,
     };
+    use crate::types::{
+        cards, Card, EffectiveSuit, Number, PermanentTrumps, PlayerID, Suit, Trump, FULL_DECK,
+    };
+
+    /// Stacks the deck so that the players in `hands` draw their cards in
+    /// turn, starting from the first player, with the bottom filled with
+    /// `kitty` (if given).
+    fn stack_deck<const N: usize>(
+        draw: &mut DrawPhase,
+        hands: &[(PlayerID, [Card; N])],
+        kitty: Option<Card>,
+    ) {
+        draw.deck = (0..N)
+            .flat_map(|i| hands.iter().map(move |(_, h)| h[i]))
+            .rev()
+            .collect();
+        if let Some(kitty) = kitty {
+            for card in &mut draw.kitty {
+                *card = kitty;
+            }
+        }
+        draw.position = 0;
+    }
+
+    /// Starts the game and deals each player their cards from `hands`, with
+    /// the bottom filled with `kitty` (if given).
+    fn deal<const N: usize>(
+        init: InitializePhase,
+        hands: &[(PlayerID, [Card; N])],
+        kitty: Option<Card>,
+    ) -> DrawPhase {
+        let mut draw = init.start(PlayerID(0)).unwrap();
+        stack_deck(&mut draw, hands, kitty);
+        for _ in 0..N {
+            for (p, _) in hands {
+                draw.draw_card(*p).unwrap();
+            }
+        }
+        draw
+    }
 
-    use crate::settings::FriendSelectionPolicy;
-    use crate::types::{cards, Card, Number, PlayerID, FULL_DECK};
+    /// Deals each player their cards from `hands` as in `deal`, and has
+    /// `bidder` win with a single `bid` card.
+    fn deal_and_bid<const N: usize>(
+        init: InitializePhase,
+        hands: &[(PlayerID, [Card; N])],
+        kitty: Option<Card>,
+        (bidder, bid): (PlayerID, Card),
+    ) -> ExchangePhase {
+        let mut draw = deal(init, hands, kitty);
+        assert!(draw.bid(bidder, bid, 1));
+        draw.advance(bidder).unwrap()
+    }
 
     #[test]
     fn test_player_level_deltas() {
@@ -1514,24 +3654,28 @@ mod tests {
                 name: "p1".into(),
                 level: Number::Four,
                 metalevel: 0,
+                reconnect_token: String::new(),
             },
             Player {
                 id: PlayerID(1),
                 name: "p2".into(),
                 level: Number::Four,
                 metalevel: 0,
+                reconnect_token: String::new(),
             },
             Player {
                 id: PlayerID(2),
                 name: "p3".into(),
                 level: Number::Four,
                 metalevel: 0,
+                reconnect_token: String::new(),
             },
             Player {
                 id: PlayerID(3),
                 name: "p4".into(),
                 level: Number::Four,
                 metalevel: 0,
+                reconnect_token: String::new(),
             },
         ];
         let mut players_ = players.clone();
@@ -1545,6 +3689,7 @@ mod tests {
             true,
             (PlayerID(0), Number::Ace),
             AdvancementPolicy::Unrestricted,
+            &HashMap::new(),
         );
         for p in &players {
             assert_eq!(p.rank(), Number::Six);
@@ -1559,6 +3704,7 @@ mod tests {
             true,
             (PlayerID(0), Number::Ace),
             AdvancementPolicy::DefendPoints,
+            &HashMap::new(),
         );
         for p in &players_ {
             assert_eq!(p.rank(), Number::Five);
@@ -1574,6 +3720,7 @@ mod tests {
             true,
             (PlayerID(0), Number::Ace),
             AdvancementPolicy::DefendPoints,
+            &HashMap::new(),
         );
         for p in &players_ {
             if p.id == PlayerID(0) || p.id == PlayerID(2) {
@@ -1584,6 +3731,79 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_defend_points_then_freeze() {
+        for ceiling in &[Number::Ten, Number::King, Number::Ace] {
+            let policy = AdvancementPolicy::DefendPointsThenFreeze { ceiling: *ceiling };
+            let mut players = [
+                Player {
+                    id: PlayerID(0),
+                    name: "p1".into(),
+                    level: Number::Four,
+                    metalevel: 0,
+                    reconnect_token: String::new(),
+                },
+                Player {
+                    id: PlayerID(1),
+                    name: "p2".into(),
+                    level: Number::Four,
+                    metalevel: 0,
+                    reconnect_token: String::new(),
+                },
+            ];
+
+            // Attackers still have to stop and defend on point ranks.
+            let _ = PlayPhase::compute_player_level_deltas(
+                players[1..].iter_mut(),
+                3,
+                0,
+                &[PlayerID(0)],
+                false,
+                (PlayerID(0), Number::Four),
+                policy,
+                &HashMap::new(),
+            );
+            assert_eq!(players[1].rank(), Number::Five);
+
+            // Defending one rank at a time walks up through the point ranks
+            // until the ceiling is reached.
+            let mut rank = Number::Four;
+            while rank < *ceiling {
+                let msgs = PlayPhase::compute_player_level_deltas(
+                    players[..1].iter_mut(),
+                    0,
+                    1,
+                    &[PlayerID(0)],
+                    true,
+                    (PlayerID(0), rank),
+                    policy,
+                    &HashMap::new(),
+                );
+                assert!(!msgs
+                    .iter()
+                    .any(|m| matches!(m, MessageVariant::AdvancementBlocked { .. })));
+                rank = rank.successor().unwrap();
+                assert_eq!(players[0].rank(), rank);
+            }
+
+            let msgs = PlayPhase::compute_player_level_deltas(
+                players[..1].iter_mut(),
+                0,
+                2,
+                &[PlayerID(0)],
+                true,
+                (PlayerID(0), rank),
+                policy,
+                &HashMap::new(),
+            );
+            assert_eq!(players[0].rank(), *ceiling);
+            assert!(msgs.iter().any(|m| matches!(
+                m,
+                MessageVariant::AdvancementBlocked { player: PlayerID(0), rank } if rank == ceiling
+            )));
+        }
+    }
+
     #[test]
     fn test_unusual_kitty_sizes() {
         let mut init = InitializePhase::new();
@@ -1612,6 +3832,208 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_diff_from_default() {
+        let mut init = InitializePhase::new();
+        init.add_player("p1".into()).unwrap();
+        init.add_player("p2".into()).unwrap();
+        assert_eq!(PropagatedState::default().diff_from_default(), vec![]);
+        // Players aren't part of the rules.
+        assert_eq!(init.diff_from_default(), vec![]);
+
+        init.set_kitty_penalty(KittyPenalty::Power).unwrap();
+        init.hide_played_cards(true).unwrap();
+        assert_eq!(
+            init.diff_from_default(),
+            vec![
+                ("kitty_penalty".to_string(), "Power".to_string()),
+                ("hide_played_cards".to_string(), "true".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_valid_kitty_sizes() {
+        let mut init = InitializePhase::new();
+        let p1 = init.add_player("p1".into()).unwrap().0;
+        init.add_player("p2".into()).unwrap();
+        init.add_player("p3".into()).unwrap();
+        assert!(init.valid_kitty_sizes().is_err());
+        init.set_game_mode(GameModeSettings::FindingFriends { num_friends: None })
+            .unwrap();
+        for n_players in 4..8 {
+            init.add_player(format!("p{}", n_players)).unwrap();
+            for n_decks in 1..n_players {
+                let mut init_ = init.clone();
+                init_.set_num_decks(Some(n_decks)).unwrap();
+                let valid_sizes = init_.valid_kitty_sizes().unwrap();
+                assert!(!valid_sizes.is_empty());
+                for kitty_size in 1..n_decks * FULL_DECK.len() {
+                    let mut init_ = init_.clone();
+                    init_.propagated.kitty_size = Some(kitty_size);
+                    match init_.start(p1) {
+                        Ok(draw) => {
+                            assert!(valid_sizes.contains(&kitty_size));
+                            assert_eq!(draw.kitty.len(), kitty_size);
+                        }
+                        Err(_) => assert!(!valid_sizes.contains(&kitty_size)),
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_excluded_cards() {
+        let mut init = InitializePhase::new();
+        for i in 0..4 {
+            init.add_player(format!("p{}", i)).unwrap();
+        }
+        let params = init.game_scoring_parameters.clone();
+        let total_points = |decks: &[Deck]| params.materialize(decks).unwrap().total_points();
+        let full_points = total_points(&init.decks().unwrap());
+
+        // Remove the spade point cards.
+        let excluded = vec![cards::S_5, cards::S_10, cards::S_K];
+        init.set_excluded_cards(excluded.clone()).unwrap();
+        let draw = init.start(PlayerID(0)).unwrap();
+        let dealt = draw
+            .deck
+            .iter()
+            .chain(draw.kitty.iter())
+            .chain(draw.removed_cards.iter())
+            .collect::<Vec<_>>();
+        assert_eq!(dealt.len(), 2 * 54 - 6);
+        assert!(dealt.iter().all(|c| !excluded.contains(c)));
+        assert_eq!(draw.deck.len() % 4, 0);
+        assert_eq!(total_points(&draw.decks), full_points - 2 * 25);
+
+        // Nobody could bid if all of the twos were gone.
+        init.set_excluded_cards(vec![cards::S_2, cards::H_2, cards::C_2, cards::D_2])
+            .unwrap_err();
+        init.set_excluded_cards(vec![Card::Unknown]).unwrap_err();
+        init.set_excluded_cards(vec![]).unwrap();
+        assert_eq!(total_points(&init.decks().unwrap()), full_points);
+
+        // Cards which have to be removed to even out the deal come from the
+        // lowest rank which is left.
+        init.set_excluded_cards(vec![cards::S_3, cards::H_3, cards::C_3, cards::D_3])
+            .unwrap();
+        init.set_kitty_size(Some(5)).unwrap();
+        let draw = init.start(PlayerID(0)).unwrap();
+        assert!(!draw.removed_cards.is_empty());
+        assert!(draw
+            .removed_cards
+            .iter()
+            .all(|c| c.number() == Some(Number::Four)));
+    }
+
+    #[test]
+    fn test_jokerless_decks() {
+        let jokerless = Deck {
+            num_big_jokers: 0,
+            num_small_jokers: 0,
+            ..Default::default()
+        };
+        let mut init = InitializePhase::new();
+        let p1 = init.add_player("p1".into()).unwrap().0;
+        init.add_player("p2".into()).unwrap();
+        init.add_player("p3".into()).unwrap();
+        init.set_game_mode(GameModeSettings::FindingFriends { num_friends: None })
+            .unwrap();
+        for n_players in 4..8 {
+            init.add_player(format!("p{}", n_players)).unwrap();
+            for n_decks in 1..=n_players {
+                let mut init_ = init.clone();
+                init_.set_num_decks(Some(n_decks)).unwrap();
+                init_
+                    .set_special_decks(vec![jokerless.clone(); n_decks])
+                    .unwrap();
+                let draw = init_.start(p1).unwrap();
+                assert_eq!(draw.deck.len() % n_players, 0);
+                assert_eq!(
+                    draw.removed_cards.len() + draw.deck.len() + draw.kitty.len(),
+                    n_decks * jokerless.len()
+                );
+                assert!(draw
+                    .deck
+                    .iter()
+                    .chain(draw.kitty.iter())
+                    .all(|c| !c.is_joker()));
+            }
+        }
+
+        let mut init_ = init.clone();
+        assert!(init_
+            .set_special_decks(vec![Deck {
+                num_big_jokers: 5,
+                ..Default::default()
+            }])
+            .is_err());
+    }
+
+    #[test]
+    fn test_kitty_arrangement() {
+        let decks = [Deck::default(), Deck::default()];
+        let kitty_sizes = (4..=8)
+            .map(|n| {
+                kitty_arrangement(&decks, n, None, RemainderPolicy::GrowKitty)
+                    .unwrap()
+                    .kitty_size
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(kitty_sizes, vec![8, 8, 6, 10, 12]);
+        for n in 4..=8 {
+            let arrangement =
+                kitty_arrangement(&decks, n, None, RemainderPolicy::GrowKitty).unwrap();
+            assert_eq!(arrangement.num_removed_cards, 0);
+        }
+
+        assert_eq!(
+            kitty_arrangement(&decks, 7, None, RemainderPolicy::RemoveCards).unwrap(),
+            KittyArrangement {
+                kitty_size: 7,
+                num_removed_cards: 3,
+            }
+        );
+        assert!(kitty_arrangement(&decks, 8, Some(104), RemainderPolicy::GrowKitty).is_err());
+        assert!(kitty_arrangement(&decks[..1], 8, Some(47), RemainderPolicy::GrowKitty).is_err());
+    }
+
+    #[test]
+    fn test_remainder_policy() {
+        let mut init = InitializePhase::new();
+        let p1 = init.add_player("p1".into()).unwrap().0;
+        for p in 2..=5 {
+            init.add_player(format!("p{}", p)).unwrap();
+        }
+        init.set_game_mode(GameModeSettings::FindingFriends { num_friends: None })
+            .unwrap();
+        // Two decks can't be evenly split between five players.
+        assert_eq!(init.num_decks(), 2);
+
+        let draw = init.start(p1).unwrap();
+        assert_eq!(draw.kitty.len(), 8);
+        assert!(draw.removed_cards.is_empty());
+
+        init.set_remainder_policy(RemainderPolicy::RemoveCards)
+            .unwrap();
+        let draw = init.start(p1).unwrap();
+        assert_eq!(draw.kitty.len(), 5);
+        assert_eq!(draw.removed_cards.len(), 3);
+        assert_eq!(draw.deck.len() % 5, 0);
+        assert_eq!(
+            draw.removed_cards.len() + draw.deck.len() + draw.kitty.len(),
+            2 * FULL_DECK.len()
+        );
+
+        init.set_remainder_policy(RemainderPolicy::SmallerLastRound)
+            .unwrap();
+        let draw = init.start(p1).unwrap();
+        assert_eq!(draw.kitty.len(), 3);
+        assert!(draw.removed_cards.is_empty());
+    }
+
     #[test]
     fn test_bid_sequence() {
         let mut init = InitializePhase::new();
@@ -1650,27 +4072,1486 @@ mod tests {
     }
 
     #[test]
-    fn test_kitty_stealing_bid_sequence() {
+    fn test_blind_bid() {
         let mut init = InitializePhase::new();
         let p1 = init.add_player("p1".into()).unwrap().0;
         let p2 = init.add_player("p2".into()).unwrap().0;
         let p3 = init.add_player("p3".into()).unwrap().0;
         let p4 = init.add_player("p4".into()).unwrap().0;
-        init.set_kitty_theft_policy(KittyTheftPolicy::AllowKittyTheft)
-            .unwrap();
+        init.set_blind_bid_bonus(Some(2)).unwrap();
+
         let mut draw = init.start(PlayerID(0)).unwrap();
-        // Hackily ensure that everyone can bid.
-        draw.deck = vec![
-            cards::S_2,
-            Card::SmallJoker,
-            Card::BigJoker,
-            cards::H_2,
-            cards::S_2,
-            Card::SmallJoker,
-            Card::BigJoker,
-            cards::H_2,
+        let hands = [
+            (p1, [cards::S_2, cards::S_A]),
+            (p2, [cards::C_3, cards::C_4]),
+            (p3, [cards::D_3, cards::D_4]),
+            (p4, [cards::H_3, cards::H_4]),
         ];
-        draw.position = 0;
+        stack_deck(&mut draw, &hands, None);
+
+        // p1 bids on the two of spades before having seen any cards.
+        draw.blind_bid(p1, cards::S_2, 1).unwrap();
+        // Bids which the player can already back aren't blind.
+        draw.draw_card(p1).unwrap();
+        assert!(draw.blind_bid(p1, cards::S_2, 1).is_err());
+
+        for _ in 0..2 {
+            for p in &[p2, p3, p4, p1] {
+                if !draw.done_drawing() {
+                    assert!(draw.draw_card(*p).unwrap().is_empty());
+                }
+            }
+        }
+        // Once the hand is fully drawn, the player can't bid blindly.
+        assert!(draw.blind_bid(p2, Card::BigJoker, 2).is_err());
+
+        let exchange = draw.advance(p1).unwrap();
+        assert_eq!(
+            exchange.trump(),
+            Trump::Standard {
+                suit: Suit::Spades,
+                number: Number::Two,
+                permanent: PermanentTrumps::NONE
+            }
+        );
+        let mut play = exchange.advance(p1).unwrap();
+        assert_eq!(play.blind_bidder, Some(p1));
+
+        for i in 0..2 {
+            for (p, h) in &hands {
+                play.play_cards(*p, &h[i..=i]).unwrap();
+            }
+            play.finish_trick().unwrap();
+        }
+
+        // The landlord team holds the opponents to zero points, and gets two
+        // more levels for winning on a blind bid.
+        let (new_init_phase, summary, msgs) = play.finish_game().unwrap();
+        assert!(summary.landlord_won);
+        assert!(msgs
+            .iter()
+            .any(|m| matches!(m, MessageVariant::BlindBidBonusEarned { bonus: 2 })));
+        assert_eq!(
+            new_init_phase
+                .propagated
+                .players
+                .into_iter()
+                .map(|p| p.level)
+                .collect::<Vec<Number>>(),
+            vec![Number::Seven, Number::Two, Number::Seven, Number::Two],
+        );
+    }
+
+    #[test]
+    fn test_max_metalevel() {
+        let play_game = |max_metalevel: Option<usize>, attackers_metalevel: usize| {
+            let mut init = InitializePhase::new();
+            let p1 = init.add_player("p1".into()).unwrap().0;
+            let p2 = init.add_player("p2".into()).unwrap().0;
+            let p3 = init.add_player("p3".into()).unwrap().0;
+            let p4 = init.add_player("p4".into()).unwrap().0;
+            for p in &[p1, p2, p3, p4] {
+                init.set_rank(*p, Number::Ace).unwrap();
+            }
+            for player in &mut init.propagated.players {
+                if player.id == p2 || player.id == p4 {
+                    player.metalevel = attackers_metalevel;
+                }
+            }
+            init.set_landlord(Some(p1)).unwrap();
+            init.set_max_metalevel(max_metalevel).unwrap();
+
+            let hands = [
+                (p1, [cards::S_A, cards::S_K]),
+                (p2, [cards::C_3, cards::C_4]),
+                (p3, [cards::D_3, cards::D_4]),
+                (p4, [cards::H_3, cards::H_4]),
+            ];
+            let exchange = deal_and_bid(init, &hands, None, (p1, cards::S_A));
+            let mut play = exchange.advance(p1).unwrap();
+            for i in 0..2 {
+                for (p, h) in &hands {
+                    play.play_cards(*p, &h[i..=i]).unwrap();
+                }
+                play.finish_trick().unwrap();
+            }
+            let (init, summary, msgs) = play.finish_game().unwrap();
+            assert!(summary.landlord_won);
+            let winners = msgs
+                .iter()
+                .filter_map(|m| match m {
+                    MessageVariant::MatchWon { player } => Some(*player),
+                    _ => None,
+                })
+                .collect::<Vec<_>>();
+            (init, winners, vec![p1, p3])
+        };
+
+        // Without a maximum, the landlord's team just goes around again.
+        let (mut init, winners, _) = play_game(None, 1);
+        assert!(winners.is_empty());
+        assert!(init.match_winners().is_empty());
+        assert!(init.start(PlayerID(0)).is_ok());
+        // Nobody has won, so there's no match to restart.
+        assert!(init.start_new_match().is_err());
+
+        // Defending on Ace takes the landlord's team to the second metalevel,
+        // which ends the match.
+        let (mut init, winners, landlords_team) = play_game(Some(2), 1);
+        assert_eq!(winners, landlords_team);
+        assert_eq!(init.match_winners(), &landlords_team[..]);
+        assert!(init.start(PlayerID(0)).is_err());
+
+        assert!(matches!(
+            init.start_new_match().unwrap()[..],
+            [MessageVariant::NewMatchStarted]
+        ));
+        assert!(init.match_winners().is_empty());
+        assert!(init
+            .propagated
+            .players
+            .iter()
+            .all(|p| p.level == Number::Two && p.metalevel == 1));
+        assert!(init.start(PlayerID(0)).is_ok());
+
+        // The match may go on for longer.
+        let (init, winners, _) = play_game(Some(3), 1);
+        assert!(winners.is_empty());
+        assert!(init.match_winners().is_empty());
+
+        // Players who were already past the maximum didn't win it this game.
+        let (init, winners, landlords_team) = play_game(Some(2), 2);
+        assert_eq!(winners, landlords_team);
+        assert_eq!(init.match_winners(), &landlords_team[..]);
+
+        assert!(InitializePhase::new().set_max_metalevel(Some(1)).is_err());
+    }
+
+    #[test]
+    fn test_kitty_reveal_policy() {
+        for (policy, kitty_card, attackers_win, should_reveal) in [
+            (KittyRevealPolicy::Never, cards::C_K, true, false),
+            (KittyRevealPolicy::Always, cards::C_7, true, true),
+            (
+                KittyRevealPolicy::OnlyIfContestedPoints,
+                cards::C_7,
+                true,
+                false,
+            ),
+            (
+                KittyRevealPolicy::OnlyIfContestedPoints,
+                cards::C_K,
+                true,
+                true,
+            ),
+            // The landlord's team keeps the points in the kitty.
+            (
+                KittyRevealPolicy::OnlyIfContestedPoints,
+                cards::C_K,
+                false,
+                false,
+            ),
+        ] {
+            let mut init = InitializePhase::new();
+            let p1 = init.add_player("p1".into()).unwrap().0;
+            let p2 = init.add_player("p2".into()).unwrap().0;
+            let p3 = init.add_player("p3".into()).unwrap().0;
+            let p4 = init.add_player("p4".into()).unwrap().0;
+            init.set_landlord(Some(p1)).unwrap();
+            init.set_kitty_reveal_policy(policy).unwrap();
+
+            // Whoever plays the ace wins the last trick, and with it the
+            // kitty.
+            let (landlord_last, attacker_last) = if attackers_win {
+                (cards::D_3, cards::D_A)
+            } else {
+                (cards::D_A, cards::D_3)
+            };
+            let hands = [
+                (p1, [cards::S_2, landlord_last]),
+                (p2, [cards::C_3, cards::D_4]),
+                (p3, [cards::C_4, cards::D_5]),
+                (p4, [cards::C_6, attacker_last]),
+            ];
+            let exchange = deal_and_bid(init, &hands, Some(kitty_card), (p1, cards::S_2));
+            let mut play = exchange.advance(p1).unwrap();
+            let mut msgs = vec![];
+            for i in 0..2 {
+                for (p, h) in &hands {
+                    play.play_cards(*p, &h[i..=i]).unwrap();
+                }
+                msgs.extend(play.finish_trick().unwrap());
+            }
+            assert_eq!(
+                msgs.iter()
+                    .any(|m| matches!(m, MessageVariant::EndOfGameKittyReveal { .. })),
+                should_reveal,
+                "{:?}",
+                policy
+            );
+        }
+
+        // The deprecated flag still reveals the kitty if no policy is set.
+        let mut propagated = PropagatedState {
+            should_reveal_kitty_at_end_of_game: true,
+            ..Default::default()
+        };
+        assert_eq!(propagated.kitty_reveal_policy(), KittyRevealPolicy::Always);
+        propagated
+            .set_kitty_reveal_policy(KittyRevealPolicy::OnlyIfContestedPoints)
+            .unwrap();
+        assert!(!propagated.should_reveal_kitty_at_end_of_game);
+        assert_eq!(
+            propagated.kitty_reveal_policy(),
+            KittyRevealPolicy::OnlyIfContestedPoints
+        );
+    }
+
+    #[test]
+    fn test_score_breakdown() {
+        let mut init = InitializePhase::new();
+        let p1 = init.add_player("p1".into()).unwrap().0;
+        let p2 = init.add_player("p2".into()).unwrap().0;
+        let p3 = init.add_player("p3".into()).unwrap().0;
+        let p4 = init.add_player("p4".into()).unwrap().0;
+        let director = init.add_observer("director".into()).unwrap();
+        init.set_landlord(Some(p1)).unwrap();
+        init.set_director(Some(director)).unwrap();
+
+        // The attackers win the last trick, which has fifteen points, and the
+        // kings in the kitty.
+        let hands = [
+            (p1, [cards::S_2, cards::D_3]),
+            (p2, [cards::C_3, cards::D_10]),
+            (p3, [cards::C_4, cards::D_5]),
+            (p4, [cards::C_6, cards::D_A]),
+        ];
+        let exchange = deal_and_bid(init, &hands, Some(cards::C_K), (p1, cards::S_2));
+        let mut play = exchange.advance(p1).unwrap();
+        play.penalties.insert(p1, 10);
+        for i in 0..2 {
+            for (p, h) in &hands {
+                play.play_cards(*p, &h[i..=i]).unwrap();
+            }
+            play.finish_trick().unwrap();
+        }
+
+        let breakdown = play.score_breakdown();
+        assert_eq!(breakdown.trick_points[&p2], 0);
+        assert_eq!(breakdown.trick_points[&p4], 15);
+        assert!(!breakdown.trick_points.contains_key(&p1));
+        assert_eq!(breakdown.kitty_points, 10 * play.kitty.len());
+        assert_eq!(breakdown.kitty_multiplier, 2);
+        assert_eq!(breakdown.penalty_points, 10);
+
+        let (non_landlord_points, score) = play.final_score().unwrap();
+        assert_eq!(breakdown.total(), non_landlord_points);
+        assert_eq!(
+            compute_level_deltas(
+                &play.propagated.game_scoring_parameters,
+                &play.decks,
+                breakdown.total(),
+                false,
+            )
+            .unwrap(),
+            score
+        );
+
+        // Moving the kitty's points away from the last trick's winner doesn't
+        // change the total.
+        let kings = vec![cards::C_K; 2 * play.kitty.len()];
+        play.director_reassign_points(director, p4, p2, kings)
+            .unwrap();
+        let moved = play.score_breakdown();
+        assert_eq!(moved.kitty_points, breakdown.kitty_points);
+        assert_eq!(moved.total(), breakdown.total());
+        assert_eq!(
+            moved.trick_points.values().sum::<usize>(),
+            breakdown.trick_points.values().sum::<usize>()
+        );
+    }
+
+    #[test]
+    fn test_kitty_on_loss_policy() {
+        for (policy, penalty, expected_points) in &[
+            (KittyOnLossPolicy::LastTrickWinner, 100, 100),
+            (KittyOnLossPolicy::AttackersOnLoss, 100, 180),
+            // The landlord's team won, so they keep the kitty.
+            (KittyOnLossPolicy::AttackersOnLoss, 0, 0),
+        ] {
+            let mut init = InitializePhase::new();
+            let p1 = init.add_player("p1".into()).unwrap().0;
+            let p2 = init.add_player("p2".into()).unwrap().0;
+            let p3 = init.add_player("p3".into()).unwrap().0;
+            let p4 = init.add_player("p4".into()).unwrap().0;
+            init.set_landlord(Some(p1)).unwrap();
+            init.set_kitty_on_loss_policy(*policy).unwrap();
+
+            // The landlord wins the only trick, and with it the kitty.
+            let hands = [
+                (p1, [cards::S_2]),
+                (p2, [cards::C_3]),
+                (p3, [cards::C_4]),
+                (p4, [cards::C_6]),
+            ];
+            let exchange = deal_and_bid(init, &hands, Some(cards::C_K), (p1, cards::S_2));
+            assert_eq!(exchange.kitty.len(), 8);
+            let mut play = exchange.advance(p1).unwrap();
+            // The landlord's penalty is enough to lose the game.
+            play.penalties.insert(p1, *penalty);
+            for (p, h) in &hands {
+                play.play_cards(*p, h).unwrap();
+            }
+            play.finish_trick().unwrap();
+
+            let (non_landlord_points, score) = play.final_score().unwrap();
+            assert_eq!(non_landlord_points, *expected_points);
+            assert_eq!(score.landlord_won, *penalty == 0);
+            assert_eq!(play.score_breakdown().total(), non_landlord_points);
+            // The live scores agree with the final result.
+            let (live_points, observed_points) = play.calculate_points();
+            assert_eq!(live_points, non_landlord_points);
+            assert_eq!(
+                play.points_needed_for_next_level(false).unwrap(),
+                crate::scoring::points_needed_for_next_level(
+                    &play.propagated.game_scoring_parameters,
+                    &play.decks,
+                    non_landlord_points,
+                    observed_points,
+                    false
+                )
+                .unwrap()
+            );
+
+            let (_, summary, msgs) = play.finish_game().unwrap();
+            assert_eq!(summary.non_landlord_points, *expected_points);
+            assert_eq!(
+                msgs.iter()
+                    .any(|m| matches!(m, MessageVariant::KittyAwardedOnLoss { points: 80 })),
+                *expected_points == 180
+            );
+        }
+    }
+
+    #[test]
+    fn test_next_landlord_preview() {
+        let mut init = InitializePhase::new();
+        let p1 = init.add_player("p1".into()).unwrap().0;
+        let p2 = init.add_player("p2".into()).unwrap().0;
+        let p3 = init.add_player("p3".into()).unwrap().0;
+        let p4 = init.add_player("p4".into()).unwrap().0;
+        init.set_landlord(Some(p1)).unwrap();
+
+        // The attackers win the last trick, and with it a kitty full of kings.
+        let hands = [
+            (p1, [cards::S_2, cards::D_3]),
+            (p2, [cards::C_3, cards::D_4]),
+            (p3, [cards::C_4, cards::D_5]),
+            (p4, [cards::C_6, cards::D_A]),
+        ];
+        let exchange = deal_and_bid(init, &hands, Some(cards::C_K), (p1, cards::S_2));
+        let mut play = exchange.advance(p1).unwrap();
+        for i in 0..2 {
+            for (p, h) in &hands {
+                play.play_cards(*p, &h[i..=i]).unwrap();
+            }
+            play.finish_trick().unwrap();
+        }
+
+        let next_landlord = play.next_landlord().unwrap();
+        assert_eq!(next_landlord, p2);
+        // The order of the landlord's team doesn't matter.
+        play.landlords_team.reverse();
+        assert_eq!(play.next_landlord().unwrap(), next_landlord);
+
+        let (_, summary, msgs) = play.finish_game().unwrap();
+        assert!(!summary.landlord_won);
+        assert!(msgs.iter().any(|m| matches!(
+            m,
+            MessageVariant::NewLandlordForNextGame { landlord } if *landlord == next_landlord
+        )));
+    }
+
+    #[test]
+    fn test_promote_observer() {
+        let mut init = InitializePhase::new();
+        let p1 = init.add_player("p1".into()).unwrap().0;
+        let p2 = init.add_player("p2".into()).unwrap().0;
+        let p3 = init.add_player("p3".into()).unwrap().0;
+        let p4 = init.add_player("p4".into()).unwrap().0;
+        init.set_landlord(Some(p1)).unwrap();
+        init.set_vacated_seat_policy(VacatedSeatPolicy::AllowObserverTakeover)
+            .unwrap();
+        let hands = [
+            (p1, [cards::S_2]),
+            (p2, [cards::D_4]),
+            (p3, [cards::C_4]),
+            (p4, [cards::D_A]),
+        ];
+        let mut draw = deal(init, &hands, None);
+        let observer = draw.add_observer("observer".into()).unwrap();
+        let late = draw.add_observer("late".into()).unwrap();
+        let mut state = GameState::Draw(draw);
+
+        // Nobody can be promoted into a seat that's still taken.
+        assert!(state.promote_observer(observer, 1).is_err());
+
+        // p2 is kicked, but their seat stays put and they can't rejoin.
+        let msgs = state.kick(p2).unwrap();
+        assert!(matches!(
+            msgs[..],
+            [MessageVariant::LeftGame { ref name }] if name == "p2"
+        ));
+        assert!(state.is_player(p2));
+        assert!(state.kick(p2).is_err());
+        assert!(state.register("p2".into()).is_err());
+
+        assert!(state.promote_observer(p1, 1).is_err());
+        assert!(state.promote_observer(observer, 0).is_err());
+        assert!(state.promote_observer(observer, 4).is_err());
+
+        // The observer takes over p2's seat.
+        let msgs = state.promote_observer(observer, 1).unwrap();
+        assert!(matches!(
+            msgs[..],
+            [MessageVariant::ObserverPromoted { player, ref replaced }]
+                if player == p2 && replaced == "p2"
+        ));
+        let propagated = state.propagated();
+        assert_eq!(
+            propagated.players.iter().map(|p| p.id).collect::<Vec<_>>(),
+            vec![p1, p2, p3, p4]
+        );
+        assert_eq!(propagated.players[1].name, "observer");
+        assert_eq!(propagated.observers.len(), 1);
+        assert!(propagated.vacated_seats.is_empty());
+        assert_eq!(state.player_id("observer").unwrap(), p2);
+        assert_eq!(state.register("observer".into()).unwrap().0, p2);
+        assert!(state.promote_observer(late, 1).is_err());
+
+        // The seat's hand now belongs to the observer, and the observer's old
+        // ID doesn't get to see anybody's cards.
+        let hand_of = |s: &GameState| match s {
+            GameState::Draw(d) => d.hands.get(p2).unwrap().clone(),
+            _ => unreachable!(),
+        };
+        assert_eq!(hand_of(&state.for_player(p2)).get(&cards::D_4), Some(&1));
+        assert_eq!(hand_of(&state.for_player(observer)).get(&cards::D_4), None);
+
+        // Seats which are still vacant when the game ends are given up.
+        state.kick(p3).unwrap();
+        state.reset().unwrap();
+        assert_eq!(
+            state
+                .propagated()
+                .players
+                .iter()
+                .map(|p| p.id)
+                .collect::<Vec<_>>(),
+            vec![p1, p2, p4, late]
+        );
+        assert!(state.propagated().vacated_seats.is_empty());
+
+        let mut init = InitializePhase::new();
+        init.add_player("p1".into()).unwrap();
+        let observer = init.add_observer("observer".into()).unwrap();
+        assert!(GameState::Initialize(init)
+            .promote_observer(observer, 0)
+            .is_err());
+    }
+
+    #[test]
+    fn test_vacated_seat_stays_vacant_by_default() {
+        let mut init = InitializePhase::new();
+        let p1 = init.add_player("p1".into()).unwrap().0;
+        let p2 = init.add_player("p2".into()).unwrap().0;
+        init.add_player("p3".into()).unwrap();
+        init.add_player("p4".into()).unwrap();
+        init.set_landlord(Some(p1)).unwrap();
+        let mut draw = init.start(PlayerID(0)).unwrap();
+        let observer = draw.add_observer("observer".into()).unwrap();
+        let mut state = GameState::Draw(draw);
+
+        state.kick(p2).unwrap();
+        assert!(state.promote_observer(observer, 1).is_err());
+        assert!(state.is_observer(observer));
+        assert!(state.propagated().is_vacated(p2));
+    }
+
+    #[test]
+    fn test_game_mvp() {
+        let mut init = InitializePhase::new();
+        let p1 = init.add_player("p1".into()).unwrap().0;
+        let p2 = init.add_player("p2".into()).unwrap().0;
+        let p3 = init.add_player("p3".into()).unwrap().0;
+        let p4 = init.add_player("p4".into()).unwrap().0;
+        init.set_landlord(Some(p1)).unwrap();
+
+        // p2 wins the first trick with a ten, and p4 wins the other two,
+        // including the last trick with a kitty full of kings.
+        let hands = [
+            (p1, [cards::C_3, cards::D_3, cards::H_2]),
+            (p2, [cards::C_10, cards::D_4, cards::C_7]),
+            (p3, [cards::C_4, cards::D_5, cards::C_8]),
+            (p4, [cards::C_6, cards::D_A, Card::BigJoker]),
+        ];
+        let exchange = deal_and_bid(init, &hands, Some(cards::C_K), (p1, cards::H_2));
+        let mut play = exchange.advance(p1).unwrap();
+        for (i, leader) in [0, 1, 3].iter().enumerate() {
+            for offset in 0..4 {
+                let (p, h) = hands[(leader + offset) % 4];
+                play.play_cards(p, &h[i..=i]).unwrap();
+            }
+            play.finish_trick().unwrap();
+        }
+
+        let points = |id| {
+            play.points[&id]
+                .iter()
+                .flat_map(|c| c.points())
+                .sum::<usize>()
+        };
+        assert_eq!(points(p2), 10);
+        assert!(points(p4) > points(p2));
+        assert_eq!(play.compute_mvp(), Some(p4));
+
+        let (_, summary, msgs) = play.finish_game().unwrap();
+        assert!(!summary.landlord_won);
+        assert!(msgs.iter().any(|m| matches!(
+            m,
+            MessageVariant::GameMvp { player } if *player == p4
+        )));
+    }
+
+    #[test]
+    fn test_puzzle_round_trip() {
+        let mut init = InitializePhase::new();
+        let p1 = init.add_player("p1".into()).unwrap().0;
+        let p2 = init.add_player("p2".into()).unwrap().0;
+        let p3 = init.add_player("p3".into()).unwrap().0;
+        let p4 = init.add_player("p4".into()).unwrap().0;
+        init.set_landlord(Some(p1)).unwrap();
+
+        let hands = [
+            (p1, [cards::S_2, cards::D_3]),
+            (p2, [cards::C_3, cards::D_4]),
+            (p3, [cards::C_4, cards::D_5]),
+            (p4, [cards::C_6, cards::D_A]),
+        ];
+        let exchange = deal_and_bid(init, &hands, None, (p1, cards::S_2));
+        let mut play = exchange.advance(p1).unwrap();
+        play.play_cards(p1, &[cards::S_2]).unwrap();
+
+        let puzzle = play.to_puzzle().unwrap();
+        assert_eq!(
+            puzzle.objective,
+            PuzzleObjective::MaximizePoints { player: p2 }
+        );
+        assert!(puzzle.players.iter().all(|p| p.reconnect_token.is_empty()));
+
+        let puzzle: Puzzle =
+            serde_json::from_str(&serde_json::to_string(&puzzle).unwrap()).unwrap();
+        let mut restored = PlayPhase::from_puzzle(puzzle).unwrap();
+        assert_eq!(
+            restored
+                .propagated
+                .players
+                .iter()
+                .map(|p| p.id)
+                .collect::<Vec<_>>(),
+            vec![p1, p2, p3, p4]
+        );
+        assert_eq!(restored.hands.get(p2).unwrap().get(&cards::C_3), Some(&1));
+
+        // The restored position can be played out from where it left off.
+        assert!(restored.play_cards(p3, &[cards::C_4]).is_err());
+        for (p, h) in &hands[1..] {
+            restored.play_cards(*p, &h[..1]).unwrap();
+        }
+        restored.finish_trick().unwrap();
+        assert_eq!(restored.trick.next_player(), Some(p1));
+    }
+
+    #[test]
+    fn test_game_summary() {
+        let mut init = InitializePhase::new();
+        let p1 = init.add_player("p1".into()).unwrap().0;
+        let p2 = init.add_player("p2".into()).unwrap().0;
+        let p3 = init.add_player("p3".into()).unwrap().0;
+        let p4 = init.add_player("p4".into()).unwrap().0;
+        init.set_landlord(Some(p1)).unwrap();
+
+        // The attackers only take a king, and the landlord trumps their ten.
+        let hands = [
+            (p1, [cards::C_3, cards::S_2]),
+            (p2, [cards::C_K, cards::D_4]),
+            (p3, [cards::C_4, cards::D_3]),
+            (p4, [cards::C_A, cards::D_10]),
+        ];
+        let exchange = deal_and_bid(init, &hands, Some(cards::C_7), (p1, cards::S_2));
+        let mut play = exchange.advance(p1).unwrap();
+        for (p, h) in &hands {
+            play.play_cards(*p, &h[..1]).unwrap();
+        }
+        play.finish_trick().unwrap();
+        for (p, h) in hands[3..].iter().chain(hands[..3].iter()) {
+            play.play_cards(*p, &h[1..]).unwrap();
+        }
+        play.finish_trick().unwrap();
+
+        let (init, summary, msgs) = play.finish_game().unwrap();
+        assert_eq!(summary.landlord, p1);
+        assert_eq!(summary.non_landlord_points, 10);
+        for msg in &msgs {
+            match msg {
+                MessageVariant::EndOfGameSummary {
+                    landlord_won,
+                    non_landlords_points,
+                } => {
+                    assert_eq!(summary.landlord_won, *landlord_won);
+                    assert_eq!(summary.non_landlord_points, *non_landlords_points);
+                }
+                MessageVariant::GameFinished { result } => {
+                    assert_eq!(&summary.results, result);
+                }
+                MessageVariant::RankAdvanced { player, new_rank } => {
+                    assert_eq!(summary.ranks_after[player], *new_rank);
+                }
+                _ => (),
+            }
+        }
+        assert_eq!(
+            summary.bonus_level,
+            msgs.iter()
+                .any(|m| matches!(m, MessageVariant::BonusLevelEarned))
+        );
+        for p in init.players() {
+            assert_eq!(summary.ranks_before[&p.id], Number::Two);
+            assert_eq!(summary.ranks_after[&p.id], p.rank());
+        }
+        assert!(summary.ranks_after[&p1] > Number::Two);
+        assert_eq!(summary.ranks_after[&p2], Number::Two);
+    }
+
+    #[test]
+    fn test_peek_next_card() {
+        let mut init = InitializePhase::new();
+        let p1 = init.add_player("p1".into()).unwrap().0;
+        let p2 = init.add_player("p2".into()).unwrap().0;
+        let p3 = init.add_player("p3".into()).unwrap().0;
+        let p4 = init.add_player("p4".into()).unwrap().0;
+
+        let mut draw = init.start(PlayerID(0)).unwrap();
+        let hands = [
+            (p1, [cards::S_3]),
+            (p2, [cards::D_3]),
+            (p3, [cards::C_3]),
+            (p4, [cards::H_3]),
+        ];
+        stack_deck(&mut draw, &hands, None);
+        assert_eq!(draw.cards_remaining(), 4);
+        assert_eq!(draw.peek_next_card(), Some(cards::S_3));
+        assert_eq!(draw.peek_next_card(), Some(cards::S_3));
+
+        match GameState::Draw(draw.clone()).for_player(p1) {
+            GameState::Draw(redacted) => {
+                assert_eq!(redacted.peek_next_card(), Some(Card::Unknown));
+            }
+            _ => unreachable!(),
+        }
+
+        draw.draw_card(p1).unwrap();
+        assert_eq!(draw.hands.get(p1).unwrap().get(&cards::S_3), Some(&1));
+        assert_eq!(draw.cards_remaining(), 3);
+        let next = draw.peek_next_card().unwrap();
+        draw.draw_card(p2).unwrap();
+        assert_eq!(draw.hands.get(p2).unwrap().get(&next), Some(&1));
+        assert_eq!(next, cards::D_3);
+
+        draw.deck.clear();
+        assert_eq!(draw.peek_next_card(), None);
+        assert_eq!(draw.cards_remaining(), 0);
+    }
+
+    #[test]
+    fn test_weak_hand_redeal() {
+        let mut init = InitializePhase::new();
+        let p1 = init.add_player("p1".into()).unwrap().0;
+        let p2 = init.add_player("p2".into()).unwrap().0;
+        let p3 = init.add_player("p3".into()).unwrap().0;
+        let p4 = init.add_player("p4".into()).unwrap().0;
+        assert!(init
+            .set_weak_hand_redeal(Some(WeakHandRedeal {
+                point_threshold: 0,
+                trump_threshold: 0,
+            }))
+            .is_err());
+
+        let hands = [
+            (p1, [cards::S_2, cards::S_A]),
+            (p2, [cards::C_3, cards::C_4]),
+            (p3, [cards::D_5, cards::D_K]),
+            (p4, [cards::H_3, cards::H_4]),
+        ];
+        let mut draw = deal(init, &hands, None);
+        // Weak hands can't be redealt unless the rule is enabled.
+        assert!(draw.declare_weak_hand(p2).is_err());
+
+        draw.propagated.weak_hand_redeal = Some(WeakHandRedeal {
+            point_threshold: 10,
+            trump_threshold: 0,
+        });
+        // p1 holds a trump, and p3 holds fifteen points.
+        assert!(draw.declare_weak_hand(p1).is_err());
+        assert!(draw.declare_weak_hand(p3).is_err());
+
+        let mut redealt = draw.declare_weak_hand(p2).unwrap();
+        assert_eq!(redealt.deck.len(), 100);
+        assert!(redealt.bids.is_empty());
+        assert_eq!(redealt.weak_hand_declarations, vec![p2]);
+
+        // p2 can't force another redeal, even with another weak hand.
+        let hands = [
+            (p1, [cards::S_3]),
+            (p2, [cards::D_3]),
+            (p3, [cards::C_3]),
+            (p4, [cards::H_3]),
+        ];
+        stack_deck(&mut redealt, &hands, None);
+        redealt.hands = Hands::new(vec![p1, p2, p3, p4]);
+        for (p, _) in &hands {
+            redealt.draw_card(*p).unwrap();
+        }
+        assert!(redealt.declare_weak_hand(p2).is_err());
+        assert!(redealt.declare_weak_hand(p3).is_ok());
+    }
+
+    #[test]
+    fn test_blind_bid_revoked() {
+        let mut init = InitializePhase::new();
+        let p1 = init.add_player("p1".into()).unwrap().0;
+        let p2 = init.add_player("p2".into()).unwrap().0;
+        let p3 = init.add_player("p3".into()).unwrap().0;
+        let p4 = init.add_player("p4".into()).unwrap().0;
+        init.set_blind_bid_bonus(Some(1)).unwrap();
+
+        let mut draw = init.start(PlayerID(0)).unwrap();
+        let hands = [
+            (p1, [cards::S_3]),
+            (p2, [cards::D_3]),
+            (p3, [cards::C_3]),
+            (p4, [cards::H_3]),
+        ];
+        stack_deck(&mut draw, &hands, None);
+
+        // p1 bids on a two of spades, but only draws a three.
+        draw.blind_bid(p1, cards::S_2, 1).unwrap();
+        let msgs = draw.draw_card(p1).unwrap();
+        assert!(matches!(
+            msgs[..],
+            [MessageVariant::BlindBidRevoked {
+                card: cards::S_2,
+                count: 1
+            }]
+        ));
+        assert!(draw.bids.is_empty());
+        draw.draw_card(p2).unwrap();
+        draw.draw_card(p3).unwrap();
+        draw.draw_card(p4).unwrap();
+        assert!(draw.advance(p1).is_err());
+    }
+
+    #[test]
+    fn test_capture_bonus_rules() {
+        let mut init = InitializePhase::new();
+        let p1 = init.add_player("p1".into()).unwrap().0;
+        let p2 = init.add_player("p2".into()).unwrap().0;
+        let p3 = init.add_player("p3".into()).unwrap().0;
+        let p4 = init.add_player("p4".into()).unwrap().0;
+        init.set_landlord(Some(p1)).unwrap();
+        assert!(init.set_capture_bonus_rules(vec![(vec![], 1)]).is_err());
+        assert!(init
+            .set_capture_bonus_rules(vec![(vec![Card::BigJoker], 0)])
+            .is_err());
+        init.set_capture_bonus_rules(vec![(vec![Card::BigJoker, Card::BigJoker], 2)])
+            .unwrap();
+
+        let hands = [
+            (p1, [cards::S_2]),
+            (p2, [Card::BigJoker]),
+            (p3, [cards::C_3]),
+            (p4, [Card::BigJoker]),
+        ];
+        // Keep points out of the kitty so that the landlord team wins big.
+        let exchange = deal_and_bid(init, &hands, Some(cards::C_3), (p1, cards::S_2));
+        let mut play = exchange.advance(p1).unwrap();
+        for (p, h) in &hands {
+            play.play_cards(*p, h).unwrap();
+        }
+        let msgs = play.finish_trick().unwrap();
+        assert!(msgs.iter().any(|m| matches!(
+            m,
+            MessageVariant::CaptureBonusEarned { player, bonus: 2, .. } if *player == p2
+        )));
+
+        // The landlord team holds the opponents to zero points, but p2 still
+        // advances for capturing both big jokers.
+        let (new_init_phase, summary, _) = play.finish_game().unwrap();
+        assert!(summary.landlord_won);
+        assert_eq!(
+            new_init_phase
+                .propagated
+                .players
+                .into_iter()
+                .map(|p| p.level)
+                .collect::<Vec<Number>>(),
+            vec![Number::Five, Number::Four, Number::Five, Number::Two],
+        );
+    }
+
+    #[test]
+    fn test_rematch() {
+        let mut init = InitializePhase::new();
+        let p1 = init.add_player("p1".into()).unwrap().0;
+        let p2 = init.add_player("p2".into()).unwrap().0;
+        let p3 = init.add_player("p3".into()).unwrap().0;
+        let p4 = init.add_player("p4".into()).unwrap().0;
+        init.set_landlord(Some(p1)).unwrap();
+        init.set_quick_rematch(true).unwrap();
+        // There's no previous game to rematch.
+        assert!(init.rematch(p1).is_err());
+
+        let hands = [
+            (p1, [cards::S_2]),
+            (p2, [cards::C_4]),
+            (p3, [cards::C_3]),
+            (p4, [cards::C_5]),
+        ];
+        let exchange = deal_and_bid(init, &hands, Some(cards::C_3), (p1, cards::S_2));
+        let mut play = exchange.advance(p1).unwrap();
+        for (p, h) in &hands {
+            play.play_cards(*p, h).unwrap();
+        }
+        play.finish_trick().unwrap();
+        let (new_init_phase, summary, _) = play.finish_game().unwrap();
+        assert!(summary.landlord_won);
+
+        let mut game = InteractiveGame::new_from_state(GameState::Initialize(new_init_phase));
+        game.rematch(p2, &Logger::root(Discard, o!())).unwrap();
+        match game.into_state() {
+            GameState::Draw(draw) => {
+                assert_eq!(draw.propagated.landlord, Some(p3));
+                assert_eq!(draw.level, Some(Number::Five));
+                assert_eq!(draw.deck.len(), 100);
+                assert!(draw.hands.is_empty());
+                assert_eq!(
+                    draw.propagated
+                        .players
+                        .iter()
+                        .map(|p| p.level)
+                        .collect::<Vec<Number>>(),
+                    vec![Number::Five, Number::Two, Number::Five, Number::Two],
+                );
+                assert!(draw.propagated.quick_rematch);
+            }
+            _ => panic!("expected a new draw phase"),
+        }
+    }
+
+    #[test]
+    fn test_team_level_spread() {
+        let mut init = InitializePhase::new();
+        let p1 = init.add_player("p1".into()).unwrap().0;
+        init.add_player("p2".into()).unwrap();
+        let p3 = init.add_player("p3".into()).unwrap().0;
+        let p4 = init.add_player("p4".into()).unwrap().0;
+        init.set_rank(p1, Number::Ace).unwrap();
+        init.set_rank(p3, Number::Five).unwrap();
+        init.set_rank(p4, Number::Four).unwrap();
+
+        assert_eq!(
+            init.team_level_spread(),
+            vec![
+                (0, Number::Five, Number::Ace),
+                (1, Number::Two, Number::Four)
+            ]
+        );
+        // Only the first team is far enough apart to warrant a warning.
+        let warnings = init.team_level_spread_warnings();
+        assert_eq!(warnings.len(), 1);
+        assert!(matches!(
+            warnings[0],
+            MessageVariant::TeamLevelSpread {
+                team: 0,
+                min_level: Number::Five,
+                max_level: Number::Ace,
+            }
+        ));
+
+        // There are no fixed teams when finding friends.
+        init.set_game_mode(GameModeSettings::FindingFriends { num_friends: None })
+            .unwrap();
+        assert!(init.team_level_spread().is_empty());
+    }
+
+    #[test]
+    fn test_skip_exchange() {
+        let mut init = InitializePhase::new();
+        let p1 = init.add_player("p1".into()).unwrap().0;
+        let p2 = init.add_player("p2".into()).unwrap().0;
+        let p3 = init.add_player("p3".into()).unwrap().0;
+        let p4 = init.add_player("p4".into()).unwrap().0;
+        init.set_landlord(Some(p1)).unwrap();
+        init.set_kitty_theft_policy(KittyTheftPolicy::AllowKittyTheft)
+            .unwrap();
+        assert!(init.set_skip_exchange(true).is_err());
+        init.set_kitty_theft_policy(KittyTheftPolicy::NoKittyTheft)
+            .unwrap();
+        init.set_skip_exchange(true).unwrap();
+        // Friends are picked during the exchange, so they can't be combined.
+        assert!(init
+            .set_game_mode(GameModeSettings::FindingFriends { num_friends: None })
+            .is_err());
+        assert!(init
+            .set_kitty_theft_policy(KittyTheftPolicy::AllowKittyTheft)
+            .is_err());
+
+        let hands = [
+            (p1, [cards::S_2]),
+            (p2, [cards::C_4]),
+            (p3, [cards::C_3]),
+            (p4, [cards::C_5]),
+        ];
+        let mut draw = deal(init, &hands, None);
+        assert!(draw.bid(p1, cards::S_2, 1));
+        let kitty = draw.kitty.clone();
+
+        let play = draw.advance_to_play(p1).unwrap();
+        assert_eq!(play.kitty, kitty);
+        assert_eq!(
+            play.trump,
+            Trump::Standard {
+                suit: Suit::Spades,
+                number: Number::Two,
+                permanent: PermanentTrumps::NONE
+            }
+        );
+        assert_eq!(play.landlords_team, vec![p1, p3]);
+    }
+
+    #[test]
+    fn test_preview_hand_after() {
+        let mut init = InitializePhase::new();
+        let p1 = init.add_player("p1".into()).unwrap().0;
+        let p2 = init.add_player("p2".into()).unwrap().0;
+        let p3 = init.add_player("p3".into()).unwrap().0;
+        let p4 = init.add_player("p4".into()).unwrap().0;
+        init.set_landlord(Some(p1)).unwrap();
+
+        let hands = [
+            (p1, [cards::S_2, cards::H_3]),
+            (p2, [cards::C_4, cards::H_4]),
+            (p3, [cards::C_3, cards::H_5]),
+            (p4, [cards::C_5, cards::H_6]),
+        ];
+        let mut exchange = deal_and_bid(init, &hands, None, (p1, cards::S_2));
+        let from_kitty = exchange.kitty[0];
+
+        let hand = exchange.hands.cards(p1, exchange.trump.number()).unwrap();
+        let kitty = exchange.kitty.clone();
+
+        let preview = exchange
+            .preview_hand_after(p1, &[cards::H_3], &[from_kitty])
+            .unwrap();
+        // Previewing doesn't move anything.
+        assert_eq!(
+            exchange.hands.cards(p1, exchange.trump.number()).unwrap(),
+            hand
+        );
+        assert_eq!(exchange.kitty, kitty);
+
+        exchange.move_card_to_kitty(p1, cards::H_3).unwrap();
+        exchange.move_card_to_hand(p1, from_kitty).unwrap();
+        assert_eq!(
+            preview,
+            exchange.hands.cards(p1, exchange.trump.number()).unwrap()
+        );
+
+        // The cards have to be available to be moved.
+        assert!(exchange.preview_hand_after(p1, &[cards::H_3], &[]).is_err());
+        let not_in_kitty = *FULL_DECK
+            .iter()
+            .find(|c| !exchange.kitty.contains(c))
+            .unwrap();
+        assert!(exchange
+            .preview_hand_after(p1, &[], &[not_in_kitty])
+            .is_err());
+        assert!(exchange.preview_hand_after(p2, &[], &[]).is_err());
+    }
+
+    #[test]
+    fn test_friend_bounds() {
+        let mut init = InitializePhase::new();
+        for i in 0..6 {
+            init.add_player(format!("p{}", i)).unwrap();
+        }
+        init.set_game_mode(GameModeSettings::FindingFriends { num_friends: None })
+            .unwrap();
+        let num_friends = |init: &InitializePhase| match init.start(PlayerID(0)).unwrap().game_mode
+        {
+            GameMode::FindingFriends { num_friends, .. } => num_friends,
+            GameMode::Tractor => panic!("expected to be finding friends"),
+        };
+        assert_eq!(num_friends(&init), 2);
+
+        assert!(init.set_friend_bounds(Some(2), Some(1)).is_err());
+        init.set_friend_bounds(None, Some(1)).unwrap();
+        assert_eq!(num_friends(&init), 1);
+        init.set_friend_bounds(Some(3), None).unwrap();
+        assert_eq!(num_friends(&init), 3);
+
+        // There aren't enough players to satisfy the minimum.
+        init.set_friend_bounds(Some(5), None).unwrap();
+        assert!(init.start(PlayerID(0)).is_err());
+        init.set_friend_bounds(Some(usize::MAX), None).unwrap();
+        assert!(init.start(PlayerID(0)).is_err());
+
+        // An explicit number of friends must fall within the bounds.
+        init.set_friend_bounds(None, Some(1)).unwrap();
+        init.set_game_mode(GameModeSettings::FindingFriends {
+            num_friends: Some(2),
+        })
+        .unwrap();
+        assert!(init.start(PlayerID(0)).is_err());
+    }
+
+    #[test]
+    fn test_spectator_card_visibility() {
+        let mut init = InitializePhase::new();
+        let p1 = init.add_player("p1".into()).unwrap().0;
+        let p2 = init.add_player("p2".into()).unwrap().0;
+        let p3 = init.add_player("p3".into()).unwrap().0;
+        let p4 = init.add_player("p4".into()).unwrap().0;
+        init.set_landlord(Some(p1)).unwrap();
+        init.hide_landlord_points(true).unwrap();
+        init.set_spectator_card_visibility(SpectatorCardVisibility::AlwaysVisible)
+            .unwrap();
+
+        let hands = [
+            (p1, [cards::S_2, cards::S_3]),
+            (p2, [cards::C_5, cards::C_4]),
+            (p3, [cards::C_3, cards::C_6]),
+            (p4, [cards::C_7, cards::C_8]),
+        ];
+        let exchange = deal_and_bid(init, &hands, None, (p1, cards::S_2));
+        let mut play = exchange.advance(p1).unwrap();
+        // p1 wins the first trick, and takes p2's five.
+        for (p, h) in &hands {
+            play.play_cards(*p, &h[..1]).unwrap();
+        }
+        play.finish_trick().unwrap();
+        let observer = play.add_observer("observer".into()).unwrap();
+
+        let visible_hand =
+            |play: &PlayPhase, id: PlayerID| match GameState::Play(play.clone()).for_player(id) {
+                GameState::Play(p) => {
+                    // The landlord team's points are hidden from everyone.
+                    assert!(p.points[&p1].is_empty());
+                    Card::cards(p.hands.get(p2).unwrap().iter())
+                        .copied()
+                        .collect::<Vec<_>>()
+                }
+                _ => unreachable!(),
+            };
+        assert_eq!(visible_hand(&play, observer), vec![cards::C_4]);
+        // Players never see the other hands, regardless of the setting.
+        assert_eq!(visible_hand(&play, p3), vec![Card::Unknown]);
+
+        play.propagated.spectator_card_visibility = SpectatorCardVisibility::Hidden;
+        assert_eq!(visible_hand(&play, observer), vec![Card::Unknown]);
+    }
+
+    #[test]
+    fn test_point_flow() {
+        let mut init = InitializePhase::new();
+        let p1 = init.add_player("p1".into()).unwrap().0;
+        let p2 = init.add_player("p2".into()).unwrap().0;
+        let p3 = init.add_player("p3".into()).unwrap().0;
+        let p4 = init.add_player("p4".into()).unwrap().0;
+        init.set_landlord(Some(p1)).unwrap();
+
+        let hands = [
+            (p1, [cards::S_2, cards::H_3]),
+            (p2, [cards::C_5, cards::H_10]),
+            (p3, [cards::C_3, cards::H_4]),
+            (p4, [cards::C_7, cards::H_5]),
+        ];
+        let exchange = deal_and_bid(init, &hands, Some(cards::C_3), (p1, cards::S_2));
+        let mut play = exchange.advance(p1).unwrap();
+        assert!(play.point_flow().is_empty());
+        for i in 0..2 {
+            for (p, h) in &hands {
+                play.play_cards(*p, &h[i..=i]).unwrap();
+            }
+            play.finish_trick().unwrap();
+        }
+
+        // p1 trumps the first trick and takes five points, and then p2 takes
+        // fifteen points in the second.
+        let flow = play.point_flow();
+        assert_eq!(
+            flow,
+            vec![
+                PointFlowEntry {
+                    trick_index: 0,
+                    attackers_cumulative: 0,
+                    defenders_cumulative: Some(5),
+                },
+                PointFlowEntry {
+                    trick_index: 1,
+                    attackers_cumulative: 15,
+                    defenders_cumulative: Some(5),
+                },
+            ]
+        );
+        assert!(flow.windows(2).all(|w| {
+            w[0].attackers_cumulative <= w[1].attackers_cumulative
+                && w[0].defenders_cumulative <= w[1].defenders_cumulative
+        }));
+
+        play.propagated.hide_landlord_points = true;
+        assert!(play
+            .point_flow()
+            .iter()
+            .all(|e| e.defenders_cumulative.is_none()));
+    }
+
+    #[test]
+    fn test_kitty_penalty_by_unit_type() {
+        let mut init = InitializePhase::new();
+        let p1 = init.add_player("p1".into()).unwrap().0;
+        let p2 = init.add_player("p2".into()).unwrap().0;
+        let p3 = init.add_player("p3".into()).unwrap().0;
+        let p4 = init.add_player("p4".into()).unwrap().0;
+        init.set_landlord(Some(p1)).unwrap();
+        init.set_kitty_penalty(KittyPenalty::ByUnitType {
+            single: 1,
+            pair: 3,
+            tractor: 5,
+        })
+        .unwrap();
+
+        let hands = [
+            (
+                p1,
+                [cards::S_2, cards::H_5, cards::H_5, cards::H_6, cards::H_6],
+            ),
+            (
+                p2,
+                [cards::C_3, cards::C_4, cards::C_6, cards::C_7, cards::C_8],
+            ),
+            (
+                p3,
+                [cards::D_3, cards::D_4, cards::D_6, cards::D_7, cards::D_8],
+            ),
+            (
+                p4,
+                [cards::C_9, cards::D_9, cards::D_J, cards::D_Q, cards::C_J],
+            ),
+        ];
+        let exchange = deal_and_bid(init, &hands, Some(cards::C_5), (p1, cards::S_2));
+        let mut play = exchange.advance(p1).unwrap();
+
+        for (p, h) in &hands {
+            play.play_cards(*p, &h[..1]).unwrap();
+        }
+        play.finish_trick().unwrap();
+
+        // The landlord wins the last trick with a tractor.
+        for (p, h) in &hands {
+            play.play_cards(*p, &h[1..]).unwrap();
+        }
+        let msgs = play.finish_trick().unwrap();
+        assert!(msgs.iter().any(|m| matches!(
+            m,
+            MessageVariant::PointsInKitty {
+                points: 40,
+                multiplier: 5
+            }
+        )));
+    }
+
+    #[test]
+    fn test_kitty_penalty_none_and_fixed() {
+        let non_landlord_points = |penalty: KittyPenalty| {
+            let mut init = InitializePhase::new();
+            let p1 = init.add_player("p1".into()).unwrap().0;
+            let p2 = init.add_player("p2".into()).unwrap().0;
+            let p3 = init.add_player("p3".into()).unwrap().0;
+            let p4 = init.add_player("p4".into()).unwrap().0;
+            init.set_landlord(Some(p1)).unwrap();
+            init.set_kitty_penalty(penalty).unwrap();
+
+            let hands = [
+                (p1, [cards::H_6, cards::S_2, cards::H_5, cards::H_5]),
+                (p2, [cards::C_3, cards::S_4, cards::S_3, cards::S_3]),
+                (p3, [cards::D_3, cards::D_4, cards::D_6, cards::D_7]),
+                (p4, [cards::C_9, cards::D_9, cards::D_J, cards::C_J]),
+            ];
+            let exchange = deal_and_bid(init, &hands, Some(cards::C_5), (p1, cards::S_2));
+            let mut play = exchange.advance(p1).unwrap();
+
+            for (p, h) in &hands {
+                play.play_cards(*p, &h[..1]).unwrap();
+            }
+            play.finish_trick().unwrap();
+            for (p, h) in &hands {
+                play.play_cards(*p, &h[1..2]).unwrap();
+            }
+            play.finish_trick().unwrap();
+
+            // The defenders win the last trick with a pair of trumps.
+            for (p, h) in &hands {
+                play.play_cards(*p, &h[2..]).unwrap();
+            }
+            let msgs = play.finish_trick().unwrap();
+            assert!(msgs
+                .iter()
+                .any(|m| matches!(m, MessageVariant::PointsInKitty { points: 40, .. })));
+            [p2, p4]
+                .iter()
+                .flat_map(|p| play.points[p].iter().flat_map(|c| c.points()))
+                .sum::<usize>()
+        };
+
+        // The defenders always get the pair of fives from the last trick.
+        assert_eq!(non_landlord_points(KittyPenalty::Times), 10 + 40 * 4);
+        assert_eq!(non_landlord_points(KittyPenalty::None), 10 + 40);
+        assert_eq!(non_landlord_points(KittyPenalty::Fixed(3)), 10 + 40 * 3);
+    }
+
+    #[test]
+    fn test_reset_counts_abandoned_games() {
+        let mut init = InitializePhase::new();
+        let p1 = init.add_player("p1".into()).unwrap().0;
+        for i in 2..=4 {
+            init.add_player(format!("p{}", i)).unwrap();
+        }
+
+        // By default, resets aren't tracked at all.
+        let draw = init.start(p1).unwrap();
+        let (mut init, _) = draw.return_to_initialize().unwrap();
+        assert_eq!(init.num_games_abandoned(), 0);
+
+        init.set_abandoned_game_policy(AbandonedGamePolicy::CountResetsAsAbandoned)
+            .unwrap();
+        let draw = init.start(p1).unwrap();
+        let (init, msgs) = draw.return_to_initialize().unwrap();
+        assert!(matches!(msgs[0], MessageVariant::ResettingGame));
+        assert_eq!(init.num_games_abandoned(), 1);
+        assert_eq!(init.num_games_finished(), 0);
+    }
+
+    #[test]
+    fn test_register_with_token() {
+        let mut state = GameState::Initialize(InitializePhase::new());
+        let (alice, _) = state.register("alice".into()).unwrap();
+        let (bob, _) = state.register("bob".into()).unwrap();
+        let alice_token = state.reconnect_token(alice).unwrap().to_string();
+        let bob_token = state.reconnect_token(bob).unwrap().to_string();
+        assert!(!alice_token.is_empty());
+        assert_ne!(alice_token, bob_token);
+
+        // Rejoining with a token gets the same seat back.
+        let (id, msgs) = state.register_with_token(&alice_token).unwrap();
+        assert_eq!(id, alice);
+        assert!(matches!(
+            msgs[0],
+            MessageVariant::JoinedGameAgain { player, .. } if player == alice
+        ));
+        assert_eq!(state.register_with_token(&bob_token).unwrap().0, bob);
+
+        // Wrong tokens are rejected, rather than seating anybody.
+        assert!(state.register_with_token("not a token").is_err());
+        assert!(state.register_with_token("").is_err());
+        assert_eq!(state.players().len(), 2);
+
+        // Tokens are only visible to the player that they belong to.
+        let redacted = state.for_player(alice);
+        assert_eq!(redacted.reconnect_token(alice).unwrap(), alice_token);
+        assert_eq!(redacted.reconnect_token(bob).unwrap(), "");
+
+        let mut public = state.clone();
+        public.redact_reconnect_tokens();
+        assert_eq!(public.reconnect_token(alice).unwrap(), "");
+        assert_eq!(public.reconnect_token(bob).unwrap(), "");
+    }
+
+    #[test]
+    fn test_register_with_token_disambiguates_names() {
+        let mut init = InitializePhase::new();
+        let p1 = init.add_player("p1".into()).unwrap().0;
+        for i in 2..=4 {
+            init.add_player(format!("p{}", i)).unwrap();
+        }
+        let mut state = GameState::Draw(init.start(p1).unwrap());
+        let token = state.reconnect_token(p1).unwrap().to_string();
+
+        // Joining with a new name during the game makes an observer, whose
+        // token is distinct from the player with the same name.
+        let (observer, _) = state.register("alice".into()).unwrap();
+        assert!(state.is_observer(observer));
+        let observer_token = state.reconnect_token(observer).unwrap().to_string();
+        assert_ne!(observer_token, token);
+        assert_eq!(
+            state.register_with_token(&observer_token).unwrap().0,
+            observer
+        );
+
+        // The name-based path is still available, but the token always
+        // resolves to the original player.
+        assert_eq!(state.register("p1".into()).unwrap().0, p1);
+        assert_eq!(state.register_with_token(&token).unwrap().0, p1);
+        assert!(!state.is_observer(p1));
+    }
+
+    #[test]
+    fn test_safe_throws() {
+        let mut init = InitializePhase::new();
+        let p1 = init.add_player("p1".into()).unwrap().0;
+        let p2 = init.add_player("p2".into()).unwrap().0;
+        let p3 = init.add_player("p3".into()).unwrap().0;
+        let p4 = init.add_player("p4".into()).unwrap().0;
+        init.set_landlord(Some(p1)).unwrap();
+
+        let hands = [
+            (
+                p1,
+                [
+                    cards::S_2,
+                    cards::H_A,
+                    cards::H_A,
+                    cards::H_K,
+                    cards::C_A,
+                    cards::C_Q,
+                ],
+            ),
+            (
+                p2,
+                [
+                    cards::C_K,
+                    cards::C_3,
+                    cards::D_3,
+                    cards::D_4,
+                    cards::D_6,
+                    cards::D_7,
+                ],
+            ),
+            (
+                p3,
+                [
+                    cards::D_8,
+                    cards::D_9,
+                    cards::D_10,
+                    cards::D_J,
+                    cards::S_3,
+                    cards::S_4,
+                ],
+            ),
+            (
+                p4,
+                [
+                    cards::C_4,
+                    cards::C_6,
+                    cards::C_7,
+                    cards::S_5,
+                    cards::S_6,
+                    cards::S_7,
+                ],
+            ),
+        ];
+        let exchange = deal_and_bid(init, &hands, Some(cards::C_5), (p1, cards::S_2));
+        let play = exchange.advance(p1).unwrap();
+
+        // Nobody else has any hearts, but someone might have the king of
+        // clubs, which leaves only a single ace.
+        let safe = play.safe_throws(p1);
+        assert_eq!(safe.len(), 1);
+        assert_eq!(
+            safe[&EffectiveSuit::Hearts],
+            vec![cards::H_K, cards::H_A, cards::H_A]
+        );
+        assert!(!safe.contains_key(&EffectiveSuit::Clubs));
+
+        assert!(play.safe_throws(p2).is_empty());
+    }
+
+    #[test]
+    fn test_past_games() {
+        let mut init = InitializePhase::new();
+        let p1 = init.add_player("p1".into()).unwrap().0;
+        let p2 = init.add_player("p2".into()).unwrap().0;
+        let p3 = init.add_player("p3".into()).unwrap().0;
+        let p4 = init.add_player("p4".into()).unwrap().0;
+        init.set_landlord(Some(p1)).unwrap();
+
+        let hands = [
+            (p1, [cards::H_2]),
+            (p2, [cards::C_3]),
+            (p3, [cards::C_4]),
+            (p4, [cards::C_6]),
+        ];
+        let exchange = deal_and_bid(init, &hands, None, (p1, cards::H_2));
+        let mut play = exchange.advance(p1).unwrap();
+        for (p, h) in &hands {
+            play.play_cards(*p, h).unwrap();
+        }
+        play.finish_trick().unwrap();
+
+        let (mut init, _, _) = play.finish_game().unwrap();
+        let past_games = init.propagated.past_games();
+        assert_eq!(past_games.len(), 1);
+        assert_eq!(past_games[0].winning_bid.id, p1);
+        assert_eq!(past_games[0].winning_bid.card, cards::H_2);
+        assert_eq!(
+            past_games[0].trump,
+            Trump::Standard {
+                suit: Suit::Hearts,
+                number: Number::Two,
+                permanent: PermanentTrumps::NONE
+            }
+        );
+
+        // Only the most recent games are kept.
+        let first = past_games[0];
+        for _ in 0..MAX_PAST_GAMES {
+            init.propagated.record_past_game(first);
+        }
+        assert_eq!(init.propagated.past_games().len(), MAX_PAST_GAMES);
+    }
+
+    #[test]
+    fn test_kitty_stealing_bid_sequence() {
+        let mut init = InitializePhase::new();
+        let p1 = init.add_player("p1".into()).unwrap().0;
+        let p2 = init.add_player("p2".into()).unwrap().0;
+        let p3 = init.add_player("p3".into()).unwrap().0;
+        let p4 = init.add_player("p4".into()).unwrap().0;
+        init.set_kitty_theft_policy(KittyTheftPolicy::AllowKittyTheft)
+            .unwrap();
+        let mut draw = init.start(PlayerID(0)).unwrap();
+        // Hackily ensure that everyone can bid.
+        draw.deck = vec![
+            cards::S_2,
+            Card::SmallJoker,
+            Card::BigJoker,
+            cards::H_2,
+            cards::S_2,
+            Card::SmallJoker,
+            Card::BigJoker,
+            cards::H_2,
+        ];
+        draw.position = 0;
 
         draw.draw_card(p1).unwrap();
         draw.draw_card(p2).unwrap();
@@ -1696,6 +5577,263 @@ mod tests {
         exchange.advance(p1).unwrap();
     }
 
+    #[test]
+    fn test_kitty_theft_bid_policy() {
+        for policy in &[
+            KittyTheftBidPolicy::MustExceed,
+            KittyTheftBidPolicy::MustMatch,
+        ] {
+            let mut init = InitializePhase::new();
+            let p1 = init.add_player("p1".into()).unwrap().0;
+            let p2 = init.add_player("p2".into()).unwrap().0;
+            let p3 = init.add_player("p3".into()).unwrap().0;
+            let p4 = init.add_player("p4".into()).unwrap().0;
+            init.set_kitty_theft_policy(KittyTheftPolicy::AllowKittyTheft)
+                .unwrap();
+            init.set_kitty_theft_bid_policy(*policy).unwrap();
+            let hands = [
+                (p1, [cards::H_4, cards::H_2]),
+                (p2, [cards::D_4, cards::D_3]),
+                (p3, [cards::C_4, cards::C_3]),
+                (p4, [cards::S_4, cards::S_2]),
+            ];
+            let mut exchange = deal_and_bid(init, &hands, None, (p1, cards::H_2));
+            exchange.finalize(p1).unwrap();
+
+            // p4's single S_2 is exactly as strong as p1's single H_2.
+            let stole = exchange.bid(p4, cards::S_2, 1);
+            assert_eq!(stole, *policy == KittyTheftBidPolicy::MustMatch);
+            // Nobody can match with cards they don't have, or that aren't
+            // at the bid level.
+            assert!(!exchange.bid(p2, cards::S_2, 1));
+            assert!(!exchange.bid(p3, cards::D_3, 1));
+            if stole {
+                exchange.pick_up_cards(p4).unwrap();
+                exchange.finalize(p4).unwrap();
+                // p4 is now the winning bidder, and can't match their own bid.
+                assert!(!exchange.bid(p4, cards::S_2, 1));
+            } else {
+                exchange.pick_up_cards(p4).unwrap_err();
+            }
+        }
+    }
+
+    #[test]
+    fn test_seeded_deals_are_balanced() {
+        use crate::hands::analyze_hand_shape;
+
+        let mut init = InitializePhase::new();
+        for i in 0..4 {
+            init.add_player(format!("p{}", i)).unwrap();
+        }
+        let trump = Trump::Standard {
+            suit: Suit::Spades,
+            number: Number::Two,
+            permanent: PermanentTrumps::NONE,
+        };
+
+        let num_deals = 200;
+        let mut trump_counts = vec![];
+        let mut expected = 0.0;
+        for seed in 0..num_deals {
+            let mut draw = init.start_with_seed(PlayerID(0), seed).unwrap();
+            let deck_len = draw.deck.len() + draw.kitty.len();
+            let num_trumps = draw
+                .deck
+                .iter()
+                .chain(draw.kitty.iter())
+                .filter(|c| trump.effective_suit(**c) == EffectiveSuit::Trump)
+                .count();
+            expected = (draw.deck.len() / 4) as f64 * num_trumps as f64 / deck_len as f64;
+
+            while !draw.deck.is_empty() {
+                let id = draw.propagated.players[draw.position].id;
+                draw.draw_card(id).unwrap();
+            }
+            for p in &draw.propagated.players {
+                let shape = analyze_hand_shape(draw.hands.get(p.id).unwrap(), trump);
+                trump_counts.push(shape.trump);
+            }
+        }
+
+        let average = trump_counts.iter().sum::<usize>() as f64 / trump_counts.len() as f64;
+        assert!(
+            (average - expected).abs() < 0.3,
+            "average of {} trumps per hand, expected {}",
+            average,
+            expected
+        );
+    }
+
+    #[test]
+    fn test_start_with_seed() {
+        let mut init = InitializePhase::new();
+        for i in 0..4 {
+            init.add_player(format!("p{}", i)).unwrap();
+        }
+        let deal = |seed| {
+            let draw = init.start_with_seed(PlayerID(0), seed).unwrap();
+            (draw.position, draw.deck, draw.kitty, draw.removed_cards)
+        };
+
+        assert_eq!(deal(1234), deal(1234));
+        // A whole deck shuffled the same way by chance is vanishingly unlikely.
+        assert_ne!(deal(1234).1, deal(4321).1);
+    }
+
+    #[test]
+    fn test_randomize_settings() {
+        let mut init = InitializePhase::new();
+        for i in 0..3 {
+            init.add_player(format!("p{}", i)).unwrap();
+        }
+        assert!(init.randomize_settings(0).is_err());
+
+        for num_players in 4..8 {
+            init.add_player(format!("p{}", num_players)).unwrap();
+            let mut game_modes = HashSet::new();
+            for seed in 0..1000 {
+                let mut init_ = init.clone();
+                init_.randomize_settings(seed).unwrap();
+                init_.start_with_seed(PlayerID(0), seed).unwrap();
+                game_modes.insert(init_.game_mode.variant());
+            }
+            // Tractor needs an even number of players.
+            assert_eq!(game_modes.len(), 2 - num_players % 2);
+        }
+
+        // Skipping the exchange is turned off when friends have to be picked.
+        let mut init_ = init.clone();
+        init_.set_skip_exchange(true).unwrap();
+        init_.set_friend_bounds(Some(3), None).unwrap();
+        for seed in 0..1000 {
+            let mut init_ = init_.clone();
+            init_.randomize_settings(seed).unwrap();
+            init_.start(PlayerID(0)).unwrap();
+        }
+    }
+
+    #[test]
+    fn test_experimental_features() {
+        let mut init = InitializePhase::new();
+        assert!(!init.has_experimental_feature("sealed_kitty"));
+
+        let known = vec!["sealed_kitty".to_string()].into_iter().collect();
+        assert!(crate::settings::validate_experimental_features(&known).is_ok());
+        assert_eq!(
+            init.set_experimental_features(known.clone()).unwrap().len(),
+            1
+        );
+        assert!(init.has_experimental_feature("sealed_kitty"));
+        assert!(init.set_experimental_features(known).unwrap().is_empty());
+
+        let unknown = vec!["sealed_kitty".to_string(), "time_travel".to_string()]
+            .into_iter()
+            .collect();
+        assert_eq!(
+            init.set_experimental_features(unknown)
+                .unwrap_err()
+                .to_string(),
+            "unknown experimental features: time_travel"
+        );
+        assert!(init.has_experimental_feature("sealed_kitty"));
+
+        // Saved settings are checked the same way.
+        let mut saved = serde_json::to_value(&init.propagated).unwrap();
+        assert!(serde_json::from_value::<PropagatedState>(saved.clone()).is_ok());
+        saved["experimental_features"] = serde_json::json!(["time_travel"]);
+        assert!(serde_json::from_value::<PropagatedState>(saved).is_err());
+
+        assert_eq!(
+            init.set_experimental_features(HashSet::new())
+                .unwrap()
+                .len(),
+            1
+        );
+        assert!(!init.has_experimental_feature("sealed_kitty"));
+    }
+
+    #[test]
+    fn test_sealed_kitty() {
+        let mut init = InitializePhase::new();
+        let p1 = init.add_player("p1".into()).unwrap().0;
+        let p2 = init.add_player("p2".into()).unwrap().0;
+        let p3 = init.add_player("p3".into()).unwrap().0;
+        let p4 = init.add_player("p4".into()).unwrap().0;
+        init.set_landlord(Some(p1)).unwrap();
+        init.set_experimental_features(vec!["sealed_kitty".to_string()].into_iter().collect())
+            .unwrap();
+
+        let hands = [
+            (p1, [cards::S_2]),
+            (p2, [cards::C_3]),
+            (p3, [cards::C_4]),
+            (p4, [cards::C_6]),
+        ];
+        let mut exchange = deal_and_bid(init, &hands, Some(cards::C_K), (p1, cards::S_2));
+        assert!(exchange.move_card_to_hand(p1, cards::C_K).is_err());
+        assert!(exchange.move_card_to_kitty(p1, cards::S_2).is_err());
+        let play = exchange.advance(p1).unwrap();
+        assert_eq!(play.kitty, vec![cards::C_K; 8]);
+    }
+
+    #[test]
+    fn test_kitty_theft_reveal_policy() {
+        for policy in &[
+            KittyTheftRevealPolicy::RevealPreviousDiscards,
+            KittyTheftRevealPolicy::ResetToOriginalKitty,
+        ] {
+            let mut init = InitializePhase::new();
+            let p1 = init.add_player("p1".into()).unwrap().0;
+            let p2 = init.add_player("p2".into()).unwrap().0;
+            let p3 = init.add_player("p3".into()).unwrap().0;
+            let p4 = init.add_player("p4".into()).unwrap().0;
+            init.set_kitty_theft_policy(KittyTheftPolicy::AllowKittyTheft)
+                .unwrap();
+            init.set_kitty_theft_reveal_policy(*policy).unwrap();
+            let hands = [
+                (p1, [cards::H_2, cards::H_2]),
+                (p2, [cards::D_5, cards::D_4]),
+                (p3, [Card::SmallJoker, Card::SmallJoker]),
+                (p4, [cards::S_4, cards::S_3]),
+            ];
+            let mut draw = deal(init, &hands, None);
+            draw.kitty = vec![cards::C_3, cards::C_4];
+            let p1_hand = Card::count(draw.hands.cards(p1, Number::Two).unwrap());
+
+            assert!(draw.bid(p1, cards::H_2, 1));
+            let mut exchange = draw.advance(p1).unwrap();
+            // p1 keeps one of the bottom cards, and buries an H_2 instead.
+            exchange.move_card_to_hand(p1, cards::C_3).unwrap();
+            exchange.move_card_to_kitty(p1, cards::H_2).unwrap();
+            exchange.finalize(p1).unwrap();
+
+            assert!(exchange.bid(p3, Card::SmallJoker, 2));
+            exchange.pick_up_cards(p3).unwrap();
+            match policy {
+                KittyTheftRevealPolicy::RevealPreviousDiscards => {
+                    assert_eq!(
+                        Card::count(exchange.kitty.iter().copied()),
+                        Card::count(vec![cards::C_4, cards::H_2])
+                    );
+                }
+                KittyTheftRevealPolicy::ResetToOriginalKitty => {
+                    assert_eq!(exchange.kitty, vec![cards::C_3, cards::C_4]);
+                    assert_eq!(exchange.hands.get(p1).unwrap(), &p1_hand);
+                }
+            }
+            // No cards are lost either way.
+            assert_eq!(
+                exchange.kitty.len()
+                    + [p1, p2, p3, p4]
+                        .iter()
+                        .map(|p| exchange.hands.get(*p).unwrap().values().sum::<usize>())
+                        .sum::<usize>(),
+                10
+            );
+        }
+    }
+
     #[test]
     fn test_tuple_protection_case() {
         use cards::*;
@@ -1823,6 +5961,91 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_friend_selection_skips() {
+        use cards::*;
+
+        let setup_exchange = |num_decks: usize| {
+            let mut init = InitializePhase::new();
+            init.set_game_mode(GameModeSettings::FindingFriends { num_friends: None })
+                .unwrap();
+            let p1 = init.add_player("p1".into()).unwrap().0;
+            let p2 = init.add_player("p2".into()).unwrap().0;
+            let p3 = init.add_player("p3".into()).unwrap().0;
+            let p4 = init.add_player("p4".into()).unwrap().0;
+            init.set_num_decks(Some(num_decks)).unwrap();
+            init.set_landlord(Some(p2)).unwrap();
+
+            let mut draw = init.start(PlayerID(1)).unwrap();
+            draw.deck = vec![S_2, S_2, S_2, S_2];
+            draw.draw_card(p2).unwrap();
+            draw.draw_card(p3).unwrap();
+            draw.draw_card(p4).unwrap();
+            draw.draw_card(p1).unwrap();
+            assert!(draw.bid(p2, S_2, 1));
+
+            (p2, draw.advance(p2).unwrap())
+        };
+        let friend = |card, initial_skip| FriendSelection { card, initial_skip };
+
+        let (p2, mut exchange) = setup_exchange(1);
+        assert_eq!(exchange.valid_skip_range(C_K), 0..1);
+        assert_eq!(exchange.valid_skip_range(Card::BigJoker), 0..1);
+        assert_eq!(
+            exchange.describe_friend_selection(&friend(C_K, 0)),
+            "the 1st King of clubs played"
+        );
+        assert!(exchange.set_friends(p2, vec![friend(C_K, 0)]).is_ok());
+        let err = exchange
+            .set_friends(p2, vec![friend(C_K, 1)])
+            .unwrap_err()
+            .to_string();
+        assert_eq!(
+            err,
+            "there is only 1 copy of that card, so you can only pick the 1st one played"
+        );
+
+        let (p2, mut exchange) = setup_exchange(3);
+        assert_eq!(exchange.valid_skip_range(H_10), 0..3);
+        assert_eq!(
+            exchange.describe_friend_selection(&friend(H_10, 1)),
+            "the 2nd Ten of hearts played"
+        );
+        assert_eq!(
+            exchange.describe_friend_selection(&friend(D_A, 2)),
+            "the 3rd Ace of diamonds played"
+        );
+        assert_eq!(
+            exchange.describe_friend_selection(&friend(Card::SmallJoker, 0)),
+            "the 1st small joker played"
+        );
+        for skip in 0..3 {
+            assert!(exchange.set_friends(p2, vec![friend(H_10, skip)]).is_ok());
+        }
+        assert_eq!(
+            exchange
+                .set_friends(p2, vec![friend(H_10, 3)])
+                .unwrap_err()
+                .to_string(),
+            "there are only 3 copies of that card, so you can pick at most the 3rd one played"
+        );
+    }
+
+    #[test]
+    fn test_ordinal() {
+        let ordinals = [1, 2, 3, 4, 11, 12, 13, 21, 22, 23, 101, 111]
+            .iter()
+            .map(|n| ordinal(*n))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            ordinals,
+            vec![
+                "1st", "2nd", "3rd", "4th", "11th", "12th", "13th", "21st", "22nd", "23rd",
+                "101st", "111th"
+            ]
+        );
+    }
+
     #[test]
     fn test_full_game_play() {
         use cards::*;
@@ -2338,6 +6561,223 @@ mod tests {
         };
     }
 
+    #[test]
+    fn test_friends_join_in_seat_order() {
+        let mut init = InitializePhase::new();
+        init.set_game_mode(GameModeSettings::FindingFriends {
+            num_friends: Some(2),
+        })
+        .unwrap();
+        let p1 = init.add_player("p1".into()).unwrap().0;
+        let p2 = init.add_player("p2".into()).unwrap().0;
+        let p3 = init.add_player("p3".into()).unwrap().0;
+        let p4 = init.add_player("p4".into()).unwrap().0;
+        let p5 = init.add_player("p5".into()).unwrap().0;
+        let p6 = init.add_player("p6".into()).unwrap().0;
+
+        init.set_landlord(Some(p3)).unwrap();
+        init.set_rank(p3, Number::Seven).unwrap();
+
+        // p3 leads, so p6 plays their friend card before p1 does.
+        let hands = [
+            (p1, [cards::D_4]),
+            (p2, [cards::C_3]),
+            (p3, [cards::S_7]),
+            (p4, [cards::C_4]),
+            (p5, [cards::C_5]),
+            (p6, [cards::D_3]),
+        ];
+        let mut exchange = deal_and_bid(init, &hands, None, (p3, cards::S_7));
+        exchange
+            .set_friends(
+                p3,
+                vec![
+                    FriendSelection {
+                        card: cards::D_3,
+                        initial_skip: 0,
+                    },
+                    FriendSelection {
+                        card: cards::D_4,
+                        initial_skip: 0,
+                    },
+                ],
+            )
+            .unwrap();
+        let mut play = exchange.advance(p3).unwrap();
+        for (p, h) in hands[2..].iter().chain(hands[..2].iter()) {
+            play.play_cards(*p, h).unwrap();
+        }
+        let msgs = play.finish_trick().unwrap();
+
+        assert_eq!(play.landlords_team, vec![p3, p1, p6]);
+        assert_eq!(
+            msgs.into_iter()
+                .filter_map(|m| match m {
+                    MessageVariant::JoinedTeam { player, .. } => Some(player),
+                    _ => None,
+                })
+                .collect::<Vec<_>>(),
+            vec![p1, p6]
+        );
+    }
+
+    #[test]
+    fn test_join_and_double_join_in_one_trick() {
+        let mut init = InitializePhase::new();
+        init.set_game_mode(GameModeSettings::FindingFriends {
+            num_friends: Some(2),
+        })
+        .unwrap();
+        let p1 = init.add_player("p1".into()).unwrap().0;
+        let p2 = init.add_player("p2".into()).unwrap().0;
+        let p3 = init.add_player("p3".into()).unwrap().0;
+        let p4 = init.add_player("p4".into()).unwrap().0;
+        let p5 = init.add_player("p5".into()).unwrap().0;
+        let p6 = init.add_player("p6".into()).unwrap().0;
+        init.set_landlord(Some(p1)).unwrap();
+
+        // p2 plays both friend cards when following p1's pair.
+        let hands = [
+            (p1, [cards::S_2, cards::D_5, cards::D_5]),
+            (p2, [cards::C_3, cards::D_3, cards::D_4]),
+            (p3, [cards::C_4, cards::C_5, cards::C_6]),
+            (p4, [cards::C_7, cards::C_8, cards::C_9]),
+            (p5, [cards::H_3, cards::H_4, cards::H_5]),
+            (p6, [cards::H_6, cards::H_7, cards::H_8]),
+        ];
+        let mut exchange = deal_and_bid(init, &hands, None, (p1, cards::S_2));
+        exchange
+            .set_friends(
+                p1,
+                vec![
+                    FriendSelection {
+                        card: cards::D_3,
+                        initial_skip: 0,
+                    },
+                    FriendSelection {
+                        card: cards::D_4,
+                        initial_skip: 0,
+                    },
+                ],
+            )
+            .unwrap();
+        let mut play = exchange.advance(p1).unwrap();
+        for (p, h) in &hands {
+            play.play_cards(*p, &h[1..]).unwrap();
+        }
+        let msgs = play.finish_trick().unwrap();
+
+        assert_eq!(play.landlords_team, vec![p1, p2]);
+        assert_eq!(
+            msgs.into_iter()
+                .filter_map(|m| match m {
+                    MessageVariant::JoinedTeam {
+                        player,
+                        already_joined,
+                        ..
+                    } => Some((player, already_joined)),
+                    _ => None,
+                })
+                .collect::<Vec<_>>(),
+            vec![(p2, false), (p2, true)]
+        );
+    }
+
+    #[test]
+    fn test_friend_point_transfer_policy() {
+        let non_landlord_points = |policy: FriendPointTransferPolicy| {
+            let mut init = InitializePhase::new();
+            init.set_game_mode(GameModeSettings::FindingFriends {
+                num_friends: Some(1),
+            })
+            .unwrap();
+            let p1 = init.add_player("p1".into()).unwrap().0;
+            let p2 = init.add_player("p2".into()).unwrap().0;
+            let p3 = init.add_player("p3".into()).unwrap().0;
+            let p4 = init.add_player("p4".into()).unwrap().0;
+            init.set_landlord(Some(p1)).unwrap();
+            init.set_friend_point_transfer_policy(policy).unwrap();
+
+            // p3 captures a king in the first trick, and only joins the
+            // landlord's team in the second trick.
+            let hands = [
+                (p1, [cards::C_3, cards::S_2]),
+                (p2, [cards::C_4, cards::D_4]),
+                (p3, [cards::C_K, cards::D_A]),
+                (p4, [cards::C_6, cards::D_3]),
+            ];
+            let mut exchange = deal_and_bid(init, &hands, Some(cards::C_7), (p1, cards::S_2));
+            exchange
+                .set_friends(
+                    p1,
+                    vec![FriendSelection {
+                        card: cards::D_A,
+                        initial_skip: 0,
+                    }],
+                )
+                .unwrap();
+            let mut play = exchange.advance(p1).unwrap();
+
+            for (p, h) in &hands {
+                play.play_cards(*p, &h[..1]).unwrap();
+            }
+            play.finish_trick().unwrap();
+            for (p, h) in hands[2..].iter().chain(hands[..2].iter()) {
+                play.play_cards(*p, &h[1..]).unwrap();
+            }
+            play.finish_trick().unwrap();
+            assert_eq!(play.landlords_team, vec![p1, p3]);
+
+            play.calculate_points().0
+        };
+
+        assert_eq!(
+            non_landlord_points(FriendPointTransferPolicy::MoveToLandlordTeam),
+            0
+        );
+        assert_eq!(
+            non_landlord_points(FriendPointTransferPolicy::KeepWithCaptor),
+            10
+        );
+    }
+
+    #[test]
+    fn test_sole_legal_play() {
+        let mut init = InitializePhase::new();
+        let p1 = init.add_player("p1".into()).unwrap().0;
+        let p2 = init.add_player("p2".into()).unwrap().0;
+        let p3 = init.add_player("p3".into()).unwrap().0;
+        let p4 = init.add_player("p4".into()).unwrap().0;
+        init.set_num_decks(Some(3)).unwrap();
+        init.set_landlord(Some(p1)).unwrap();
+
+        let hands = [
+            (p1, [cards::H_3, cards::H_3, cards::S_2]),
+            (p2, [cards::H_4, cards::H_4, cards::H_6]),
+            (p3, [cards::H_5, cards::H_7, cards::H_8]),
+            (p4, [cards::C_4, cards::C_4, cards::C_4]),
+        ];
+        let exchange = deal_and_bid(init, &hands, None, (p1, cards::S_2));
+        let mut play = exchange.advance(p1).unwrap();
+
+        // The leader always has a choice.
+        assert_eq!(play.sole_legal_play(p1), None);
+        play.play_cards(p1, &[cards::H_3, cards::H_3]).unwrap();
+
+        // p2 has to play their pair.
+        assert_eq!(play.sole_legal_play(p2), Some(vec![cards::H_4, cards::H_4]));
+        // Only the next player to play is considered.
+        assert_eq!(play.sole_legal_play(p3), None);
+        play.play_cards(p2, &[cards::H_4, cards::H_4]).unwrap();
+
+        // p3 can play any two of their hearts.
+        assert_eq!(play.sole_legal_play(p3), None);
+        play.play_cards(p3, &[cards::H_5, cards::H_7]).unwrap();
+
+        // p4 is void, but can only play clubs.
+        assert_eq!(play.sole_legal_play(p4), Some(vec![cards::C_4, cards::C_4]));
+    }
+
     #[test]
     fn test_landlord_small_team() {
         let mut init = InitializePhase::new();
@@ -2443,7 +6883,7 @@ mod tests {
         let msgs = play.finish_trick().unwrap();
         assert_eq!(
             msgs.into_iter()
-                .filter(|m| matches!(m, MessageVariant::JoinedTeam { player, already_joined: false } if *player == p2))
+                .filter(|m| matches!(m, MessageVariant::JoinedTeam { player, already_joined: false, via_card, remaining_friends: 2 } if *player == p2 && *via_card == p2_hand[0]))
                 .count(),
             1
         );
@@ -2465,7 +6905,7 @@ mod tests {
         let msgs = play.finish_trick().unwrap();
         assert_eq!(
             msgs.into_iter()
-                .filter(|m| matches!(m, MessageVariant::JoinedTeam { player, already_joined: true } if *player == p2))
+                .filter(|m| matches!(m, MessageVariant::JoinedTeam { player, already_joined: true, via_card, remaining_friends: 0 } if *player == p2 && *via_card == p2_hand[1]))
                 .count(),
             1
         );
@@ -2523,4 +6963,756 @@ mod tests {
             "Check that propagated players have the right new levels"
         );
     }
+
+    #[test]
+    fn test_move_deadline() {
+        let mut init = InitializePhase::new();
+        let p1 = init.add_player("p1".into()).unwrap().0;
+        let p2 = init.add_player("p2".into()).unwrap().0;
+        let p3 = init.add_player("p3".into()).unwrap().0;
+        let p4 = init.add_player("p4".into()).unwrap().0;
+        init.set_landlord(Some(p1)).unwrap();
+        assert!(init.set_move_deadline_secs(Some(0)).is_err());
+        init.set_move_deadline_secs(Some(30)).unwrap();
+
+        let mut draw = init.start(PlayerID(0)).unwrap();
+        let hands = [
+            (p1, [cards::S_2, cards::S_2]),
+            (p2, [cards::C_3, cards::C_3]),
+            (p3, [cards::C_4, cards::C_4]),
+            (p4, [cards::C_6, cards::C_6]),
+        ];
+        stack_deck(&mut draw, &hands, None);
+
+        let mut state = GameState::Draw(draw);
+        assert_eq!(state.time_since_last_action(1000), None);
+        assert_eq!(state.timed_out_player(1000), None);
+        state.record_action_at(1000);
+        assert_eq!(state.time_since_last_action(1010), Some(10));
+        assert_eq!(state.timed_out_player(1030), None);
+        assert_eq!(state.timed_out_player(1031), Some(p1));
+        // Clocks that move backwards shouldn't underflow.
+        assert_eq!(state.time_since_last_action(999), Some(0));
+
+        let mut draw = match state {
+            GameState::Draw(draw) => draw,
+            _ => unreachable!(),
+        };
+        for _ in 0..2 {
+            for (p, _) in &hands {
+                draw.draw_card(*p).unwrap();
+            }
+        }
+        assert!(draw.bid(p1, cards::S_2, 1));
+        let exchange = draw.advance(p1).unwrap();
+        let mut play = exchange.advance(p1).unwrap();
+        // The clock carries over between phases until the next action.
+        assert_eq!(play.time_since_last_action(1045), Some(45));
+        play.last_action_at = Some(1050);
+        assert_eq!(play.time_since_last_action(1065), Some(15));
+        play.propagated.paused = true;
+        assert_eq!(GameState::Play(play).timed_out_player(2000), None);
+    }
+
+    #[test]
+    fn test_take_back_requires_approval() {
+        let mut init = InitializePhase::new();
+        let p1 = init.add_player("p1".into()).unwrap().0;
+        let p2 = init.add_player("p2".into()).unwrap().0;
+        let p3 = init.add_player("p3".into()).unwrap().0;
+        let p4 = init.add_player("p4".into()).unwrap().0;
+        init.set_landlord(Some(p1)).unwrap();
+        init.set_play_takeback_policy(PlayTakebackPolicy::RequiresApproval)
+            .unwrap();
+
+        let hands = [
+            (p1, [cards::S_2, cards::C_3]),
+            (p2, [cards::C_4, cards::C_5]),
+            (p3, [cards::C_6, cards::C_7]),
+            (p4, [cards::C_8, cards::C_9]),
+        ];
+        let exchange = deal_and_bid(init, &hands, None, (p1, cards::S_2));
+        let mut play = exchange.advance(p1).unwrap();
+
+        play.play_cards(p1, &[cards::C_3]).unwrap();
+        play.play_cards(p2, &[cards::C_4]).unwrap();
+        assert!(play.take_back_cards(p2).is_err());
+        // Only the most recent play can be taken back.
+        assert!(play.request_take_back(p1).is_err());
+
+        assert!(matches!(
+            play.request_take_back(p2).unwrap()[..],
+            [MessageVariant::TakeBackRequested]
+        ));
+        assert!(play.request_take_back(p2).is_err());
+        // p4 is on p2's team, so they can't approve.
+        assert!(play.approve_take_back(p4).is_err());
+        assert!(matches!(
+            play.approve_take_back(p1).unwrap()[..],
+            [MessageVariant::TakeBackApproved]
+        ));
+        assert!(play.approve_take_back(p1).is_err());
+        assert_eq!(play.trick().played_cards().len(), 2);
+        assert_eq!(
+            play.pending_take_back(),
+            Some(&PendingTakeBack {
+                requester: p2,
+                approvals: vec![p1],
+            })
+        );
+
+        assert!(matches!(
+            play.approve_take_back(p3).unwrap()[..],
+            [
+                MessageVariant::TakeBackApproved,
+                MessageVariant::TakeBackCompleted { player }
+            ] if player == p2
+        ));
+        assert_eq!(play.pending_take_back(), None);
+        assert_eq!(play.trick().played_cards().len(), 1);
+        assert_eq!(play.hands().get(p2).unwrap().values().sum::<usize>(), 2);
+
+        // A cancelled request leaves the play in place.
+        play.play_cards(p2, &[cards::C_5]).unwrap();
+        play.request_take_back(p2).unwrap();
+        assert!(play.cancel_take_back(p4).is_err());
+        assert!(matches!(
+            play.cancel_take_back(p3).unwrap()[..],
+            [MessageVariant::TakeBackCancelled]
+        ));
+        assert_eq!(play.pending_take_back(), None);
+        assert_eq!(play.trick().played_cards().len(), 2);
+        assert!(play.approve_take_back(p1).is_err());
+
+        // Moving on to the next play discards any pending request.
+        play.request_take_back(p2).unwrap();
+        play.play_cards(p3, &[cards::C_6]).unwrap();
+        assert_eq!(play.pending_take_back(), None);
+    }
+
+    #[test]
+    fn test_game_modifiers() {
+        let setup = |modifiers: Vec<GameModifier>| {
+            let mut init = InitializePhase::new();
+            let p1 = init.add_player("p1".into()).unwrap().0;
+            let p2 = init.add_player("p2".into()).unwrap().0;
+            let p3 = init.add_player("p3".into()).unwrap().0;
+            let p4 = init.add_player("p4".into()).unwrap().0;
+            init.set_landlord(Some(p1)).unwrap();
+            init.set_game_modifiers(modifiers).unwrap();
+
+            let hands = [
+                (p1, [cards::S_2, cards::C_3, cards::C_K]),
+                (p2, [cards::C_4, cards::C_5, cards::C_6]),
+                (p3, [cards::C_7, cards::C_8, cards::C_9]),
+                (p4, [cards::C_10, cards::C_J, cards::C_Q]),
+            ];
+            let exchange = deal_and_bid(init, &hands, None, (p1, cards::S_2));
+            let mut play = exchange.advance(p1).unwrap();
+            // The attackers have captured 25 points, and the game is over.
+            play.points
+                .insert(p2, vec![cards::C_5, cards::C_10, cards::C_K]);
+            play.game_ended_early = true;
+            play
+        };
+        let non_landlords_points = |play: &PlayPhase| {
+            let (_, _, msgs) = play.finish_game().unwrap();
+            msgs.into_iter()
+                .find_map(|m| match m {
+                    MessageVariant::EndOfGameSummary {
+                        non_landlords_points,
+                        ..
+                    } => Some(non_landlords_points),
+                    _ => None,
+                })
+                .unwrap()
+        };
+
+        let play = setup(vec![]);
+        assert_eq!(non_landlords_points(&play), 25);
+
+        let play = setup(vec![GameModifier::DoublePoints, GameModifier::DoublePoints]);
+        assert_eq!(
+            play.propagated.game_modifiers,
+            vec![GameModifier::DoublePoints]
+        );
+        assert_eq!(non_landlords_points(&play), 50);
+        // Modifiers only last for a single game.
+        let (init, _, _) = play.finish_game().unwrap();
+        assert!(init.propagated.game_modifiers.is_empty());
+
+        // A doubled game can still end early once the remaining points can't
+        // change the outcome.
+        let mut play = setup(vec![GameModifier::DoublePoints]);
+        play.game_ended_early = false;
+        assert!(play.finish_game_early().is_err());
+        let p1 = play.landlord;
+        // The landlord has captured all of the remaining points.
+        let total_points = play
+            .propagated
+            .game_scoring_parameters
+            .materialize(&play.decks)
+            .unwrap()
+            .total_points();
+        play.points
+            .insert(p1, vec![cards::C_5; (total_points as usize - 25) / 5]);
+        play.finish_game_early().unwrap();
+        assert!(play.game_finished());
+
+        let mut play = setup(vec![]);
+        play.game_ended_early = false;
+        let p1 = play.landlord;
+        play.play_cards(p1, &[cards::C_3, cards::C_K]).unwrap();
+
+        let mut play = setup(vec![GameModifier::NoThrows]);
+        play.game_ended_early = false;
+        let p1 = play.landlord;
+        assert!(play.play_cards(p1, &[cards::C_3, cards::C_K]).is_err());
+        play.play_cards(p1, &[cards::C_3]).unwrap();
+
+        // The attackers win the last trick with a single, so the kitty
+        // would otherwise count double.
+        for (modifiers, expected_points) in
+            [(vec![], 85), (vec![GameModifier::NoKittyMultiplier], 45)]
+        {
+            let mut init = InitializePhase::new();
+            let p1 = init.add_player("p1".into()).unwrap().0;
+            let p2 = init.add_player("p2".into()).unwrap().0;
+            let p3 = init.add_player("p3".into()).unwrap().0;
+            let p4 = init.add_player("p4".into()).unwrap().0;
+            init.set_landlord(Some(p1)).unwrap();
+            init.set_game_modifiers(modifiers).unwrap();
+
+            let hands = [
+                (p1, [cards::S_2, cards::D_3]),
+                (p2, [cards::C_3, cards::D_4]),
+                (p3, [cards::C_4, cards::D_5]),
+                (p4, [cards::C_6, cards::D_A]),
+            ];
+            let exchange = deal_and_bid(init, &hands, Some(cards::C_5), (p1, cards::S_2));
+            let mut play = exchange.advance(p1).unwrap();
+            for i in 0..2 {
+                for (p, h) in &hands {
+                    play.play_cards(*p, &h[i..=i]).unwrap();
+                }
+                play.finish_trick().unwrap();
+            }
+            assert_eq!(play.final_score().unwrap().0, expected_points);
+        }
+    }
+
+    #[test]
+    fn test_history_replays_take_backs() {
+        let mut init = InitializePhase::new();
+        let p1 = init.add_player("p1".into()).unwrap().0;
+        let p2 = init.add_player("p2".into()).unwrap().0;
+        let p3 = init.add_player("p3".into()).unwrap().0;
+        let p4 = init.add_player("p4".into()).unwrap().0;
+        let director = init.add_observer("director".into()).unwrap();
+        init.set_landlord(Some(p1)).unwrap();
+        init.set_play_takeback_policy(PlayTakebackPolicy::RequiresApproval)
+            .unwrap();
+        init.set_director(Some(director)).unwrap();
+
+        let hands = [
+            (p1, [cards::S_2, cards::C_3, cards::C_K]),
+            (p2, [cards::C_4, cards::C_5, cards::C_6]),
+            (p3, [cards::C_7, cards::C_8, cards::C_9]),
+            (p4, [cards::C_10, cards::C_J, cards::C_Q]),
+        ];
+        let exchange = deal_and_bid(init, &hands, None, (p1, cards::S_2));
+        let mut play = exchange.advance(p1).unwrap();
+        let initial = play.clone();
+
+        play.play_cards(p1, &[cards::C_3]).unwrap();
+        play.play_cards(p2, &[cards::C_5]).unwrap();
+        play.request_take_back(p2).unwrap();
+        play.approve_take_back(p1).unwrap();
+        play.approve_take_back(p3).unwrap();
+        assert!(play.pending_take_back().is_none());
+        play.play_cards(p2, &[cards::C_4]).unwrap();
+        play.play_cards(p3, &[cards::C_7]).unwrap();
+        play.play_cards(p4, &[cards::C_10]).unwrap();
+        play.finish_trick().unwrap();
+        play.director_reassign_points(director, p4, p2, vec![cards::C_10])
+            .unwrap();
+        play.play_cards(p4, &[cards::C_J]).unwrap();
+        play.play_cards(p1, &[cards::C_K]).unwrap();
+        play.play_cards(p2, &[cards::C_5]).unwrap();
+        play.play_cards(p3, &[cards::C_8]).unwrap();
+        play.finish_trick().unwrap();
+
+        // Replaying the history from the start of play reproduces the game.
+        let mut replay = initial;
+        let start = replay.history.len();
+        for entry in &play.history[start..] {
+            match entry.message {
+                MessageVariant::PlayedCards { ref cards } => {
+                    replay.play_cards(entry.actor, cards).unwrap();
+                }
+                MessageVariant::TookBackPlay => {
+                    replay
+                        .trick
+                        .take_back(
+                            entry.actor,
+                            &mut replay.hands,
+                            replay.propagated.throw_evaluation_policy,
+                            replay.propagated.throw_tie_break_policy,
+                        )
+                        .unwrap();
+                }
+                MessageVariant::TrickWon { .. } => {
+                    replay.finish_trick().unwrap();
+                }
+                MessageVariant::DirectorCorrection {
+                    player,
+                    correction: Correction::ReassignedPoints { to, ref cards },
+                } => {
+                    replay
+                        .director_reassign_points(entry.actor, player, to, cards.clone())
+                        .unwrap();
+                }
+                ref m => panic!("unexpected history entry {:?}", m),
+            }
+        }
+        for p in &[p1, p2, p3, p4] {
+            assert_eq!(replay.hands.get(*p).unwrap(), play.hands.get(*p).unwrap());
+        }
+        assert_eq!(replay.points, play.points);
+        assert_eq!(play.points[&p2], vec![cards::C_10]);
+        assert_eq!(
+            play.history
+                .iter()
+                .filter(|e| matches!(e.message, MessageVariant::TookBackPlay))
+                .map(|e| e.actor)
+                .collect::<Vec<_>>(),
+            vec![p2]
+        );
+    }
+
+    #[test]
+    fn test_history() {
+        let mut init = InitializePhase::new();
+        let p1 = init.add_player("p1".into()).unwrap().0;
+        let p2 = init.add_player("p2".into()).unwrap().0;
+        let p3 = init.add_player("p3".into()).unwrap().0;
+        let p4 = init.add_player("p4".into()).unwrap().0;
+        init.set_landlord(Some(p1)).unwrap();
+
+        let hands = [
+            (p1, [cards::S_2, cards::C_3, cards::C_K]),
+            (p2, [cards::C_4, cards::C_5, cards::C_6]),
+            (p3, [cards::C_7, cards::C_8, cards::C_9]),
+            (p4, [cards::C_10, cards::C_J, cards::C_Q]),
+        ];
+        let exchange = deal_and_bid(init, &hands, None, (p1, cards::S_2));
+        let mut play = exchange.advance(p1).unwrap();
+
+        play.play_cards(p1, &[cards::C_3]).unwrap();
+        play.play_cards(p2, &[cards::C_4]).unwrap();
+        play.play_cards(p3, &[cards::C_7]).unwrap();
+        play.play_cards(p4, &[cards::C_10]).unwrap();
+        play.finish_trick().unwrap();
+        play.play_cards(p4, &[cards::C_J]).unwrap();
+        play.play_cards(p1, &[cards::C_K]).unwrap();
+        play.play_cards(p2, &[cards::C_5]).unwrap();
+        play.play_cards(p3, &[cards::C_8]).unwrap();
+        play.finish_trick().unwrap();
+
+        let state = GameState::Play(play);
+        let history = state.history();
+        assert!(history.iter().enumerate().all(|(idx, e)| e.seq == idx));
+        let summary = history
+            .iter()
+            .map(|e| match e.message {
+                MessageVariant::MadeBid { card, .. } => (e.actor, "bid", card),
+                MessageVariant::PlayedCards { ref cards } => (e.actor, "play", cards[0]),
+                MessageVariant::TrickWon { .. } => (e.actor, "won", Card::Unknown),
+                ref m => panic!("unexpected history entry {:?}", m),
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            summary,
+            vec![
+                (p1, "bid", cards::S_2),
+                (p1, "play", cards::C_3),
+                (p2, "play", cards::C_4),
+                (p3, "play", cards::C_7),
+                (p4, "play", cards::C_10),
+                (p4, "won", Card::Unknown),
+                (p4, "play", cards::C_J),
+                (p1, "play", cards::C_K),
+                (p2, "play", cards::C_5),
+                (p3, "play", cards::C_8),
+                (p1, "won", Card::Unknown),
+            ]
+        );
+
+        // Hidden cards stay hidden in the history until the game is over.
+        let mut state = state;
+        if let GameState::Play(ref mut p) = state {
+            p.propagated.hide_played_cards = true;
+        }
+        assert!(state
+            .for_player(p2)
+            .history()
+            .iter()
+            .all(|e| match e.message {
+                MessageVariant::PlayedCards { ref cards } => cards == &[Card::Unknown],
+                _ => true,
+            }));
+    }
+
+    #[test]
+    fn test_first_lead_policy() {
+        let first_leader = |policy: FirstLeadPolicy| {
+            let mut init = InitializePhase::new();
+            let p1 = init.add_player("p1".into()).unwrap().0;
+            let p2 = init.add_player("p2".into()).unwrap().0;
+            let p3 = init.add_player("p3".into()).unwrap().0;
+            let p4 = init.add_player("p4".into()).unwrap().0;
+            init.set_landlord(Some(p1)).unwrap();
+            init.set_first_lead_policy(policy).unwrap();
+
+            let hands = [
+                (p1, [cards::S_3, cards::C_3, cards::C_K]),
+                (p2, [cards::C_4, cards::C_5, cards::C_6]),
+                (p3, [cards::H_2, cards::C_8, cards::C_9]),
+                (p4, [cards::C_10, cards::C_J, cards::C_Q]),
+            ];
+            let mut draw = deal(init, &hands, None);
+            // The landlord was already chosen, but p3 won the bid.
+            assert!(draw.bid(p3, cards::H_2, 1));
+            let exchange = draw.advance(p1).unwrap();
+            let play = exchange.advance(p1).unwrap();
+            assert_eq!(play.landlord, p1);
+            play.next_player().unwrap()
+        };
+
+        assert_eq!(first_leader(FirstLeadPolicy::Landlord), PlayerID(0));
+        assert_eq!(first_leader(FirstLeadPolicy::LeftOfLandlord), PlayerID(1));
+        assert_eq!(first_leader(FirstLeadPolicy::BidWinner), PlayerID(2));
+    }
+
+    #[test]
+    fn test_can_take_back_bid() {
+        let setup = |policy: BidTakebackPolicy| {
+            let mut init = InitializePhase::new();
+            let p1 = init.add_player("p1".into()).unwrap().0;
+            let p2 = init.add_player("p2".into()).unwrap().0;
+            init.add_player("p3".into()).unwrap();
+            init.add_player("p4".into()).unwrap();
+            init.set_landlord(Some(p1)).unwrap();
+            init.set_bid_takeback_policy(policy).unwrap();
+            let mut draw = init.start(PlayerID(0)).unwrap();
+            let hands = [(p1, [cards::S_2]), (p2, [cards::H_2])];
+            stack_deck(&mut draw, &hands, None);
+            for (p, _) in &hands {
+                draw.draw_card(*p).unwrap();
+            }
+            assert!(draw.bid(p1, cards::S_2, 1));
+            (draw, p1, p2)
+        };
+
+        let (mut draw, p1, _) = setup(BidTakebackPolicy::NoBidTakeback);
+        assert!(!draw.can_take_back_bid(p1));
+        assert!(draw.take_back_bid(p1).is_err());
+
+        let (mut draw, p1, p2) = setup(BidTakebackPolicy::AllowBidTakeback);
+        assert!(!draw.can_take_back_bid(p2));
+        assert!(draw.take_back_bid(p2).is_err());
+        assert!(draw.can_take_back_bid(p1));
+        assert!(GameState::Draw(draw.clone()).can_take_back_bid(p1));
+        draw.take_back_bid(p1).unwrap();
+        assert!(!draw.can_take_back_bid(p1));
+
+        // Bids made before the exchange phase can't be taken back afterwards.
+        assert!(draw.bid(p1, cards::S_2, 1));
+        let mut exchange = draw.advance(p1).unwrap();
+        exchange.finalize(p1).unwrap();
+        assert!(!exchange.can_take_back_bid(p1));
+        assert!(exchange.take_back_bid(p1).is_err());
+    }
+
+    #[test]
+    fn test_estimated_trump_remaining() {
+        let mut init = InitializePhase::new();
+        let p1 = init.add_player("p1".into()).unwrap().0;
+        let p2 = init.add_player("p2".into()).unwrap().0;
+        let p3 = init.add_player("p3".into()).unwrap().0;
+        let p4 = init.add_player("p4".into()).unwrap().0;
+        init.set_landlord(Some(p1)).unwrap();
+        init.set_rank(p1, Number::Jack).unwrap();
+
+        let hands = [
+            (p1, [cards::S_J, cards::S_K, cards::H_Q, cards::C_Q]),
+            (p2, [cards::S_A, cards::H_J, Card::BigJoker, cards::D_Q]),
+            (p3, [cards::S_Q, cards::H_K, cards::H_A, cards::C_K]),
+            (p4, [cards::C_J, cards::D_J, Card::SmallJoker, cards::D_K]),
+        ];
+        let exchange = deal_and_bid(init, &hands, None, (p1, cards::S_J));
+        let mut play = exchange.advance(p1).unwrap();
+        // Play with the jacks and up, leaving two aces in the bottom.
+        play.decks = vec![Deck {
+            min: Number::Jack,
+            ..Default::default()
+        }];
+        play.kitty = vec![cards::C_A, cards::D_A];
+
+        // Nine trumps are spread across 18 cards.
+        let estimate = play.estimated_trump_remaining();
+        for p in &[p1, p2, p3, p4] {
+            assert_eq!(estimate[p], 2);
+        }
+
+        play.play_cards(p1, &[cards::S_K]).unwrap();
+        play.play_cards(p2, &[cards::S_A]).unwrap();
+        play.play_cards(p3, &[cards::S_Q]).unwrap();
+        play.play_cards(p4, &[cards::C_J]).unwrap();
+        play.finish_trick().unwrap();
+        // Five trumps are left among 14 cards.
+        let estimate = play.estimated_trump_remaining();
+        for p in &[p1, p2, p3, p4] {
+            assert_eq!(estimate[p], 1);
+        }
+
+        play.play_cards(p4, &[cards::D_J]).unwrap();
+        play.play_cards(p1, &[cards::S_J]).unwrap();
+        play.play_cards(p2, &[Card::BigJoker]).unwrap();
+        play.play_cards(p3, &[cards::H_K]).unwrap();
+        // p3 didn't follow the trump lead, so they're out of trumps. The
+        // other two trumps are split among the six other unseen cards.
+        let estimate = play.estimated_trump_remaining();
+        assert_eq!(estimate[&p3], 0);
+        assert_eq!(estimate[&p1], 1);
+        assert_eq!(estimate[&p2], 1);
+        assert_eq!(estimate[&p4], 1);
+
+        // Taking back the play removes what it revealed.
+        play.propagated.play_takeback_policy = PlayTakebackPolicy::AllowPlayTakeback;
+        play.take_back_cards(p3).unwrap();
+        let estimate = play.estimated_trump_remaining();
+        assert_eq!(estimate[&p3], 1);
+    }
+
+    #[test]
+    fn test_out_of_turn_penalty() {
+        let logger = Logger::root(Discard, o!());
+        let mut init = InitializePhase::new();
+        let p1 = init.add_player("p1".into()).unwrap().0;
+        let p2 = init.add_player("p2".into()).unwrap().0;
+        let p3 = init.add_player("p3".into()).unwrap().0;
+        let p4 = init.add_player("p4".into()).unwrap().0;
+        let observer = init.add_observer("observer".into()).unwrap();
+        init.set_landlord(Some(p1)).unwrap();
+        init.set_out_of_turn_policy(OutOfTurnPolicy::WarnThenPenalize {
+            warnings: 1,
+            points: 10,
+        })
+        .unwrap();
+
+        let hands = [
+            (p1, [cards::S_2, cards::C_3]),
+            (p2, [cards::C_4, cards::C_5]),
+            (p3, [cards::C_6, cards::C_7]),
+            (p4, [cards::C_8, cards::C_9]),
+        ];
+        let exchange = deal_and_bid(init, &hands, None, (p1, cards::S_2));
+        let play = exchange.advance(p1).unwrap();
+
+        let mut game = InteractiveGame::new_from_state(GameState::Play(play));
+        // Observers aren't seated, so their attempts are just errors.
+        for _ in 0..3 {
+            assert!(game
+                .interact(Action::PlayCards(vec![cards::C_4]), observer, &logger)
+                .is_err());
+        }
+        // The first attempt only gets a warning.
+        let msgs = game
+            .interact(Action::PlayCards(vec![cards::C_4]), p2, &logger)
+            .unwrap();
+        assert_eq!(msgs.len(), 1);
+        assert_eq!(msgs[0].1, "p2 was warned for playing out of turn");
+        // Subsequent attempts accrue the configured penalty.
+        for _ in 0..2 {
+            let msgs = game
+                .interact(Action::PlayCards(vec![cards::C_4]), p2, &logger)
+                .unwrap();
+            assert_eq!(msgs.len(), 1);
+            assert_eq!(
+                msgs[0].1,
+                "p2 was penalized 10 points for playing out of turn"
+            );
+        }
+        let mut play = match game.into_state() {
+            GameState::Play(play) => play,
+            _ => panic!("expected to still be in the play phase"),
+        };
+        assert_eq!(play.penalties[&p2], 20);
+        assert_eq!(play.penalties[&p3], 0);
+        assert!(!play.penalties.contains_key(&observer));
+        assert!(!play.out_of_turn_attempts.contains_key(&observer));
+        assert_eq!(play.calculate_points().0, -20);
+        assert_eq!(play.hands.get(p2).unwrap().get(&cards::C_4), Some(&1));
+
+        // Once it's their turn, the play goes through as usual.
+        play.play_cards(p1, &[cards::C_3]).unwrap();
+        play.play_cards(p2, &[cards::C_4]).unwrap();
+
+        // Without a policy, playing out of turn is just an error.
+        play.propagated.out_of_turn_policy = OutOfTurnPolicy::Ignore;
+        assert!(play.play_cards(p4, &[cards::C_8]).is_err());
+        assert!(play.record_out_of_turn(p4).is_empty());
+    }
+
+    #[test]
+    fn test_director_corrections() {
+        let mut init = InitializePhase::new();
+        let p1 = init.add_player("p1".into()).unwrap().0;
+        let p2 = init.add_player("p2".into()).unwrap().0;
+        let p3 = init.add_player("p3".into()).unwrap().0;
+        let p4 = init.add_player("p4".into()).unwrap().0;
+        let director = init.add_observer("director".into()).unwrap();
+        init.set_landlord(Some(p1)).unwrap();
+        init.set_play_takeback_policy(PlayTakebackPolicy::NoPlayTakeback)
+            .unwrap();
+        assert!(init.set_director(Some(PlayerID(100))).is_err());
+        init.set_director(Some(director)).unwrap();
+
+        let hands = [
+            (p1, [cards::S_2, cards::C_3]),
+            (p2, [cards::C_4, cards::C_5]),
+            (p3, [cards::C_6, cards::C_K]),
+            (p4, [cards::C_8, cards::C_9]),
+        ];
+        let exchange = deal_and_bid(init, &hands, None, (p1, cards::S_2));
+        let mut play = exchange.advance(p1).unwrap();
+
+        play.play_cards(p1, &[cards::C_3]).unwrap();
+        play.play_cards(p2, &[cards::C_5]).unwrap();
+        // Neither p2 nor another player can take back p2's play.
+        assert!(play.take_back_cards(p2).is_err());
+        assert!(play.director_take_back(p1, p2).is_err());
+        assert!(matches!(
+            play.director_take_back(director, p2).unwrap()[..],
+            [MessageVariant::DirectorCorrection {
+                player,
+                correction: Correction::TookBackCards,
+            }] if player == p2
+        ));
+        assert_eq!(play.hands.get(p2).unwrap().get(&cards::C_5), Some(&1));
+
+        play.play_cards(p2, &[cards::C_4]).unwrap();
+        play.play_cards(p3, &[cards::C_K]).unwrap();
+        play.play_cards(p4, &[cards::C_8]).unwrap();
+        play.finish_trick().unwrap();
+        assert_eq!(play.points[&p3], vec![cards::C_K]);
+
+        assert!(play
+            .director_reassign_points(p3, p3, p4, vec![cards::C_K])
+            .is_err());
+        assert!(play
+            .director_reassign_points(director, p4, p3, vec![cards::C_K])
+            .is_err());
+        play.director_reassign_points(director, p3, p4, vec![cards::C_K])
+            .unwrap();
+        assert!(play.points[&p3].is_empty());
+        assert_eq!(play.points[&p4], vec![cards::C_K]);
+    }
+
+    #[test]
+    fn test_reveal_kitty_point_count() {
+        let setup = |reveal: bool| {
+            let mut init = InitializePhase::new();
+            let p1 = init.add_player("p1".into()).unwrap().0;
+            let p2 = init.add_player("p2".into()).unwrap().0;
+            let p3 = init.add_player("p3".into()).unwrap().0;
+            let p4 = init.add_player("p4".into()).unwrap().0;
+            init.set_landlord(Some(p1)).unwrap();
+            init.set_reveal_kitty_point_count(reveal).unwrap();
+            let hands = [
+                (p1, [cards::S_2]),
+                (p2, [cards::C_9]),
+                (p3, [cards::C_8]),
+                (p4, [cards::C_7]),
+            ];
+            let exchange = deal_and_bid(init, &hands, None, (p1, cards::S_2));
+            let mut play = exchange.advance(p1).unwrap();
+            play.kitty = vec![cards::C_5, cards::C_K, cards::C_3];
+            (GameState::Play(play), p1, p2)
+        };
+        let kitty_for = |state: &GameState, id: PlayerID| match state.for_player(id) {
+            GameState::Play(p) => (p.kitty, p.kitty_point_count),
+            _ => unreachable!(),
+        };
+
+        let (state, p1, p2) = setup(true);
+        assert_eq!(
+            kitty_for(&state, p2),
+            (vec![Card::Unknown, Card::Unknown, Card::Unknown], Some(2))
+        );
+        assert_eq!(
+            kitty_for(&state, p1),
+            (vec![cards::C_5, cards::C_K, cards::C_3], None)
+        );
+
+        let (state, _, p2) = setup(false);
+        assert_eq!(
+            kitty_for(&state, p2),
+            (vec![Card::Unknown, Card::Unknown, Card::Unknown], None)
+        );
+    }
+
+    #[test]
+    fn test_joker_bid_suit_policy() {
+        let setup = |policy: JokerBidSuitPolicy| {
+            let mut init = InitializePhase::new();
+            let p1 = init.add_player("p1".into()).unwrap().0;
+            let p2 = init.add_player("p2".into()).unwrap().0;
+            let p3 = init.add_player("p3".into()).unwrap().0;
+            let p4 = init.add_player("p4".into()).unwrap().0;
+            init.set_landlord(Some(p1)).unwrap();
+            init.set_joker_bid_suit_policy(policy).unwrap();
+            let hands = [
+                (p1, [Card::BigJoker, Card::BigJoker]),
+                (p2, [cards::C_6, cards::C_9]),
+                (p3, [cards::C_4, cards::C_8]),
+                (p4, [cards::C_3, cards::C_7]),
+            ];
+            let mut draw = deal(init, &hands, None);
+            assert!(draw.bid(p1, Card::BigJoker, 2));
+            (draw.advance(p1).unwrap(), p1, p2)
+        };
+
+        let (mut exchange, p1, _) = setup(JokerBidSuitPolicy::ForceNoTrump);
+        assert_eq!(
+            exchange.trump,
+            Trump::NoTrump {
+                number: Number::Two,
+                permanent: PermanentTrumps::NONE
+            }
+        );
+        assert!(exchange.set_trump_suit(p1, Suit::Hearts).is_err());
+        exchange.finalize(p1).unwrap();
+
+        let (mut exchange, p1, p2) = setup(JokerBidSuitPolicy::LandlordChoosesSuit);
+        assert!(exchange.finalize(p1).is_err());
+        assert!(exchange.clone().advance(p1).is_err());
+        assert!(exchange.set_trump_suit(p2, Suit::Hearts).is_err());
+        exchange.set_trump_suit(p1, Suit::Hearts).unwrap();
+        // The suit can be changed until the bottom is finalized.
+        exchange.set_trump_suit(p1, Suit::Clubs).unwrap();
+        exchange.finalize(p1).unwrap();
+        assert!(exchange.set_trump_suit(p1, Suit::Spades).is_err());
+        assert_eq!(
+            exchange.trump,
+            Trump::Standard {
+                suit: Suit::Clubs,
+                number: Number::Two,
+                permanent: PermanentTrumps::NONE
+            }
+        );
+        let play = exchange.advance(p1).unwrap();
+        assert_eq!(play.trump, exchange.trump);
+    }
 }
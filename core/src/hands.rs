@@ -1,10 +1,11 @@
+use std::cmp::Ordering;
 use std::collections::hash_map::Entry;
 use std::collections::HashMap;
 
 use serde::{Deserialize, Serialize};
 use thiserror::Error;
 
-use crate::types::{Card, EffectiveSuit, Number, PlayerID, Trump};
+use crate::types::{Card, EffectiveSuit, Number, PermanentTrumps, PlayerID, Suit, Trump};
 
 #[derive(Error, Clone, Debug, Serialize, Deserialize)]
 pub enum HandError {
@@ -111,14 +112,30 @@ impl Hands {
         let mut cards = Card::cards(self.hands[&id].iter())
             .copied()
             .collect::<Vec<Card>>();
-        if let Some(trump) = self.trump {
-            cards.sort_by(|a, b| trump.compare(*a, *b));
-        } else {
-            cards.sort_by(|a, b| Trump::NoTrump { number: level }.compare(*a, *b));
-        }
+        let comparator = self
+            .trump
+            .unwrap_or(Trump::NoTrump {
+                number: level,
+                permanent: PermanentTrumps::NONE,
+            })
+            .comparator();
+        cards.sort_by(|a, b| comparator.compare(*a, *b));
         Ok(cards)
     }
 
+    /// Returns the player's hand sorted by `trump` and grouped by effective
+    /// suit, in the same order that the frontend displays it. The trump group
+    /// comes after the side suits, and any unknown cards come last.
+    pub fn sorted_for_player(
+        &self,
+        id: PlayerID,
+        trump: Trump,
+    ) -> Result<Vec<SuitGroup>, HandError> {
+        self.exists(id)?;
+        let cards = Card::cards(self.hands[&id].iter()).copied().collect();
+        Ok(group_cards(trump, cards, UnknownCardPlacement::Last))
+    }
+
     pub fn add(
         &mut self,
         id: PlayerID,
@@ -157,12 +174,217 @@ impl Hands {
     }
 }
 
+/// Where unknown (redacted) cards are placed when sorting a hand.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum UnknownCardPlacement {
+    First,
+    Last,
+}
+
+impl Default for UnknownCardPlacement {
+    fn default() -> Self {
+        UnknownCardPlacement::Last
+    }
+}
+
+/// A run of cards in a sorted hand which share an effective suit.
+#[derive(Debug, PartialEq, Serialize)]
+pub struct SuitGroup<C = Card> {
+    pub suit: EffectiveSuit,
+    pub cards: Vec<C>,
+}
+
+/// Sorts the cards and groups them by effective suit. Unknown cards are placed
+/// in their own group, either before or after the rest of the cards.
+pub fn group_cards(
+    trump: Trump,
+    cards: Vec<Card>,
+    unknown_placement: UnknownCardPlacement,
+) -> Vec<SuitGroup> {
+    let (unknown, mut cards): (Vec<Card>, Vec<Card>) =
+        cards.into_iter().partition(|c| *c == Card::Unknown);
+    let comparator = trump.comparator();
+    cards.sort_by(|a, b| comparator.compare(*a, *b));
+
+    let mut results: Vec<SuitGroup> = vec![];
+    for card in cards {
+        let suit = comparator.effective_suit(card);
+        if let Some(group) = results.last_mut() {
+            if group.suit == suit {
+                group.cards.push(card);
+                continue;
+            }
+        }
+        results.push(SuitGroup {
+            suit,
+            cards: vec![card],
+        })
+    }
+    if !unknown.is_empty() {
+        let group = SuitGroup {
+            suit: EffectiveSuit::Unknown,
+            cards: unknown,
+        };
+        match unknown_placement {
+            UnknownCardPlacement::First => results.insert(0, group),
+            UnknownCardPlacement::Last => results.push(group),
+        }
+    }
+    results
+}
+
+/// Rates how strong a dealt hand is, from 0 for a weak hand to 1 for a strong
+/// one. Trumps, point cards, tractors and void side suits all make a hand
+/// stronger.
+pub fn analyze_hand_difficulty(hand: &HashMap<Card, usize>, trump: Trump) -> f32 {
+    let num_cards = hand.values().sum::<usize>();
+    if num_cards == 0 {
+        return 0.0;
+    }
+
+    let num_trumps = hand
+        .iter()
+        .filter(|(card, _)| trump.effective_suit(**card) == EffectiveSuit::Trump)
+        .map(|(_, count)| count)
+        .sum::<usize>();
+    let points = hand
+        .iter()
+        .map(|(card, count)| card.points().unwrap_or(0) * count)
+        .sum::<usize>();
+
+    // A pair is part of a tractor if it's adjacent to another pair in the
+    // same effective suit.
+    let is_pair = |card: &Card| hand.get(card).copied().unwrap_or(0) >= 2;
+    let num_tractor_cards = hand
+        .keys()
+        .filter(|card| is_pair(card))
+        .filter(|card| {
+            trump
+                .successor(**card)
+                .iter()
+                .chain(hand.keys().filter(|c| trump.successor(**c).contains(card)))
+                .any(|c| is_pair(c) && trump.effective_suit(*c) == trump.effective_suit(**card))
+        })
+        .count()
+        * 2;
+
+    let side_suits = [
+        (Suit::Clubs, EffectiveSuit::Clubs),
+        (Suit::Diamonds, EffectiveSuit::Diamonds),
+        (Suit::Spades, EffectiveSuit::Spades),
+        (Suit::Hearts, EffectiveSuit::Hearts),
+    ]
+    .iter()
+    .filter(|(suit, _)| trump.suit() != Some(*suit))
+    .map(|(_, effective_suit)| *effective_suit)
+    .collect::<Vec<_>>();
+    let num_voids = side_suits
+        .iter()
+        .filter(|s| !hand.keys().any(|c| trump.effective_suit(*c) == **s))
+        .count();
+
+    let trump_score = num_trumps as f32 / num_cards as f32;
+    let point_score = points as f32 / (10 * num_cards) as f32;
+    let tractor_score = num_tractor_cards.min(num_cards) as f32 / num_cards as f32;
+    let void_score = num_voids as f32 / side_suits.len() as f32;
+
+    // Each score is between 0 and 1, and the weights add up to 1.
+    0.35 * trump_score + 0.2 * point_score + 0.25 * tractor_score + 0.2 * void_score
+}
+
+/// The number of cards a hand holds in each effective suit.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HandShape {
+    pub clubs: usize,
+    pub diamonds: usize,
+    pub spades: usize,
+    pub hearts: usize,
+    pub trump: usize,
+}
+
+impl HandShape {
+    pub fn count(&self, suit: EffectiveSuit) -> usize {
+        match suit {
+            EffectiveSuit::Clubs => self.clubs,
+            EffectiveSuit::Diamonds => self.diamonds,
+            EffectiveSuit::Spades => self.spades,
+            EffectiveSuit::Hearts => self.hearts,
+            EffectiveSuit::Trump => self.trump,
+            EffectiveSuit::Unknown => 0,
+        }
+    }
+}
+
+/// Counts the cards in `hand` by effective suit, e.g. to check that deals are
+/// balanced. Unknown cards aren't counted.
+pub fn analyze_hand_shape(hand: &HashMap<Card, usize>, trump: Trump) -> HandShape {
+    let mut shape = HandShape::default();
+    for (card, count) in hand {
+        match trump.effective_suit(*card) {
+            EffectiveSuit::Clubs => shape.clubs += count,
+            EffectiveSuit::Diamonds => shape.diamonds += count,
+            EffectiveSuit::Spades => shape.spades += count,
+            EffectiveSuit::Hearts => shape.hearts += count,
+            EffectiveSuit::Trump => shape.trump += count,
+            EffectiveSuit::Unknown => (),
+        }
+    }
+    shape
+}
+
+/// Ranks each of the cards in a leader's hand by how urgently it should be
+/// led, from 0 to 1, given the cards which the leader hasn't seen yet. Cards
+/// which can't be beaten come first, and trumps before side suits since
+/// leading them pulls the other players' trumps. Point cards which can be
+/// beaten come last, so that they aren't given away. This is only advice.
+pub fn analyze_lead_priority(
+    hand: &HashMap<Card, usize>,
+    trump: Trump,
+    unseen: &HashMap<Card, usize>,
+) -> Vec<(Card, f32)> {
+    let mut priorities = hand
+        .keys()
+        .map(|card| {
+            let suit = trump.effective_suit(*card);
+            let num_higher = unseen
+                .iter()
+                .filter(|(c, _)| {
+                    trump.effective_suit(**c) == suit
+                        && trump.compare(**c, *card) == Ordering::Greater
+                })
+                .map(|(_, count)| count)
+                .sum::<usize>();
+            let has_points = card.points().is_some();
+            let priority = match (num_higher, suit) {
+                (0, EffectiveSuit::Trump) => 1.0,
+                (0, _) if has_points => 0.9,
+                (0, _) => 0.8,
+                // Cards which can be beaten are ranked by how many cards can
+                // beat them.
+                (_, _) if has_points => 0.2 / (1 + num_higher) as f32,
+                (_, _) => 0.5 / (1 + num_higher) as f32,
+            };
+            (*card, priority)
+        })
+        .collect::<Vec<_>>();
+    priorities.sort_by(|(a, a_priority), (b, b_priority)| {
+        b_priority
+            .partial_cmp(a_priority)
+            .unwrap_or(Ordering::Equal)
+            .then(trump.compare(*b, *a))
+    });
+    priorities
+}
+
 #[cfg(test)]
 mod tests {
-    use super::Hands;
+    use super::{
+        analyze_hand_difficulty, analyze_hand_shape, analyze_lead_priority, group_cards, HandShape,
+        Hands, SuitGroup, UnknownCardPlacement,
+    };
     use crate::types::{
-        cards::{S_2, S_3, S_4, S_5},
-        Number, PlayerID,
+        cards::{self, S_2, S_3, S_4, S_5},
+        Card, EffectiveSuit, Number, PermanentTrumps, PlayerID, Suit, Trump,
     };
 
     const P1: PlayerID = PlayerID(1);
@@ -193,4 +415,237 @@ mod tests {
             hands.cards(P4, Number::Two).unwrap()
         );
     }
+
+    #[test]
+    fn test_analyze_hand_shape() {
+        let trump = Trump::Standard {
+            suit: Suit::Spades,
+            number: Number::Two,
+            permanent: PermanentTrumps::NONE,
+        };
+        let hand = Card::count(vec![
+            S_3,
+            S_4,
+            cards::H_2,
+            cards::H_5,
+            cards::C_5,
+            cards::C_5,
+            Card::BigJoker,
+            Card::Unknown,
+        ]);
+        let shape = analyze_hand_shape(&hand, trump);
+        assert_eq!(
+            shape,
+            HandShape {
+                clubs: 2,
+                diamonds: 0,
+                spades: 0,
+                hearts: 1,
+                trump: 4,
+            }
+        );
+        assert_eq!(shape.count(EffectiveSuit::Trump), 4);
+        assert_eq!(shape.count(EffectiveSuit::Unknown), 0);
+    }
+
+    #[test]
+    fn test_analyze_hand_difficulty() {
+        let trump = Trump::Standard {
+            suit: Suit::Spades,
+            number: Number::Two,
+            permanent: PermanentTrumps::NONE,
+        };
+        let strong = Card::count(vec![
+            Card::BigJoker,
+            Card::SmallJoker,
+            cards::S_2,
+            cards::H_2,
+            cards::S_K,
+            cards::S_K,
+            cards::S_A,
+            cards::S_A,
+            cards::S_10,
+            cards::S_5,
+            cards::H_10,
+            cards::H_K,
+        ]);
+        let flat = Card::count(vec![
+            cards::H_3,
+            cards::H_6,
+            cards::H_9,
+            cards::C_3,
+            cards::C_6,
+            cards::C_8,
+            cards::D_3,
+            cards::D_4,
+            cards::D_7,
+            cards::D_9,
+            cards::S_3,
+            cards::C_J,
+        ]);
+        let strong_rating = analyze_hand_difficulty(&strong, trump);
+        let flat_rating = analyze_hand_difficulty(&flat, trump);
+        assert!(strong_rating > flat_rating);
+        assert!((0.0..=1.0).contains(&strong_rating));
+        assert!((0.0..=1.0).contains(&flat_rating));
+        assert_eq!(analyze_hand_difficulty(&Card::count(vec![]), trump), 0.0);
+    }
+
+    #[test]
+    fn test_analyze_lead_priority() {
+        let trump = Trump::Standard {
+            suit: Suit::Spades,
+            number: Number::Two,
+            permanent: PermanentTrumps::NONE,
+        };
+        let hand = Card::count(vec![
+            Card::BigJoker,
+            cards::S_2,
+            cards::H_3,
+            cards::H_10,
+            cards::D_A,
+        ]);
+        // The other big joker is gone, so ours can't be beaten.
+        let unseen = Card::count(vec![
+            Card::SmallJoker,
+            cards::S_2,
+            cards::S_A,
+            cards::H_A,
+            cards::H_5,
+            cards::D_3,
+        ]);
+        let priorities = analyze_lead_priority(&hand, trump, &unseen);
+        assert_eq!(priorities.len(), 5);
+        let priority = |card| priorities.iter().find(|(c, _)| *c == card).unwrap().1;
+
+        assert_eq!(priorities[0].0, Card::BigJoker);
+        assert!(priority(Card::BigJoker) > priority(cards::D_A));
+        assert!(priority(cards::D_A) > priority(cards::H_3));
+        assert!(priority(cards::S_2) > priority(cards::H_3));
+        // Beatable points shouldn't be given away.
+        assert!(priority(cards::H_3) > priority(cards::H_10));
+        assert!(priorities.iter().all(|(_, p)| (0.0..=1.0).contains(p)));
+    }
+
+    #[test]
+    fn test_group_cards_with_unknowns() {
+        let trump = Trump::Standard {
+            suit: Suit::Hearts,
+            number: Number::Two,
+            permanent: PermanentTrumps::NONE,
+        };
+        let hand = vec![
+            Card::Unknown,
+            cards::S_2,
+            cards::C_3,
+            Card::Unknown,
+            cards::H_5,
+            cards::C_K,
+            Card::BigJoker,
+        ];
+        let groups = group_cards(trump, hand.clone(), UnknownCardPlacement::Last);
+        assert_eq!(
+            groups,
+            vec![
+                SuitGroup {
+                    suit: EffectiveSuit::Clubs,
+                    cards: vec![cards::C_3, cards::C_K],
+                },
+                SuitGroup {
+                    suit: EffectiveSuit::Trump,
+                    cards: vec![cards::H_5, cards::S_2, Card::BigJoker],
+                },
+                SuitGroup {
+                    suit: EffectiveSuit::Unknown,
+                    cards: vec![Card::Unknown, Card::Unknown],
+                },
+            ]
+        );
+
+        // Unknown cards stay together, wherever they are in the hand.
+        let mut reversed = hand;
+        reversed.reverse();
+        let groups = group_cards(trump, reversed, UnknownCardPlacement::First);
+        assert_eq!(groups.len(), 3);
+        assert_eq!(
+            groups[0],
+            SuitGroup {
+                suit: EffectiveSuit::Unknown,
+                cards: vec![Card::Unknown, Card::Unknown],
+            }
+        );
+        assert_eq!(groups[1].cards, vec![cards::C_3, cards::C_K]);
+    }
+
+    #[test]
+    fn test_sorted_for_player() {
+        let trump = Trump::Standard {
+            suit: Suit::Diamonds,
+            number: Number::Five,
+            permanent: PermanentTrumps::NONE,
+        };
+        let mut hands = Hands::new(vec![P1, P2]);
+        hands
+            .add(
+                P1,
+                vec![
+                    Card::BigJoker,
+                    cards::S_A,
+                    cards::H_5,
+                    cards::D_3,
+                    cards::C_10,
+                    Card::SmallJoker,
+                    cards::S_5,
+                    cards::H_K,
+                    cards::D_5,
+                    cards::C_2,
+                    cards::S_3,
+                    cards::H_3,
+                    cards::D_A,
+                    cards::C_5,
+                ],
+            )
+            .unwrap();
+        assert!(hands.sorted_for_player(P3, trump).is_err());
+        assert!(hands.sorted_for_player(P2, trump).unwrap().is_empty());
+
+        let groups = hands.sorted_for_player(P1, trump).unwrap();
+        assert_eq!(
+            groups.iter().map(|g| g.suit).collect::<Vec<_>>(),
+            vec![
+                EffectiveSuit::Spades,
+                EffectiveSuit::Hearts,
+                EffectiveSuit::Clubs,
+                EffectiveSuit::Trump,
+            ]
+        );
+        assert_eq!(groups[0].cards, vec![cards::S_3, cards::S_A]);
+        assert_eq!(groups[1].cards, vec![cards::H_3, cards::H_K]);
+        assert_eq!(groups[2].cards, vec![cards::C_2, cards::C_10]);
+        // Off-suit trump-number cards, the trump-suit number card and the
+        // jokers are all part of the trump group.
+        assert_eq!(
+            groups[3].cards,
+            vec![
+                cards::D_3,
+                cards::D_A,
+                cards::S_5,
+                cards::H_5,
+                cards::C_5,
+                cards::D_5,
+                Card::SmallJoker,
+                Card::BigJoker,
+            ]
+        );
+        for group in &groups {
+            assert!(group
+                .cards
+                .windows(2)
+                .all(|w| trump.compare(w[0], w[1]) != std::cmp::Ordering::Greater));
+            assert!(group
+                .cards
+                .iter()
+                .all(|c| trump.effective_suit(*c) == group.suit));
+        }
+    }
 }
@@ -1,22 +1,33 @@
+use std::collections::HashSet;
+
 use anyhow::{bail, Error};
 use serde::{Deserialize, Serialize};
-use slog::{debug, info, o, Logger};
+use slog::{debug, info, o, warn, Logger};
 
 use crate::bidding::{BidPolicy, BidReinforcementPolicy, BidTakebackPolicy, JokerBidPolicy};
+use crate::bot;
 use crate::deck::Deck;
-use crate::game_state::{GameState, InitializePhase};
-use crate::message::MessageVariant;
+use crate::game_state::{Correction, GameState, GameSummary, InitializePhase, PlayPhase};
+use crate::message::{MessageLocale, MessageVariant};
 use crate::scoring::GameScoringParameters;
 use crate::settings::{
-    AdvancementPolicy, FirstLandlordSelectionPolicy, FriendSelection, FriendSelectionPolicy,
-    GameModeSettings, GameShadowingPolicy, GameStartPolicy, KittyBidPolicy, KittyPenalty,
-    KittyTheftPolicy, MultipleJoinPolicy, PlayTakebackPolicy, PropagatedState, ThrowPenalty,
+    AbandonedGamePolicy, AbsentLandlordPolicy, AdvancementPolicy, FirstLandlordSelectionPolicy,
+    FirstLeadPolicy, FriendPointTransferPolicy, FriendSelection, FriendSelectionPolicy,
+    GameModeSettings, GameModifier, GameShadowingPolicy, GameStartPolicy, JokerBidSuitPolicy,
+    KittyBidPolicy, KittyOnLossPolicy, KittyPenalty, KittyRevealPolicy, KittyTheftBidPolicy,
+    KittyTheftPolicy, KittyTheftRevealPolicy, MultipleJoinPolicy, OutOfTurnPolicy, PausePolicy,
+    PlayTakebackPolicy, PropagatedState, RemainderPolicy, SpectatorCardVisibility, ThrowPenalty,
+    VacatedSeatPolicy, WeakHandRedeal,
+};
+use crate::trick::{
+    ThrowEvaluationPolicy, ThrowTieBreakPolicy, TractorRequirements, TrickDrawPolicy, TrickError,
+    TrickUnit,
 };
-use crate::trick::{ThrowEvaluationPolicy, TractorRequirements, TrickDrawPolicy, TrickUnit};
-use crate::types::{Card, Number, PlayerID};
+use crate::types::{Card, Number, PlayerID, Suit};
 
 pub struct InteractiveGame {
     state: GameState,
+    summary: Option<GameSummary>,
 }
 
 impl InteractiveGame {
@@ -25,7 +36,15 @@ impl InteractiveGame {
     }
 
     pub fn new_from_state(state: GameState) -> Self {
-        Self { state }
+        Self {
+            state,
+            summary: None,
+        }
+    }
+
+    /// Takes the summary of the last game, if it just finished.
+    pub fn take_summary(&mut self) -> Option<GameSummary> {
+        self.summary.take()
     }
 
     pub fn into_state(self) -> GameState {
@@ -41,6 +60,15 @@ impl InteractiveGame {
         Ok((actor, self.hydrate_messages(actor, msgs)?))
     }
 
+    pub fn register_with_token(
+        &mut self,
+        token: &str,
+    ) -> Result<(PlayerID, Vec<(BroadcastMessage, String)>), Error> {
+        let (actor, msgs) = self.state.register_with_token(token)?;
+
+        Ok((actor, self.hydrate_messages(actor, msgs)?))
+    }
+
     pub fn kick(
         &mut self,
         actor: PlayerID,
@@ -50,6 +78,45 @@ impl InteractiveGame {
         self.hydrate_messages(actor, msgs)
     }
 
+    /// Takes the bots' turns until it's a person's turn.
+    pub fn run_bots(&mut self, logger: &Logger) -> Vec<(BroadcastMessage, String)> {
+        let mut out = vec![];
+        for _ in 0..bot::MAX_BOT_ACTIONS {
+            let next = self
+                .state
+                .bots
+                .iter()
+                .find_map(|id| bot::next_action(&self.state, *id).map(|action| (*id, action)));
+            let (id, action) = match next {
+                Some(next) => next,
+                None => break,
+            };
+            match self.interact_once(action, id, logger) {
+                Ok(msgs) => out.extend(msgs),
+                Err(e) => {
+                    warn!(logger, "Bot action failed"; "bot" => id.0, "error" => e.to_string());
+                    break;
+                }
+            }
+        }
+        out
+    }
+
+    pub fn is_observer(&self, id: PlayerID) -> bool {
+        self.state.is_observer(id)
+    }
+
+    /// Removes an observer whose connection was dropped for being idle.
+    pub fn remove_idle_observer(
+        &mut self,
+        id: PlayerID,
+    ) -> Result<Vec<(BroadcastMessage, String)>, Error> {
+        // The message is hydrated first, while the observer still has a name.
+        let msgs = self.hydrate_messages(id, vec![MessageVariant::IdleObserverRemoved])?;
+        self.state.remove_observer(id)?;
+        Ok(msgs)
+    }
+
     pub fn dump_state(&self) -> Result<GameState, Error> {
         Ok(self.state.clone())
     }
@@ -70,21 +137,139 @@ impl InteractiveGame {
         self.state.player_name(player_id)
     }
 
-    #[allow(clippy::cognitive_complexity)]
+    pub fn player_id(&self, name: &str) -> Result<PlayerID, Error> {
+        self.state.player_id(name)
+    }
+
+    pub fn record_action_at(&mut self, now: u64) {
+        self.state.record_action_at(now)
+    }
+
+    /// Finishes the exchange on the landlord's behalf, with the cards that
+    /// they have already put in the bottom, if they have exceeded the move
+    /// deadline as of `now` and `AbsentLandlordPolicy::AutoFinalizeExchange`
+    /// is set.
+    pub fn auto_finalize_exchange(
+        &mut self,
+        now: u64,
+        logger: &Logger,
+    ) -> Result<Vec<(BroadcastMessage, String)>, Error> {
+        let landlord = match self.state {
+            GameState::Exchange(ref exchange)
+                if self.state.absent_landlord_policy
+                    == AbsentLandlordPolicy::AutoFinalizeExchange
+                    && self.state.timed_out_player(now) == Some(exchange.landlord()) =>
+            {
+                exchange.landlord()
+            }
+            _ => return Ok(vec![]),
+        };
+        info!(logger, "Finishing exchange for absent landlord"; "landlord" => landlord.0);
+        let mut msgs = self.hydrate_messages(
+            landlord,
+            vec![MessageVariant::ExchangeAutoFinalized { landlord }],
+        )?;
+        for _ in 0..bot::MAX_BOT_ACTIONS {
+            let action = match self.state {
+                GameState::Exchange(ref exchange) => {
+                    bot::finish_exchange_action(exchange, landlord)
+                }
+                _ => None,
+            };
+            match action {
+                Some(action) => msgs.extend(self.interact_once(action, landlord, logger)?),
+                None => break,
+            }
+        }
+        msgs.extend(self.run_bots(logger));
+        Ok(msgs)
+    }
+
+    /// Reports the player whose turn it is if they have exceeded the move
+    /// deadline as of `now`.
+    pub fn check_move_deadline(&self, now: u64) -> Result<Vec<(BroadcastMessage, String)>, Error> {
+        match self.state.timed_out_player(now) {
+            Some(player) => {
+                self.hydrate_messages(player, vec![MessageVariant::PlayerTimedOut { player }])
+            }
+            None => Ok(vec![]),
+        }
+    }
+
+    pub fn pause(
+        &mut self,
+        id: PlayerID,
+        logger: &Logger,
+    ) -> Result<Vec<(BroadcastMessage, String)>, Error> {
+        self.interact(Action::PauseGame, id, logger)
+    }
+
+    pub fn resume(
+        &mut self,
+        id: PlayerID,
+        logger: &Logger,
+    ) -> Result<Vec<(BroadcastMessage, String)>, Error> {
+        self.interact(Action::ResumeGame, id, logger)
+    }
+
+    pub fn rematch(
+        &mut self,
+        id: PlayerID,
+        logger: &Logger,
+    ) -> Result<Vec<(BroadcastMessage, String)>, Error> {
+        self.interact(Action::Rematch, id, logger)
+    }
+
     pub fn interact(
         &mut self,
         msg: Action,
         id: PlayerID,
         logger: &Logger,
+    ) -> Result<Vec<(BroadcastMessage, String)>, Error> {
+        let mut msgs = self.interact_once(msg, id, logger)?;
+        msgs.extend(self.run_bots(logger));
+        Ok(msgs)
+    }
+
+    #[allow(clippy::cognitive_complexity)]
+    fn interact_once(
+        &mut self,
+        msg: Action,
+        id: PlayerID,
+        logger: &Logger,
     ) -> Result<Vec<(BroadcastMessage, String)>, Error> {
         let logger = logger.new(o!(
             "num_players" => self.state.players.len(),
             "num_observers" => self.state.observers.len(),
             "mode" => self.state.game_mode.variant(),
             "num_games_finished" => self.state.num_games_finished,
+            "num_games_abandoned" => self.state.num_games_abandoned,
         ));
 
+        if self.state.is_vacated(id) {
+            bail!("You were kicked from the game")
+        }
+
+        if self.state.paused {
+            match msg {
+                Action::ResetGame
+                | Action::SetChatLink(_)
+                | Action::PauseGame
+                | Action::ResumeGame
+                | Action::PromoteObserver(_, _) => (),
+                _ => bail!("Game is paused"),
+            }
+        }
+
         let msgs = match (msg, &mut self.state) {
+            (Action::PauseGame, _) => {
+                info!(logger, "Pausing game");
+                self.state.set_paused(id, true)?
+            }
+            (Action::ResumeGame, _) => {
+                info!(logger, "Resuming game");
+                self.state.set_paused(id, false)?
+            }
             (Action::ResetGame, _) => {
                 info!(logger, "Resetting game");
                 self.state.reset()?
@@ -93,17 +278,44 @@ impl InteractiveGame {
                 self.state.set_chat_link(link.clone())?;
                 vec![]
             }
+            (Action::PromoteObserver(observer, seat_index), _) => {
+                if id != observer && !self.state.is_player(id) {
+                    bail!("Only players can promote another observer")
+                }
+                info!(logger, "Promoting observer"; "observer" => observer.0, "seat_index" => seat_index);
+                let msgs = self.state.promote_observer(observer, seat_index)?;
+                // An observer who took the seat themselves now acts from it.
+                let actor = match msgs[..] {
+                    [MessageVariant::ObserverPromoted { player, .. }] if id == observer => player,
+                    _ => id,
+                };
+                return self.hydrate_messages(actor, msgs);
+            }
+            (Action::Rematch, GameState::Initialize(ref mut state)) => {
+                info!(logger, "Starting rematch");
+                let mut msgs = vec![MessageVariant::StartingGame];
+                msgs.extend(state.team_level_spread_warnings());
+                self.state = GameState::Draw(state.rematch(id)?);
+                msgs
+            }
             (Action::StartGame, GameState::Initialize(ref mut state)) => {
                 let s: &'_ PropagatedState = state;
                 info!(logger, "Starting game"; s);
+                let mut msgs = vec![MessageVariant::StartingGame];
+                msgs.extend(state.team_level_spread_warnings());
                 self.state = GameState::Draw(state.start(id)?);
-                vec![MessageVariant::StartingGame]
+                msgs
             }
             (Action::ReorderPlayers(ref players), GameState::Initialize(ref mut state)) => {
                 info!(logger, "Reordering players");
                 state.reorder_players(&players)?;
                 vec![]
             }
+            (Action::AddBot, GameState::Initialize(ref mut state)) => {
+                info!(logger, "Adding bot");
+                let (bot, msgs) = state.add_bot()?;
+                return self.hydrate_messages(bot, msgs);
+            }
             (Action::MakeObserver(id), GameState::Initialize(ref mut state)) => {
                 info!(logger, "Making player an observer"; "id" => id.0);
                 state.make_observer(id)?
@@ -127,6 +339,19 @@ impl InteractiveGame {
             }
             (Action::SetKittySize(size), GameState::Initialize(ref mut state)) => {
                 info!(logger, "Setting kitty size"; "size" => size);
+                if let (Some(size), Ok(valid_sizes)) = (size, state.valid_kitty_sizes()) {
+                    if !valid_sizes.contains(&size) {
+                        bail!(
+                            "can't start the game with {} cards in the bottom; try one of {}",
+                            size,
+                            valid_sizes
+                                .iter()
+                                .map(|s| s.to_string())
+                                .collect::<Vec<_>>()
+                                .join(", ")
+                        )
+                    }
+                }
                 state.set_kitty_size(size)?.into_iter().collect()
             }
             (Action::SetFriendSelectionPolicy(policy), GameState::Initialize(ref mut state)) => {
@@ -137,6 +362,13 @@ impl InteractiveGame {
                 info!(logger, "Setting multiple join policy"; "policy" => policy);
                 state.set_multiple_join_policy(policy)?
             }
+            (
+                Action::SetFriendPointTransferPolicy(policy),
+                GameState::Initialize(ref mut state),
+            ) => {
+                info!(logger, "Setting friend point transfer policy"; "policy" => policy);
+                state.set_friend_point_transfer_policy(policy)?
+            }
             (
                 Action::SetFirstLandlordSelectionPolicy(policy),
                 GameState::Initialize(ref mut state),
@@ -144,6 +376,10 @@ impl InteractiveGame {
                 info!(logger, "Setting first landlord selection policy"; "policy" => policy);
                 state.set_first_landlord_selection_policy(policy)?
             }
+            (Action::SetFirstLeadPolicy(policy), GameState::Initialize(ref mut state)) => {
+                info!(logger, "Setting first lead policy"; "policy" => policy);
+                state.set_first_lead_policy(policy)?
+            }
             (Action::SetBidPolicy(policy), GameState::Initialize(ref mut state)) => {
                 info!(logger, "Setting bid selection policy"; "policy" => policy);
                 state.set_bid_policy(policy)?
@@ -156,6 +392,10 @@ impl InteractiveGame {
                 info!(logger, "Setting joker bid selection policy"; "policy" => policy);
                 state.set_joker_bid_policy(policy)?
             }
+            (Action::SetJokerBidSuitPolicy(policy), GameState::Initialize(ref mut state)) => {
+                info!(logger, "Setting joker bid suit policy"; "policy" => policy);
+                state.set_joker_bid_suit_policy(policy)?
+            }
             (
                 Action::SetShouldRevealKittyAtEndOfGame(should_reveal),
                 GameState::Initialize(ref mut state),
@@ -163,6 +403,10 @@ impl InteractiveGame {
                 info!(logger, "Setting should reveal kitty at end of game"; "should_reveal" => should_reveal);
                 state.set_should_reveal_kitty_at_end_of_game(should_reveal)?
             }
+            (Action::SetKittyRevealPolicy(policy), GameState::Initialize(ref mut state)) => {
+                info!(logger, "Setting kitty reveal policy"; "policy" => policy);
+                state.set_kitty_reveal_policy(policy)?
+            }
             (Action::SetLandlord(landlord), GameState::Initialize(ref mut state)) => {
                 info!(logger, "Setting landlord"; "landlord" => landlord.map(|l| l.0));
                 state.set_landlord(landlord)?;
@@ -200,6 +444,13 @@ impl InteractiveGame {
                 info!(logger, "Setting hide throw halting player"; "hide_throw_halting_player" => hide_throw_halting_player);
                 state.set_hide_throw_halting_player(hide_throw_halting_player)?
             }
+            (
+                Action::SetRevealKittyPointCount(reveal_kitty_point_count),
+                GameState::Initialize(ref mut state),
+            ) => {
+                info!(logger, "Setting reveal kitty point count"; "reveal_kitty_point_count" => reveal_kitty_point_count);
+                state.set_reveal_kitty_point_count(reveal_kitty_point_count)?
+            }
             (Action::SetGameMode(game_mode), GameState::Initialize(ref mut state)) => {
                 info!(logger, "Setting game mode"; "game_mode" => game_mode.variant());
                 state.set_game_mode(game_mode)?
@@ -212,6 +463,10 @@ impl InteractiveGame {
                 info!(logger, "Setting kitty bid policy"; "bid_policy" => kitty_bid_policy);
                 state.set_kitty_bid_policy(kitty_bid_policy)?
             }
+            (Action::SetKittyOnLossPolicy(policy), GameState::Initialize(ref mut state)) => {
+                info!(logger, "Setting kitty on loss policy"; "policy" => policy);
+                state.set_kitty_on_loss_policy(policy)?
+            }
             (Action::SetTrickDrawPolicy(policy), GameState::Initialize(ref mut state)) => {
                 info!(logger, "Setting trick draw policy"; "draw_policy" => policy);
                 state.set_trick_draw_policy(policy)?
@@ -231,10 +486,18 @@ impl InteractiveGame {
                 info!(logger, "Setting throw penalty"; "penalty" => throw_penalty);
                 state.set_throw_penalty(throw_penalty)?
             }
+            (Action::SetOutOfTurnPolicy(policy), GameState::Initialize(ref mut state)) => {
+                info!(logger, "Setting out of turn policy"; "policy" => policy);
+                state.set_out_of_turn_policy(policy)?
+            }
             (Action::SetThrowEvaluationPolicy(policy), GameState::Initialize(ref mut state)) => {
                 info!(logger, "Setting throw evaluation policy"; "policy" => policy);
                 state.set_throw_evaluation_policy(policy)?
             }
+            (Action::SetThrowTieBreakPolicy(policy), GameState::Initialize(ref mut state)) => {
+                info!(logger, "Setting throw tie-break policy"; "policy" => policy);
+                state.set_throw_tie_break_policy(policy)?
+            }
             (Action::SetPlayTakebackPolicy(policy), GameState::Initialize(ref mut state)) => {
                 info!(logger, "Setting play takeback policy"; "policy" => policy);
                 state.set_play_takeback_policy(policy)?
@@ -247,6 +510,14 @@ impl InteractiveGame {
                 info!(logger, "Setting kitty theft policy"; "policy" => policy);
                 state.set_kitty_theft_policy(policy)?
             }
+            (Action::SetKittyTheftRevealPolicy(policy), GameState::Initialize(ref mut state)) => {
+                info!(logger, "Setting kitty theft reveal policy"; "policy" => policy);
+                state.set_kitty_theft_reveal_policy(policy)?
+            }
+            (Action::SetKittyTheftBidPolicy(policy), GameState::Initialize(ref mut state)) => {
+                info!(logger, "Setting kitty theft bid policy"; "policy" => policy);
+                state.set_kitty_theft_bid_policy(policy)?
+            }
             (Action::SetGameShadowingPolicy(policy), GameState::Initialize(ref mut state)) => {
                 info!(logger, "Setting user multiple game session policy"; "policy" => policy);
                 state.set_user_multiple_game_session_policy(policy)?
@@ -255,6 +526,14 @@ impl InteractiveGame {
                 info!(logger, "Setting game start policy"; "policy" => policy);
                 state.set_game_start_policy(policy)?
             }
+            (Action::SetAbsentLandlordPolicy(policy), GameState::Initialize(ref mut state)) => {
+                info!(logger, "Setting absent landlord policy"; "policy" => policy);
+                state.set_absent_landlord_policy(policy)?
+            }
+            (Action::SetAbandonedGamePolicy(policy), GameState::Initialize(ref mut state)) => {
+                info!(logger, "Setting abandoned game policy"; "policy" => policy);
+                state.set_abandoned_game_policy(policy)?
+            }
             (
                 Action::SetTractorRequirements(requirements),
                 GameState::Initialize(ref mut state),
@@ -262,10 +541,94 @@ impl InteractiveGame {
                 info!(logger, "Setting tractor requirements"; "tractor_requirements" => requirements);
                 state.set_tractor_requirements(requirements)?
             }
+            (Action::SetBlindBidBonus(bonus), GameState::Initialize(ref mut state)) => {
+                info!(logger, "Setting blind bid bonus"; "bonus" => bonus);
+                state.set_blind_bid_bonus(bonus)?
+            }
+            (Action::SetMaxMetalevel(max_metalevel), GameState::Initialize(ref mut state)) => {
+                info!(logger, "Setting max metalevel"; "max_metalevel" => max_metalevel);
+                state.set_max_metalevel(max_metalevel)?
+            }
+            (Action::StartNewMatch, GameState::Initialize(ref mut state)) => {
+                info!(logger, "Starting new match");
+                state.start_new_match()?
+            }
+            (Action::RandomizeSettings, GameState::Initialize(ref mut state)) => {
+                let seed = rand::random::<u64>();
+                info!(logger, "Randomizing settings"; "seed" => seed);
+                state.randomize_settings(seed)?
+            }
+            (Action::SetRemainderPolicy(policy), GameState::Initialize(ref mut state)) => {
+                info!(logger, "Setting remainder policy"; "policy" => policy);
+                state.set_remainder_policy(policy)?
+            }
+            (Action::SetCaptureBonusRules(rules), GameState::Initialize(ref mut state)) => {
+                info!(logger, "Setting capture bonus rules"; "num_rules" => rules.len());
+                state.set_capture_bonus_rules(rules)?
+            }
+            (Action::SetWeakHandRedeal(rule), GameState::Initialize(ref mut state)) => {
+                info!(logger, "Setting weak hand redeal rule"; "rule" => format!("{:?}", rule));
+                state.set_weak_hand_redeal(rule)?
+            }
+            (
+                Action::SetFriendBounds(min_friends, max_friends),
+                GameState::Initialize(ref mut state),
+            ) => {
+                info!(logger, "Setting friend bounds"; "min_friends" => min_friends, "max_friends" => max_friends);
+                state.set_friend_bounds(min_friends, max_friends)?
+            }
+            (Action::SetSkipExchange(skip_exchange), GameState::Initialize(ref mut state)) => {
+                info!(logger, "Setting skip exchange"; "skip_exchange" => skip_exchange);
+                state.set_skip_exchange(skip_exchange)?
+            }
+            (Action::SetQuickRematch(quick_rematch), GameState::Initialize(ref mut state)) => {
+                info!(logger, "Setting quick rematch"; "quick_rematch" => quick_rematch);
+                state.set_quick_rematch(quick_rematch)?
+            }
+            (Action::SetPausePolicy(policy), GameState::Initialize(ref mut state)) => {
+                info!(logger, "Setting pause policy"; "policy" => policy);
+                state.set_pause_policy(policy)?
+            }
+            (Action::SetSpectatorCardVisibility(policy), GameState::Initialize(ref mut state)) => {
+                info!(logger, "Setting spectator card visibility"; "policy" => policy);
+                state.set_spectator_card_visibility(policy)?
+            }
+            (Action::SetVacatedSeatPolicy(policy), GameState::Initialize(ref mut state)) => {
+                info!(logger, "Setting vacated seat policy"; "policy" => policy);
+                state.set_vacated_seat_policy(policy)?
+            }
+            (Action::SetGameModifiers(modifiers), GameState::Initialize(ref mut state)) => {
+                info!(logger, "Setting game modifiers");
+                state.set_game_modifiers(modifiers)?
+            }
+            (Action::SetExperimentalFeatures(features), GameState::Initialize(ref mut state)) => {
+                info!(logger, "Setting experimental features"; "num_features" => features.len());
+                state.set_experimental_features(features)?
+            }
+            (Action::SetPermanentTrumpNumbers(numbers), GameState::Initialize(ref mut state)) => {
+                info!(logger, "Setting permanent trump numbers");
+                state.set_permanent_trump_numbers(numbers)?
+            }
+            (Action::SetExcludedCards(cards), GameState::Initialize(ref mut state)) => {
+                info!(logger, "Setting excluded cards");
+                state.set_excluded_cards(cards)?
+            }
+            (Action::SetDirector(director), GameState::Initialize(ref mut state)) => {
+                info!(logger, "Setting director"; "director" => director.map(|d| d.0));
+                state.set_director(director)?
+            }
+            (Action::SetMoveDeadline(secs), GameState::Initialize(ref mut state)) => {
+                info!(logger, "Setting move deadline"; "secs" => secs);
+                state.set_move_deadline_secs(secs)?
+            }
             (Action::DrawCard, GameState::Draw(ref mut state)) => {
                 debug!(logger, "Drawing card");
-                state.draw_card(id)?;
-                vec![]
+                state.draw_card(id)?
+            }
+            (Action::DeclareWeakHand, GameState::Draw(ref mut state)) => {
+                info!(logger, "Declaring weak hand");
+                self.state = GameState::Draw(state.declare_weak_hand(id)?);
+                vec![MessageVariant::DeclaredWeakHand { player: id }]
             }
             (Action::RevealCard, GameState::Draw(ref mut state)) => {
                 info!(logger, "Revealing card");
@@ -279,11 +642,21 @@ impl InteractiveGame {
                     bail!("bid was invalid")
                 }
             }
+            (Action::BlindBid(card, count), GameState::Draw(ref mut state)) => {
+                info!(logger, "Making blind bid");
+                state.blind_bid(id, card, count)?;
+                vec![MessageVariant::MadeBlindBid { card, count }]
+            }
             (Action::TakeBackBid, GameState::Draw(ref mut state)) => {
                 info!(logger, "Taking back bid");
                 state.take_back_bid(id)?;
                 vec![MessageVariant::TookBackBid]
             }
+            (Action::PickUpKitty, GameState::Draw(ref mut state)) if state.skips_exchange() => {
+                info!(logger, "Skipping exchange phase");
+                self.state = GameState::Play(state.advance_to_play(id)?);
+                vec![]
+            }
             (Action::PickUpKitty, GameState::Draw(ref mut state)) => {
                 info!(logger, "Entering exchange phase");
                 self.state = GameState::Exchange(state.advance(id)?);
@@ -312,6 +685,11 @@ impl InteractiveGame {
                 state.finalize(id)?;
                 vec![MessageVariant::PutDownCards]
             }
+            (Action::SetTrumpSuit(suit), GameState::Exchange(ref mut state)) => {
+                info!(logger, "Setting trump suit"; "suit" => format!("{:?}", suit));
+                state.set_trump_suit(id, suit)?;
+                vec![MessageVariant::TrumpSuitSet { suit }]
+            }
             (Action::MoveCardToKitty(card), GameState::Exchange(ref mut state)) => {
                 info!(logger, "Moving card to kitty");
                 state.move_card_to_kitty(id, card)?;
@@ -334,14 +712,16 @@ impl InteractiveGame {
             }
             (Action::PlayCards(ref cards), GameState::Play(ref mut state)) => {
                 info!(logger, "Playing cards");
-                state.play_cards(id, cards)?
+                let result = state.play_cards(id, cards);
+                check_out_of_turn(state, id, result)?
             }
             (
                 Action::PlayCardsWithHint(ref cards, ref format_hint),
                 GameState::Play(ref mut state),
             ) => {
                 info!(logger, "Playing cards with formatting hint");
-                state.play_cards_with_hint(id, cards, Some(format_hint))?
+                let result = state.play_cards_with_hint(id, cards, Some(format_hint));
+                check_out_of_turn(state, id, result)?
             }
             (Action::EndTrick, GameState::Play(ref mut state)) => {
                 info!(logger, "Finishing trick");
@@ -349,8 +729,26 @@ impl InteractiveGame {
             }
             (Action::TakeBackCards, GameState::Play(ref mut state)) => {
                 info!(logger, "Taking back cards");
-                state.take_back_cards(id)?;
-                vec![MessageVariant::TookBackPlay]
+                state.request_take_back(id)?
+            }
+            (Action::DirectorTakeBack(player), GameState::Play(ref mut state)) => {
+                info!(logger, "Taking back cards as director"; "player" => player.0);
+                state.director_take_back(id, player)?
+            }
+            (
+                Action::DirectorReassignPoints(from, to, ref cards),
+                GameState::Play(ref mut state),
+            ) => {
+                info!(logger, "Reassigning points as director"; "from" => from.0, "to" => to.0);
+                state.director_reassign_points(id, from, to, cards.clone())?
+            }
+            (Action::ApproveTakeBack, GameState::Play(ref mut state)) => {
+                info!(logger, "Approving takeback");
+                state.approve_take_back(id)?
+            }
+            (Action::CancelTakeBack, GameState::Play(ref mut state)) => {
+                info!(logger, "Cancelling takeback");
+                state.cancel_take_back(id)?
             }
             (Action::EndGameEarly, GameState::Play(ref mut state)) => {
                 info!(logger, "Ending game early");
@@ -358,9 +756,10 @@ impl InteractiveGame {
             }
             (Action::StartNewGame, GameState::Play(ref mut state)) => {
                 let s = state.propagated();
-                let (new_s, landlord_won, msgs) = state.finish_game()?;
-                info!(logger, "Starting new game"; s, "landlord_won_last_game" => landlord_won);
+                let (new_s, summary, msgs) = state.finish_game()?;
+                info!(logger, "Starting new game"; s, "landlord_won_last_game" => summary.landlord_won);
                 self.state = GameState::Initialize(new_s);
+                self.summary = Some(summary);
                 msgs
             }
             _ => bail!("not supported in current phase"),
@@ -394,6 +793,7 @@ impl InteractiveGame {
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub enum Action {
     ResetGame,
+    AddBot,
     MakeObserver(PlayerID),
     MakePlayer(PlayerID),
     SetChatLink(Option<String>),
@@ -402,38 +802,76 @@ pub enum Action {
     SetKittySize(Option<usize>),
     SetFriendSelectionPolicy(FriendSelectionPolicy),
     SetMultipleJoinPolicy(MultipleJoinPolicy),
+    SetFriendPointTransferPolicy(FriendPointTransferPolicy),
     SetFirstLandlordSelectionPolicy(FirstLandlordSelectionPolicy),
+    SetFirstLeadPolicy(FirstLeadPolicy),
     SetBidPolicy(BidPolicy),
     SetBidReinforcementPolicy(BidReinforcementPolicy),
     SetJokerBidPolicy(JokerBidPolicy),
+    SetJokerBidSuitPolicy(JokerBidSuitPolicy),
     SetHideLandlordsPoints(bool),
     SetHidePlayedCards(bool),
     ReorderPlayers(Vec<PlayerID>),
     SetRank(Number),
     SetLandlord(Option<PlayerID>),
     SetLandlordEmoji(Option<String>),
+    SetDirector(Option<PlayerID>),
     SetGameMode(GameModeSettings),
     SetAdvancementPolicy(AdvancementPolicy),
     SetGameScoringParameters(GameScoringParameters),
     SetKittyPenalty(KittyPenalty),
     SetKittyBidPolicy(KittyBidPolicy),
+    SetKittyOnLossPolicy(KittyOnLossPolicy),
     SetTrickDrawPolicy(TrickDrawPolicy),
     SetThrowPenalty(ThrowPenalty),
+    SetOutOfTurnPolicy(OutOfTurnPolicy),
     SetThrowEvaluationPolicy(ThrowEvaluationPolicy),
+    SetThrowTieBreakPolicy(ThrowTieBreakPolicy),
     SetPlayTakebackPolicy(PlayTakebackPolicy),
     SetBidTakebackPolicy(BidTakebackPolicy),
     SetKittyTheftPolicy(KittyTheftPolicy),
+    SetKittyTheftRevealPolicy(KittyTheftRevealPolicy),
+    SetKittyTheftBidPolicy(KittyTheftBidPolicy),
     SetGameShadowingPolicy(GameShadowingPolicy),
     SetGameStartPolicy(GameStartPolicy),
+    SetAbsentLandlordPolicy(AbsentLandlordPolicy),
+    SetAbandonedGamePolicy(AbandonedGamePolicy),
     SetShouldRevealKittyAtEndOfGame(bool),
+    SetKittyRevealPolicy(KittyRevealPolicy),
     SetHideThrowHaltingPlayer(bool),
+    SetRevealKittyPointCount(bool),
     SetTractorRequirements(TractorRequirements),
+    SetBlindBidBonus(Option<usize>),
+    SetMaxMetalevel(Option<usize>),
+    SetRemainderPolicy(RemainderPolicy),
+    SetCaptureBonusRules(Vec<(Vec<Card>, isize)>),
+    SetWeakHandRedeal(Option<WeakHandRedeal>),
+    SetQuickRematch(bool),
+    SetSkipExchange(bool),
+    SetFriendBounds(Option<usize>, Option<usize>),
+    SetSpectatorCardVisibility(SpectatorCardVisibility),
+    SetVacatedSeatPolicy(VacatedSeatPolicy),
+    SetMoveDeadline(Option<u64>),
+    SetGameModifiers(Vec<GameModifier>),
+    SetExperimentalFeatures(HashSet<String>),
+    SetPermanentTrumpNumbers(Vec<Number>),
+    SetExcludedCards(Vec<Card>),
+    SetPausePolicy(PausePolicy),
+    PauseGame,
+    ResumeGame,
+    PromoteObserver(PlayerID, usize),
     StartGame,
+    Rematch,
+    StartNewMatch,
+    RandomizeSettings,
     DrawCard,
     RevealCard,
+    DeclareWeakHand,
     Bid(Card, usize),
+    BlindBid(Card, usize),
     PickUpKitty,
     PutDownKitty,
+    SetTrumpSuit(Suit),
     MoveCardToKitty(Card),
     MoveCardToHand(Card),
     SetFriends(Vec<FriendSelection>),
@@ -442,6 +880,10 @@ pub enum Action {
     PlayCardsWithHint(Vec<Card>, Vec<TrickUnit>),
     EndTrick,
     TakeBackCards,
+    ApproveTakeBack,
+    CancelTakeBack,
+    DirectorTakeBack(PlayerID),
+    DirectorReassignPoints(PlayerID, PlayerID, Vec<Card>),
     TakeBackBid,
     EndGameEarly,
     StartNewGame,
@@ -455,17 +897,59 @@ pub struct BroadcastMessage {
     variant: MessageVariant,
 }
 
+/// If a play failed because it was out of turn, records the attempt so that
+/// the player can be warned or penalized. The attempt is reported as a
+/// success in that case, since the state has changed.
+fn check_out_of_turn(
+    state: &mut PlayPhase,
+    id: PlayerID,
+    result: Result<Vec<MessageVariant>, Error>,
+) -> Result<Vec<MessageVariant>, Error> {
+    match result {
+        Err(e) if matches!(e.downcast_ref(), Some(TrickError::OutOfOrder)) => {
+            let msgs = state.record_out_of_turn(id);
+            if msgs.is_empty() {
+                Err(e)
+            } else {
+                Ok(msgs)
+            }
+        }
+        result => result,
+    }
+}
+
 impl BroadcastMessage {
     pub fn to_string<'a>(
         &'a self,
         player_name: impl Fn(PlayerID) -> Result<&'a str, Error>,
     ) -> Result<String, Error> {
-        let n = player_name(self.actor);
+        self.variant.render(self.actor, &English, &player_name)
+    }
+}
+
+/// The default locale, which renders messages in English.
+pub struct English;
+
+impl MessageLocale for English {
+    fn render<'a>(
+        &self,
+        actor: PlayerID,
+        variant: &MessageVariant,
+        player_name: &dyn Fn(PlayerID) -> Result<&'a str, Error>,
+    ) -> Result<String, Error> {
+        let n = player_name(actor);
 
         use MessageVariant::*;
-        Ok(match self.variant {
+        Ok(match *variant {
             ResettingGame => format!("{} reset the game", n?),
             StartingGame => format!("{} started the game", n?),
+            FriendBoundsSet { min_friends: None, max_friends: None } => format!("{} removed the bounds on the number of friends", n?),
+            FriendBoundsSet { min_friends: Some(min), max_friends: None } => format!("{} required at least {} friend(s)", n?, min),
+            FriendBoundsSet { min_friends: None, max_friends: Some(max) } => format!("{} allowed at most {} friend(s)", n?, max),
+            FriendBoundsSet { min_friends: Some(min), max_friends: Some(max) } => format!("{} required between {} and {} friend(s)", n?, min, max),
+            SkipExchangeSet { set: true } => format!("{} made the landlord play without exchanging cards with the kitty", n?),
+            SkipExchangeSet { set: false } => format!("{} restored the exchange phase", n?),
+            TeamLevelSpread { team, min_level, max_level } => format!("Warning: team {} has players at levels ranging from {} to {}", team + 1, min_level.as_str(), max_level.as_str()),
             TrickWon { winner, points } =>if points > 0 {
                     format!("{} wins the trick and gets {} points", player_name(winner)?, points)
                 } else {
@@ -474,16 +958,22 @@ impl BroadcastMessage {
             RankAdvanced { player, new_rank } => format!("{} has advanced to rank {}", player_name(player)?, new_rank.as_str()),
             AdvancementBlocked { player, rank } => format!("{} must defend on rank {}", player_name(player)?, rank.as_str()),
             NewLandlordForNextGame { landlord } => format!("{} will start the next game", player_name(landlord)?),
+            GameMvp { player } => format!("{} was the most valuable player", player_name(player)?),
+            MatchWon { player } => format!("{} has won the match!", player_name(player)?),
+            NewMatchStarted => format!("{} started a new match", n?),
+            KittyAwardedOnLoss { points } => format!("The landlord team lost, so the {} points in the bottom go to the opposing team", points),
             PointsInKitty { points, multiplier } => format!("{} points were buried and are attached to the last trick, with a multiplier of {}", points, multiplier),
             JoinedGame { player } => format!("{} has joined the game", player_name(player)?),
             JoinedGameAgain { player, game_shadowing_policy: GameShadowingPolicy::SingleSessionOnly } => format!("{} has joined the game again, prior connection removed", player_name(player)?),
             JoinedGameAgain { player, game_shadowing_policy: GameShadowingPolicy::AllowMultipleSessions } => format!("{} is being shadowed", player_name(player)?),
-            JoinedTeam { player, already_joined: false } => format!("{} has joined the team", player_name(player)?),
-            JoinedTeam { player, already_joined: true } => format!("{} tried to join the team, but was already a member", player_name(player)?),
+            JoinedTeam { player, already_joined: false, .. } => format!("{} has joined the team", player_name(player)?),
+            JoinedTeam { player, already_joined: true, .. } => format!("{} tried to join the team, but was already a member", player_name(player)?),
             LeftGame { ref name } => format!("{} has left the game", name),
+            ObserverPromoted { player, ref replaced } => format!("{} has taken over {}'s seat", player_name(player)?, replaced),
             AdvancementPolicySet { policy: AdvancementPolicy::FullyUnrestricted } => format!("{} removed all advancement restrictions", n?),
             AdvancementPolicySet { policy: AdvancementPolicy::Unrestricted } => format!("{} required players to defend on A", n?),
             AdvancementPolicySet { policy: AdvancementPolicy::DefendPoints } => format!("{} required players to defend on points and A", n?),
+            AdvancementPolicySet { policy: AdvancementPolicy::DefendPointsThenFreeze { ceiling } } => format!("{} required players to defend on points, and stopped advancement at {}", n?, ceiling.as_str()),
             GameScoringParametersChanged { .. } => format!("{} changed the game's scoring parameters", n?),
             KittySizeSet { size: Some(size) } => format!("{} set the number of cards in the bottom to {}", n?, size),
             KittySizeSet { size: None } => format!("{} set the number of cards in the bottom to default", n?),
@@ -493,19 +983,30 @@ impl BroadcastMessage {
             FriendSelectionPolicySet { policy: FriendSelectionPolicy::PointCardNotAllowed } => format!("{} disallowed point cards, as well as trump cards, from being selected as a friend", n?),
             MultipleJoinPolicySet { policy: MultipleJoinPolicy::Unrestricted } => format!("{} allowed players to join the team multiple times", n?),
             MultipleJoinPolicySet { policy: MultipleJoinPolicy::NoDoubleJoin } => format!("{} prevented players from joining the team multiple times", n?),
+            FriendPointTransferPolicySet { policy: FriendPointTransferPolicy::MoveToLandlordTeam } => format!("{} made friends bring their earlier points to the landlord's team", n?),
+            FriendPointTransferPolicySet { policy: FriendPointTransferPolicy::KeepWithCaptor } => format!("{} made friends' earlier points stay with the attacking team", n?),
             FirstLandlordSelectionPolicySet { policy: FirstLandlordSelectionPolicy::ByWinningBid } => format!("{} set winning bid to decide both landlord and trump", n?),
             FirstLandlordSelectionPolicySet { policy: FirstLandlordSelectionPolicy::ByFirstBid } => format!("{} set first bid to decide landlord, winning bid to decide trump", n?),
+            FirstLeadPolicySet { policy: FirstLeadPolicy::Landlord } => format!("{} set the landlord to lead the first trick", n?),
+            FirstLeadPolicySet { policy: FirstLeadPolicy::LeftOfLandlord } => format!("{} set the player after the landlord to lead the first trick", n?),
+            FirstLeadPolicySet { policy: FirstLeadPolicy::BidWinner } => format!("{} set the winning bidder to lead the first trick", n?),
             BidPolicySet { policy: BidPolicy::JokerOrHigherSuit } => format!("{} allowed joker or higher suit bids to outbid non-joker bids with the same number of cards", n?),
             BidPolicySet { policy: BidPolicy::JokerOrGreaterLength } => format!("{} allowed joker bids to outbid non-joker bids with the same number of cards", n?),
             BidPolicySet { policy: BidPolicy::GreaterLength } => format!("{} required all bids to have more cards than the previous bids", n?),
+            BidPolicySet { policy: BidPolicy::StrictlyIncreasing } => format!("{} required all bids, including reinforcements, to have more cards than the winning bid", n?),
             BidReinforcementPolicySet { policy: BidReinforcementPolicy::ReinforceWhileWinning } => format!("{} allowed reinforcing the winning bid", n?),
             BidReinforcementPolicySet { policy: BidReinforcementPolicy::ReinforceWhileEquivalent } => format!("{} allowed reinforcing bids after they have been overturned", n?),
             BidReinforcementPolicySet { policy: BidReinforcementPolicy::OverturnOrReinforceWhileWinning } => format!("{} allowed overturning your own bids", n?),
+            JokerBidSuitPolicySet { policy: JokerBidSuitPolicy::ForceNoTrump } => format!("{} made joker bids play without a trump suit", n?),
+            JokerBidSuitPolicySet { policy: JokerBidSuitPolicy::LandlordChoosesSuit } => format!("{} let the leader choose the trump suit after a joker bid", n?),
             JokerBidPolicySet { policy: JokerBidPolicy::BothNumDecks } => format!("{} required no-trump bids to have every low or high joker", n?),
             JokerBidPolicySet { policy: JokerBidPolicy::LJNumDecksHJNumDecksLessOne } => format!("{} required low no-trump bids to have every low joker (one less required for high joker)", n?),
             JokerBidPolicySet { policy: JokerBidPolicy::BothTwoOrMore } => format!("{} required no-trump bids to have at least two low or high jokers", n?),
             ShouldRevealKittyAtEndOfGameSet { should_reveal: true } => format!("{} enabled the kitty to be revealed at the end of each game", n?),
             ShouldRevealKittyAtEndOfGameSet { should_reveal: false } => format!("{} disabled the kitty from being revealed at the end of each game", n?),
+            KittyRevealPolicySet { policy: KittyRevealPolicy::Never } => format!("{} disabled the kitty from being revealed at the end of each game", n?),
+            KittyRevealPolicySet { policy: KittyRevealPolicy::Always } => format!("{} enabled the kitty to be revealed at the end of each game", n?),
+            KittyRevealPolicySet { policy: KittyRevealPolicy::OnlyIfContestedPoints } => format!("{} enabled the kitty to be revealed at the end of the game if its points were counted", n?),
             NumDecksSet { num_decks: Some(num_decks) } => format!("{} set the number of decks to {}", n?, num_decks),
             NumDecksSet { num_decks: None } => format!("{} set the number of decks to default", n?),
             SpecialDecksSet { ref special_decks } if special_decks.is_empty() => format!("{} set the decks to standard 54-card decks", n?),
@@ -518,6 +1019,10 @@ impl BroadcastMessage {
             GameModeSet { game_mode: GameModeSettings::FindingFriends { num_friends: Some(friends) }} => format!("{} set the game mode to Finding Friends with {} friends", n?, friends),
             TookBackBid => format!("{} took back their last bid", n?),
             TookBackPlay => format!("{} took back their last play", n?),
+            TakeBackRequested => format!("{} asked to take back their last play", n?),
+            TakeBackApproved => format!("{} approved taking back the last play", n?),
+            TakeBackCancelled => format!("{} cancelled the request to take back the last play", n?),
+            TakeBackCompleted { player } => format!("{} took back their last play with the other team's approval", player_name(player)?),
             PlayedCards { ref cards } => format!("{} played {}", n?, cards.iter().map(|c| c.as_char()).collect::<String>()),
             EndOfGameKittyReveal { ref cards } => format!("{} in kitty", cards.iter().map(|c| c.as_char()).collect::<String>()),
             ThrowFailed { ref original_cards, better_player: Some(better_player) } => format!("{} tried to throw {}, but {} can beat it", n?, original_cards.iter().map(|c| c.as_char()).collect::<String>(), player_name(better_player)?),
@@ -531,40 +1036,293 @@ impl BroadcastMessage {
             SetLandlordEmoji { ref emoji } => format!("{} set landlord emoji to {}", n?, *emoji),
             SetRank { rank } => format!("{} set their rank to {}", n?, rank.as_str()),
             MadeBid { card, count } => format!("{} bid {} {:?}", n?, count, card),
+            MadeBlindBid { card, count } => format!("{} blindly bid {} {:?}", n?, count, card),
+            BlindBidRevoked { card, count } => format!("{} didn't draw {} {:?}, so their blind bid was revoked", n?, count, card),
             KittyPenaltySet { kitty_penalty: KittyPenalty::Times } => format!("{} set the penalty for points in the bottom to twice the size of the last trick", n?),
             KittyPenaltySet { kitty_penalty: KittyPenalty::Power } => format!("{} set the penalty for points in the bottom to two to the power of the size of the last trick", n?),
+            KittyPenaltySet { kitty_penalty: KittyPenalty::None } => format!("{} set the points in the bottom to only be counted once", n?),
+            KittyPenaltySet { kitty_penalty: KittyPenalty::Fixed(multiplier) } => format!("{} set the penalty for points in the bottom to {} times", n?, multiplier),
+            KittyPenaltySet { kitty_penalty: KittyPenalty::ByUnitType { single, pair, tractor } } => format!("{} set the penalty for points in the bottom to {} times for singles, {} times for tuples, and {} times for tractors in the last trick", n?, single, pair, tractor),
             ThrowPenaltySet { throw_penalty: ThrowPenalty::None } => format!("{} removed the throw penalty", n?),
             ThrowPenaltySet { throw_penalty: ThrowPenalty::TenPointsPerAttempt } => format!("{} set the throw penalty to 10 points per throw", n?),
+            OutOfTurnPolicySet { policy: OutOfTurnPolicy::Ignore } => format!("{} removed the penalty for playing out of turn", n?),
+            OutOfTurnPolicySet { policy: OutOfTurnPolicy::WarnThenPenalize { warnings, points } } => format!("{} set playing out of turn to be penalized {} points after {} warnings", n?, points, warnings),
+            OutOfTurnWarning { player, .. } => format!("{} was warned for playing out of turn", player_name(player)?),
+            OutOfTurnPenalty { player, points } => format!("{} was penalized {} points for playing out of turn", player_name(player)?, points),
+            KittyOnLossPolicySet { policy: KittyOnLossPolicy::LastTrickWinner } => format!("{} gave the bottom cards' points to the winner of the last trick", n?),
+            KittyOnLossPolicySet { policy: KittyOnLossPolicy::AttackersOnLoss } => format!("{} gave the bottom cards' points to the opposing team whenever the landlord team loses", n?),
             KittyBidPolicySet { policy: KittyBidPolicy::FirstCard } => format!("{} set the bid-from-bottom policy to be the first card revealed", n?),
             KittyBidPolicySet { policy: KittyBidPolicy::FirstCardOfLevelOrHighest } => format!("{} set the bid-from-bottom policy to be the first card of the appropriate level, or the highest if none are found", n?),
             TrickDrawPolicySet { policy: TrickDrawPolicy::NoProtections } => format!("{} removed all protections (pair can draw triple)", n?),
             TrickDrawPolicySet { policy: TrickDrawPolicy::NoFormatBasedDraw } => format!("{} removed format-based forced-plays (pairs do not draw pairs)", n?),
             TrickDrawPolicySet { policy: TrickDrawPolicy::LongerTuplesProtected } => format!("{} protected longer tuples from being drawn out by shorter ones (pair does not draw triple)", n?),
             TrickDrawPolicySet { policy: TrickDrawPolicy::OnlyDrawTractorOnTractor } => format!("{} protected tractors from being drawn out by non-tractors", n?),
+            TrickDrawPolicySet { policy: TrickDrawPolicy::OnlyDrawTractorOnTractorStrict } => format!("{} protected tractors from being drawn out by non-tractors, or past pairs", n?),
             ThrowEvaluationPolicySet { policy: ThrowEvaluationPolicy::All } => format!("{} set throws to be evaluated based on all of the cards", n?),
             ThrowEvaluationPolicySet { policy: ThrowEvaluationPolicy::Highest } => format!("{} set throws to be evaluated based on the highest card", n?),
             ThrowEvaluationPolicySet { policy: ThrowEvaluationPolicy::TrickUnitLength } => format!("{} set throws to be evaluated based on the longest component", n?),
+            ThrowEvaluationPolicySet { policy: ThrowEvaluationPolicy::Cumulative } => format!("{} set throws to be evaluated based on the combined rank of their components", n?),
+            ThrowTieBreakPolicySet { policy: ThrowTieBreakPolicy::FirstPlayed } => format!("{} set equally strong throws to be won by the first one played", n?),
+            ThrowTieBreakPolicySet { policy: ThrowTieBreakPolicy::LastPlayed } => format!("{} set equally strong throws to be won by the last one played", n?),
             PlayTakebackPolicySet { policy: PlayTakebackPolicy::AllowPlayTakeback } => format!("{} allowed taking back plays", n?),
             PlayTakebackPolicySet { policy: PlayTakebackPolicy::NoPlayTakeback } => format!("{} disallowed taking back plays", n?),
+            PlayTakebackPolicySet { policy: PlayTakebackPolicy::RequiresApproval } => format!("{} required the other team to approve taking back plays", n?),
             BidTakebackPolicySet { policy: BidTakebackPolicy::AllowBidTakeback } => format!("{} allowed taking back bids", n?),
             BidTakebackPolicySet { policy: BidTakebackPolicy::NoBidTakeback } => format!("{} disallowed taking back bids", n?),
             KittyTheftPolicySet { policy: KittyTheftPolicy::AllowKittyTheft } => format!("{} allowed stealing the bottom cards after the leader", n?),
             KittyTheftPolicySet { policy: KittyTheftPolicy::NoKittyTheft } => format!("{} disabled stealing the bottom cards after the leader", n?),
+            KittyTheftRevealPolicySet { policy: KittyTheftRevealPolicy::RevealPreviousDiscards } => format!("{} made stolen bottom cards include the previous exchanger's discards", n?),
+            KittyTheftRevealPolicySet { policy: KittyTheftRevealPolicy::ResetToOriginalKitty } => format!("{} made stolen bottom cards reset to the original bottom cards", n?),
+            KittyTheftBidPolicySet { policy: KittyTheftBidPolicy::MustExceed } => format!("{} required a higher bid to steal the bottom cards", n?),
+            KittyTheftBidPolicySet { policy: KittyTheftBidPolicy::MustMatch } => format!("{} allowed an equal bid to steal the bottom cards", n?),
             GameShadowingPolicySet { policy: GameShadowingPolicy::AllowMultipleSessions } => format!("{} allowed players to be shadowed by joining with the same name", n?),
             GameShadowingPolicySet { policy: GameShadowingPolicy::SingleSessionOnly } => format!("{} prohibited players from being shadowed", n?),
             GameStartPolicySet { policy: GameStartPolicy::AllowAnyPlayer } => format!("{} allowed any player to start a game", n?),
             GameStartPolicySet { policy: GameStartPolicy::AllowLandlordOnly } => format!("{} allowed only landlord to start a game", n?),
+            AbsentLandlordPolicySet { policy: AbsentLandlordPolicy::Wait } => format!("{} made the game wait for the landlord to finish exchanging", n?),
+            AbsentLandlordPolicySet { policy: AbsentLandlordPolicy::AutoFinalizeExchange } => format!("{} made the landlord's exchange finish automatically once they run out of time", n?),
+            AbandonedGamePolicySet { policy: AbandonedGamePolicy::IgnoreResets } => format!("{} stopped counting reset games as abandoned", n?),
+            AbandonedGamePolicySet { policy: AbandonedGamePolicy::CountResetsAsAbandoned } => format!("{} started counting reset games as abandoned", n?),
             RevealedCardFromKitty => format!("{} revealed a card from the bottom of the deck", n?),
             PickedUpCards => format!("{} picked up the bottom cards", n?),
             PutDownCards => format!("{} put down the bottom cards", n?),
+            TrumpSuitSet { suit } => format!("{} chose {:?} as the trump suit", n?, suit),
             GameFinished { result: _ } => "The game has finished".to_string(),
             GameEndedEarly => format!("{} ended the game early", n?),
             BonusLevelEarned => "Landlord team earned a bonus level for defending with a smaller team".to_string(),
+            BlindBidBonusEarned { bonus } => format!("Landlord team earned {} bonus level(s) for winning with a blind bid", bonus),
             EndOfGameSummary { landlord_won : true, non_landlords_points } => format!("Landlord team won, opposing team only collected {} points", non_landlords_points),
             EndOfGameSummary { landlord_won: false, non_landlords_points } => format!("Landlord team lost, opposing team collected {} points", non_landlords_points),
             HideThrowHaltingPlayer { set: true } => format!("{} hid the player who prevents throws", n?),
             HideThrowHaltingPlayer { set: false } => format!("{} un-hid the player who prevents throws", n?),
+            RevealKittyPointCount { set: true } => format!("{} revealed how many point cards are in the bottom", n?),
+            RevealKittyPointCount { set: false } => format!("{} hid how many point cards are in the bottom", n?),
             TractorRequirementsChanged { tractor_requirements } => format!("{} required tractors to be at least {} cards wide by {} tuples long", n?, tractor_requirements.min_count, tractor_requirements.min_length),
+            BlindBidBonusSet { bonus: Some(bonus) } => format!("{} allowed blind bids, worth {} bonus level(s) if the landlord team wins", n?, bonus),
+            BlindBidBonusSet { bonus: None } => format!("{} disallowed blind bids", n?),
+            MaxMetalevelSet { max_metalevel: Some(max_metalevel) } => format!("{} made the match end when a player reaches metalevel {}", n?, max_metalevel),
+            MaxMetalevelSet { max_metalevel: None } => format!("{} made the match go on indefinitely", n?),
+            RemainderPolicySet { policy: RemainderPolicy::GrowKitty } => format!("{} set leftover cards to be added to the bottom", n?),
+            RemainderPolicySet { policy: RemainderPolicy::RemoveCards } => format!("{} set leftover cards to be removed from the deck", n?),
+            RemainderPolicySet { policy: RemainderPolicy::SmallerLastRound } => format!("{} set the bottom to be the last, partial round of drawing", n?),
+            CaptureBonusRulesSet { ref rules } if rules.is_empty() => format!("{} removed the capture bonus rules", n?),
+            CaptureBonusRulesSet { ref rules } => format!("{} set {} capture bonus rule(s)", n?, rules.len()),
+            CaptureBonusEarned { player, ref cards, bonus } => format!("{} earned {} bonus level(s) for capturing {}", player_name(player)?, bonus, cards.iter().map(|c| c.as_char()).collect::<String>()),
+            PausePolicySet { policy: PausePolicy::NoPausing } => format!("{} disallowed pausing the game", n?),
+            PausePolicySet { policy: PausePolicy::AllowPausing } => format!("{} allowed pausing the game", n?),
+            GamePaused => format!("{} paused the game", n?),
+            GameResumed => format!("{} resumed the game", n?),
+            WeakHandRedealSet { rule: None } => format!("{} disallowed redealing weak hands", n?),
+            WeakHandRedealSet { rule: Some(WeakHandRedeal { point_threshold, trump_threshold }) } => format!("{} allowed redealing hands with fewer than {} points and at most {} trumps", n?, point_threshold, trump_threshold),
+            QuickRematchSet { set: true } => format!("{} allowed starting a rematch right after each game", n?),
+            QuickRematchSet { set: false } => format!("{} disallowed quick rematches", n?),
+            DeclaredWeakHand { player } => format!("{} declared a weak hand, so the cards will be redealt", player_name(player)?),
+            SpectatorCardVisibilitySet { policy: SpectatorCardVisibility::Hidden } => format!("{} hid all hands from spectators", n?),
+            SpectatorCardVisibilitySet { policy: SpectatorCardVisibility::AfterGameEnd } => format!("{} let spectators see the hands once the game is over", n?),
+            SpectatorCardVisibilitySet { policy: SpectatorCardVisibility::AlwaysVisible } => format!("{} let spectators see every hand as the game is played", n?),
+            VacatedSeatPolicySet { policy: VacatedSeatPolicy::LeaveVacant } => format!("{} left the seats of kicked players empty until the game is over", n?),
+            VacatedSeatPolicySet { policy: VacatedSeatPolicy::AllowObserverTakeover } => format!("{} let observers take over the seats of kicked players", n?),
+            MoveDeadlineSet { secs: Some(secs) } => format!("{} gave each player {} seconds to make a move", n?, secs),
+            MoveDeadlineSet { secs: None } => format!("{} removed the time limit on moves", n?),
+            GameModifiersSet { ref modifiers } if modifiers.is_empty() => format!("{} removed all game modifiers", n?),
+            GameModifiersSet { ref modifiers } => format!("{} set the game modifiers to {}", n?, modifiers.iter().map(|m| m.description()).collect::<Vec<_>>().join(", ")),
+            ExperimentalFeaturesSet { ref features } if features.is_empty() => format!("{} disabled all experimental features", n?),
+            ExperimentalFeaturesSet { ref features } => format!("{} enabled the experimental features {}", n?, features.join(", ")),
+            PermanentTrumpNumbersSet { ref numbers } if numbers.is_empty() => format!("{} made only the landlord's level trump", n?),
+            ExcludedCardsSet { ref cards } if cards.is_empty() => format!("{} put all of the cards back in the deck", n?),
+            ExcludedCardsSet { ref cards } => format!("{} removed {} from the deck", n?, cards.iter().map(|c| format!("{:?}", c)).collect::<Vec<_>>().join(", ")),
+            PermanentTrumpNumbersSet { ref numbers } => format!("{} made {} always trump", n?, numbers.iter().map(|n| format!("{}s", n.as_str())).collect::<Vec<_>>().join(", ")),
+            PlayerTimedOut { player } => format!("{} has run out of time to make a move", player_name(player)?),
+            IdleObserverRemoved => format!("{} was disconnected for being idle", n?),
+            ExchangeAutoFinalized { landlord } => format!("{} ran out of time, so their exchange was finished for them", player_name(landlord)?),
+            DirectorSet { director: None } => format!("{} removed the director", n?),
+            DirectorSet { director: Some(director) } => format!("{} made {} the director", n?, player_name(director)?),
+            DirectorCorrection { player, correction: Correction::TookBackCards } => format!("{} (director) took back {}'s play", n?, player_name(player)?),
+            DirectorCorrection { player, correction: Correction::ReassignedPoints { to, ref cards } } => format!("{} (director) moved {} points from {} to {}", n?, cards.iter().flat_map(|c| c.points()).sum::<usize>(), player_name(player)?, player_name(to)?),
         })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use slog::{o, Discard, Logger};
+
+    use super::{Action, English, InteractiveGame};
+    use crate::game_state::{GameState, InitializePhase};
+    use crate::message::MessageVariant;
+    use crate::settings::{AbsentLandlordPolicy, PausePolicy, VacatedSeatPolicy};
+    use crate::types::PlayerID;
+
+    #[test]
+    fn test_pause_and_resume() {
+        let logger = Logger::root(Discard, o!());
+        let mut game = InteractiveGame::new();
+        let players = (0..4)
+            .map(|i| game.register(format!("p{}", i)).unwrap().0)
+            .collect::<Vec<_>>();
+        game.interact(
+            Action::SetPausePolicy(PausePolicy::AllowPausing),
+            players[0],
+            &logger,
+        )
+        .unwrap();
+        game.interact(Action::StartGame, players[0], &logger)
+            .unwrap();
+
+        let next = game.next_player().unwrap();
+        game.interact(Action::PauseGame, players[1], &logger)
+            .unwrap();
+        assert!(game.pause(players[1], &logger).is_err());
+        assert!(game.interact(Action::DrawCard, next, &logger).is_err());
+
+        game.interact(Action::ResumeGame, players[2], &logger)
+            .unwrap();
+        game.interact(Action::DrawCard, next, &logger).unwrap();
+        assert!(game.resume(players[2], &logger).is_err());
+    }
+
+    #[test]
+    fn test_promote_observer_action() {
+        let logger = Logger::root(Discard, o!());
+        let mut game = InteractiveGame::new();
+        let players = (0..4)
+            .map(|i| game.register(format!("p{}", i)).unwrap().0)
+            .collect::<Vec<_>>();
+        game.interact(
+            Action::SetVacatedSeatPolicy(VacatedSeatPolicy::AllowObserverTakeover),
+            players[0],
+            &logger,
+        )
+        .unwrap();
+        game.interact(Action::StartGame, players[0], &logger)
+            .unwrap();
+        let observer = game.register("observer".into()).unwrap().0;
+        let other = game.register("other".into()).unwrap().0;
+        game.kick(players[0], players[2]).unwrap();
+
+        // Observers can only promote themselves.
+        assert!(game
+            .interact(Action::PromoteObserver(observer, 2), other, &logger)
+            .is_err());
+        game.interact(Action::PromoteObserver(observer, 2), observer, &logger)
+            .unwrap();
+        assert_eq!(
+            game.dump_state().unwrap().player_id("observer").unwrap(),
+            players[2]
+        );
+        assert!(game.is_observer(other));
+    }
+
+    #[test]
+    fn test_remove_idle_observer() {
+        let mut game = InteractiveGame::new();
+        for i in 0..4 {
+            game.register(format!("p{}", i)).unwrap();
+        }
+        game.interact(Action::StartGame, PlayerID(0), &Logger::root(Discard, o!()))
+            .unwrap();
+        let observer = game.register("observer".into()).unwrap().0;
+
+        let msgs = game.remove_idle_observer(observer).unwrap();
+        assert_eq!(
+            msgs.iter().map(|(_, s)| s.as_str()).collect::<Vec<_>>(),
+            vec!["observer was disconnected for being idle"]
+        );
+        assert!(!game.is_observer(observer));
+    }
+
+    #[test]
+    fn test_auto_finalize_exchange() {
+        let logger = Logger::root(Discard, o!());
+        let mut init = InitializePhase::new();
+        let p1 = init.add_player("p1".into()).unwrap().0;
+        for name in &["p2", "p3", "p4"] {
+            init.add_player(name.to_string()).unwrap();
+        }
+        init.set_absent_landlord_policy(AbsentLandlordPolicy::AutoFinalizeExchange)
+            .unwrap();
+        init.set_move_deadline_secs(Some(30)).unwrap();
+        let draw = init.start_with_seed(p1, 42).unwrap();
+        let mut game = InteractiveGame::new_from_state(GameState::Draw(draw));
+
+        loop {
+            let state = game.dump_state().unwrap();
+            let no_bids = !state
+                .history()
+                .iter()
+                .any(|entry| matches!(entry.message, MessageVariant::MadeBid { .. }));
+            let draw = match state {
+                GameState::Draw(draw) => draw,
+                _ => break,
+            };
+            let next = draw.next_player().unwrap();
+            let bids = draw.valid_bids(next);
+            let action = if no_bids && !bids.is_empty() {
+                Action::Bid(bids[0].card, bids[0].count)
+            } else if draw.done_drawing() {
+                Action::PickUpKitty
+            } else {
+                Action::DrawCard
+            };
+            game.interact(action, next, &logger).unwrap();
+        }
+
+        let (landlord, kitty) = match game.dump_state().unwrap() {
+            GameState::Exchange(exchange) => (exchange.landlord(), exchange.kitty().to_vec()),
+            _ => panic!("expected to be exchanging"),
+        };
+        // The landlord takes a card out of the bottom, and then disappears.
+        game.interact(Action::MoveCardToHand(kitty[0]), landlord, &logger)
+            .unwrap();
+        game.record_action_at(1000);
+        assert!(game
+            .auto_finalize_exchange(1030, &logger)
+            .unwrap()
+            .is_empty());
+        assert!(matches!(game.dump_state().unwrap(), GameState::Exchange(_)));
+
+        let msgs = game.auto_finalize_exchange(1031, &logger).unwrap();
+        assert!(msgs.iter().any(|(b, _)| matches!(
+            b.variant,
+            MessageVariant::ExchangeAutoFinalized { landlord: l } if l == landlord
+        )));
+        match game.dump_state().unwrap() {
+            GameState::Play(play) => {
+                // The bottom was topped back up, so everyone has the same
+                // number of cards.
+                let hand_sizes = play
+                    .propagated()
+                    .players
+                    .iter()
+                    .map(|p| play.hands().get(p.id).unwrap().values().sum::<usize>())
+                    .collect::<Vec<_>>();
+                assert!(hand_sizes.iter().all(|n| *n == hand_sizes[0]));
+            }
+            _ => panic!("expected the exchange to be finished"),
+        }
+    }
+
+    #[test]
+    fn test_render_trick_won() {
+        let player_name = |id: PlayerID| match id {
+            PlayerID(1) => Ok("p1"),
+            _ => Err(anyhow::anyhow!("no such player")),
+        };
+        let won = MessageVariant::TrickWon {
+            winner: PlayerID(1),
+            points: 25,
+        };
+        assert_eq!(
+            won.render(PlayerID(1), &English, &player_name).unwrap(),
+            "p1 wins the trick and gets 25 points"
+        );
+        let won = MessageVariant::TrickWon {
+            winner: PlayerID(1),
+            points: 0,
+        };
+        assert_eq!(
+            won.render(PlayerID(1), &English, &player_name).unwrap(),
+            "p1 wins the trick, but gets no points :("
+        );
+    }
+}
@@ -8,6 +8,7 @@
 pub mod settings;
 
 pub mod bidding;
+pub mod bot;
 pub mod deck;
 pub mod game_state;
 pub mod hands;
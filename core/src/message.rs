@@ -1,18 +1,24 @@
 use std::collections::HashMap;
 
+use anyhow::Error;
 use serde::{Deserialize, Serialize};
 
 use crate::bidding::{BidPolicy, BidReinforcementPolicy, BidTakebackPolicy, JokerBidPolicy};
 use crate::deck::Deck;
-use crate::game_state::PlayerGameFinishedResult;
+use crate::game_state::{Correction, PlayerGameFinishedResult};
 use crate::scoring::GameScoringParameters;
 use crate::settings::{
-    AdvancementPolicy, FirstLandlordSelectionPolicy, FriendSelectionPolicy, GameModeSettings,
-    GameShadowingPolicy, GameStartPolicy, KittyBidPolicy, KittyPenalty, KittyTheftPolicy,
-    MultipleJoinPolicy, PlayTakebackPolicy, ThrowPenalty,
+    AbandonedGamePolicy, AbsentLandlordPolicy, AdvancementPolicy, FirstLandlordSelectionPolicy,
+    FirstLeadPolicy, FriendPointTransferPolicy, FriendSelectionPolicy, GameModeSettings,
+    GameModifier, GameShadowingPolicy, GameStartPolicy, JokerBidSuitPolicy, KittyBidPolicy,
+    KittyOnLossPolicy, KittyPenalty, KittyRevealPolicy, KittyTheftBidPolicy, KittyTheftPolicy,
+    KittyTheftRevealPolicy, MultipleJoinPolicy, OutOfTurnPolicy, PausePolicy, PlayTakebackPolicy,
+    RemainderPolicy, SpectatorCardVisibility, ThrowPenalty, VacatedSeatPolicy, WeakHandRedeal,
 };
-use crate::trick::{ThrowEvaluationPolicy, TractorRequirements, TrickDrawPolicy};
-use crate::types::{Card, Number, PlayerID};
+use crate::trick::{
+    ThrowEvaluationPolicy, ThrowTieBreakPolicy, TractorRequirements, TrickDrawPolicy,
+};
+use crate::types::{Card, Number, PlayerID, Suit};
 
 #[derive(Debug, Clone, Deserialize, Serialize)]
 #[serde(tag = "type")]
@@ -34,10 +40,20 @@ pub enum MessageVariant {
     NewLandlordForNextGame {
         landlord: PlayerID,
     },
+    GameMvp {
+        player: PlayerID,
+    },
+    MatchWon {
+        player: PlayerID,
+    },
+    NewMatchStarted,
     PointsInKitty {
         points: usize,
         multiplier: usize,
     },
+    KittyAwardedOnLoss {
+        points: usize,
+    },
     EndOfGameKittyReveal {
         cards: Vec<Card>,
     },
@@ -51,10 +67,16 @@ pub enum MessageVariant {
     JoinedTeam {
         player: PlayerID,
         already_joined: bool,
+        via_card: Card,
+        remaining_friends: usize,
     },
     LeftGame {
         name: String,
     },
+    ObserverPromoted {
+        player: PlayerID,
+        replaced: String,
+    },
     AdvancementPolicySet {
         policy: AdvancementPolicy,
     },
@@ -67,21 +89,33 @@ pub enum MessageVariant {
     MultipleJoinPolicySet {
         policy: MultipleJoinPolicy,
     },
+    FriendPointTransferPolicySet {
+        policy: FriendPointTransferPolicy,
+    },
     FirstLandlordSelectionPolicySet {
         policy: FirstLandlordSelectionPolicy,
     },
+    FirstLeadPolicySet {
+        policy: FirstLeadPolicy,
+    },
     BidPolicySet {
         policy: BidPolicy,
     },
     BidReinforcementPolicySet {
         policy: BidReinforcementPolicy,
     },
+    JokerBidSuitPolicySet {
+        policy: JokerBidSuitPolicy,
+    },
     JokerBidPolicySet {
         policy: JokerBidPolicy,
     },
     ShouldRevealKittyAtEndOfGameSet {
         should_reveal: bool,
     },
+    KittyRevealPolicySet {
+        policy: KittyRevealPolicy,
+    },
     SpecialDecksSet {
         special_decks: Vec<Deck>,
     },
@@ -97,7 +131,19 @@ pub enum MessageVariant {
     KittyTheftPolicySet {
         policy: KittyTheftPolicy,
     },
+    KittyTheftRevealPolicySet {
+        policy: KittyTheftRevealPolicy,
+    },
+    KittyTheftBidPolicySet {
+        policy: KittyTheftBidPolicy,
+    },
     TookBackPlay,
+    TakeBackRequested,
+    TakeBackApproved,
+    TakeBackCancelled,
+    TakeBackCompleted {
+        player: PlayerID,
+    },
     TookBackBid,
     PlayedCards {
         cards: Vec<Card>,
@@ -125,21 +171,46 @@ pub enum MessageVariant {
         card: Card,
         count: usize,
     },
+    MadeBlindBid {
+        card: Card,
+        count: usize,
+    },
+    BlindBidRevoked {
+        card: Card,
+        count: usize,
+    },
     KittyPenaltySet {
         kitty_penalty: KittyPenalty,
     },
     ThrowPenaltySet {
         throw_penalty: ThrowPenalty,
     },
+    OutOfTurnPolicySet {
+        policy: OutOfTurnPolicy,
+    },
+    OutOfTurnWarning {
+        player: PlayerID,
+        attempts: usize,
+    },
+    OutOfTurnPenalty {
+        player: PlayerID,
+        points: usize,
+    },
     KittyBidPolicySet {
         policy: KittyBidPolicy,
     },
+    KittyOnLossPolicySet {
+        policy: KittyOnLossPolicy,
+    },
     TrickDrawPolicySet {
         policy: TrickDrawPolicy,
     },
     ThrowEvaluationPolicySet {
         policy: ThrowEvaluationPolicy,
     },
+    ThrowTieBreakPolicySet {
+        policy: ThrowTieBreakPolicy,
+    },
     PlayTakebackPolicySet {
         policy: PlayTakebackPolicy,
     },
@@ -152,18 +223,30 @@ pub enum MessageVariant {
     GameStartPolicySet {
         policy: GameStartPolicy,
     },
+    AbandonedGamePolicySet {
+        policy: AbandonedGamePolicy,
+    },
+    AbsentLandlordPolicySet {
+        policy: AbsentLandlordPolicy,
+    },
     GameScoringParametersChanged {
         parameters: GameScoringParameters,
         old_parameters: GameScoringParameters,
     },
     PickedUpCards,
     PutDownCards,
+    TrumpSuitSet {
+        suit: Suit,
+    },
     RevealedCardFromKitty,
     GameEndedEarly,
     GameFinished {
         result: HashMap<String, PlayerGameFinishedResult>,
     },
     BonusLevelEarned,
+    BlindBidBonusEarned {
+        bonus: usize,
+    },
     EndOfGameSummary {
         landlord_won: bool,
         non_landlords_points: isize,
@@ -171,7 +254,112 @@ pub enum MessageVariant {
     HideThrowHaltingPlayer {
         set: bool,
     },
+    RevealKittyPointCount {
+        set: bool,
+    },
     TractorRequirementsChanged {
         tractor_requirements: TractorRequirements,
     },
+    BlindBidBonusSet {
+        bonus: Option<usize>,
+    },
+    MaxMetalevelSet {
+        max_metalevel: Option<usize>,
+    },
+    RemainderPolicySet {
+        policy: RemainderPolicy,
+    },
+    CaptureBonusRulesSet {
+        rules: Vec<(Vec<Card>, isize)>,
+    },
+    CaptureBonusEarned {
+        player: PlayerID,
+        cards: Vec<Card>,
+        bonus: isize,
+    },
+    PausePolicySet {
+        policy: PausePolicy,
+    },
+    GamePaused,
+    GameResumed,
+    WeakHandRedealSet {
+        rule: Option<WeakHandRedeal>,
+    },
+    DeclaredWeakHand {
+        player: PlayerID,
+    },
+    QuickRematchSet {
+        set: bool,
+    },
+    FriendBoundsSet {
+        min_friends: Option<usize>,
+        max_friends: Option<usize>,
+    },
+    SkipExchangeSet {
+        set: bool,
+    },
+    TeamLevelSpread {
+        team: usize,
+        min_level: Number,
+        max_level: Number,
+    },
+    SpectatorCardVisibilitySet {
+        policy: SpectatorCardVisibility,
+    },
+    VacatedSeatPolicySet {
+        policy: VacatedSeatPolicy,
+    },
+    MoveDeadlineSet {
+        secs: Option<u64>,
+    },
+    GameModifiersSet {
+        modifiers: Vec<GameModifier>,
+    },
+    ExperimentalFeaturesSet {
+        features: Vec<String>,
+    },
+    PermanentTrumpNumbersSet {
+        numbers: Vec<Number>,
+    },
+    ExcludedCardsSet {
+        cards: Vec<Card>,
+    },
+    PlayerTimedOut {
+        player: PlayerID,
+    },
+    IdleObserverRemoved,
+    ExchangeAutoFinalized {
+        landlord: PlayerID,
+    },
+    DirectorSet {
+        director: Option<PlayerID>,
+    },
+    DirectorCorrection {
+        player: PlayerID,
+        correction: Correction,
+    },
+}
+
+/// Renders messages into human-readable text, so that backends and clients
+/// can localize them consistently.
+pub trait MessageLocale {
+    /// Renders `variant`, which was caused by `actor`, looking up any players
+    /// it mentions with `player_name`.
+    fn render<'a>(
+        &self,
+        actor: PlayerID,
+        variant: &MessageVariant,
+        player_name: &dyn Fn(PlayerID) -> Result<&'a str, Error>,
+    ) -> Result<String, Error>;
+}
+
+impl MessageVariant {
+    pub fn render<'a>(
+        &self,
+        actor: PlayerID,
+        locale: &dyn MessageLocale,
+        player_name: &dyn Fn(PlayerID) -> Result<&'a str, Error>,
+    ) -> Result<String, Error> {
+        locale.render(actor, self, player_name)
+    }
 }
@@ -419,7 +419,7 @@ fn usize_partitions(n: usize) -> Vec<Vec<Usizes>> {
 mod tests {
     use crate::types::{
         cards::{S_2, S_3, S_5},
-        Card, Number, Suit, Trump,
+        Card, Number, PermanentTrumps, Suit, Trump,
     };
 
     use super::{
@@ -430,6 +430,7 @@ mod tests {
     const TRUMP: Trump = Trump::Standard {
         number: Number::Four,
         suit: Suit::Spades,
+        permanent: PermanentTrumps::NONE,
     };
     macro_rules! oc {
         ($card:expr) => {
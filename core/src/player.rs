@@ -1,13 +1,20 @@
+use rand::{distributions::Alphanumeric, Rng};
 use serde::{Deserialize, Serialize};
 
 use crate::types::{Number, PlayerID};
 
+const RECONNECT_TOKEN_LEN: usize = 24;
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Player {
     pub(crate) id: PlayerID,
     pub(crate) name: String,
     pub(crate) level: Number,
     pub(crate) metalevel: usize,
+    /// A secret which lets the player rejoin as themselves, regardless of the
+    /// name that they join with. Only ever sent to the player themselves.
+    #[serde(default)]
+    pub(crate) reconnect_token: String,
 }
 
 impl Player {
@@ -17,9 +24,17 @@ impl Player {
             name,
             level: Number::Two,
             metalevel: 1,
+            reconnect_token: rand::thread_rng()
+                .sample_iter(&Alphanumeric)
+                .take(RECONNECT_TOKEN_LEN)
+                .collect(),
         }
     }
 
+    pub fn reconnect_token(&self) -> &str {
+        &self.reconnect_token
+    }
+
     pub fn rank(&self) -> Number {
         self.level
     }
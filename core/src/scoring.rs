@@ -81,6 +81,18 @@ pub struct GameScoringParameters {
     deadzone_size: usize,
     truncate_zero_crossing_window: bool,
     pub bonus_level_policy: BonusLevelPolicy,
+    /// Number of points by which the attacking team must exceed the turnover
+    /// threshold; within this margin, the landlord team defends successfully.
+    #[serde(default)]
+    win_margin: usize,
+    /// Overrides `step_size_per_deck` for the steps below the turnover
+    /// threshold, which decide how far the landlord team advances.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    landlord_step_size_per_deck: Option<usize>,
+    /// Overrides `step_size_per_deck` for the steps above the turnover
+    /// threshold, which decide how far the attacking team advances.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    non_landlord_step_size_per_deck: Option<usize>,
 }
 impl_slog_value!(GameScoringParameters);
 
@@ -93,15 +105,40 @@ impl Default for GameScoringParameters {
             truncate_zero_crossing_window: true,
             step_adjustments: HashMap::new(),
             bonus_level_policy: BonusLevelPolicy::default(),
+            win_margin: 0,
+            landlord_step_size_per_deck: None,
+            non_landlord_step_size_per_deck: None,
         }
     }
 }
 
 impl GameScoringParameters {
     pub fn step_size(&self, decks: &[Deck]) -> Result<usize, Error> {
+        self.step_size_with(decks, self.step_size_per_deck)
+    }
+
+    /// The size of the steps below the turnover threshold.
+    pub fn landlord_step_size(&self, decks: &[Deck]) -> Result<usize, Error> {
+        self.step_size_with(
+            decks,
+            self.landlord_step_size_per_deck
+                .unwrap_or(self.step_size_per_deck),
+        )
+    }
+
+    /// The size of the steps above the turnover threshold.
+    pub fn non_landlord_step_size(&self, decks: &[Deck]) -> Result<usize, Error> {
+        self.step_size_with(
+            decks,
+            self.non_landlord_step_size_per_deck
+                .unwrap_or(self.step_size_per_deck),
+        )
+    }
+
+    fn step_size_with(&self, decks: &[Deck], step_size_per_deck: usize) -> Result<usize, Error> {
         let num_decks = decks.len();
         let total_points = decks.iter().map(|d| d.points() as isize).sum::<isize>();
-        let step_size = (num_decks * self.step_size_per_deck) as isize
+        let step_size = (num_decks * step_size_per_deck) as isize
             + self
                 .step_adjustments
                 .get(&num_decks)
@@ -120,20 +157,36 @@ impl GameScoringParameters {
         }
     }
 
+    /// Lists every point threshold at which the outcome of the game changes,
+    /// in increasing order, along with the outcome from that threshold up to
+    /// the next one.
+    pub fn all_relevant_scores(
+        &self,
+        decks: &[Deck],
+    ) -> Result<Vec<(isize, GameScoreResult)>, Error> {
+        explain_level_deltas(self, decks, false)
+    }
+
     pub fn materialize(&self, decks: &[Deck]) -> Result<MaterializedScoringParameters, Error> {
         if self.num_steps_to_non_landlord_turnover == 0 {
             bail!("Landlord team must be able to win")
         }
 
         let s = self.step_size(decks)? as isize;
-        let landlord_wins = if self.truncate_zero_crossing_window {
+        // The turnover threshold is always measured in regular steps; the
+        // overrides only change the steps on either side of it.
+        let turnover = self.num_steps_to_non_landlord_turnover as isize * s;
+        let landlord_s = self.landlord_step_size(decks)? as isize;
+        let non_landlord_s = self.non_landlord_step_size(decks)? as isize;
+
+        let mut landlord_wins = if self.truncate_zero_crossing_window {
             let mut landlord_wins = vec![];
 
             let mut delta = 1;
-            for offset in (2..=self.num_steps_to_non_landlord_turnover).rev() {
+            for steps in 1..self.num_steps_to_non_landlord_turnover as isize {
                 landlord_wins.push(LandlordWinningScoreSegment {
-                    start: (offset as isize - 1) * s,
-                    end: offset as isize * s,
+                    start: turnover - steps * landlord_s,
+                    end: turnover - (steps - 1) * landlord_s,
                     landlord_delta: delta,
                 });
                 delta += 1;
@@ -141,40 +194,76 @@ impl GameScoringParameters {
             // Note: it's kind of strange here that the intervals are not
             // all exactly 40 points; in particular, the window including
             // zero is "special" and results in 3 levels.
+            let zero_crossing_end =
+                turnover - (self.num_steps_to_non_landlord_turnover as isize - 1) * landlord_s;
+            if zero_crossing_end <= 5 {
+                bail!(
+                    "Landlord step size of {} is too large to fit {} steps below {} points",
+                    landlord_s,
+                    self.num_steps_to_non_landlord_turnover,
+                    turnover
+                );
+            }
             landlord_wins.push(LandlordWinningScoreSegment {
                 start: 5,
-                end: s,
+                end: zero_crossing_end,
                 landlord_delta: delta,
             });
             landlord_wins.push(LandlordWinningScoreSegment {
-                start: 5 - s,
+                start: 5 - landlord_s,
                 end: 5,
                 landlord_delta: delta + 1,
             });
             landlord_wins
         } else {
             vec![LandlordWinningScoreSegment {
-                start: (self.num_steps_to_non_landlord_turnover - 1) as isize * s,
-                end: self.num_steps_to_non_landlord_turnover as isize * s,
+                start: turnover - landlord_s,
+                end: turnover,
                 landlord_delta: 1,
             }]
         };
 
+        let deadzone_end = turnover + self.deadzone_size as isize * non_landlord_s;
         let mut landlord_loses = if self.deadzone_size == 0 {
             vec![]
         } else {
             vec![LandlordLosingScoreSegment {
-                start: self.num_steps_to_non_landlord_turnover as isize * s,
-                end: (self.num_steps_to_non_landlord_turnover + self.deadzone_size) as isize * s,
+                start: turnover,
+                end: deadzone_end,
                 non_landlord_delta: 0,
             }]
         };
         landlord_loses.push(LandlordLosingScoreSegment {
-            start: (self.num_steps_to_non_landlord_turnover + self.deadzone_size) as isize * s,
-            end: (self.num_steps_to_non_landlord_turnover + self.deadzone_size + 1) as isize * s,
+            start: deadzone_end,
+            end: deadzone_end + non_landlord_s,
             non_landlord_delta: 1,
         });
 
+        // Within the winning margin, the landlord team still defends, so the
+        // threshold moves up by the margin.
+        if self.win_margin > 0 {
+            let margin = self.win_margin as isize;
+            if margin % 5 != 0 {
+                bail!("Win margin must be a multiple of 5");
+            }
+            // The windows beyond the outermost ones are extrapolated from
+            // their sizes, so those are spelled out before resizing them.
+            if landlord_wins.len() == 1 {
+                landlord_wins.push(landlord_wins[0].propagate());
+            }
+            if landlord_loses.len() == 1 {
+                landlord_loses.push(landlord_loses[0].propagate());
+            }
+            if turnover + margin >= landlord_loses[0].end {
+                bail!(
+                    "Win margin must be less than {} points",
+                    landlord_loses[0].end - turnover
+                );
+            }
+            landlord_wins[0].end = turnover + margin;
+            landlord_loses[0].start = turnover + margin;
+        }
+
         MaterializedScoringParameters::new(
             landlord_wins.into_iter().rev(),
             landlord_loses,
@@ -246,13 +335,17 @@ impl MaterializedScoringParameters {
         Ok(gsp)
     }
 
+    /// The number of points at which control passes to the attacking team.
+    pub fn turnover_threshold(&self) -> Result<isize, Error> {
+        Ok(self
+            .landlord_wins
+            .last()
+            .ok_or_else(|| anyhow!("Landlord must be able to win"))?
+            .end)
+    }
+
     pub fn score(&self, non_landlords_points: isize) -> Result<PartialGameScoreResult, Error> {
-        let landlord_won = non_landlords_points
-            < self
-                .landlord_wins
-                .last()
-                .ok_or_else(|| anyhow!("Landlord must be able to win"))?
-                .end;
+        let landlord_won = non_landlords_points < self.turnover_threshold()?;
 
         if landlord_won {
             for s in PropagateMore::new(self.landlord_wins.iter().rev().copied()).take(50) {
@@ -283,15 +376,33 @@ impl MaterializedScoringParameters {
         current_score: isize,
     ) -> Result<(isize, PartialGameScoreResult), Error> {
         let gsr = self.score(current_score)?;
+        // The windows all start on multiples of 5, even if the score doesn't.
+        let base = current_score.div_euclid(5) * 5;
         for offset in 1..1000 {
-            let offset_gsr = self.score(current_score + offset * 5)?;
+            let offset_gsr = self.score(base + offset * 5)?;
             if gsr != offset_gsr {
-                return Ok((current_score + offset * 5, offset_gsr));
+                return Ok((base + offset * 5, offset_gsr));
             }
         }
         bail!("Failed to find next relevant score")
     }
 
+    /// Finds the lowest score which has the same result as `current_score`,
+    /// i.e. the threshold that was most recently crossed. The window around
+    /// zero extends into negative scores, but the attackers start from zero,
+    /// so it's cut off there unless they've already gone below it.
+    pub fn previous_relevant_score(&self, current_score: isize) -> Result<isize, Error> {
+        let gsr = self.score(current_score)?;
+        let base = current_score.div_euclid(5) * 5;
+        let floor = current_score.min(0);
+        for offset in 1..1000 {
+            if self.score(base - offset * 5)? != gsr {
+                return Ok((base - (offset - 1) * 5).max(floor));
+            }
+        }
+        bail!("Failed to find previous relevant score")
+    }
+
     pub fn explain(&self) -> Result<Vec<(isize, PartialGameScoreResult)>, Error> {
         let mut current_score = 0;
         let mut explanatory = vec![(0, self.score(current_score)?)];
@@ -407,8 +518,9 @@ pub fn compute_level_deltas(
     non_landlords_points: isize,
     smaller_landlord_team_size: bool,
 ) -> Result<GameScoreResult, Error> {
+    let m = gsp.materialize(decks)?;
     Ok(GameScoreResult::new(
-        gsp.materialize(decks)?.score(non_landlords_points)?,
+        m.score(non_landlords_points)?,
         gsp.bonus_level_policy,
         smaller_landlord_team_size,
     ))
@@ -431,12 +543,305 @@ pub fn next_threshold_reachable(
     Ok(non_landlords_points + remaining_points >= threshold)
 }
 
+/// Computes how many more points are needed to reach the next scoring
+/// threshold. The attacking team needs to collect that many more points, and
+/// `None` is returned if there aren't enough points left for them to do so.
+/// The defending team instead needs to capture enough of the remaining points
+/// to keep the attackers from getting there.
+///
+/// Note: does not account for kitty bonuses.
+pub fn points_needed_for_next_level(
+    gsp: &GameScoringParameters,
+    decks: &[Deck],
+    non_landlords_points: isize,
+    observed_points: isize,
+    is_defending: bool,
+) -> Result<Option<usize>, Error> {
+    let m = gsp.materialize(decks)?;
+    let threshold = m.next_relevant_score(non_landlords_points)?.0;
+    let remaining_points = m.total_points() - observed_points;
+    let gap = threshold - non_landlords_points;
+    if is_defending {
+        // Points are only ever captured in multiples of five, so the attackers
+        // are held off once they can get at most `gap - 5` more points.
+        Ok(Some((remaining_points - gap + 5).max(0) as usize))
+    } else if gap <= remaining_points {
+        Ok(Some(gap as usize))
+    } else {
+        Ok(None)
+    }
+}
+
+/// Computes how far (from 0 to 1) the attacking team is between the last
+/// scoring threshold that they crossed and the next one. If there aren't
+/// enough points left for them to reach the next threshold, no more progress
+/// can be made, and this is 0.
+///
+/// Note: does not account for kitty bonuses.
+pub fn progress_to_next_threshold(
+    gsp: &GameScoringParameters,
+    decks: &[Deck],
+    non_landlords_points: isize,
+    observed_points: isize,
+) -> Result<f32, Error> {
+    if !next_threshold_reachable(gsp, decks, non_landlords_points, observed_points)? {
+        return Ok(0.0);
+    }
+    let m = gsp.materialize(decks)?;
+    let next = m.next_relevant_score(non_landlords_points)?.0;
+    let previous = m.previous_relevant_score(non_landlords_points)?;
+    Ok((non_landlords_points - previous) as f32 / (next - previous) as f32)
+}
+
 #[cfg(test)]
 mod tests {
-    use super::{compute_level_deltas, BonusLevelPolicy, GameScoreResult, GameScoringParameters};
+    use super::{
+        compute_level_deltas, explain_level_deltas, points_needed_for_next_level,
+        progress_to_next_threshold, BonusLevelPolicy, GameScoreResult, GameScoringParameters,
+    };
 
     use crate::deck::Deck;
 
+    #[test]
+    fn test_all_relevant_scores() {
+        let decks = [Deck::default(), Deck::default()];
+        let gsp = GameScoringParameters::default();
+        let scores = gsp.all_relevant_scores(&decks).unwrap();
+        assert!(scores.len() > 2);
+        assert_eq!(scores[0].0, 0);
+        assert!(scores.windows(2).all(|w| w[0].0 < w[1].0));
+
+        for (idx, (pts, result)) in scores.iter().enumerate() {
+            assert_eq!(
+                compute_level_deltas(&gsp, &decks, *pts, false).unwrap(),
+                *result
+            );
+            if idx > 0 {
+                // Just below the boundary, the previous outcome still applies.
+                assert_eq!(
+                    compute_level_deltas(&gsp, &decks, *pts - 5, false).unwrap(),
+                    scores[idx - 1].1
+                );
+                assert_ne!(scores[idx - 1].1, *result);
+            }
+        }
+    }
+
+    #[test]
+    fn test_points_needed_for_next_level() {
+        let decks = [Deck::default(), Deck::default()];
+        let gsp = GameScoringParameters::default();
+
+        // The attackers have 60 points, and need 80 to take over.
+        assert_eq!(
+            points_needed_for_next_level(&gsp, &decks, 60, 100, false).unwrap(),
+            Some(20)
+        );
+        // Of the 100 points left, the defenders need to capture 85 so that
+        // the attackers can't get 20 more.
+        assert_eq!(
+            points_needed_for_next_level(&gsp, &decks, 60, 100, true).unwrap(),
+            Some(85)
+        );
+        // With only 10 points left, the attackers can't get there.
+        assert_eq!(
+            points_needed_for_next_level(&gsp, &decks, 60, 190, false).unwrap(),
+            None
+        );
+        assert_eq!(
+            points_needed_for_next_level(&gsp, &decks, 60, 190, true).unwrap(),
+            Some(0)
+        );
+    }
+
+    #[test]
+    fn test_progress_to_next_threshold() {
+        let decks = [Deck::default(), Deck::default()];
+        let gsp = GameScoringParameters::default();
+        let progress =
+            |points, observed| progress_to_next_threshold(&gsp, &decks, points, observed).unwrap();
+
+        // With two decks, the thresholds are 40 points apart, and the
+        // attackers are halfway from 40 to 80.
+        assert_eq!(progress(60, 100), 0.5);
+        assert_eq!(progress(40, 100), 0.0);
+        assert_eq!(progress(70, 100), 0.75);
+        assert_eq!(progress(130, 150), 0.25);
+        // With only 10 points left, the attackers can't get to 80.
+        assert_eq!(progress(60, 190), 0.0);
+        // Once all of the points are captured, the game is over.
+        assert_eq!(progress(75, 200), 0.0);
+
+        // The window around zero extends below it, but the attackers start
+        // at zero.
+        assert_eq!(progress(0, 0), 0.0);
+        assert_eq!(progress(0, 5), 0.0);
+        assert_eq!(progress(-10, 20), 0.0);
+
+        // Scores which aren't multiples of 5, e.g. after a penalty, fall in the
+        // same windows as everything else.
+        assert_eq!(progress(62, 100), 0.55);
+        assert_eq!(
+            points_needed_for_next_level(&gsp, &decks, 62, 100, false).unwrap(),
+            Some(18)
+        );
+        let m = gsp.materialize(&decks).unwrap();
+        assert_eq!(m.previous_relevant_score(62).unwrap(), 40);
+        assert_eq!(m.next_relevant_score(62).unwrap().0, 80);
+        assert_eq!(m.previous_relevant_score(-3).unwrap(), -3);
+    }
+
+    #[test]
+    fn test_asymmetric_step_sizes() {
+        let decks = [Deck::default(), Deck::default()];
+        let symmetric = GameScoringParameters::default();
+        let asymmetric = GameScoringParameters {
+            landlord_step_size_per_deck: Some(15),
+            non_landlord_step_size_per_deck: Some(30),
+            ..Default::default()
+        };
+        let deltas = |gsp: &GameScoringParameters, pts: isize| {
+            let result = compute_level_deltas(gsp, &decks, pts, false).unwrap();
+            (
+                result.landlord_won,
+                result.landlord_delta,
+                result.non_landlord_delta,
+            )
+        };
+
+        // Overriding with the regular step size changes nothing.
+        let overridden = GameScoringParameters {
+            landlord_step_size_per_deck: Some(20),
+            non_landlord_step_size_per_deck: Some(20),
+            ..Default::default()
+        };
+        assert_eq!(
+            explain_level_deltas(&overridden, &decks, false).unwrap(),
+            explain_level_deltas(&symmetric, &decks, false).unwrap()
+        );
+
+        // The turnover threshold is the same on both sides...
+        assert_eq!(deltas(&symmetric, 75), (true, 1, 0));
+        assert_eq!(deltas(&asymmetric, 75), (true, 1, 0));
+        assert_eq!(deltas(&symmetric, 80), (false, 0, 0));
+        assert_eq!(deltas(&asymmetric, 80), (false, 0, 0));
+        // ... but the landlord team's steps are 30 points instead of 40 ...
+        assert_eq!(deltas(&symmetric, 45), (true, 1, 0));
+        assert_eq!(deltas(&asymmetric, 45), (true, 2, 0));
+        assert_eq!(deltas(&symmetric, -30), (true, 3, 0));
+        assert_eq!(deltas(&asymmetric, -30), (true, 4, 0));
+        // ... and the attacking team's steps are 60 points.
+        assert_eq!(deltas(&symmetric, 130), (false, 0, 1));
+        assert_eq!(deltas(&asymmetric, 130), (false, 0, 0));
+        assert_eq!(deltas(&symmetric, 200), (false, 0, 3));
+        assert_eq!(deltas(&asymmetric, 200), (false, 0, 2));
+
+        let thresholds = explain_level_deltas(&asymmetric, &decks, false)
+            .unwrap()
+            .into_iter()
+            .map(|(pts, _)| pts)
+            .collect::<Vec<_>>();
+        assert_eq!(&thresholds[..5], &[0, 5, 50, 80, 140]);
+        assert_eq!(asymmetric.landlord_step_size(&decks).unwrap(), 30);
+        assert_eq!(asymmetric.non_landlord_step_size(&decks).unwrap(), 60);
+
+        // The landlord team's steps have to leave room for the window which
+        // includes zero.
+        let step_sizes = |landlord_step_size_per_deck| GameScoringParameters {
+            landlord_step_size_per_deck: Some(landlord_step_size_per_deck),
+            ..Default::default()
+        };
+        assert!(step_sizes(35).materialize(&decks).is_ok());
+        for too_large in &[40, 45] {
+            assert_eq!(
+                step_sizes(*too_large)
+                    .materialize(&decks)
+                    .unwrap_err()
+                    .to_string(),
+                format!(
+                    "Landlord step size of {} is too large to fit 2 steps below 80 points",
+                    too_large * 2
+                )
+            );
+        }
+    }
+
+    #[test]
+    fn test_win_margin() {
+        let decks = [Deck::default(), Deck::default()];
+        let gsp = GameScoringParameters {
+            win_margin: 10,
+            ..Default::default()
+        };
+        let defended = GameScoreResult {
+            non_landlord_delta: 0,
+            landlord_delta: 1,
+            landlord_won: true,
+            landlord_bonus: false,
+        };
+        // 80 points would normally turn over control, but falls within the
+        // margin.
+        assert!(
+            !compute_level_deltas(&GameScoringParameters::default(), &decks, 80, false)
+                .unwrap()
+                .landlord_won
+        );
+        assert_eq!(
+            compute_level_deltas(&gsp, &decks, 80, false).unwrap(),
+            defended
+        );
+        assert_eq!(
+            compute_level_deltas(&gsp, &decks, 85, false).unwrap(),
+            defended
+        );
+        assert_eq!(
+            compute_level_deltas(&gsp, &decks, 90, false).unwrap(),
+            compute_level_deltas(&GameScoringParameters::default(), &decks, 90, false).unwrap()
+        );
+
+        // Everything else which looks at the thresholds agrees.
+        let m = gsp.materialize(&decks).unwrap();
+        assert_eq!(m.turnover_threshold().unwrap(), 90);
+        assert!(gsp
+            .all_relevant_scores(&decks)
+            .unwrap()
+            .iter()
+            .any(|(pts, result)| *pts == 90 && !result.landlord_won));
+        assert_eq!(
+            points_needed_for_next_level(&gsp, &decks, 80, 100, false).unwrap(),
+            Some(10)
+        );
+        assert_eq!(
+            progress_to_next_threshold(&gsp, &decks, 80, 100).unwrap(),
+            0.8
+        );
+        // Further thresholds are where they'd be without a margin.
+        assert_eq!(m.next_relevant_score(90).unwrap().0, 120);
+        assert_eq!(m.previous_relevant_score(35).unwrap(), 5);
+
+        for (win_margin, truncate_zero_crossing_window, deadzone_size) in
+            &[(10, false, 0), (10, true, 0), (10, false, 1)]
+        {
+            let gsp = GameScoringParameters {
+                win_margin: *win_margin,
+                truncate_zero_crossing_window: *truncate_zero_crossing_window,
+                deadzone_size: *deadzone_size,
+                ..Default::default()
+            };
+            let m = gsp.materialize(&decks).unwrap();
+            assert_eq!(m.next_relevant_score(130).unwrap().0, 160);
+            assert!(m.score(-40).unwrap().landlord_won);
+        }
+
+        for win_margin in &[7, 40, 50] {
+            let gsp = GameScoringParameters {
+                win_margin: *win_margin,
+                ..Default::default()
+            };
+            assert!(gsp.materialize(&decks).is_err());
+        }
+    }
+
     #[test]
     fn test_level_deltas() {
         let decks = [Deck::default(), Deck::default()];
@@ -5,13 +5,15 @@ use serde::{Deserialize, Serialize};
 use slog_derive::KV;
 use url::Url;
 
-use crate::bidding::{BidPolicy, BidReinforcementPolicy, BidTakebackPolicy, JokerBidPolicy};
-use crate::deck::Deck;
+use crate::bidding::{Bid, BidPolicy, BidReinforcementPolicy, BidTakebackPolicy, JokerBidPolicy};
+use crate::deck::{Deck, MAX_JOKERS_PER_DECK};
 use crate::message::MessageVariant;
 use crate::player::Player;
 use crate::scoring::GameScoringParameters;
-use crate::trick::{ThrowEvaluationPolicy, TractorRequirements, TrickDrawPolicy};
-use crate::types::{Card, Number, PlayerID};
+use crate::trick::{
+    ThrowEvaluationPolicy, ThrowTieBreakPolicy, TractorRequirements, TrickDrawPolicy,
+};
+use crate::types::{Card, Number, PermanentTrumps, PlayerID, Trump};
 
 #[macro_export]
 macro_rules! impl_slog_value {
@@ -105,10 +107,42 @@ impl Default for ThrowPenalty {
 
 impl_slog_value!(ThrowPenalty);
 
+/// How to handle players who repeatedly try to play out of turn.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Serialize, Deserialize)]
+pub enum OutOfTurnPolicy {
+    Ignore,
+    /// Warns a player for their first `warnings` attempts, and gives the
+    /// other team `points` for each attempt after that.
+    WarnThenPenalize {
+        warnings: usize,
+        points: usize,
+    },
+}
+
+impl Default for OutOfTurnPolicy {
+    fn default() -> Self {
+        OutOfTurnPolicy::Ignore
+    }
+}
+
+impl_slog_value!(OutOfTurnPolicy);
+
 #[derive(Debug, Copy, Clone, Eq, PartialEq, Serialize, Deserialize)]
 pub enum KittyPenalty {
     Times,
     Power,
+    /// Points in the bottom are only counted once.
+    None,
+    /// Multiplies the points in the bottom by a fixed amount, regardless of
+    /// the last trick.
+    Fixed(usize),
+    /// Multiplies the points in the bottom by a fixed amount depending on
+    /// whether the last trick was led with singles, tuples, or a tractor.
+    ByUnitType {
+        single: usize,
+        pair: usize,
+        tractor: usize,
+    },
 }
 
 impl Default for KittyPenalty {
@@ -124,6 +158,11 @@ pub enum AdvancementPolicy {
     Unrestricted,
     FullyUnrestricted,
     DefendPoints,
+    /// Like `DefendPoints`, but players stop advancing entirely once they
+    /// reach `ceiling`.
+    DefendPointsThenFreeze {
+        ceiling: Number,
+    },
 }
 
 impl Default for AdvancementPolicy {
@@ -164,6 +203,43 @@ impl Default for MultipleJoinPolicy {
 
 impl_slog_value!(MultipleJoinPolicy);
 
+/// Which team gets the points that a friend captured before they joined the
+/// landlord's team.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Serialize, Deserialize)]
+pub enum FriendPointTransferPolicy {
+    /// The points move to the landlord's team along with the friend.
+    MoveToLandlordTeam,
+    /// The points stay with the team which the friend was on when they
+    /// captured them.
+    KeepWithCaptor,
+}
+
+impl Default for FriendPointTransferPolicy {
+    fn default() -> Self {
+        FriendPointTransferPolicy::MoveToLandlordTeam
+    }
+}
+
+impl_slog_value!(FriendPointTransferPolicy);
+
+/// Whether the kitty is shown in chat at the end of the game.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Serialize, Deserialize)]
+pub enum KittyRevealPolicy {
+    Never,
+    Always,
+    /// Only reveal the kitty if it had point cards which the attacking team
+    /// won in the last trick.
+    OnlyIfContestedPoints,
+}
+
+impl Default for KittyRevealPolicy {
+    fn default() -> Self {
+        KittyRevealPolicy::Never
+    }
+}
+
+impl_slog_value!(KittyRevealPolicy);
+
 #[derive(Debug, Copy, Clone, Eq, PartialEq, Serialize, Deserialize)]
 pub enum FirstLandlordSelectionPolicy {
     ByWinningBid,
@@ -178,6 +254,21 @@ impl Default for FirstLandlordSelectionPolicy {
 
 impl_slog_value!(FirstLandlordSelectionPolicy);
 
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Serialize, Deserialize)]
+pub enum FirstLeadPolicy {
+    Landlord,
+    LeftOfLandlord,
+    BidWinner,
+}
+
+impl Default for FirstLeadPolicy {
+    fn default() -> Self {
+        FirstLeadPolicy::Landlord
+    }
+}
+
+impl_slog_value!(FirstLeadPolicy);
+
 #[derive(Debug, Copy, Clone, Eq, PartialEq, Serialize, Deserialize)]
 pub enum KittyBidPolicy {
     FirstCard,
@@ -192,10 +283,32 @@ impl Default for KittyBidPolicy {
 
 impl_slog_value!(KittyBidPolicy);
 
+/// Who gets the points in the kitty when the landlord's team wins the last
+/// trick.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Serialize, Deserialize)]
+pub enum KittyOnLossPolicy {
+    /// The kitty always goes to the winner of the last trick.
+    LastTrickWinner,
+    /// If the landlord's team loses the game anyway, the attackers also get
+    /// the points in the kitty.
+    AttackersOnLoss,
+}
+
+impl Default for KittyOnLossPolicy {
+    fn default() -> Self {
+        KittyOnLossPolicy::LastTrickWinner
+    }
+}
+
+impl_slog_value!(KittyOnLossPolicy);
+
 #[derive(Debug, Copy, Clone, Eq, PartialEq, Serialize, Deserialize)]
 pub enum PlayTakebackPolicy {
     AllowPlayTakeback,
     NoPlayTakeback,
+    /// Plays can only be taken back once every opponent who still has cards
+    /// has approved.
+    RequiresApproval,
 }
 
 impl Default for PlayTakebackPolicy {
@@ -220,6 +333,58 @@ impl Default for KittyTheftPolicy {
 
 impl_slog_value!(KittyTheftPolicy);
 
+/// What the bottom cards contain when they're stolen by a new exchanger.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Serialize, Deserialize)]
+pub enum KittyTheftRevealPolicy {
+    /// The new exchanger picks up the cards buried by the previous exchanger.
+    RevealPreviousDiscards,
+    /// The previous exchanger's exchange is undone, and the new exchanger
+    /// picks up the original bottom cards.
+    ResetToOriginalKitty,
+}
+
+impl Default for KittyTheftRevealPolicy {
+    fn default() -> Self {
+        KittyTheftRevealPolicy::RevealPreviousDiscards
+    }
+}
+
+impl_slog_value!(KittyTheftRevealPolicy);
+
+/// How strong a bid must be to steal the bottom cards from the current
+/// exchanger.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Serialize, Deserialize)]
+pub enum KittyTheftBidPolicy {
+    /// The new bid must overturn the winning bid.
+    MustExceed,
+    /// A bid of the same strength as the winning bid is also enough, so later
+    /// bidders win ties.
+    MustMatch,
+}
+
+impl Default for KittyTheftBidPolicy {
+    fn default() -> Self {
+        KittyTheftBidPolicy::MustExceed
+    }
+}
+
+impl_slog_value!(KittyTheftBidPolicy);
+
+/// Decides the trump suit when the winning bid is a joker.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Serialize, Deserialize)]
+pub enum JokerBidSuitPolicy {
+    ForceNoTrump,
+    LandlordChoosesSuit,
+}
+
+impl Default for JokerBidSuitPolicy {
+    fn default() -> Self {
+        JokerBidSuitPolicy::ForceNoTrump
+    }
+}
+
+impl_slog_value!(JokerBidSuitPolicy);
+
 #[derive(Debug, Copy, Clone, Eq, PartialEq, Serialize, Deserialize)]
 pub enum GameShadowingPolicy {
     AllowMultipleSessions,
@@ -248,6 +413,162 @@ impl Default for GameStartPolicy {
 
 impl_slog_value!(GameStartPolicy);
 
+/// What happens when the landlord takes longer than the move deadline to
+/// finish exchanging cards, e.g. because they have disconnected.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Serialize, Deserialize)]
+pub enum AbsentLandlordPolicy {
+    /// Wait for the landlord to come back.
+    Wait,
+    /// Finish the exchange on the landlord's behalf, keeping the cards which
+    /// they have already put in the bottom.
+    AutoFinalizeExchange,
+}
+
+impl Default for AbsentLandlordPolicy {
+    fn default() -> Self {
+        AbsentLandlordPolicy::Wait
+    }
+}
+
+impl_slog_value!(AbsentLandlordPolicy);
+
+/// Whether resetting a game which is in progress counts towards the number of
+/// abandoned games.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Serialize, Deserialize)]
+pub enum AbandonedGamePolicy {
+    IgnoreResets,
+    CountResetsAsAbandoned,
+}
+
+impl Default for AbandonedGamePolicy {
+    fn default() -> Self {
+        AbandonedGamePolicy::IgnoreResets
+    }
+}
+
+impl_slog_value!(AbandonedGamePolicy);
+
+/// How to handle cards that can't be evenly dealt to the players, when the
+/// kitty size isn't explicitly set.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Serialize, Deserialize)]
+pub enum RemainderPolicy {
+    /// Add the remainder to the kitty.
+    GrowKitty,
+    /// Remove the remainder from the deck, leaving a smaller kitty.
+    RemoveCards,
+    /// Use only the partial last round of drawing as the kitty.
+    SmallerLastRound,
+}
+
+impl Default for RemainderPolicy {
+    fn default() -> Self {
+        RemainderPolicy::GrowKitty
+    }
+}
+
+impl_slog_value!(RemainderPolicy);
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Serialize, Deserialize)]
+pub enum PausePolicy {
+    NoPausing,
+    AllowPausing,
+}
+
+impl Default for PausePolicy {
+    fn default() -> Self {
+        PausePolicy::NoPausing
+    }
+}
+
+impl_slog_value!(PausePolicy);
+
+/// The number of finished games to remember in `PropagatedState::past_games`.
+pub const MAX_PAST_GAMES: usize = 10;
+
+/// How many ranks apart teammates' levels may be before a warning is shown
+/// when starting a game.
+pub const MAX_TEAM_LEVEL_SPREAD: u32 = 3;
+
+/// The bid that won a finished game, and the trump that resulted from it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PastGame {
+    pub winning_bid: Bid,
+    pub trump: Trump,
+}
+
+/// A player whose fully-drawn hand has fewer than `point_threshold` points
+/// and at most `trump_threshold` jokers and cards of the current level may
+/// declare it weak, forcing a redeal.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WeakHandRedeal {
+    pub point_threshold: usize,
+    pub trump_threshold: usize,
+}
+
+/// Which cards observers who aren't playing can see during the play phase.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Serialize, Deserialize)]
+pub enum SpectatorCardVisibility {
+    /// Observers never see other players' hands.
+    Hidden,
+    /// Observers see the remaining hands once the game is over.
+    AfterGameEnd,
+    /// Observers see every player's hand as the game is played.
+    AlwaysVisible,
+}
+
+impl Default for SpectatorCardVisibility {
+    fn default() -> Self {
+        SpectatorCardVisibility::AfterGameEnd
+    }
+}
+
+impl_slog_value!(SpectatorCardVisibility);
+
+/// What happens to a seat whose player is kicked partway through a game.
+#[derive(Debug, Copy, Clone, Default, Eq, PartialEq, Serialize, Deserialize)]
+pub enum VacatedSeatPolicy {
+    /// The seat stays empty until the game is over, so nobody else gets to see
+    /// the departed player's hand.
+    #[default]
+    LeaveVacant,
+    /// An observer can be promoted into the seat, taking over the departed
+    /// player's hand.
+    AllowObserverTakeover,
+}
+
+impl_slog_value!(VacatedSeatPolicy);
+
+/// Optional twists on the rules for a single game, consulted at the points in
+/// the game where they apply.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Serialize, Deserialize)]
+pub enum GameModifier {
+    /// The attacking team's points are doubled when the game is scored.
+    DoublePoints,
+    /// Every lead must be a single trick unit.
+    NoThrows,
+    /// Points in the kitty are counted once, regardless of the kitty penalty.
+    NoKittyMultiplier,
+}
+
+impl GameModifier {
+    pub fn description(self) -> &'static str {
+        match self {
+            GameModifier::DoublePoints => "double points",
+            GameModifier::NoThrows => "no throws",
+            GameModifier::NoKittyMultiplier => "no kitty multiplier",
+        }
+    }
+}
+
+/// Experimental rules which a room has to opt into before they can be used.
+/// Flags which aren't listed here are rejected, so that a typo doesn't
+/// silently do nothing.
+pub const EXPERIMENTAL_FEATURES: &[&str] = &[
+    // The landlord keeps the bottom cards as they were dealt, without
+    // exchanging any of them.
+    "sealed_kitty",
+];
+
 #[derive(Debug, Clone, Default, Serialize, Deserialize, KV)]
 pub struct PropagatedState {
     #[slog(skip)]
@@ -256,11 +577,27 @@ pub struct PropagatedState {
     pub(crate) observers: Vec<Player>,
     #[slog(skip)]
     pub(crate) landlord: Option<PlayerID>,
+    /// A player or observer who may correct mistakes during play.
+    #[slog(skip)]
+    #[serde(default)]
+    pub(crate) director: Option<PlayerID>,
+    /// Players whose turns are played automatically by `bot::next_action`.
+    #[slog(skip)]
+    #[serde(default)]
+    pub(crate) bots: Vec<PlayerID>,
     #[slog(skip)]
-    max_player_id: usize,
+    pub(crate) max_player_id: usize,
     #[slog(skip)]
     #[serde(default)]
     pub(crate) num_games_finished: usize,
+    #[slog(skip)]
+    #[serde(default)]
+    pub(crate) num_games_abandoned: usize,
+    /// The players who reached `max_metalevel`, ending the match. No more
+    /// games can be started until a new match begins.
+    #[slog(skip)]
+    #[serde(default)]
+    pub(crate) match_winners: Vec<PlayerID>,
 
     pub(crate) game_mode: GameModeSettings,
     #[serde(default)]
@@ -270,6 +607,8 @@ pub struct PropagatedState {
     pub(crate) friend_selection_policy: FriendSelectionPolicy,
     #[serde(default)]
     pub(crate) multiple_join_policy: MultipleJoinPolicy,
+    #[serde(default)]
+    pub(crate) friend_point_transfer_policy: FriendPointTransferPolicy,
     pub(crate) num_decks: Option<usize>,
     // TODO: Find a way to log this properly.
     #[slog(skip)]
@@ -283,28 +622,46 @@ pub struct PropagatedState {
     #[serde(default)]
     pub(crate) kitty_penalty: KittyPenalty,
     #[serde(default)]
+    pub(crate) kitty_on_loss_policy: KittyOnLossPolicy,
+    #[serde(default)]
     pub(crate) throw_penalty: ThrowPenalty,
     #[serde(default)]
+    pub(crate) out_of_turn_policy: OutOfTurnPolicy,
+    #[serde(default)]
     pub(crate) hide_played_cards: bool,
     #[serde(default)]
     pub(crate) kitty_bid_policy: KittyBidPolicy,
     #[serde(default)]
     pub(crate) kitty_theft_policy: KittyTheftPolicy,
     #[serde(default)]
+    pub(crate) kitty_theft_reveal_policy: KittyTheftRevealPolicy,
+    #[serde(default)]
+    pub(crate) kitty_theft_bid_policy: KittyTheftBidPolicy,
+    #[serde(default)]
     pub(crate) trick_draw_policy: TrickDrawPolicy,
     #[serde(default)]
     pub(crate) throw_evaluation_policy: ThrowEvaluationPolicy,
     #[serde(default)]
+    pub(crate) throw_tie_break_policy: ThrowTieBreakPolicy,
+    #[serde(default)]
     pub(crate) first_landlord_selection_policy: FirstLandlordSelectionPolicy,
     #[serde(default)]
+    pub(crate) first_lead_policy: FirstLeadPolicy,
+    #[serde(default)]
     pub(crate) bid_policy: BidPolicy,
     #[serde(default)]
     pub(crate) bid_reinforcement_policy: BidReinforcementPolicy,
     #[serde(default)]
     pub(crate) joker_bid_policy: JokerBidPolicy,
     #[serde(default)]
+    pub(crate) joker_bid_suit_policy: JokerBidSuitPolicy,
+    /// Deprecated in favor of `kitty_reveal_policy`, but still respected if
+    /// it is set and the policy isn't.
+    #[serde(default)]
     pub(crate) should_reveal_kitty_at_end_of_game: bool,
     #[serde(default)]
+    pub(crate) kitty_reveal_policy: KittyRevealPolicy,
+    #[serde(default)]
     pub(crate) play_takeback_policy: PlayTakebackPolicy,
     #[serde(default)]
     pub(crate) bid_takeback_policy: BidTakebackPolicy,
@@ -313,11 +670,84 @@ pub struct PropagatedState {
     #[serde(default)]
     pub(crate) game_start_policy: GameStartPolicy,
     #[serde(default)]
+    pub(crate) absent_landlord_policy: AbsentLandlordPolicy,
+    #[serde(default)]
+    pub(crate) abandoned_game_policy: AbandonedGamePolicy,
+    #[serde(default)]
     pub(crate) game_scoring_parameters: GameScoringParameters,
     #[serde(default)]
     pub(crate) hide_throw_halting_player: bool,
+    /// Whether players who can't see the bottom cards are told how many of
+    /// them are point cards.
+    #[serde(default)]
+    pub(crate) reveal_kitty_point_count: bool,
     #[serde(default)]
     pub(crate) tractor_requirements: TractorRequirements,
+    #[serde(default)]
+    pub(crate) blind_bid_bonus: Option<usize>,
+    /// The metalevel which ends the match when a player reaches it by
+    /// advancing past the highest rank.
+    #[serde(default)]
+    pub(crate) max_metalevel: Option<usize>,
+    #[serde(default)]
+    pub(crate) remainder_policy: RemainderPolicy,
+    /// Sets of cards which earn the player who captures all of them in a
+    /// single trick the given number of bonus levels.
+    #[slog(skip)]
+    #[serde(default)]
+    pub(crate) capture_bonus_rules: Vec<(Vec<Card>, isize)>,
+    #[serde(default)]
+    pub(crate) pause_policy: PausePolicy,
+    #[serde(default)]
+    pub(crate) paused: bool,
+    /// The most recently finished games, oldest first.
+    #[slog(skip)]
+    #[serde(default)]
+    pub(crate) past_games: Vec<PastGame>,
+    #[slog(skip)]
+    #[serde(default)]
+    pub(crate) weak_hand_redeal: Option<WeakHandRedeal>,
+    #[serde(default)]
+    pub(crate) quick_rematch: bool,
+    #[serde(default)]
+    pub(crate) skip_exchange: bool,
+    /// Bounds on the number of friends when finding friends, which apply
+    /// regardless of how many players there are.
+    #[serde(default)]
+    pub(crate) min_friends: Option<usize>,
+    #[serde(default)]
+    pub(crate) max_friends: Option<usize>,
+    #[serde(default)]
+    pub(crate) spectator_card_visibility: SpectatorCardVisibility,
+    #[serde(default)]
+    pub(crate) vacated_seat_policy: VacatedSeatPolicy,
+    /// Seats whose players were kicked during the current game. They're
+    /// removed when the game ends.
+    #[slog(skip)]
+    #[serde(default)]
+    pub(crate) vacated_seats: Vec<PlayerID>,
+    /// How long a player may take to act before they are considered to have
+    /// timed out.
+    #[serde(default)]
+    pub(crate) move_deadline_secs: Option<u64>,
+    /// Modifiers applied to the next game only; they're cleared once it
+    /// finishes.
+    #[slog(skip)]
+    #[serde(default)]
+    pub(crate) game_modifiers: Vec<GameModifier>,
+    /// Numbers which are always trump, alongside the landlord's level.
+    #[slog(skip)]
+    #[serde(default)]
+    pub(crate) permanent_trump_numbers: Vec<Number>,
+    /// Cards which are removed from every deck before the game starts.
+    #[slog(skip)]
+    #[serde(default)]
+    pub(crate) excluded_cards: Vec<Card>,
+    /// Experimental rules which have been opted into, from
+    /// `EXPERIMENTAL_FEATURES`.
+    #[slog(skip)]
+    #[serde(default, deserialize_with = "deserialize_experimental_features")]
+    pub(crate) experimental_features: HashSet<String>,
 }
 
 impl PropagatedState {
@@ -325,6 +755,182 @@ impl PropagatedState {
         &self.players
     }
 
+    /// Lists the settings which differ from the defaults, as pairs of the
+    /// setting name and its value. This leaves out the players and anything
+    /// else which tracks the progress of the room rather than its rules.
+    pub fn diff_from_default(&self) -> Vec<(String, String)> {
+        macro_rules! diff_fields {
+            ($default:ident, $($field:ident),* $(,)?) => {{
+                let mut diff = vec![];
+                $(
+                    if self.$field != $default.$field {
+                        diff.push((stringify!($field).to_string(), format!("{:?}", self.$field)));
+                    }
+                )*
+                diff
+            }};
+        }
+
+        let default = PropagatedState::default();
+        diff_fields!(
+            default,
+            game_mode,
+            hide_landlord_points,
+            kitty_size,
+            friend_selection_policy,
+            multiple_join_policy,
+            friend_point_transfer_policy,
+            num_decks,
+            special_decks,
+            landlord_emoji,
+            chat_link,
+            advancement_policy,
+            kitty_penalty,
+            kitty_on_loss_policy,
+            throw_penalty,
+            out_of_turn_policy,
+            hide_played_cards,
+            kitty_bid_policy,
+            kitty_theft_policy,
+            kitty_theft_reveal_policy,
+            kitty_theft_bid_policy,
+            trick_draw_policy,
+            throw_evaluation_policy,
+            throw_tie_break_policy,
+            first_landlord_selection_policy,
+            first_lead_policy,
+            bid_policy,
+            bid_reinforcement_policy,
+            joker_bid_policy,
+            joker_bid_suit_policy,
+            should_reveal_kitty_at_end_of_game,
+            kitty_reveal_policy,
+            play_takeback_policy,
+            bid_takeback_policy,
+            game_shadowing_policy,
+            game_start_policy,
+            absent_landlord_policy,
+            abandoned_game_policy,
+            game_scoring_parameters,
+            hide_throw_halting_player,
+            reveal_kitty_point_count,
+            tractor_requirements,
+            blind_bid_bonus,
+            max_metalevel,
+            remainder_policy,
+            capture_bonus_rules,
+            pause_policy,
+            weak_hand_redeal,
+            quick_rematch,
+            skip_exchange,
+            min_friends,
+            max_friends,
+            spectator_card_visibility,
+            vacated_seat_policy,
+            move_deadline_secs,
+            game_modifiers,
+            permanent_trump_numbers,
+            excluded_cards,
+            experimental_features,
+        )
+    }
+
+    /// In Tractor, partners sit across from one another, so the teams are the
+    /// players in even and odd seats. Returns each team along with the lowest
+    /// and highest level held by its members.
+    pub fn team_level_spread(&self) -> Vec<(usize, Number, Number)> {
+        if self.game_mode != GameModeSettings::Tractor {
+            return vec![];
+        }
+        (0..2)
+            .filter_map(|team| {
+                let levels = self
+                    .players
+                    .iter()
+                    .enumerate()
+                    .filter(|(idx, _)| idx % 2 == team)
+                    .map(|(_, p)| p.rank());
+                Some((team, levels.clone().min()?, levels.max()?))
+            })
+            .collect()
+    }
+
+    pub fn team_level_spread_warnings(&self) -> Vec<MessageVariant> {
+        self.team_level_spread()
+            .into_iter()
+            .filter(|(_, min_level, max_level)| {
+                *max_level as u32 - *min_level as u32 > MAX_TEAM_LEVEL_SPREAD
+            })
+            .map(
+                |(team, min_level, max_level)| MessageVariant::TeamLevelSpread {
+                    team,
+                    min_level,
+                    max_level,
+                },
+            )
+            .collect()
+    }
+
+    pub fn past_games(&self) -> &[PastGame] {
+        &self.past_games
+    }
+
+    pub(crate) fn record_past_game(&mut self, game: PastGame) {
+        self.past_games.push(game);
+        if self.past_games.len() > MAX_PAST_GAMES {
+            let excess = self.past_games.len() - MAX_PAST_GAMES;
+            self.past_games.drain(..excess);
+        }
+    }
+
+    pub fn num_games_finished(&self) -> usize {
+        self.num_games_finished
+    }
+
+    pub fn num_games_abandoned(&self) -> usize {
+        self.num_games_abandoned
+    }
+
+    pub fn match_winners(&self) -> &[PlayerID] {
+        &self.match_winners
+    }
+
+    /// Returns every player to the lowest rank, so that another match can be
+    /// played once the previous one has been won.
+    pub fn start_new_match(&mut self) -> Result<Vec<MessageVariant>, Error> {
+        if self.match_winners.is_empty() {
+            bail!("nobody has won the match yet")
+        }
+        for player in &mut self.players {
+            player.level = Number::Two;
+            player.metalevel = 1;
+        }
+        self.match_winners.clear();
+        Ok(vec![MessageVariant::NewMatchStarted])
+    }
+
+    /// Counts a game which was reset before it finished, if the room tracks
+    /// abandoned games.
+    pub(crate) fn record_reset(&mut self) {
+        if self.abandoned_game_policy == AbandonedGamePolicy::CountResetsAsAbandoned {
+            self.num_games_abandoned += 1;
+        }
+    }
+
+    pub(crate) fn redact_reconnect_tokens(&mut self) {
+        for p in self.players.iter_mut().chain(self.observers.iter_mut()) {
+            p.reconnect_token.clear();
+        }
+    }
+
+    pub(crate) fn redact_reconnect_tokens_except(&mut self, id: PlayerID) {
+        for p in self.players.iter_mut().chain(self.observers.iter_mut()) {
+            if p.id != id {
+                p.reconnect_token.clear();
+            }
+        }
+    }
+
     pub fn observers(&self) -> &[Player] {
         &self.observers
     }
@@ -342,6 +948,10 @@ impl PropagatedState {
     }
 
     pub fn decks(&self) -> Result<Vec<Deck>, Error> {
+        self.decks_excluding(&self.excluded_cards)
+    }
+
+    fn decks_excluding(&self, excluded: &[Card]) -> Result<Vec<Deck>, Error> {
         let mut decks = self.special_decks.clone();
         let num_decks = self.num_decks();
         if decks.len() > num_decks {
@@ -351,13 +961,19 @@ impl PropagatedState {
         while decks.len() < num_decks {
             decks.push(Deck::default());
         }
-        Ok(decks)
+        Ok(decks
+            .into_iter()
+            .map(|d| d.with_excluded(excluded))
+            .collect())
     }
 
     pub fn set_game_mode(
         &mut self,
         game_mode: GameModeSettings,
     ) -> Result<Vec<MessageVariant>, Error> {
+        if self.skip_exchange && game_mode != GameModeSettings::Tractor {
+            bail!("friends are picked during the exchange, which is being skipped")
+        }
         self.game_mode = game_mode;
         Ok(vec![MessageVariant::GameModeSet { game_mode }])
     }
@@ -381,6 +997,28 @@ impl PropagatedState {
         Ok(msgs)
     }
 
+    /// Adds a player whose turns are taken by `bot::next_action`. Bots are
+    /// named by the server, so nobody can pick a name which clashes with one.
+    pub fn add_bot(&mut self) -> Result<(PlayerID, Vec<MessageVariant>), Error> {
+        let name = (1..)
+            .map(|n| format!("Bot {}", n))
+            .find(|name| {
+                !self
+                    .players
+                    .iter()
+                    .chain(self.observers.iter())
+                    .any(|p| p.name == *name)
+            })
+            .unwrap();
+        let (id, msgs) = self.add_player(name)?;
+        self.bots.push(id);
+        Ok((id, msgs))
+    }
+
+    pub fn is_bot(&self, id: PlayerID) -> bool {
+        self.bots.contains(&id)
+    }
+
     pub fn add_player(&mut self, name: String) -> Result<(PlayerID, Vec<MessageVariant>), Error> {
         let id = PlayerID(self.max_player_id);
         if self.players.iter().any(|p| p.name == name)
@@ -433,6 +1071,10 @@ impl PropagatedState {
             if self.landlord == Some(id) {
                 self.landlord = None;
             }
+            if self.director == Some(id) {
+                self.director = None;
+            }
+            self.bots.retain(|b| *b != id);
             self.players.retain(|p| p.id != id);
             msgs.extend(self.num_players_changed()?);
             Ok(msgs)
@@ -442,6 +1084,9 @@ impl PropagatedState {
     }
 
     pub fn remove_observer(&mut self, id: PlayerID) -> Result<(), Error> {
+        if self.director == Some(id) {
+            self.director = None;
+        }
         self.observers.retain(|p| p.id != id);
         Ok(())
     }
@@ -467,6 +1112,17 @@ impl PropagatedState {
         &mut self,
         special_decks: Vec<Deck>,
     ) -> Result<Vec<MessageVariant>, Error> {
+        if special_decks.iter().any(|d| {
+            d.num_big_jokers > MAX_JOKERS_PER_DECK || d.num_small_jokers > MAX_JOKERS_PER_DECK
+        }) {
+            bail!(
+                "a deck can have at most {} of each joker",
+                MAX_JOKERS_PER_DECK
+            )
+        }
+        if special_decks.iter().any(|d| d.is_empty()) {
+            bail!("a deck must have at least one card")
+        }
         let mut messages = vec![];
         if special_decks.len() > self.num_decks() {
             messages.extend(self.set_num_decks(Some(special_decks.len()))?);
@@ -564,6 +1220,20 @@ impl PropagatedState {
         Ok(vec![MessageVariant::MultipleJoinPolicySet { policy }])
     }
 
+    pub fn set_friend_point_transfer_policy(
+        &mut self,
+        policy: FriendPointTransferPolicy,
+    ) -> Result<Vec<MessageVariant>, Error> {
+        if policy != self.friend_point_transfer_policy {
+            self.friend_point_transfer_policy = policy;
+            Ok(vec![MessageVariant::FriendPointTransferPolicySet {
+                policy,
+            }])
+        } else {
+            Ok(vec![])
+        }
+    }
+
     pub fn set_first_landlord_selection_policy(
         &mut self,
         policy: FirstLandlordSelectionPolicy,
@@ -574,6 +1244,14 @@ impl PropagatedState {
         }])
     }
 
+    pub fn set_first_lead_policy(
+        &mut self,
+        policy: FirstLeadPolicy,
+    ) -> Result<Vec<MessageVariant>, Error> {
+        self.first_lead_policy = policy;
+        Ok(vec![MessageVariant::FirstLeadPolicySet { policy }])
+    }
+
     pub fn set_bid_policy(&mut self, policy: BidPolicy) -> Result<Vec<MessageVariant>, Error> {
         self.bid_policy = policy;
         Ok(vec![MessageVariant::BidPolicySet { policy }])
@@ -595,16 +1273,57 @@ impl PropagatedState {
         Ok(vec![MessageVariant::JokerBidPolicySet { policy }])
     }
 
+    pub fn set_joker_bid_suit_policy(
+        &mut self,
+        policy: JokerBidSuitPolicy,
+    ) -> Result<Vec<MessageVariant>, Error> {
+        if policy != self.joker_bid_suit_policy {
+            self.joker_bid_suit_policy = policy;
+            Ok(vec![MessageVariant::JokerBidSuitPolicySet { policy }])
+        } else {
+            Ok(vec![])
+        }
+    }
+
     pub fn set_should_reveal_kitty_at_end_of_game(
         &mut self,
         should_reveal: bool,
     ) -> Result<Vec<MessageVariant>, Error> {
         self.should_reveal_kitty_at_end_of_game = should_reveal;
+        self.kitty_reveal_policy = if should_reveal {
+            KittyRevealPolicy::Always
+        } else {
+            KittyRevealPolicy::Never
+        };
         Ok(vec![MessageVariant::ShouldRevealKittyAtEndOfGameSet {
             should_reveal,
         }])
     }
 
+    pub fn set_kitty_reveal_policy(
+        &mut self,
+        policy: KittyRevealPolicy,
+    ) -> Result<Vec<MessageVariant>, Error> {
+        if policy != self.kitty_reveal_policy() {
+            self.kitty_reveal_policy = policy;
+            self.should_reveal_kitty_at_end_of_game = policy == KittyRevealPolicy::Always;
+            Ok(vec![MessageVariant::KittyRevealPolicySet { policy }])
+        } else {
+            Ok(vec![])
+        }
+    }
+
+    /// The policy for revealing the kitty, taking the deprecated
+    /// `should_reveal_kitty_at_end_of_game` flag into account.
+    pub fn kitty_reveal_policy(&self) -> KittyRevealPolicy {
+        match self.kitty_reveal_policy {
+            KittyRevealPolicy::Never if self.should_reveal_kitty_at_end_of_game => {
+                KittyRevealPolicy::Always
+            }
+            policy => policy,
+        }
+    }
+
     pub fn set_landlord(&mut self, landlord: Option<PlayerID>) -> Result<(), Error> {
         match landlord {
             Some(landlord) => {
@@ -619,6 +1338,28 @@ impl PropagatedState {
         Ok(())
     }
 
+    pub fn set_director(
+        &mut self,
+        director: Option<PlayerID>,
+    ) -> Result<Vec<MessageVariant>, Error> {
+        if let Some(director) = director {
+            if !self
+                .players
+                .iter()
+                .chain(self.observers.iter())
+                .any(|p| p.id == director)
+            {
+                bail!("player ID not found")
+            }
+        }
+        if self.director != director {
+            self.director = director;
+            Ok(vec![MessageVariant::DirectorSet { director }])
+        } else {
+            Ok(vec![])
+        }
+    }
+
     pub fn set_landlord_emoji(&mut self, emoji: Option<String>) -> Result<(), Error> {
         match emoji {
             Some(emoji) => self.landlord_emoji = Some(emoji),
@@ -655,6 +1396,18 @@ impl PropagatedState {
         }
     }
 
+    pub fn set_out_of_turn_policy(
+        &mut self,
+        policy: OutOfTurnPolicy,
+    ) -> Result<Vec<MessageVariant>, Error> {
+        if policy != self.out_of_turn_policy {
+            self.out_of_turn_policy = policy;
+            Ok(vec![MessageVariant::OutOfTurnPolicySet { policy }])
+        } else {
+            Ok(vec![])
+        }
+    }
+
     pub fn set_kitty_penalty(
         &mut self,
         penalty: KittyPenalty,
@@ -669,6 +1422,18 @@ impl PropagatedState {
         }
     }
 
+    pub fn set_kitty_on_loss_policy(
+        &mut self,
+        policy: KittyOnLossPolicy,
+    ) -> Result<Vec<MessageVariant>, Error> {
+        if policy != self.kitty_on_loss_policy {
+            self.kitty_on_loss_policy = policy;
+            Ok(vec![MessageVariant::KittyOnLossPolicySet { policy }])
+        } else {
+            Ok(vec![])
+        }
+    }
+
     pub fn set_kitty_bid_policy(
         &mut self,
         policy: KittyBidPolicy,
@@ -705,6 +1470,18 @@ impl PropagatedState {
         }
     }
 
+    pub fn set_throw_tie_break_policy(
+        &mut self,
+        policy: ThrowTieBreakPolicy,
+    ) -> Result<Vec<MessageVariant>, Error> {
+        if policy != self.throw_tie_break_policy {
+            self.throw_tie_break_policy = policy;
+            Ok(vec![MessageVariant::ThrowTieBreakPolicySet { policy }])
+        } else {
+            Ok(vec![])
+        }
+    }
+
     pub fn set_play_takeback_policy(
         &mut self,
         policy: PlayTakebackPolicy,
@@ -762,10 +1539,37 @@ impl PropagatedState {
         }
     }
 
+    pub fn set_kitty_theft_reveal_policy(
+        &mut self,
+        policy: KittyTheftRevealPolicy,
+    ) -> Result<Vec<MessageVariant>, Error> {
+        if policy != self.kitty_theft_reveal_policy {
+            self.kitty_theft_reveal_policy = policy;
+            Ok(vec![MessageVariant::KittyTheftRevealPolicySet { policy }])
+        } else {
+            Ok(vec![])
+        }
+    }
+
+    pub fn set_kitty_theft_bid_policy(
+        &mut self,
+        policy: KittyTheftBidPolicy,
+    ) -> Result<Vec<MessageVariant>, Error> {
+        if policy != self.kitty_theft_bid_policy {
+            self.kitty_theft_bid_policy = policy;
+            Ok(vec![MessageVariant::KittyTheftBidPolicySet { policy }])
+        } else {
+            Ok(vec![])
+        }
+    }
+
     pub fn set_kitty_theft_policy(
         &mut self,
         policy: KittyTheftPolicy,
     ) -> Result<Vec<MessageVariant>, Error> {
+        if self.skip_exchange && policy == KittyTheftPolicy::AllowKittyTheft {
+            bail!("the kitty can't be stolen if the exchange is skipped")
+        }
         if policy != self.kitty_theft_policy {
             self.kitty_theft_policy = policy;
             Ok(vec![MessageVariant::KittyTheftPolicySet { policy }])
@@ -798,6 +1602,32 @@ impl PropagatedState {
         }
     }
 
+    pub fn set_absent_landlord_policy(
+        &mut self,
+        policy: AbsentLandlordPolicy,
+    ) -> Result<Vec<MessageVariant>, Error> {
+        if policy != self.absent_landlord_policy {
+            self.absent_landlord_policy = policy;
+            Ok(vec![MessageVariant::AbsentLandlordPolicySet { policy }])
+        } else {
+            Ok(vec![])
+        }
+    }
+
+    pub fn set_reveal_kitty_point_count(
+        &mut self,
+        reveal_kitty_point_count: bool,
+    ) -> Result<Vec<MessageVariant>, Error> {
+        if self.reveal_kitty_point_count != reveal_kitty_point_count {
+            self.reveal_kitty_point_count = reveal_kitty_point_count;
+            Ok(vec![MessageVariant::RevealKittyPointCount {
+                set: reveal_kitty_point_count,
+            }])
+        } else {
+            Ok(vec![])
+        }
+    }
+
     pub fn set_hide_throw_halting_player(
         &mut self,
         hide_throw_halting_player: bool,
@@ -812,6 +1642,44 @@ impl PropagatedState {
         }
     }
 
+    pub fn set_abandoned_game_policy(
+        &mut self,
+        policy: AbandonedGamePolicy,
+    ) -> Result<Vec<MessageVariant>, Error> {
+        if policy != self.abandoned_game_policy {
+            self.abandoned_game_policy = policy;
+            Ok(vec![MessageVariant::AbandonedGamePolicySet { policy }])
+        } else {
+            Ok(vec![])
+        }
+    }
+
+    pub fn set_quick_rematch(&mut self, quick_rematch: bool) -> Result<Vec<MessageVariant>, Error> {
+        if self.quick_rematch != quick_rematch {
+            self.quick_rematch = quick_rematch;
+            Ok(vec![MessageVariant::QuickRematchSet { set: quick_rematch }])
+        } else {
+            Ok(vec![])
+        }
+    }
+
+    pub fn set_skip_exchange(&mut self, skip_exchange: bool) -> Result<Vec<MessageVariant>, Error> {
+        if skip_exchange {
+            if self.game_mode != GameModeSettings::Tractor {
+                bail!("friends are picked during the exchange, so it can't be skipped")
+            }
+            if self.kitty_theft_policy == KittyTheftPolicy::AllowKittyTheft {
+                bail!("the exchange can't be skipped while kitty theft is allowed")
+            }
+        }
+        if self.skip_exchange != skip_exchange {
+            self.skip_exchange = skip_exchange;
+            Ok(vec![MessageVariant::SkipExchangeSet { set: skip_exchange }])
+        } else {
+            Ok(vec![])
+        }
+    }
+
     pub fn make_observer(&mut self, player_id: PlayerID) -> Result<Vec<MessageVariant>, Error> {
         if let Some(player) = self.players.iter().find(|p| p.id == player_id).cloned() {
             self.players.retain(|p| p.id != player_id);
@@ -835,8 +1703,79 @@ impl PropagatedState {
         }
     }
 
+    /// Kicks the player `id` out of their seat partway through a game. The
+    /// seat itself stays in place, since the game can't continue without it,
+    /// but nobody can rejoin as that player.
+    pub fn vacate_seat(&mut self, id: PlayerID) -> Result<Vec<MessageVariant>, Error> {
+        let seat = match self.players.iter_mut().find(|p| p.id == id) {
+            Some(seat) => seat,
+            None => bail!("player not found"),
+        };
+        if self.vacated_seats.contains(&id) {
+            bail!("that seat is already vacant")
+        }
+        seat.reconnect_token.clear();
+        let name = seat.name.clone();
+        if self.director == Some(id) {
+            self.director = None;
+        }
+        self.bots.retain(|b| *b != id);
+        self.vacated_seats.push(id);
+        Ok(vec![MessageVariant::LeftGame { name }])
+    }
+
+    pub fn is_vacated(&self, id: PlayerID) -> bool {
+        self.vacated_seats.contains(&id)
+    }
+
+    /// Seats the observer `observer_id` in the vacated seat at `seat_index`.
+    /// The seat keeps its `PlayerID`, so the seating order, hand, points and
+    /// rank carry over to the observer unchanged; only the name and reconnect
+    /// token move into the seat.
+    pub fn promote_observer(
+        &mut self,
+        observer_id: PlayerID,
+        seat_index: usize,
+    ) -> Result<Vec<MessageVariant>, Error> {
+        if self.vacated_seat_policy != VacatedSeatPolicy::AllowObserverTakeover {
+            bail!("observers can't take over another player's hand in this game")
+        }
+        let observer_idx = match self.observers.iter().position(|p| p.id == observer_id) {
+            Some(idx) => idx,
+            None => bail!("observer not found"),
+        };
+        let seat_id = match self.players.get(seat_index) {
+            Some(seat) => seat.id,
+            None => bail!("no player sits at that position"),
+        };
+        if !self.vacated_seats.contains(&seat_id) {
+            bail!("that seat is still taken")
+        }
+        self.vacated_seats.retain(|id| *id != seat_id);
+        let observer = self.observers.remove(observer_idx);
+        let seat = &mut self.players[seat_index];
+        let replaced = std::mem::replace(&mut seat.name, observer.name);
+        seat.reconnect_token = observer.reconnect_token;
+
+        // The observer keeps directing the room, now under the seat's ID.
+        if self.director == Some(observer_id) {
+            self.director = Some(seat_id);
+        }
+
+        Ok(vec![MessageVariant::ObserverPromoted {
+            player: seat_id,
+            replaced,
+        }])
+    }
+
     pub fn make_all_observers_into_players(&mut self) -> Result<Vec<MessageVariant>, Error> {
-        if self.observers.is_empty() {
+        // Seats which were vacated during the game are given up for good.
+        let vacated_seats = std::mem::take(&mut self.vacated_seats);
+        if self.landlord.map(|id| vacated_seats.contains(&id)) == Some(true) {
+            self.landlord = None;
+        }
+        self.players.retain(|p| !vacated_seats.contains(&p.id));
+        if self.observers.is_empty() && vacated_seats.is_empty() {
             return Ok(vec![]);
         }
         let mut msgs = vec![];
@@ -871,4 +1810,301 @@ impl PropagatedState {
             Ok(vec![])
         }
     }
+
+    pub fn set_blind_bid_bonus(
+        &mut self,
+        blind_bid_bonus: Option<usize>,
+    ) -> Result<Vec<MessageVariant>, Error> {
+        if blind_bid_bonus == Some(0) {
+            bail!("blind bid bonus must be at least one level")
+        }
+        if self.blind_bid_bonus != blind_bid_bonus {
+            self.blind_bid_bonus = blind_bid_bonus;
+            Ok(vec![MessageVariant::BlindBidBonusSet {
+                bonus: blind_bid_bonus,
+            }])
+        } else {
+            Ok(vec![])
+        }
+    }
+
+    pub fn set_max_metalevel(
+        &mut self,
+        max_metalevel: Option<usize>,
+    ) -> Result<Vec<MessageVariant>, Error> {
+        if max_metalevel.map(|m| m < 2).unwrap_or(false) {
+            bail!("the match must last until someone advances past the highest rank")
+        }
+        if self.max_metalevel != max_metalevel {
+            self.max_metalevel = max_metalevel;
+            Ok(vec![MessageVariant::MaxMetalevelSet { max_metalevel }])
+        } else {
+            Ok(vec![])
+        }
+    }
+
+    pub fn set_friend_bounds(
+        &mut self,
+        min_friends: Option<usize>,
+        max_friends: Option<usize>,
+    ) -> Result<Vec<MessageVariant>, Error> {
+        if let (Some(min), Some(max)) = (min_friends, max_friends) {
+            if min > max {
+                bail!("the minimum number of friends can't exceed the maximum")
+            }
+        }
+        if self.min_friends != min_friends || self.max_friends != max_friends {
+            self.min_friends = min_friends;
+            self.max_friends = max_friends;
+            Ok(vec![MessageVariant::FriendBoundsSet {
+                min_friends,
+                max_friends,
+            }])
+        } else {
+            Ok(vec![])
+        }
+    }
+
+    pub fn set_remainder_policy(
+        &mut self,
+        policy: RemainderPolicy,
+    ) -> Result<Vec<MessageVariant>, Error> {
+        if policy != self.remainder_policy {
+            self.remainder_policy = policy;
+            Ok(vec![MessageVariant::RemainderPolicySet { policy }])
+        } else {
+            Ok(vec![])
+        }
+    }
+
+    pub fn set_capture_bonus_rules(
+        &mut self,
+        rules: Vec<(Vec<Card>, isize)>,
+    ) -> Result<Vec<MessageVariant>, Error> {
+        for (cards, bonus) in &rules {
+            if cards.is_empty() {
+                bail!("capture bonus rules must include at least one card")
+            }
+            if cards.contains(&Card::Unknown) {
+                bail!("capture bonus rules can't include unknown cards")
+            }
+            if *bonus == 0 {
+                bail!("capture bonus rules must award a non-zero bonus")
+            }
+        }
+        if self.capture_bonus_rules != rules {
+            self.capture_bonus_rules = rules.clone();
+            Ok(vec![MessageVariant::CaptureBonusRulesSet { rules }])
+        } else {
+            Ok(vec![])
+        }
+    }
+
+    pub fn set_weak_hand_redeal(
+        &mut self,
+        rule: Option<WeakHandRedeal>,
+    ) -> Result<Vec<MessageVariant>, Error> {
+        if let Some(WeakHandRedeal {
+            point_threshold: 0, ..
+        }) = rule
+        {
+            bail!("no hand can have fewer than zero points")
+        }
+        if rule != self.weak_hand_redeal {
+            self.weak_hand_redeal = rule;
+            Ok(vec![MessageVariant::WeakHandRedealSet { rule }])
+        } else {
+            Ok(vec![])
+        }
+    }
+
+    pub fn set_pause_policy(&mut self, policy: PausePolicy) -> Result<Vec<MessageVariant>, Error> {
+        if policy != self.pause_policy {
+            self.pause_policy = policy;
+            Ok(vec![MessageVariant::PausePolicySet { policy }])
+        } else {
+            Ok(vec![])
+        }
+    }
+
+    pub fn set_paused(&mut self, paused: bool) -> Result<Vec<MessageVariant>, Error> {
+        if self.pause_policy == PausePolicy::NoPausing {
+            bail!("Pausing the game is not allowed")
+        }
+        if self.paused == paused {
+            if paused {
+                bail!("Game is already paused")
+            } else {
+                bail!("Game is not paused")
+            }
+        }
+        self.paused = paused;
+        if paused {
+            Ok(vec![MessageVariant::GamePaused])
+        } else {
+            Ok(vec![MessageVariant::GameResumed])
+        }
+    }
+
+    pub fn set_vacated_seat_policy(
+        &mut self,
+        policy: VacatedSeatPolicy,
+    ) -> Result<Vec<MessageVariant>, Error> {
+        if policy != self.vacated_seat_policy {
+            self.vacated_seat_policy = policy;
+            Ok(vec![MessageVariant::VacatedSeatPolicySet { policy }])
+        } else {
+            Ok(vec![])
+        }
+    }
+
+    pub fn set_spectator_card_visibility(
+        &mut self,
+        policy: SpectatorCardVisibility,
+    ) -> Result<Vec<MessageVariant>, Error> {
+        if policy != self.spectator_card_visibility {
+            self.spectator_card_visibility = policy;
+            Ok(vec![MessageVariant::SpectatorCardVisibilitySet { policy }])
+        } else {
+            Ok(vec![])
+        }
+    }
+
+    pub fn has_game_modifier(&self, modifier: GameModifier) -> bool {
+        self.game_modifiers.contains(&modifier)
+    }
+
+    pub fn set_game_modifiers(
+        &mut self,
+        mut game_modifiers: Vec<GameModifier>,
+    ) -> Result<Vec<MessageVariant>, Error> {
+        let mut seen = vec![];
+        game_modifiers.retain(|m| {
+            let is_new = !seen.contains(m);
+            seen.push(*m);
+            is_new
+        });
+        if self.game_modifiers != game_modifiers {
+            self.game_modifiers = game_modifiers;
+            Ok(vec![MessageVariant::GameModifiersSet {
+                modifiers: self.game_modifiers.clone(),
+            }])
+        } else {
+            Ok(vec![])
+        }
+    }
+
+    pub fn has_experimental_feature(&self, feature: &str) -> bool {
+        self.experimental_features.contains(feature)
+    }
+
+    pub fn set_experimental_features(
+        &mut self,
+        experimental_features: HashSet<String>,
+    ) -> Result<Vec<MessageVariant>, Error> {
+        validate_experimental_features(&experimental_features)?;
+        if self.experimental_features != experimental_features {
+            self.experimental_features = experimental_features;
+            let mut features = self
+                .experimental_features
+                .iter()
+                .cloned()
+                .collect::<Vec<_>>();
+            features.sort();
+            Ok(vec![MessageVariant::ExperimentalFeaturesSet { features }])
+        } else {
+            Ok(vec![])
+        }
+    }
+
+    pub fn set_permanent_trump_numbers(
+        &mut self,
+        numbers: Vec<Number>,
+    ) -> Result<Vec<MessageVariant>, Error> {
+        // Round-trip through the set to drop duplicates and sort the numbers.
+        let numbers = Vec::from(PermanentTrumps::from(numbers));
+        if self.permanent_trump_numbers != numbers {
+            self.permanent_trump_numbers = numbers.clone();
+            Ok(vec![MessageVariant::PermanentTrumpNumbersSet { numbers }])
+        } else {
+            Ok(vec![])
+        }
+    }
+
+    pub fn set_excluded_cards(
+        &mut self,
+        excluded_cards: Vec<Card>,
+    ) -> Result<Vec<MessageVariant>, Error> {
+        if excluded_cards.contains(&Card::Unknown) {
+            bail!("unknown cards can't be excluded")
+        }
+        let decks = self.decks_excluding(&excluded_cards)?;
+        if decks.iter().any(|d| d.is_empty()) {
+            bail!("a deck must have at least one card")
+        }
+        if !self
+            .players
+            .iter()
+            .all(|p| decks.iter().any(|d| d.can_bid(p.level)))
+        {
+            bail!("excluding those cards would leave a player unable to bid")
+        }
+        if self.excluded_cards == excluded_cards {
+            return Ok(vec![]);
+        }
+        self.excluded_cards = excluded_cards.clone();
+        let mut msgs = vec![MessageVariant::ExcludedCardsSet {
+            cards: excluded_cards,
+        }];
+        if self
+            .game_scoring_parameters
+            .materialize(&self.decks()?)
+            .is_err()
+        {
+            msgs.extend(self.set_game_scoring_parameters(GameScoringParameters::default())?);
+        }
+        Ok(msgs)
+    }
+
+    pub fn set_move_deadline_secs(
+        &mut self,
+        move_deadline_secs: Option<u64>,
+    ) -> Result<Vec<MessageVariant>, Error> {
+        if move_deadline_secs == Some(0) {
+            bail!("move deadline must be at least one second")
+        }
+        if self.move_deadline_secs != move_deadline_secs {
+            self.move_deadline_secs = move_deadline_secs;
+            Ok(vec![MessageVariant::MoveDeadlineSet {
+                secs: move_deadline_secs,
+            }])
+        } else {
+            Ok(vec![])
+        }
+    }
+}
+
+fn deserialize_experimental_features<'de, D: serde::Deserializer<'de>>(
+    deserializer: D,
+) -> Result<HashSet<String>, D::Error> {
+    let experimental_features = HashSet::deserialize(deserializer)?;
+    validate_experimental_features(&experimental_features).map_err(serde::de::Error::custom)?;
+    Ok(experimental_features)
+}
+
+/// Checks that every flag in `experimental_features` is one of the known
+/// `EXPERIMENTAL_FEATURES`.
+pub fn validate_experimental_features(
+    experimental_features: &HashSet<String>,
+) -> Result<(), Error> {
+    let mut unknown = experimental_features
+        .iter()
+        .filter(|f| !EXPERIMENTAL_FEATURES.contains(&f.as_str()))
+        .map(|f| f.as_str())
+        .collect::<Vec<_>>();
+    if !unknown.is_empty() {
+        unknown.sort_unstable();
+        bail!("unknown experimental features: {}", unknown.join(", "))
+    }
+    Ok(())
 }
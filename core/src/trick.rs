@@ -1,6 +1,7 @@
 use std::cmp::Ordering;
 use std::collections::{BTreeMap, HashMap, HashSet, VecDeque};
 
+use rand::{rngs::StdRng, seq::SliceRandom, SeedableRng};
 use serde::{Deserialize, Serialize};
 use thiserror::Error;
 
@@ -10,7 +11,11 @@ use crate::ordered_card::{
     attempt_format_match, subsequent_decomposition_ordering, AdjacentTupleSizes, MatchingCards,
     OrderedCard,
 };
-use crate::types::{Card, EffectiveSuit, PlayerID, Trump};
+use crate::types::{Card, EffectiveSuit, PlayerID, Suit, Trump, ALL_SUITS, FULL_DECK};
+
+/// The most candidate plays that are considered when looking for ways to
+/// follow a trick, so that large hands don't blow up combinatorially.
+pub const MAX_FOLLOW_CANDIDATES: usize = 4096;
 
 #[derive(Error, Clone, Debug, Serialize, Deserialize)]
 pub enum TrickError {
@@ -21,6 +26,18 @@ pub enum TrickError {
     },
     #[error("wrong number of cards provided")]
     WrongNumberOfCards,
+    #[error(
+        "too many cards: {} were played, but the trick requires {}",
+        proposed,
+        required
+    )]
+    TooManyCards { required: usize, proposed: usize },
+    #[error(
+        "too few cards: {} were played, but the trick requires {}",
+        proposed,
+        required
+    )]
+    TooFewCards { required: usize, proposed: usize },
     #[error("the cards have the wrong number of suits")]
     WrongNumberOfSuits,
     #[error("player is playing out of order")]
@@ -31,6 +48,8 @@ pub enum TrickError {
     NonMatchingPlay,
     #[error("the proposed grouping is invalid")]
     NonMatchingProposal,
+    #[error("throws are not allowed this game")]
+    ThrowsNotAllowed,
 }
 
 #[derive(Debug, Copy, Clone, Eq, PartialEq, Serialize, Deserialize)]
@@ -40,6 +59,9 @@ pub enum TrickDrawPolicy {
     /// Only allow tractors to be drawn if the original format was also a tractor.
     OnlyDrawTractorOnTractor,
     NoFormatBasedDraw,
+    /// Like `OnlyDrawTractorOnTractor`, but a led tractor is only decomposed
+    /// down to pairs (or longer tuples), and never all the way into singles.
+    OnlyDrawTractorOnTractorStrict,
 }
 
 impl Default for TrickDrawPolicy {
@@ -55,6 +77,7 @@ pub enum ThrowEvaluationPolicy {
     All,
     Highest,
     TrickUnitLength,
+    Cumulative,
 }
 
 impl_slog_value!(ThrowEvaluationPolicy);
@@ -65,6 +88,22 @@ impl Default for ThrowEvaluationPolicy {
     }
 }
 
+/// Decides who wins when a throw is followed by another throw which is exactly
+/// as strong in every unit.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Serialize, Deserialize)]
+pub enum ThrowTieBreakPolicy {
+    FirstPlayed,
+    LastPlayed,
+}
+
+impl_slog_value!(ThrowTieBreakPolicy);
+
+impl Default for ThrowTieBreakPolicy {
+    fn default() -> Self {
+        ThrowTieBreakPolicy::FirstPlayed
+    }
+}
+
 #[derive(Debug, Copy, Clone, Eq, PartialEq, Serialize, Deserialize)]
 pub struct TractorRequirements {
     /// The minimum number of cards in each unit of the tractor
@@ -154,6 +193,58 @@ impl TrickUnit {
         )
     }
 
+    /// Returns the unit formed by `cards` if they make up exactly one
+    /// tractor, pair, or single (or larger repeated unit) with nothing left
+    /// over.
+    pub fn from_single_unit(
+        trump: Trump,
+        tractor_requirements: TractorRequirements,
+        cards: impl IntoIterator<Item = Card>,
+    ) -> Option<TrickUnit> {
+        TrickUnit::find_plays(trump, tractor_requirements, cards)
+            .into_iter()
+            .find(|units| units.len() == 1)
+            .and_then(|mut units| units.pop())
+    }
+
+    /// Whether `hand` has cards in the same suit which make a strictly
+    /// stronger unit of the same shape, so that this unit couldn't be thrown.
+    pub fn is_beaten_by(&self, trump: Trump, hand: &HashMap<Card, usize>) -> bool {
+        let suit = trump.effective_suit(self.first_card().card);
+        let subset_hands = hand.iter().filter_map(|(card, count)| {
+            if trump.effective_suit(*card) == suit {
+                Some((OrderedCard { card: *card, trump }, *count))
+            } else {
+                None
+            }
+        });
+
+        match self {
+            TrickUnit::Repeated { count, card } => subset_hands
+                .into_iter()
+                .any(|(c, ct)| ct >= *count && c.cmp_effective(*card) == Ordering::Greater),
+            TrickUnit::Tractor { count, members } => {
+                let in_suit = subset_hands.collect::<BTreeMap<OrderedCard, usize>>();
+                in_suit.range(members[1]..).any(|(c, ct)| {
+                    !find_tractors_from_start(
+                        *c,
+                        *ct,
+                        &in_suit,
+                        // Note: We base the tractor-requirements off of the
+                        // tractor we found, rather than off of the
+                        // requirements that are passed in, that way we only
+                        // find "bigger" tractors.
+                        TractorRequirements {
+                            min_count: *count,
+                            min_length: members.len(),
+                        },
+                    )
+                    .is_empty()
+                })
+            }
+        }
+    }
+
     pub fn cards(&self) -> Vec<Card> {
         match self {
             TrickUnit::Tractor {
@@ -189,6 +280,14 @@ impl TrickFormat {
         self.units.iter().map(|u| u.size()).sum()
     }
 
+    pub fn units(&self) -> &'_ [TrickUnit] {
+        &self.units
+    }
+
+    pub fn is_throw(&self) -> bool {
+        self.units.len() > 1
+    }
+
     pub fn suit(&self) -> EffectiveSuit {
         self.suit
     }
@@ -204,22 +303,38 @@ impl TrickFormat {
             .map(UnitLike::from)
             .map(|u| u.adjacent_tuples)
             .collect();
+        let include_new_adjacency = !matches!(
+            trick_draw_policy,
+            TrickDrawPolicy::OnlyDrawTractorOnTractor
+                | TrickDrawPolicy::OnlyDrawTractorOnTractorStrict
+        );
+        // Under the strict policy, stop decomposing a led tractor once it's
+        // down to pairs: every requirement past the format's own singles
+        // still has to include a pair.
+        let max_singles = if trick_draw_policy == TrickDrawPolicy::OnlyDrawTractorOnTractorStrict
+            && self.units.iter().any(|u| u.is_tractor())
+        {
+            self.units.iter().filter(|u| u.size() == 1).count()
+        } else {
+            usize::MAX
+        };
 
         // Include the current trick-format, and then the subsequent decomposition if we get that
         // far. Compute the latter lazily, since we usually won't.
         std::iter::once(units).chain(
             std::iter::once_with(move || {
-                subsequent_decomposition_ordering(
-                    adj_tuples,
-                    trick_draw_policy != TrickDrawPolicy::OnlyDrawTractorOnTractor,
-                )
-                .into_iter()
-                .map(|requirements| {
-                    requirements
-                        .into_iter()
-                        .map(|adjacent_tuples| UnitLike { adjacent_tuples })
-                        .collect()
-                })
+                subsequent_decomposition_ordering(adj_tuples, include_new_adjacency)
+                    .into_iter()
+                    .filter(move |requirements| {
+                        requirements.iter().any(|r| r[..] != [1])
+                            || requirements.len() <= max_singles
+                    })
+                    .map(|requirements| {
+                        requirements
+                            .into_iter()
+                            .map(|adjacent_tuples| UnitLike { adjacent_tuples })
+                            .collect()
+                    })
             })
             .flatten(),
         )
@@ -298,6 +413,135 @@ impl TrickFormat {
         }
     }
 
+    /// Determines what the player holding `hand` is required to play in order
+    /// to follow this trick format.
+    pub fn required_follow(
+        &self,
+        hand: &HashMap<Card, usize>,
+        trick_draw_policy: TrickDrawPolicy,
+    ) -> FollowObligation {
+        let available_cards = Card::cards(
+            hand.iter()
+                .filter(|(c, _)| self.trump.effective_suit(**c) == self.suit),
+        )
+        .copied()
+        .collect::<Vec<_>>();
+
+        if available_cards.is_empty() {
+            return FollowObligation::Void;
+        }
+        if available_cards.len() <= self.size() {
+            return FollowObligation::PlayAll {
+                cards: available_cards,
+            };
+        }
+        if let TrickDrawPolicy::NoFormatBasedDraw = trick_draw_policy {
+            return FollowObligation::AnyInSuit;
+        }
+
+        // Mirrors `is_legal_play`: the first requirement in the decomposition
+        // that the hand can satisfy must be played. A requirement of only
+        // single cards can be met by any cards in the suit.
+        for requirement in self.decomposition(trick_draw_policy) {
+            if requirement.iter().all(|u| u.num_cards() == 1) {
+                break;
+            }
+            let hand_can_play = UnitLike::check_play(
+                self.trump,
+                available_cards.iter().copied(),
+                requirement.iter().cloned(),
+                trick_draw_policy,
+            )
+            .0;
+            if hand_can_play {
+                return FollowObligation::MatchFormat {
+                    format: requirement,
+                };
+            }
+        }
+        FollowObligation::AnyInSuit
+    }
+
+    /// Lists the tuples in `hand` which can't be broken up to follow this
+    /// format under `TrickDrawPolicy::LongerTuplesProtected`, because they are
+    /// longer than any tuple the format requires. Adjacent protected tuples are
+    /// grouped together into tractors.
+    pub fn protected_units(&self, hand: &HashMap<Card, usize>) -> Vec<UnitLike> {
+        let longest_required = self
+            .units
+            .iter()
+            .map(UnitLike::from)
+            .flat_map(|u| u.adjacent_tuples)
+            .max()
+            .unwrap_or(0);
+        let protected = hand
+            .iter()
+            .filter(|(c, ct)| {
+                **ct > longest_required && self.trump.effective_suit(**c) == self.suit
+            })
+            .map(|(c, ct)| {
+                (
+                    OrderedCard {
+                        card: *c,
+                        trump: self.trump,
+                    },
+                    *ct,
+                )
+            })
+            .collect::<BTreeMap<_, _>>();
+
+        let mut units: Vec<(OrderedCard, UnitLike)> = vec![];
+        for (card, count) in protected {
+            match units.last_mut() {
+                Some((last, unit)) if last.successor().contains(&card) => {
+                    unit.adjacent_tuples.push(count);
+                    *last = card;
+                }
+                _ => units.push((
+                    card,
+                    UnitLike {
+                        adjacent_tuples: vec![count],
+                    },
+                )),
+            }
+        }
+        units.into_iter().map(|(_, unit)| unit).collect()
+    }
+
+    /// Computes the follow obligations for `hand` if this trick format had
+    /// been led in each of the effective suits instead.
+    pub fn obligations_across_hand(
+        &self,
+        hand: &HashMap<Card, usize>,
+        trick_draw_policy: TrickDrawPolicy,
+    ) -> HashMap<EffectiveSuit, FollowObligation> {
+        let trump_suit = match self.trump {
+            Trump::Standard { suit, .. } => Some(suit),
+            Trump::NoTrump { .. } => None,
+        };
+        let suits = ALL_SUITS
+            .iter()
+            .filter(|s| Some(**s) != trump_suit)
+            .map(|s| match s {
+                Suit::Clubs => EffectiveSuit::Clubs,
+                Suit::Diamonds => EffectiveSuit::Diamonds,
+                Suit::Spades => EffectiveSuit::Spades,
+                Suit::Hearts => EffectiveSuit::Hearts,
+            })
+            .chain(std::iter::once(EffectiveSuit::Trump));
+
+        suits
+            .map(|suit| {
+                let format = TrickFormat {
+                    suit,
+                    trump: self.trump,
+                    units: self.units.clone(),
+                };
+                (suit, format.required_follow(hand, trick_draw_policy))
+            })
+            .collect()
+    }
+
     pub fn matches(&self, cards: &'_ [Card]) -> Result<Units, TrickError> {
         let suit = self.trump.effective_suit(cards[0]);
         for card in cards {
@@ -411,6 +655,72 @@ impl TrickFormat {
             }
         }
     }
+
+    /// Checks a grouping the player put together by hand before it's played:
+    /// the units have to fit in `hand` together, form a valid decomposition of
+    /// their cards, and, when following, match the `leading` format.
+    pub fn validate_grouping(
+        trump: Trump,
+        tractor_requirements: TractorRequirements,
+        hand: &HashMap<Card, usize>,
+        proposed: &'_ [TrickUnit],
+        leading: Option<&'_ TrickFormat>,
+    ) -> Result<TrickFormat, TrickError> {
+        let cards = proposed.iter().flat_map(|u| u.cards()).collect::<Vec<_>>();
+        if cards.is_empty() {
+            return Err(TrickError::WrongNumberOfCards);
+        }
+        let mut counts = HashMap::new();
+        for card in &cards {
+            *counts.entry(*card).or_insert(0) += 1;
+        }
+        if counts
+            .iter()
+            .any(|(card, count)| hand.get(card).copied().unwrap_or(0) < *count)
+        {
+            return Err(HandError::CardsNotFound.into());
+        }
+
+        let format = TrickFormat::from_cards(trump, tractor_requirements, &cards, Some(proposed))?;
+        if let Some(leading) = leading {
+            leading.matches(&cards)?;
+        }
+        Ok(format)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
+pub enum FollowObligation {
+    /// The player has no cards in the suit, and can play anything.
+    Void,
+    /// The player doesn't have enough cards in the suit, and must play all of
+    /// them.
+    PlayAll { cards: Vec<Card> },
+    /// The player must play cards in the suit matching this format.
+    MatchFormat { format: Vec<UnitLike> },
+    /// The player can play any cards in the suit.
+    AnyInSuit,
+}
+
+/// What followers of a trick must play, in terms of the lead alone.
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
+pub struct FollowRequirement {
+    pub suit: EffectiveSuit,
+    pub num_cards: usize,
+    pub units: Vec<UnitLike>,
+}
+
+/// The outcome of leading a set of cards, as predicted by `Trick::check_throw`.
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
+pub enum ThrowCheckResult {
+    /// The cards would be played as-is.
+    Succeeds,
+    /// `better_player` can beat part of the throw, so only `forced_cards`
+    /// would be played.
+    Fails {
+        forced_cards: Vec<Card>,
+        better_player: PlayerID,
+    },
 }
 
 #[derive(Clone, Debug, Deserialize, Serialize)]
@@ -427,9 +737,12 @@ pub struct PlayCards<'a, 'b, 'c> {
     pub cards: &'b [Card],
     pub trick_draw_policy: TrickDrawPolicy,
     pub throw_eval_policy: ThrowEvaluationPolicy,
+    pub throw_tie_break_policy: ThrowTieBreakPolicy,
     pub format_hint: Option<&'c [TrickUnit]>,
     pub hide_throw_halting_player: bool,
     pub tractor_requirements: TractorRequirements,
+    /// Whether the leader may play more than one unit at once.
+    pub throws_allowed: bool,
 }
 
 #[derive(Clone, Debug, Deserialize, Serialize)]
@@ -476,10 +789,37 @@ impl Trick {
         self.trump
     }
 
+    pub fn current_winner(&self) -> Option<PlayerID> {
+        self.current_winner
+    }
+
     pub fn trick_format(&self) -> Option<&'_ TrickFormat> {
         self.trick_format.as_ref()
     }
 
+    /// Describes the suit and shape which followers must play, once the trick
+    /// has been led. This doesn't depend on any player's hand.
+    pub fn follow_requirement(&self) -> Option<FollowRequirement> {
+        self.trick_format.as_ref().map(|tf| FollowRequirement {
+            suit: tf.suit,
+            num_cards: tf.size(),
+            units: tf.units.iter().map(UnitLike::from).collect(),
+        })
+    }
+
+    /// Checks whether `cards` can be used to lead a trick, and if not, why.
+    pub fn is_valid_lead(
+        cards: &[Card],
+        trump: Trump,
+        tractor_requirements: TractorRequirements,
+        format_hint: Option<&'_ [TrickUnit]>,
+    ) -> Result<(), TrickError> {
+        if cards.is_empty() {
+            return Err(TrickError::WrongNumberOfCards);
+        }
+        TrickFormat::from_cards(trump, tractor_requirements, cards, format_hint).map(|_| ())
+    }
+
     ///
     /// Determines whether the player can play the cards.
     ///
@@ -496,6 +836,19 @@ impl Trick {
         hands.contains(id, cards.iter().cloned())?;
         match self.trick_format.as_ref() {
             Some(tf) => {
+                let required = tf.size();
+                if cards.len() > required {
+                    return Err(TrickError::TooManyCards {
+                        required,
+                        proposed: cards.len(),
+                    });
+                }
+                if cards.len() < required {
+                    return Err(TrickError::TooFewCards {
+                        required,
+                        proposed: cards.len(),
+                    });
+                }
                 if tf.is_legal_play(hands.get(id)?, cards, trick_draw_policy) {
                     Ok(())
                 } else {
@@ -517,6 +870,59 @@ impl Trick {
         }
     }
 
+    /// Checks, without playing anything, whether leading `cards` would be a
+    /// throw which fails, and if so, which cards would be played instead.
+    /// This looks at every other player's hand, so it must not be exposed to
+    /// the leader.
+    pub fn check_throw(
+        &self,
+        id: PlayerID,
+        hands: &Hands,
+        cards: &[Card],
+        tractor_requirements: TractorRequirements,
+        format_hint: Option<&'_ [TrickUnit]>,
+    ) -> Result<ThrowCheckResult, TrickError> {
+        if self.player_queue.front().cloned() != Some(id) {
+            return Err(TrickError::OutOfOrder);
+        }
+        hands.contains(id, cards.iter().cloned())?;
+        if self.trick_format.is_some() {
+            return Ok(ThrowCheckResult::Succeeds);
+        }
+        let mut cards = cards.to_vec();
+        cards.sort_by(|a, b| self.trump.compare(*a, *b));
+        let tf = TrickFormat::from_cards(self.trump, tractor_requirements, &cards, format_hint)?;
+        Ok(match self.find_throw_blocker(hands, &tf)? {
+            Some((better_player, forced_unit)) => ThrowCheckResult::Fails {
+                forced_cards: forced_unit.cards(),
+                better_player,
+            },
+            None => ThrowCheckResult::Succeeds,
+        })
+    }
+
+    /// Finds a player other than the leader who could strictly beat one of the
+    /// units in the thrown trick format `tf`, along with that unit.
+    fn find_throw_blocker(
+        &self,
+        hands: &Hands,
+        tf: &TrickFormat,
+    ) -> Result<Option<(PlayerID, TrickUnit)>, TrickError> {
+        if tf.units.len() <= 1 {
+            return Ok(None);
+        }
+
+        // This is a throw, let's see if any of the units can be strictly defeated by any
+        // other player.
+        for player in self.player_queue.iter().skip(1) {
+            let hand = hands.get(*player)?;
+            if let Some(unit) = tf.units.iter().find(|u| u.is_beaten_by(self.trump, hand)) {
+                return Ok(Some((*player, unit.clone())));
+            }
+        }
+        Ok(None)
+    }
+
     ///
     /// Actually plays the cards, if possible. On error, does not modify any state.
     ///
@@ -532,9 +938,11 @@ impl Trick {
             cards,
             trick_draw_policy,
             throw_eval_policy,
+            throw_tie_break_policy,
             format_hint,
             hide_throw_halting_player,
             tractor_requirements,
+            throws_allowed,
         } = args;
 
         if self.player_queue.front().cloned() != Some(id) {
@@ -548,77 +956,14 @@ impl Trick {
         let (cards, bad_throw_cards, better_player) = if self.trick_format.is_none() {
             let mut tf =
                 TrickFormat::from_cards(self.trump, tractor_requirements, &cards, format_hint)?;
-            let mut invalid = None;
-            if tf.units.len() > 1 {
-                // This is a throw, let's see if any of the units can be strictly defeated by any
-                // other player.
-                'search: for player in self.player_queue.iter().skip(1) {
-                    let subset_hands = hands.get(*player)?.iter().filter_map(|(card, count)| {
-                        if self.trump.effective_suit(*card) == tf.suit {
-                            Some((
-                                OrderedCard {
-                                    card: *card,
-                                    trump: self.trump,
-                                },
-                                *count,
-                            ))
-                        } else {
-                            None
-                        }
-                    });
-
-                    for unit in &tf.units {
-                        match unit {
-                            TrickUnit::Repeated { count, card } => {
-                                for (c, ct) in subset_hands.clone() {
-                                    if ct >= *count && c.cmp_effective(*card) == Ordering::Greater {
-                                        invalid = Some((player, unit.clone()));
-                                        break 'search;
-                                    }
-                                }
-                            }
-                            TrickUnit::Tractor { count, members } => {
-                                let in_suit = subset_hands
-                                    .clone()
-                                    .collect::<BTreeMap<OrderedCard, usize>>();
-                                for (c, ct) in in_suit.range(members[1]..) {
-                                    let higher_tractors = find_tractors_from_start(
-                                        *c,
-                                        *ct,
-                                        &in_suit,
-                                        // Note: We base the
-                                        // tractor-requirements off of the
-                                        // tractor we found, rather than off of
-                                        // the requirements that are passed in,
-                                        // that way we only find "bigger"
-                                        // tractors.
-                                        TractorRequirements {
-                                            min_count: *count,
-                                            min_length: members.len(),
-                                        },
-                                    );
-                                    if !higher_tractors.is_empty() {
-                                        invalid = Some((player, unit.clone()));
-                                        break 'search;
-                                    }
-                                }
-                            }
-                        }
-                    }
-                }
+            if !throws_allowed && tf.is_throw() {
+                return Err(TrickError::ThrowsNotAllowed);
             }
+            let invalid = self.find_throw_blocker(hands, &tf)?;
 
             let (cards, bad_throw_cards, better_player) =
                 if let Some((better_player, forced_unit)) = invalid {
-                    let forced_cards: Vec<Card> = match forced_unit {
-                        TrickUnit::Repeated { card, count } => {
-                            (0..count).map(|_| card.card).collect()
-                        }
-                        TrickUnit::Tractor { ref members, count } => members
-                            .iter()
-                            .flat_map(|card| (0..count).map(move |_| card.card))
-                            .collect(),
-                    };
+                    let forced_cards = forced_unit.cards();
 
                     tf.units = vec![forced_unit];
 
@@ -627,7 +972,7 @@ impl Trick {
                         better_player: if hide_throw_halting_player {
                             None
                         } else {
-                            Some(*better_player)
+                            Some(better_player)
                         },
                     });
 
@@ -636,7 +981,7 @@ impl Trick {
                         cards.remove(idx);
                     }
 
-                    (forced_cards, cards, Some(*better_player))
+                    (forced_cards, cards, Some(better_player))
                 } else {
                     (cards, vec![], None)
                 };
@@ -682,6 +1027,7 @@ impl Trick {
             &self.played_cards,
             self.trump,
             throw_eval_policy,
+            throw_tie_break_policy,
         );
 
         Ok(msgs)
@@ -695,6 +1041,7 @@ impl Trick {
         id: PlayerID,
         hands: &'_ mut Hands,
         throw_eval_policy: ThrowEvaluationPolicy,
+        throw_tie_break_policy: ThrowTieBreakPolicy,
     ) -> Result<(), TrickError> {
         if self.played_cards.last().map(|p| p.id) == Some(id) {
             let played = self.played_cards.pop().unwrap();
@@ -710,6 +1057,7 @@ impl Trick {
                 &self.played_cards,
                 self.trump,
                 throw_eval_policy,
+                throw_tie_break_policy,
             );
             Ok(())
         } else {
@@ -735,6 +1083,7 @@ impl Trick {
                 winner: self.current_winner.ok_or(TrickError::OutOfOrder)?,
                 points: all_card_points,
                 largest_trick_unit_size: tf.units.iter().map(|u| u.size()).max().unwrap_or(0),
+                has_tractor: tf.units.iter().any(|u| u.is_tractor()),
                 failed_throw_size: self
                     .played_cards
                     .get(0)
@@ -747,90 +1096,469 @@ impl Trick {
         }
     }
 
+    /**
+     * Suggests what `id` should play to follow the trick: the legal play that
+     * wins the trick with the most points in it or, if no legal play can win,
+     * the legal play which gives away the fewest points and lowest cards.
+     * Only the first `MAX_FOLLOW_CANDIDATES` plays are considered.
+     */
+    pub fn find_optimal_play(
+        &self,
+        id: PlayerID,
+        hands: &Hands,
+        trick_draw_policy: TrickDrawPolicy,
+        throw_eval_policy: ThrowEvaluationPolicy,
+        throw_tie_break_policy: ThrowTieBreakPolicy,
+    ) -> Result<Vec<Card>, TrickError> {
+        let tf = self.trick_format.as_ref().ok_or(TrickError::OutOfOrder)?;
+        if self.next_player() != Some(id) {
+            return Err(TrickError::OutOfOrder);
+        }
+        let hand = hands.get(id)?;
+        let trump = self.trump;
+        let candidates = Self::candidate_follows(tf, hand, MAX_FOLLOW_CANDIDATES);
+
+        let strength = |cards: &[Card]| {
+            let mut cards = cards.to_vec();
+            cards.sort_by(|a, b| trump.compare(*b, *a));
+            cards
+        };
+        let compare_strength = |a: &[Card], b: &[Card]| {
+            strength(a)
+                .into_iter()
+                .zip(strength(b))
+                .map(|(a, b)| trump.compare(a, b))
+                .find(|o| *o != Ordering::Equal)
+                .unwrap_or(Ordering::Equal)
+        };
+        let points = |cards: &[Card]| cards.iter().flat_map(|c| c.points()).sum::<usize>();
+
+        candidates
+            .into_iter()
+            .filter(|cards| tf.is_legal_play(hand, cards, trick_draw_policy))
+            .map(|cards| {
+                let mut played_cards = self.played_cards.clone();
+                played_cards.push(PlayedCards {
+                    id,
+                    cards: cards.clone(),
+                    bad_throw_cards: vec![],
+                    better_player: None,
+                });
+                let wins = Self::winner(
+                    Some(tf),
+                    &played_cards,
+                    trump,
+                    throw_eval_policy,
+                    throw_tie_break_policy,
+                ) == Some(id);
+                (wins, cards)
+            })
+            .max_by(|(a_wins, a), (b_wins, b)| {
+                a_wins.cmp(b_wins).then_with(|| {
+                    if *a_wins {
+                        // Capture as many points as possible, as cheaply as
+                        // possible.
+                        points(a)
+                            .cmp(&points(b))
+                            .then_with(|| compare_strength(b, a))
+                    } else {
+                        points(b)
+                            .cmp(&points(a))
+                            .then_with(|| compare_strength(b, a))
+                    }
+                })
+            })
+            .map(|(_, cards)| cards)
+            .ok_or(TrickError::IllegalPlay)
+    }
+
+    /**
+     * Estimates the probability that whoever is currently winning the trick
+     * still wins it once everyone else has played. The `unseen` cards are
+     * assumed to be dealt uniformly at random, `hand_size` to each player who
+     * has yet to play, and a player takes the trick if any of their legal
+     * plays would beat the current winner. Only the first
+     * `MAX_FOLLOW_CANDIDATES` plays in each hand are considered.
+     *
+     * The estimate is taken over `num_samples` deals from a fixed seed, so the
+     * same position always gets the same answer.
+     */
+    pub fn lead_survival_probability(
+        &self,
+        unseen: &[Card],
+        hand_size: usize,
+        trick_draw_policy: TrickDrawPolicy,
+        throw_eval_policy: ThrowEvaluationPolicy,
+        throw_tie_break_policy: ThrowTieBreakPolicy,
+        num_samples: usize,
+    ) -> Result<f64, TrickError> {
+        let tf = self.trick_format.as_ref().ok_or(TrickError::OutOfOrder)?;
+        if self.player_queue.is_empty() || num_samples == 0 {
+            return Ok(1.0);
+        }
+        if hand_size < tf.size() || unseen.len() < hand_size * self.player_queue.len() {
+            return Err(TrickError::WrongNumberOfCards);
+        }
+
+        let mut rng = StdRng::seed_from_u64(0);
+        let mut deck = unseen.to_vec();
+        let mut survived = 0;
+        for _ in 0..num_samples {
+            deck.shuffle(&mut rng);
+            let overtaken =
+                self.player_queue
+                    .iter()
+                    .zip(deck.chunks(hand_size))
+                    .any(|(id, dealt)| {
+                        let mut hand = HashMap::new();
+                        for card in dealt {
+                            *hand.entry(*card).or_insert(0) += 1;
+                        }
+                        Self::candidate_follows(tf, &hand, MAX_FOLLOW_CANDIDATES)
+                            .into_iter()
+                            .filter(|cards| tf.is_legal_play(&hand, cards, trick_draw_policy))
+                            .any(|cards| {
+                                let mut played_cards = self.played_cards.clone();
+                                played_cards.push(PlayedCards {
+                                    id: *id,
+                                    cards,
+                                    bad_throw_cards: vec![],
+                                    better_player: None,
+                                });
+                                Self::winner(
+                                    Some(tf),
+                                    &played_cards,
+                                    self.trump,
+                                    throw_eval_policy,
+                                    throw_tie_break_policy,
+                                ) == Some(*id)
+                            })
+                    });
+            if !overtaken {
+                survived += 1;
+            }
+        }
+        Ok(survived as f64 / num_samples as f64)
+    }
+
+    /// Lists every legal way for the player holding `hand` to follow
+    /// `trick_format`, rather than just the minimal requirement. At most
+    /// `MAX_FOLLOW_CANDIDATES` plays are considered, so some legal follows may
+    /// be left out for very large hands.
+    pub fn all_legal_follows(
+        hand: &HashMap<Card, usize>,
+        trick_format: &TrickFormat,
+        trick_draw_policy: TrickDrawPolicy,
+    ) -> Vec<Vec<Card>> {
+        Self::candidate_follows(trick_format, hand, MAX_FOLLOW_CANDIDATES)
+            .into_iter()
+            .filter(|cards| trick_format.is_legal_play(hand, cards, trick_draw_policy))
+            .collect()
+    }
+
+    /// Returns the only legal way for the player holding `hand` to follow
+    /// `trick_format`, if there is exactly one. If there were more than
+    /// `MAX_FOLLOW_CANDIDATES` plays to consider, this gives up rather than
+    /// risk missing a second legal follow.
+    pub fn sole_legal_follow(
+        hand: &HashMap<Card, usize>,
+        trick_format: &TrickFormat,
+        trick_draw_policy: TrickDrawPolicy,
+    ) -> Option<Vec<Card>> {
+        let candidates = Self::candidate_follows(trick_format, hand, MAX_FOLLOW_CANDIDATES);
+        if candidates.len() >= MAX_FOLLOW_CANDIDATES {
+            return None;
+        }
+        let mut legal_plays = candidates
+            .into_iter()
+            .filter(|cards| trick_format.is_legal_play(hand, cards, trick_draw_policy))
+            .take(2)
+            .collect::<Vec<_>>();
+        if legal_plays.len() == 1 {
+            legal_plays.pop()
+        } else {
+            None
+        }
+    }
+
+    /// Picks the legal follow which gives away the fewest points, for when the
+    /// trick is going to be won by the other team. Ties are broken by keeping
+    /// trumps and high cards for later tricks.
+    ///
+    /// Note that the kitty multiplier on the final trick only depends on how
+    /// the trick was led, so the choice of follow doesn't affect it.
+    pub fn analyze_defensive_discard(
+        hand: &HashMap<Card, usize>,
+        trick_format: &TrickFormat,
+        trick_draw_policy: TrickDrawPolicy,
+    ) -> Option<Vec<Card>> {
+        let trump = trick_format.trump;
+        Self::all_legal_follows(hand, trick_format, trick_draw_policy)
+            .into_iter()
+            .min_by(|a, b| cheapest(trump, a, b))
+    }
+
+    /// Lists up to `limit` sets of cards from `hand` which follow suit as far
+    /// as possible, without checking the rest of the format.
+    fn candidate_follows(
+        tf: &TrickFormat,
+        hand: &HashMap<Card, usize>,
+        limit: usize,
+    ) -> Vec<Vec<Card>> {
+        let trump = tf.trump;
+        let mut counts = hand.iter().map(|(c, ct)| (*c, *ct)).collect::<Vec<_>>();
+        // Sort the cards so that equally good plays are always broken the same
+        // way.
+        counts.sort_by(|(a, _), (b, _)| {
+            trump
+                .compare(*a, *b)
+                .then_with(|| a.as_char().cmp(&b.as_char()))
+        });
+        let (in_suit, off_suit): (Vec<_>, Vec<_>) = counts
+            .into_iter()
+            .partition(|(c, _)| trump.effective_suit(*c) == tf.suit);
+
+        // Players have to follow suit as far as they can, so only the cards
+        // beyond that are chosen from the rest of the hand.
+        let num_in_suit = in_suit.iter().map(|(_, ct)| ct).sum::<usize>();
+        let mut candidates = vec![];
+        let mut collect = |cards: &[Card]| {
+            candidates.push(cards.to_vec());
+            candidates.len() < limit
+        };
+        if num_in_suit >= tf.size() {
+            for_each_combination(&in_suit, tf.size(), &mut vec![], &mut collect);
+        } else {
+            let mut all_in_suit = Card::cards(in_suit.iter().map(|(c, ct)| (c, ct)))
+                .copied()
+                .collect::<Vec<_>>();
+            for_each_combination(
+                &off_suit,
+                tf.size() - num_in_suit,
+                &mut all_in_suit,
+                &mut collect,
+            );
+        }
+        candidates
+    }
+
+    /**
+     * Explains how the winner of the trick was determined, play-by-play.
+     */
+    pub fn explain_winner(
+        &self,
+        trump: Trump,
+        throw_eval_policy: ThrowEvaluationPolicy,
+        throw_tie_break_policy: ThrowTieBreakPolicy,
+    ) -> WinnerExplanation {
+        Self::explain(
+            self.trick_format.as_ref(),
+            &self.played_cards,
+            trump,
+            throw_eval_policy,
+            throw_tie_break_policy,
+        )
+    }
+
     fn winner(
         trick_format: Option<&'_ TrickFormat>,
         played_cards: &'_ [PlayedCards],
         trump: Trump,
         throw_eval_policy: ThrowEvaluationPolicy,
+        throw_tie_break_policy: ThrowTieBreakPolicy,
     ) -> Option<PlayerID> {
+        Self::explain(
+            trick_format,
+            played_cards,
+            trump,
+            throw_eval_policy,
+            throw_tie_break_policy,
+        )
+        .winner
+    }
+
+    fn explain(
+        trick_format: Option<&'_ TrickFormat>,
+        played_cards: &'_ [PlayedCards],
+        trump: Trump,
+        throw_eval_policy: ThrowEvaluationPolicy,
+        throw_tie_break_policy: ThrowTieBreakPolicy,
+    ) -> WinnerExplanation {
         match trick_format {
             Some(tf) => {
                 let mut winner = (0, tf.units.to_vec());
-
-                for (idx, pc) in played_cards.iter().enumerate().skip(1) {
-                    if let Ok(m) = tf.matches(&pc.cards) {
-                        let greater = match throw_eval_policy {
-                            ThrowEvaluationPolicy::All => {
-                                m.iter().zip(winner.1.iter()).all(|(n, w)| {
-                                    trump
-                                        .compare_effective(n.first_card().card, w.first_card().card)
-                                        == Ordering::Greater
-                                })
-                            }
-                            ThrowEvaluationPolicy::Highest => {
-                                let n_max = m
-                                    .iter()
-                                    .map(|u| u.last_card())
-                                    .max()
-                                    .expect("trick format cannot be empty");
-                                let w_max = winner
-                                    .1
-                                    .iter()
-                                    .map(|u| u.last_card())
-                                    .max()
-                                    .expect("trick format cannot be empty");
-                                trump.compare_effective(n_max.card, w_max.card) == Ordering::Greater
-                            }
-                            ThrowEvaluationPolicy::TrickUnitLength => {
-                                // Don't worry about single cards if this is a throw with at
-                                // least one unit that is longer than a single card, but do
-                                // evaluate them if it isn't!
-                                let skip_single_cards =
-                                    m.len() > 1 && m.iter().any(|n| n.size() > 1);
-
-                                let mut comparisons = m
-                                    .iter()
-                                    .zip(winner.1.iter())
-                                    .filter(|(n, _)| !skip_single_cards || n.size() > 1)
-                                    .map(|(n, w)| {
-                                        (
-                                            n.size(),
-                                            trump.compare_effective(
-                                                n.first_card().card,
-                                                w.first_card().card,
-                                            ),
-                                        )
-                                    })
-                                    .collect::<Vec<_>>();
-                                // Compare by size first, then try to skip equal-comparisons.
-                                comparisons
-                                    .sort_by_key(|(s, c)| (-(*s as isize), *c == Ordering::Equal));
-                                let mut iter = comparisons.into_iter().map(|(_, c)| c);
-                                loop {
-                                    match iter.next() {
-                                        Some(Ordering::Equal) => {}
-                                        Some(Ordering::Greater) => break true,
-                                        Some(Ordering::Less) | None => break false,
-                                    }
-                                }
+                let mut plays = Vec::with_capacity(played_cards.len());
+
+                for (idx, pc) in played_cards.iter().enumerate() {
+                    if idx == 0 {
+                        plays.push(PlayExplanation {
+                            id: pc.id,
+                            matches_format: true,
+                            compared_against: None,
+                            beats_winner: false,
+                        });
+                        continue;
+                    }
+                    let compared_against = played_cards[winner.0].id;
+                    let (matches_format, greater) = match tf.matches(&pc.cards) {
+                        Ok(m) => {
+                            let greater = Self::beats(&m, &winner.1, trump, throw_eval_policy)
+                                || (throw_tie_break_policy == ThrowTieBreakPolicy::LastPlayed
+                                    && Self::ties(&m, &winner.1, trump));
+                            if greater {
+                                winner = (idx, m);
                             }
-                        };
-                        if greater {
-                            winner = (idx, m);
+                            (true, greater)
                         }
+                        Err(_) => (false, false),
+                    };
+                    plays.push(PlayExplanation {
+                        id: pc.id,
+                        matches_format,
+                        compared_against: Some(compared_against),
+                        beats_winner: greater,
+                    });
+                }
+                WinnerExplanation {
+                    plays,
+                    winner: Some(played_cards[winner.0].id),
+                }
+            }
+            None => WinnerExplanation {
+                plays: vec![],
+                winner: None,
+            },
+        }
+    }
+
+    /// Whether `proposed` is a throw which is exactly as strong as `current`
+    /// in every unit.
+    fn ties(proposed: &'_ [TrickUnit], current: &'_ [TrickUnit], trump: Trump) -> bool {
+        proposed.len() > 1
+            && proposed.iter().zip(current.iter()).all(|(n, w)| {
+                trump.compare_effective(n.first_card().card, w.first_card().card) == Ordering::Equal
+            })
+    }
+
+    fn beats(
+        proposed: &'_ [TrickUnit],
+        current: &'_ [TrickUnit],
+        trump: Trump,
+        throw_eval_policy: ThrowEvaluationPolicy,
+    ) -> bool {
+        match throw_eval_policy {
+            ThrowEvaluationPolicy::All => proposed.iter().zip(current.iter()).all(|(n, w)| {
+                trump.compare_effective(n.first_card().card, w.first_card().card)
+                    == Ordering::Greater
+            }),
+            ThrowEvaluationPolicy::Highest => {
+                let n_max = proposed
+                    .iter()
+                    .map(|u| u.last_card())
+                    .max()
+                    .expect("trick format cannot be empty");
+                let w_max = current
+                    .iter()
+                    .map(|u| u.last_card())
+                    .max()
+                    .expect("trick format cannot be empty");
+                trump.compare_effective(n_max.card, w_max.card) == Ordering::Greater
+            }
+            ThrowEvaluationPolicy::TrickUnitLength => {
+                // Don't worry about single cards if this is a throw with at
+                // least one unit that is longer than a single card, but do
+                // evaluate them if it isn't!
+                let skip_single_cards = proposed.len() > 1 && proposed.iter().any(|n| n.size() > 1);
+
+                let mut comparisons = proposed
+                    .iter()
+                    .zip(current.iter())
+                    .filter(|(n, _)| !skip_single_cards || n.size() > 1)
+                    .map(|(n, w)| {
+                        (
+                            n.size(),
+                            trump.compare_effective(n.first_card().card, w.first_card().card),
+                        )
+                    })
+                    .collect::<Vec<_>>();
+                // Compare by size first, then try to skip equal-comparisons.
+                comparisons.sort_by_key(|(s, c)| (-(*s as isize), *c == Ordering::Equal));
+                let mut iter = comparisons.into_iter().map(|(_, c)| c);
+                loop {
+                    match iter.next() {
+                        Some(Ordering::Equal) => {}
+                        Some(Ordering::Greater) => break true,
+                        Some(Ordering::Less) | None => break false,
                     }
                 }
-                Some(played_cards[winner.0].id)
             }
-            None => None,
+            ThrowEvaluationPolicy::Cumulative => {
+                // Rank each unit by how many cards in a deck are strictly
+                // weaker than it, so that ranks can be added up across the
+                // units in a throw. For a single unit, this is the same as
+                // comparing the units directly.
+                let rank = |unit: &TrickUnit| {
+                    let card = unit.first_card().card;
+                    FULL_DECK
+                        .iter()
+                        .filter(|c| trump.compare_effective(**c, card) == Ordering::Less)
+                        .count()
+                };
+                proposed.iter().map(rank).sum::<usize>() > current.iter().map(rank).sum::<usize>()
+            }
         }
     }
 }
 
+/// A step-by-step account of how the winner of a trick was determined.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct WinnerExplanation {
+    /// One entry per play, in the order the cards were played.
+    pub plays: Vec<PlayExplanation>,
+    pub winner: Option<PlayerID>,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct PlayExplanation {
+    pub id: PlayerID,
+    /// Whether the play matched the format set by the leading play. Plays which
+    /// don't match the format can't win the trick.
+    pub matches_format: bool,
+    /// The player who was winning the trick when this play was evaluated, or
+    /// `None` for the leading play.
+    pub compared_against: Option<PlayerID>,
+    /// Whether this play took over as the winner of the trick.
+    pub beats_winner: bool,
+}
+
+/// Compares two plays by the points that they give away, and then by how
+/// strong the cards are.
+fn cheapest(trump: Trump, a: &[Card], b: &[Card]) -> Ordering {
+    let points = |cards: &[Card]| cards.iter().flat_map(|c| c.points()).sum::<usize>();
+    let num_trumps = |cards: &[Card]| {
+        cards
+            .iter()
+            .filter(|c| trump.effective_suit(**c) == EffectiveSuit::Trump)
+            .count()
+    };
+    points(a)
+        .cmp(&points(b))
+        .then(num_trumps(a).cmp(&num_trumps(b)))
+        .then_with(|| {
+            a.iter()
+                .zip(b.iter())
+                .map(|(x, y)| trump.compare(*x, *y))
+                .find(|o| *o != Ordering::Equal)
+                .unwrap_or(Ordering::Equal)
+        })
+}
+
 pub struct TrickEnded {
     pub winner: PlayerID,
     pub points: Vec<Card>,
     pub largest_trick_unit_size: usize,
+    /// Whether the trick format includes a tractor.
+    pub has_tractor: bool,
     pub failed_throw_size: usize,
 }
 
@@ -947,7 +1675,8 @@ impl UnitLike {
             |counts, matching| match trick_draw_policy {
                 TrickDrawPolicy::NoFormatBasedDraw
                 | TrickDrawPolicy::NoProtections
-                | TrickDrawPolicy::OnlyDrawTractorOnTractor => true,
+                | TrickDrawPolicy::OnlyDrawTractorOnTractor
+                | TrickDrawPolicy::OnlyDrawTractorOnTractorStrict => true,
                 TrickDrawPolicy::LongerTuplesProtected => !matching
                     .iter()
                     .any(|(card, count)| counts.get(card).copied().unwrap_or_default() > *count),
@@ -1145,28 +1874,59 @@ fn find_plays_inner(
     }
 }
 
+/// Calls `f` with `prefix` extended by each distinct multiset of `n` cards
+/// drawn from `pool`, which maps each distinct card to the number of copies
+/// available, stopping early if `f` returns `false`.
+pub(crate) fn for_each_combination(
+    pool: &[(Card, usize)],
+    n: usize,
+    prefix: &mut Vec<Card>,
+    f: &mut impl FnMut(&[Card]) -> bool,
+) -> bool {
+    if n == 0 {
+        return f(prefix);
+    }
+    let ((card, count), rest) = match pool.split_first() {
+        Some(split) => split,
+        None => return true,
+    };
+    for k in (0..=n.min(*count)).rev() {
+        prefix.extend((0..k).map(|_| *card));
+        let keep_going = for_each_combination(rest, n - k, prefix, f);
+        prefix.truncate(prefix.len() - k);
+        if !keep_going {
+            return false;
+        }
+    }
+    true
+}
+
 #[cfg(test)]
 mod tests {
     use std::collections::HashSet;
     use std::iter::FromIterator;
 
-    use crate::hands::Hands;
+    use crate::hands::{HandError, Hands};
+    use crate::message::MessageVariant;
     use crate::types::{
         cards::{
-            C_10, C_4, C_5, C_6, C_7, C_8, C_A, C_K, D_4, D_A, D_K, H_2, H_3, H_4, H_5, H_7, H_8,
-            H_9, H_A, H_K, S_10, S_2, S_3, S_4, S_5, S_6, S_7, S_8, S_9, S_A, S_J, S_K, S_Q,
+            C_10, C_4, C_5, C_6, C_7, C_8, C_A, C_K, D_4, D_5, D_A, D_K, H_2, H_3, H_4, H_5, H_6,
+            H_7, H_8, H_9, H_A, H_J, H_K, H_Q, S_10, S_2, S_3, S_4, S_5, S_6, S_7, S_8, S_9, S_A,
+            S_J, S_K, S_Q,
         },
-        Card, EffectiveSuit, Number, PlayerID, Suit, Trump,
+        Card, EffectiveSuit, Number, PermanentTrumps, PlayerID, Suit, Trump, FULL_DECK,
     };
 
     use super::{
-        OrderedCard, PlayCards, ThrowEvaluationPolicy, TractorRequirements, Trick, TrickDrawPolicy,
-        TrickEnded, TrickError, TrickFormat, TrickUnit, UnitLike,
+        FollowObligation, FollowRequirement, OrderedCard, PlayCards, ThrowCheckResult,
+        ThrowEvaluationPolicy, ThrowTieBreakPolicy, TractorRequirements, Trick, TrickDrawPolicy,
+        TrickEnded, TrickError, TrickFormat, TrickUnit, UnitLike, MAX_FOLLOW_CANDIDATES,
     };
 
     const TRUMP: Trump = Trump::Standard {
         number: Number::Four,
         suit: Suit::Spades,
+        permanent: PermanentTrumps::NONE,
     };
     const P1: PlayerID = PlayerID(1);
     const P2: PlayerID = PlayerID(2);
@@ -1196,9 +1956,11 @@ mod tests {
                 cards: $cards,
                 trick_draw_policy: $tdp,
                 throw_eval_policy: $tep,
+                throw_tie_break_policy: ThrowTieBreakPolicy::FirstPlayed,
                 format_hint: $fmt,
                 hide_throw_halting_player: $h,
                 tractor_requirements: TractorRequirements::default(),
+                throws_allowed: true,
             }
         };
         ($id:expr, $hands:expr, $cards:expr, $tdp:expr, $tep:expr) => {
@@ -1208,9 +1970,11 @@ mod tests {
                 cards: $cards,
                 trick_draw_policy: $tdp,
                 throw_eval_policy: $tep,
+                throw_tie_break_policy: ThrowTieBreakPolicy::FirstPlayed,
                 format_hint: None,
                 hide_throw_halting_player: false,
                 tractor_requirements: TractorRequirements::default(),
+                throws_allowed: true,
             }
         };
         ($id:expr, $hands:expr, $cards:expr, $tep:expr) => {
@@ -1220,9 +1984,11 @@ mod tests {
                 cards: $cards,
                 trick_draw_policy: TrickDrawPolicy::NoProtections,
                 throw_eval_policy: $tep,
+                throw_tie_break_policy: ThrowTieBreakPolicy::FirstPlayed,
                 format_hint: None,
                 hide_throw_halting_player: false,
                 tractor_requirements: TractorRequirements::default(),
+                throws_allowed: true,
             }
         };
         ($id:expr, $hands:expr, $cards:expr) => {
@@ -1232,9 +1998,11 @@ mod tests {
                 cards: $cards,
                 trick_draw_policy: TrickDrawPolicy::NoProtections,
                 throw_eval_policy: ThrowEvaluationPolicy::All,
+                throw_tie_break_policy: ThrowTieBreakPolicy::FirstPlayed,
                 format_hint: None,
                 hide_throw_halting_player: false,
                 tractor_requirements: TractorRequirements::default(),
+                throws_allowed: true,
             }
         };
     }
@@ -1297,6 +2065,94 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_from_single_unit() {
+        let tr = TractorRequirements::default();
+        let unit = TrickUnit::from_single_unit(TRUMP, tr, vec![H_2, H_2, H_3, H_3]).unwrap();
+        assert!(unit.is_tractor());
+        assert_eq!(unit.size(), 4);
+
+        let unit = TrickUnit::from_single_unit(TRUMP, tr, vec![H_7, H_7]).unwrap();
+        assert!(unit.is_repeated());
+        assert_eq!(unit.size(), 2);
+        assert!(TrickUnit::from_single_unit(TRUMP, tr, vec![H_7]).is_some());
+
+        // Trump-number cards of different suits can still form a tractor.
+        assert!(TrickUnit::from_single_unit(TRUMP, tr, vec![H_4, H_4, S_4, S_4]).is_some());
+
+        assert!(TrickUnit::from_single_unit(TRUMP, tr, vec![H_2, H_2, H_3, H_3, H_7]).is_none());
+        assert!(TrickUnit::from_single_unit(TRUMP, tr, vec![H_2, H_2, H_3, H_3, H_3]).is_none());
+        assert!(TrickUnit::from_single_unit(TRUMP, tr, vec![H_2, H_3]).is_none());
+        assert!(TrickUnit::from_single_unit(TRUMP, tr, vec![H_2, H_2, C_4, C_4]).is_none());
+        assert!(TrickUnit::from_single_unit(TRUMP, tr, vec![]).is_none());
+
+        // A tractor which is too short under the requirements is two pairs.
+        let tr = TractorRequirements {
+            min_count: 2,
+            min_length: 3,
+        };
+        assert!(TrickUnit::from_single_unit(TRUMP, tr, vec![H_2, H_2, H_3, H_3]).is_none());
+    }
+
+    #[test]
+    fn test_throws_not_allowed() {
+        let setup = || {
+            let mut hands = Hands::new(vec![P1, P2, P3, P4]);
+            hands.add(P1, vec![H_A, H_K, H_3, H_3]).unwrap();
+            hands.add(P2, vec![S_5, S_6, S_7, S_8]).unwrap();
+            hands.add(P3, vec![S_5, S_6, S_7, S_8]).unwrap();
+            hands.add(P4, vec![S_5, S_6, S_7, S_8]).unwrap();
+            (hands, Trick::new(TRUMP, vec![P1, P2, P3, P4]))
+        };
+        let play = |trick: &mut Trick, hands: &mut Hands, cards: &[Card], throws_allowed| {
+            trick.play_cards(PlayCards {
+                throws_allowed,
+                ..pc!(P1, hands, cards)
+            })
+        };
+
+        let (mut hands, mut trick) = setup();
+        assert!(matches!(
+            play(&mut trick, &mut hands, &[H_A, H_K], false),
+            Err(TrickError::ThrowsNotAllowed)
+        ));
+        assert!(matches!(
+            play(&mut trick, &mut hands, &[H_A, H_3, H_3], false),
+            Err(TrickError::ThrowsNotAllowed)
+        ));
+        // Leading a single unit is still fine.
+        play(&mut trick, &mut hands, &[H_3, H_3], false).unwrap();
+
+        let (mut hands, mut trick) = setup();
+        play(&mut trick, &mut hands, &[H_A, H_K], true).unwrap();
+        assert!(trick.trick_format().unwrap().is_throw());
+    }
+
+    #[test]
+    fn test_analyze_defensive_discard() {
+        let tr = TractorRequirements::default();
+        let hand = |cards: &[Card]| {
+            let mut hands = Hands::new(vec![P1]);
+            hands.add(P1, cards.iter().copied()).unwrap();
+            hands.get(P1).unwrap().clone()
+        };
+        let discard = |lead: &[Card], cards: &[Card]| {
+            let tf = TrickFormat::from_cards(TRUMP, tr, lead, None).unwrap();
+            Trick::analyze_defensive_discard(&hand(cards), &tf, TrickDrawPolicy::NoProtections)
+        };
+
+        // Following suit, shed the card without points.
+        assert_eq!(discard(&[H_3], &[H_K, H_5, H_7, S_2]), Some(vec![H_7]));
+        // Pairs have to be followed with a pair if possible.
+        assert_eq!(
+            discard(&[H_3, H_3], &[H_K, H_K, H_6, H_6, H_8]),
+            Some(vec![H_6, H_6])
+        );
+        // Out of the suit, avoid points and then avoid wasting trumps.
+        assert_eq!(discard(&[H_3], &[S_K, C_10, C_7, D_5]), Some(vec![C_7]));
+        assert_eq!(discard(&[H_3], &[S_6, C_6]), Some(vec![C_6]));
+    }
+
     #[test]
     fn test_play_singles_trick() {
         let run = |tep: ThrowEvaluationPolicy| {
@@ -1392,6 +2248,163 @@ mod tests {
         run(ThrowEvaluationPolicy::TrickUnitLength);
     }
 
+    #[test]
+    fn test_follow_requirement() {
+        let mut hands = Hands::new(vec![P1, P2, P3, P4]);
+        hands.add(P1, vec![H_5, H_5, H_7]).unwrap();
+        let mut trick = Trick::new(TRUMP, vec![P1, P2, P3, P4]);
+        assert_eq!(trick.follow_requirement(), None);
+
+        trick
+            .play_cards(pc!(P1, &mut hands, &[H_5, H_5], ThrowEvaluationPolicy::All))
+            .unwrap();
+        assert_eq!(
+            trick.follow_requirement(),
+            Some(FollowRequirement {
+                suit: EffectiveSuit::Hearts,
+                num_cards: 2,
+                units: vec![UnitLike {
+                    adjacent_tuples: vec![2]
+                }],
+            })
+        );
+    }
+
+    #[test]
+    fn test_is_valid_lead() {
+        let tr = TractorRequirements::default();
+        assert!(Trick::is_valid_lead(&[H_5, H_5, H_7], TRUMP, tr, None).is_ok());
+        assert!(matches!(
+            Trick::is_valid_lead(&[], TRUMP, tr, None),
+            Err(TrickError::WrongNumberOfCards)
+        ));
+        // Hearts and clubs can't be led together, but the trump-numbered heart
+        // is a trump, so it can be led with spades.
+        assert!(matches!(
+            Trick::is_valid_lead(&[H_5, C_5], TRUMP, tr, None),
+            Err(TrickError::WrongNumberOfSuits)
+        ));
+        assert!(Trick::is_valid_lead(&[H_4, S_5], TRUMP, tr, None).is_ok());
+        // The cards can't be decomposed into the requested tractor.
+        assert!(matches!(
+            Trick::is_valid_lead(
+                &[H_5, H_5, H_8, H_8],
+                TRUMP,
+                tr,
+                Some(&[TrickUnit::Tractor {
+                    count: 2,
+                    members: vec![oc!(H_5), oc!(H_8)],
+                }]),
+            ),
+            Err(TrickError::NonMatchingProposal)
+        ));
+    }
+
+    #[test]
+    fn test_validate_grouping() {
+        let tr = TractorRequirements::default();
+        let mut hands = Hands::new(vec![P1]);
+        hands
+            .add(P1, vec![H_5, H_5, H_6, H_6, H_8, H_8, H_9, H_9])
+            .unwrap();
+        let hand = hands.get(P1).unwrap();
+        let tractor = |a, b| TrickUnit::Tractor {
+            count: 2,
+            members: vec![a, b],
+        };
+
+        let format =
+            TrickFormat::validate_grouping(TRUMP, tr, hand, &[tractor(oc!(H_5), oc!(H_6))], None)
+                .unwrap();
+        assert_eq!(format.units(), &[tractor(oc!(H_5), oc!(H_6))]);
+
+        // Both pairs claim the same two fives, but the hand only has two.
+        assert!(matches!(
+            TrickFormat::validate_grouping(
+                TRUMP,
+                tr,
+                hand,
+                &[
+                    TrickUnit::Repeated {
+                        count: 2,
+                        card: oc!(H_5),
+                    },
+                    TrickUnit::Repeated {
+                        count: 2,
+                        card: oc!(H_5),
+                    },
+                ],
+                None,
+            ),
+            Err(TrickError::HandError {
+                source: HandError::CardsNotFound
+            })
+        ));
+
+        // Following a tractor needs a tractor, not two loose pairs.
+        let leading = TrickFormat::from_cards(TRUMP, tr, &[H_J, H_J, H_Q, H_Q], None).unwrap();
+        assert!(TrickFormat::validate_grouping(
+            TRUMP,
+            tr,
+            hand,
+            &[tractor(oc!(H_8), oc!(H_9))],
+            Some(&leading),
+        )
+        .is_ok());
+        assert!(matches!(
+            TrickFormat::validate_grouping(
+                TRUMP,
+                tr,
+                hand,
+                &[
+                    TrickUnit::Repeated {
+                        count: 2,
+                        card: oc!(H_5),
+                    },
+                    TrickUnit::Repeated {
+                        count: 2,
+                        card: oc!(H_9),
+                    },
+                ],
+                Some(&leading),
+            ),
+            Err(TrickError::NonMatchingPlay)
+        ));
+    }
+
+    #[test]
+    fn test_play_wrong_number_of_cards() {
+        let mut hands = Hands::new(vec![P1, P2, P3, P4]);
+        hands.add(P1, vec![H_5, H_5]).unwrap();
+        hands.add(P2, vec![H_3, H_7, H_7, H_8]).unwrap();
+        let mut trick = Trick::new(TRUMP, vec![P1, P2, P3, P4]);
+        trick
+            .play_cards(pc!(P1, &mut hands, &[H_5, H_5], ThrowEvaluationPolicy::All))
+            .unwrap();
+
+        let check = |cards: &[Card]| {
+            trick
+                .can_play_cards(P2, &hands, cards, TrickDrawPolicy::NoProtections)
+                .unwrap_err()
+        };
+        assert!(matches!(
+            check(&[H_7, H_7, H_8]),
+            TrickError::TooManyCards {
+                required: 2,
+                proposed: 3
+            }
+        ));
+        assert!(matches!(
+            check(&[H_7]),
+            TrickError::TooFewCards {
+                required: 2,
+                proposed: 1
+            }
+        ));
+        // The right number of cards, but p2 is holding a pair.
+        assert!(matches!(check(&[H_3, H_8]), TrickError::IllegalPlay));
+    }
+
     #[test]
     fn test_play_tractor_trick() {
         let run = |tep: ThrowEvaluationPolicy| {
@@ -1460,6 +2473,26 @@ mod tests {
         assert_eq!(points, vec![]);
     }
 
+    #[test]
+    fn test_play_permanent_trump() {
+        // The 2s are trump, even though the 4s are being played.
+        let trump = TRUMP.with_permanent(vec![Number::Two].into());
+        let mut hands = Hands::new(vec![P1, P2, P3, P4]);
+        hands.add(P1, vec![H_A]).unwrap();
+        hands.add(P2, vec![H_2]).unwrap();
+        hands.add(P3, vec![H_K]).unwrap();
+        hands.add(P4, vec![S_A]).unwrap();
+        hands.set_trump(trump);
+        let mut trick = Trick::new(trump, vec![P1, P2, P3, P4]);
+        trick.play_cards(pc!(P1, &mut hands, &[H_A])).unwrap();
+        trick.play_cards(pc!(P2, &mut hands, &[H_2])).unwrap();
+        trick.play_cards(pc!(P3, &mut hands, &[H_K])).unwrap();
+        trick.play_cards(pc!(P4, &mut hands, &[S_A])).unwrap();
+        let TrickEnded { winner, points, .. } = trick.complete().unwrap();
+        assert_eq!(winner, P2);
+        assert_eq!(points, vec![H_K]);
+    }
+
     #[test]
     fn test_play_throw_trick_failure() {
         let mut hands = Hands::new(vec![P1, P2, P3, P4]);
@@ -1487,6 +2520,83 @@ mod tests {
         assert_eq!(failed_throw_size, 3);
     }
 
+    #[test]
+    fn test_check_throw_failure() {
+        let mut hands = Hands::new(vec![P1, P2, P3, P4]);
+        hands.add(P1, vec![H_8, H_8, H_7, H_2]).unwrap();
+        hands.add(P2, vec![H_2, S_2, S_2, S_2]).unwrap();
+        hands.add(P3, vec![S_2, S_2, S_3, S_4]).unwrap();
+        hands.add(P4, vec![S_4, S_4, S_4, H_3]).unwrap();
+        let mut trick = Trick::new(TRUMP, vec![P1, P2, P3, P4]);
+        let check = trick
+            .check_throw(
+                P1,
+                &hands,
+                &[H_8, H_8, H_7, H_2],
+                TractorRequirements::default(),
+                None,
+            )
+            .unwrap();
+        assert_eq!(
+            check,
+            ThrowCheckResult::Fails {
+                forced_cards: vec![H_2],
+                better_player: P4,
+            }
+        );
+        assert!(matches!(
+            trick.check_throw(P2, &hands, &[H_2], TractorRequirements::default(), None),
+            Err(TrickError::OutOfOrder)
+        ));
+
+        let msgs = trick
+            .play_cards(pc!(P1, &mut hands, &[H_8, H_8, H_7, H_2]))
+            .unwrap();
+        assert!(matches!(
+            msgs[0],
+            MessageVariant::ThrowFailed {
+                better_player: Some(P4),
+                ..
+            }
+        ));
+        assert_eq!(trick.played_cards()[0].cards, vec![H_2]);
+        assert_eq!(trick.played_cards()[0].better_player, Some(P4));
+
+        // Once the trick has been led, following is never a throw.
+        assert_eq!(
+            trick
+                .check_throw(P2, &hands, &[H_2], TractorRequirements::default(), None)
+                .unwrap(),
+            ThrowCheckResult::Succeeds
+        );
+    }
+
+    #[test]
+    fn test_check_throw_success() {
+        let mut hands = Hands::new(vec![P1, P2, P3, P4]);
+        hands.add(P1, vec![H_A, H_A, H_K, H_2]).unwrap();
+        hands.add(P2, vec![H_2, S_2, S_2, S_2]).unwrap();
+        hands.add(P3, vec![S_2, S_2, S_3, S_4]).unwrap();
+        hands.add(P4, vec![S_4, S_4, S_4, H_3]).unwrap();
+        let mut trick = Trick::new(TRUMP, vec![P1, P2, P3, P4]);
+        let check = trick
+            .check_throw(
+                P1,
+                &hands,
+                &[H_A, H_A, H_K],
+                TractorRequirements::default(),
+                None,
+            )
+            .unwrap();
+        assert_eq!(check, ThrowCheckResult::Succeeds);
+
+        trick
+            .play_cards(pc!(P1, &mut hands, &[H_A, H_A, H_K]))
+            .unwrap();
+        assert_eq!(trick.played_cards()[0].cards.len(), 3);
+        assert_eq!(trick.played_cards()[0].better_player, None);
+    }
+
     #[test]
     fn test_play_throw_tractor_extra_cards() {
         let mut hands = Hands::new(vec![P1, P2, P3, P4]);
@@ -1856,6 +2966,79 @@ mod tests {
         ));
     }
 
+    #[test]
+    fn test_obligations_across_hand() {
+        let tf = TrickFormat::from_cards(TRUMP, TractorRequirements::default(), &[C_7, C_7], None)
+            .unwrap();
+        let pair = tf.units.iter().map(UnitLike::from).collect::<Vec<_>>();
+
+        // Rich in hearts, one diamond, void in clubs and trump.
+        let hand = Card::count(vec![H_5, H_5, H_7, H_8, H_9, D_A]);
+        let obligations = tf.obligations_across_hand(&hand, TrickDrawPolicy::NoProtections);
+
+        // Spades are trump, so they aren't a separate suit.
+        assert_eq!(obligations.len(), 4);
+        assert_eq!(obligations[&EffectiveSuit::Clubs], FollowObligation::Void);
+        assert_eq!(obligations[&EffectiveSuit::Trump], FollowObligation::Void);
+        assert_eq!(
+            obligations[&EffectiveSuit::Diamonds],
+            FollowObligation::PlayAll { cards: vec![D_A] }
+        );
+        assert_eq!(
+            obligations[&EffectiveSuit::Hearts],
+            FollowObligation::MatchFormat { format: pair }
+        );
+        assert_eq!(
+            tf.required_follow(&hand, TrickDrawPolicy::NoProtections),
+            FollowObligation::Void
+        );
+
+        // Without the pair, any two hearts will do.
+        let hand = Card::count(vec![H_5, H_7, H_8, H_9]);
+        let obligations = tf.obligations_across_hand(&hand, TrickDrawPolicy::NoProtections);
+        assert_eq!(
+            obligations[&EffectiveSuit::Hearts],
+            FollowObligation::AnyInSuit
+        );
+    }
+
+    #[test]
+    fn test_protected_units() {
+        let tf = TrickFormat {
+            suit: EffectiveSuit::Trump,
+            trump: TRUMP,
+            units: vec![TrickUnit::Repeated {
+                card: oc!(S_6),
+                count: 2,
+            }],
+        };
+        let hand = Card::count(vec![
+            S_2, S_2, S_2, S_3, S_3, S_3, S_5, S_5, S_7, S_9, S_9, S_9, H_9, H_9, H_9,
+        ]);
+        // The triples can't be drawn out by a pair, but the pair can.
+        assert_eq!(
+            tf.protected_units(&hand),
+            vec![
+                UnitLike {
+                    adjacent_tuples: vec![3, 3]
+                },
+                UnitLike {
+                    adjacent_tuples: vec![3]
+                },
+            ]
+        );
+
+        let tf = TrickFormat {
+            suit: EffectiveSuit::Trump,
+            trump: TRUMP,
+            units: vec![TrickUnit::Repeated {
+                card: oc!(S_6),
+                count: 3,
+            }],
+        };
+        assert_eq!(tf.protected_units(&hand), vec![]);
+    }
+
     #[test]
     fn test_protected_tuple() {
         let tf = TrickFormat {
@@ -1918,6 +3101,7 @@ mod tests {
         const HEART_TRUMP: Trump = Trump::Standard {
             number: Number::Four,
             suit: Suit::Hearts,
+            permanent: PermanentTrumps::NONE,
         };
         let tf = TrickFormat {
             suit: EffectiveSuit::Spades,
@@ -1961,11 +3145,106 @@ mod tests {
         ));
     }
 
+    #[test]
+    fn test_strict_tractor_protection() {
+        const HEART_TRUMP: Trump = Trump::Standard {
+            number: Number::Four,
+            suit: Suit::Hearts,
+            permanent: PermanentTrumps::NONE,
+        };
+        let tf = TrickFormat {
+            suit: EffectiveSuit::Spades,
+            trump: HEART_TRUMP,
+            units: vec![
+                TrickUnit::Tractor {
+                    members: vec![oc!(S_9, HEART_TRUMP), oc!(S_9, HEART_TRUMP)],
+                    count: 2,
+                },
+                TrickUnit::Repeated {
+                    card: oc!(S_K, HEART_TRUMP),
+                    count: 1,
+                },
+            ],
+        };
+        // The decomposition stops once the tractor is down to pairs.
+        let decomposition = |policy| {
+            tf.decomposition(policy)
+                .map(|requirements| {
+                    requirements
+                        .into_iter()
+                        .map(|u| u.adjacent_tuples)
+                        .collect::<Vec<_>>()
+                })
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(
+            decomposition(TrickDrawPolicy::OnlyDrawTractorOnTractorStrict),
+            vec![
+                vec![vec![2, 2], vec![1]],
+                vec![vec![2], vec![2], vec![1]],
+                vec![vec![2], vec![1], vec![1], vec![1]],
+            ]
+        );
+        assert_eq!(
+            decomposition(TrickDrawPolicy::OnlyDrawTractorOnTractor).last(),
+            Some(&vec![vec![1]; 5])
+        );
+
+        // A follower holding exactly one pair is still forced to play it.
+        let hand = Card::count(vec![S_3, S_5, S_10, S_J, S_Q, S_8, S_8]);
+        assert!(!tf.is_legal_play(
+            &hand,
+            &[S_3, S_5, S_10, S_J, S_Q],
+            TrickDrawPolicy::OnlyDrawTractorOnTractorStrict
+        ));
+        assert!(tf.is_legal_play(
+            &hand,
+            &[S_3, S_5, S_10, S_8, S_8],
+            TrickDrawPolicy::OnlyDrawTractorOnTractorStrict
+        ));
+        let hand = Card::count(vec![S_3, S_5, S_10, S_J, S_Q, S_6, S_8, S_8, S_8]);
+        assert!(tf.is_legal_play(
+            &hand,
+            &[S_3, S_6, S_8, S_8, S_8],
+            TrickDrawPolicy::OnlyDrawTractorOnTractorStrict
+        ));
+
+        // Two separate pairs still have to be played.
+        let hand = Card::count(vec![S_3, S_5, S_6, S_6, S_10, S_10, S_Q, S_K]);
+        assert!(!tf.is_legal_play(
+            &hand,
+            &[S_3, S_5, S_6, S_10, S_Q],
+            TrickDrawPolicy::OnlyDrawTractorOnTractorStrict
+        ));
+        assert!(tf.is_legal_play(
+            &hand,
+            &[S_6, S_6, S_10, S_10, S_3],
+            TrickDrawPolicy::OnlyDrawTractorOnTractorStrict
+        ));
+
+        // Formats without a tractor are unaffected.
+        let tf = TrickFormat {
+            suit: EffectiveSuit::Spades,
+            trump: HEART_TRUMP,
+            units: vec![TrickUnit::Repeated {
+                card: oc!(S_9, HEART_TRUMP),
+                count: 3,
+            }],
+        };
+        let hand = Card::count(vec![S_3, S_5, S_8, S_8]);
+        assert!(!tf.is_legal_play(
+            &hand,
+            &[S_3, S_5, S_8],
+            TrickDrawPolicy::OnlyDrawTractorOnTractorStrict
+        ));
+    }
+
     #[test]
     fn test_play_throw_tractor_with_other_tractor_in_game() {
         let trump = Trump::Standard {
             number: Number::Four,
             suit: Suit::Hearts,
+            permanent: PermanentTrumps::NONE,
         };
 
         let mut hands = Hands::new(vec![P1, P2, P3, P4]);
@@ -2001,6 +3280,7 @@ mod tests {
         let trump = Trump::Standard {
             number: Number::King,
             suit: Suit::Spades,
+            permanent: PermanentTrumps::NONE,
         };
 
         let p1_hand = vec![S_7, S_7, S_8, S_8, S_9, S_9, C_4, C_4];
@@ -2012,6 +3292,7 @@ mod tests {
             TrickDrawPolicy::NoProtections,
             TrickDrawPolicy::LongerTuplesProtected,
             TrickDrawPolicy::NoFormatBasedDraw,
+            TrickDrawPolicy::OnlyDrawTractorOnTractorStrict,
         ] {
             let mut hands = Hands::new(vec![P1, P2, P3, P4]);
 
@@ -2046,7 +3327,8 @@ mod tests {
                 }
                 TrickDrawPolicy::LongerTuplesProtected
                 | TrickDrawPolicy::NoProtections
-                | TrickDrawPolicy::OnlyDrawTractorOnTractor => {
+                | TrickDrawPolicy::OnlyDrawTractorOnTractor
+                | TrickDrawPolicy::OnlyDrawTractorOnTractorStrict => {
                     // This play should not succeed, because P2 also has S_K, S_K which is a pair.
                     if let Err(TrickError::IllegalPlay) = trick.play_cards(pc!(
                         P2,
@@ -2090,11 +3372,76 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_all_legal_follows() {
+        let tf = TrickFormat {
+            suit: EffectiveSuit::Hearts,
+            trump: TRUMP,
+            units: vec![TrickUnit::Repeated {
+                count: 2,
+                card: oc!(H_3),
+            }],
+        };
+        let hand = Card::count(vec![H_5, H_5, H_7, H_7, H_8, C_4]);
+        let follows = |policy| {
+            Trick::all_legal_follows(&hand, &tf, policy)
+                .into_iter()
+                .map(|mut cards| {
+                    cards.sort_by(|a, b| TRUMP.compare(*a, *b));
+                    cards
+                })
+                .collect::<HashSet<_>>()
+        };
+
+        assert_eq!(
+            follows(TrickDrawPolicy::NoProtections),
+            HashSet::from_iter(vec![vec![H_5, H_5], vec![H_7, H_7]])
+        );
+        assert_eq!(
+            follows(TrickDrawPolicy::NoFormatBasedDraw),
+            HashSet::from_iter(vec![
+                vec![H_5, H_5],
+                vec![H_7, H_7],
+                vec![H_5, H_7],
+                vec![H_5, H_8],
+                vec![H_7, H_8],
+            ])
+        );
+
+        // Large hands are capped rather than enumerated exhaustively.
+        let tf = TrickFormat {
+            suit: EffectiveSuit::Trump,
+            trump: TRUMP,
+            units: vec![
+                TrickUnit::Repeated {
+                    count: 1,
+                    card: oc!(S_3),
+                };
+                8
+            ],
+        };
+        let hand = Card::count(
+            [S_3, S_4, S_5, S_6, S_7, S_8, S_9, S_10, S_J, S_Q, S_K, S_A]
+                .iter()
+                .flat_map(|c| vec![*c; 2])
+                .collect::<Vec<_>>(),
+        );
+        assert_eq!(
+            Trick::all_legal_follows(&hand, &tf, TrickDrawPolicy::NoFormatBasedDraw).len(),
+            MAX_FOLLOW_CANDIDATES
+        );
+        assert_eq!(
+            Trick::sole_legal_follow(&hand, &tf, TrickDrawPolicy::NoFormatBasedDraw),
+            None
+        );
+    }
+
     #[test]
     fn test_throw_evaluation_policy_highest_card() {
         let trump = Trump::Standard {
             number: Number::King,
             suit: Suit::Spades,
+            permanent: PermanentTrumps::NONE,
         };
 
         let p1_hand = vec![C_4, C_6];
@@ -2136,11 +3483,82 @@ mod tests {
         assert_eq!(run(ThrowEvaluationPolicy::Highest), P4);
     }
 
+    #[test]
+    fn test_explain_winner_throw() {
+        let trump = Trump::Standard {
+            number: Number::King,
+            suit: Suit::Spades,
+            permanent: PermanentTrumps::NONE,
+        };
+
+        let mut hands = Hands::new(vec![P1, P2, P3, P4]);
+        hands.add(P1, vec![C_4, C_6]).unwrap();
+        hands.add(P2, vec![S_2, S_3]).unwrap();
+        hands.add(P3, vec![S_3, S_4]).unwrap();
+        hands.add(P4, vec![S_2, Card::BigJoker]).unwrap();
+
+        let mut trick = Trick::new(trump, vec![P1, P2, P3, P4]);
+        let policy = ThrowEvaluationPolicy::All;
+        trick
+            .play_cards(pc!(P1, &mut hands, &[C_4, C_6], policy))
+            .unwrap();
+        trick
+            .play_cards(pc!(P2, &mut hands, &[S_2, S_3], policy))
+            .unwrap();
+        trick
+            .play_cards(pc!(P3, &mut hands, &[S_3, S_4], policy))
+            .unwrap();
+        trick
+            .play_cards(pc!(P4, &mut hands, &[S_2, Card::BigJoker], policy))
+            .unwrap();
+
+        let explanation = trick.explain_winner(trump, policy, ThrowTieBreakPolicy::FirstPlayed);
+        assert_eq!(explanation.winner, Some(P3));
+        assert_eq!(
+            explanation
+                .plays
+                .iter()
+                .map(|p| (p.id, p.matches_format, p.compared_against, p.beats_winner))
+                .collect::<Vec<_>>(),
+            vec![
+                (P1, true, None, false),
+                (P2, true, Some(P1), true),
+                (P3, true, Some(P2), true),
+                // P4's small trump loses to P3's lowest card.
+                (P4, true, Some(P3), false),
+            ]
+        );
+
+        // When only the highest card matters, the big joker takes the trick.
+        let explanation = trick.explain_winner(
+            trump,
+            ThrowEvaluationPolicy::Highest,
+            ThrowTieBreakPolicy::FirstPlayed,
+        );
+        assert_eq!(explanation.winner, Some(P4));
+        assert!(explanation.plays[3].beats_winner);
+
+        // A play which doesn't match the format is never compared.
+        let mut trick = Trick::new(trump, vec![P1, P2, P3, P4]);
+        hands.add(P1, vec![C_4, C_6]).unwrap();
+        hands.add(P2, vec![D_4, D_A]).unwrap();
+        trick
+            .play_cards(pc!(P1, &mut hands, &[C_4, C_6], policy))
+            .unwrap();
+        trick
+            .play_cards(pc!(P2, &mut hands, &[D_4, D_A], policy))
+            .unwrap();
+        let explanation = trick.explain_winner(trump, policy, ThrowTieBreakPolicy::FirstPlayed);
+        assert_eq!(explanation.winner, Some(P1));
+        assert!(!explanation.plays[1].matches_format);
+    }
+
     #[test]
     fn test_throw_evaluation_policy_trick_unit_length() {
         let trump = Trump::Standard {
             number: Number::Two,
             suit: Suit::Spades,
+            permanent: PermanentTrumps::NONE,
         };
 
         let p1_hand = vec![H_A, H_K, H_K, H_K, H_9, H_9];
@@ -2183,6 +3601,105 @@ mod tests {
         assert_eq!(run(ThrowEvaluationPolicy::TrickUnitLength), P3);
     }
 
+    #[test]
+    fn test_play_throw_cumulative() {
+        let run = |policy: ThrowEvaluationPolicy| {
+            let mut hands = Hands::new(vec![P1, P2, P3, P4]);
+            hands.add(P1, vec![H_A, H_K]).unwrap();
+            hands.add(P2, vec![S_3, S_A]).unwrap();
+            hands.add(P3, vec![S_2, Card::BigJoker]).unwrap();
+            hands.add(P4, vec![C_4, C_5]).unwrap();
+
+            let mut trick = Trick::new(TRUMP, vec![P1, P2, P3, P4]);
+            trick
+                .play_cards(pc!(P1, &mut hands, &[H_A, H_K], policy))
+                .unwrap();
+            trick
+                .play_cards(pc!(P2, &mut hands, &[S_3, S_A], policy))
+                .unwrap();
+            trick
+                .play_cards(pc!(P3, &mut hands, &[S_2, Card::BigJoker], policy))
+                .unwrap();
+            trick
+                .play_cards(pc!(P4, &mut hands, &[C_4, C_5], policy))
+                .unwrap();
+            let TrickEnded { winner, .. } = trick.complete().unwrap();
+            winner
+        };
+
+        // P3's two doesn't beat P2's three, so P2 keeps the trick when every
+        // card has to be beaten.
+        assert_eq!(run(ThrowEvaluationPolicy::All), P2);
+        // But the big joker is far enough above the ace that P3's cards are
+        // stronger overall.
+        assert_eq!(run(ThrowEvaluationPolicy::Cumulative), P3);
+    }
+
+    #[test]
+    fn test_single_card_cumulative() {
+        let mut hands = Hands::new(vec![P1, P2, P3, P4]);
+        hands.add(P1, vec![H_7]).unwrap();
+        hands.add(P2, vec![H_K]).unwrap();
+        hands.add(P3, vec![H_8]).unwrap();
+        hands.add(P4, vec![H_K]).unwrap();
+
+        let mut trick = Trick::new(TRUMP, vec![P1, P2, P3, P4]);
+        for (id, card) in &[(P1, H_7), (P2, H_K), (P3, H_8), (P4, H_K)] {
+            trick
+                .play_cards(pc!(
+                    *id,
+                    &mut hands,
+                    &[*card],
+                    ThrowEvaluationPolicy::Cumulative
+                ))
+                .unwrap();
+        }
+        // Ties go to the earlier play, just like they do for the other
+        // policies.
+        let TrickEnded { winner, .. } = trick.complete().unwrap();
+        assert_eq!(winner, P2);
+    }
+
+    #[test]
+    fn test_throw_tie_break_policy() {
+        let run = |throw_tie_break_policy: ThrowTieBreakPolicy| {
+            let mut hands = Hands::new(vec![P1, P2, P3, P4]);
+            hands.add(P1, vec![H_A, H_K]).unwrap();
+            hands.add(P2, vec![S_5, S_6]).unwrap();
+            hands.add(P3, vec![S_5, S_6]).unwrap();
+            hands.add(P4, vec![H_3, H_5]).unwrap();
+
+            let mut trick = Trick::new(TRUMP, vec![P1, P2, P3, P4]);
+            for (id, cards) in &[
+                (P1, [H_A, H_K]),
+                (P2, [S_5, S_6]),
+                (P3, [S_5, S_6]),
+                (P4, [H_3, H_5]),
+            ] {
+                trick
+                    .play_cards(PlayCards {
+                        id: *id,
+                        hands: &mut hands,
+                        cards: &cards[..],
+                        trick_draw_policy: TrickDrawPolicy::NoProtections,
+                        throw_eval_policy: ThrowEvaluationPolicy::All,
+                        throw_tie_break_policy,
+                        format_hint: None,
+                        hide_throw_halting_player: false,
+                        tractor_requirements: TractorRequirements::default(),
+                        throws_allowed: true,
+                    })
+                    .unwrap();
+            }
+            let TrickEnded { winner, .. } = trick.complete().unwrap();
+            winner
+        };
+
+        // P2 and P3 trumped the throw with identical cards.
+        assert_eq!(run(ThrowTieBreakPolicy::FirstPlayed), P2);
+        assert_eq!(run(ThrowTieBreakPolicy::LastPlayed), P3);
+    }
+
     #[test]
     fn test_throw_of_trump_rank_in_trump() {
         let mut hands = Hands::new(vec![P1, P2, P3, P4]);
@@ -2213,6 +3730,7 @@ mod tests {
             let trump = Trump::Standard {
                 number: Number::Five,
                 suit: Suit::Diamonds,
+                permanent: PermanentTrumps::NONE,
             };
             let mut hands = Hands::new(vec![P1, P2, P3, P4]);
             hands.set_trump(trump);
@@ -2245,4 +3763,159 @@ mod tests {
         let TrickEnded { winner, .. } = f(ThrowEvaluationPolicy::TrickUnitLength);
         assert_eq!(winner, P3);
     }
+
+    #[test]
+    fn test_find_optimal_play() {
+        let optimal_play = |lead: &[Card], hand: &[Card]| {
+            let mut hands = Hands::new(vec![P1, P2]);
+            hands.add(P1, lead.to_vec()).unwrap();
+            hands.add(P2, hand.to_vec()).unwrap();
+            let mut trick = Trick::new(TRUMP, vec![P1, P2]);
+            trick.play_cards(pc!(P1, &mut hands, lead)).unwrap();
+            let mut play = trick
+                .find_optimal_play(
+                    P2,
+                    &hands,
+                    TrickDrawPolicy::NoProtections,
+                    ThrowEvaluationPolicy::All,
+                    ThrowTieBreakPolicy::FirstPlayed,
+                )
+                .unwrap();
+            play.sort_by(|a, b| TRUMP.compare(*a, *b));
+            play
+        };
+
+        // With only a tractor to follow a pair, the tractor has to be broken:
+        // either pair wins, but the higher one captures more points.
+        assert_eq!(
+            optimal_play(&[H_2, H_2], &[H_3, H_3, H_5, H_5, C_5]),
+            vec![H_5, H_5]
+        );
+        // If the tractor can't win anyway, the pair without points is given up.
+        assert_eq!(
+            optimal_play(&[H_A, H_A], &[H_3, H_3, H_5, H_5, C_5]),
+            vec![H_3, H_3]
+        );
+
+        // Without any hearts, trumping wins the trick, and points are captured
+        // where possible.
+        assert_eq!(optimal_play(&[H_K], &[S_3, S_5, C_A]), vec![S_5]);
+        assert_eq!(optimal_play(&[H_3], &[S_3, S_6, C_A]), vec![S_3]);
+
+        // Following suit takes precedence over trumping, and when the trick
+        // can't be won, no points are given away.
+        assert_eq!(optimal_play(&[H_A], &[H_5, H_7, S_A]), vec![H_7]);
+        assert_eq!(
+            optimal_play(&[H_A, H_A], &[H_5, C_5, C_6, S_A]),
+            vec![H_5, C_6]
+        );
+
+        // There's nothing to follow before the trick has been led.
+        let hands = Hands::new(vec![P1, P2]);
+        let trick = Trick::new(TRUMP, vec![P1, P2]);
+        assert!(trick
+            .find_optimal_play(
+                P1,
+                &hands,
+                TrickDrawPolicy::NoProtections,
+                ThrowEvaluationPolicy::All,
+                ThrowTieBreakPolicy::FirstPlayed,
+            )
+            .is_err());
+    }
+
+    #[test]
+    fn test_follow_search_is_capped() {
+        // Following a tractor with a huge hand which is void in the suit has
+        // far too many candidate plays to try them all.
+        let lead = [H_5, H_5, H_6, H_6, H_7, H_7, H_8, H_8];
+        let hand = FULL_DECK
+            .iter()
+            .filter(|c| TRUMP.effective_suit(**c) != EffectiveSuit::Hearts)
+            .flat_map(|c| vec![*c; 2])
+            .collect::<Vec<_>>();
+        let mut hands = Hands::new(vec![P1, P2]);
+        hands.add(P1, lead.to_vec()).unwrap();
+        hands.add(P2, hand.clone()).unwrap();
+        let mut trick = Trick::new(TRUMP, vec![P1, P2]);
+        trick.play_cards(pc!(P1, &mut hands, &lead)).unwrap();
+
+        let play = trick
+            .find_optimal_play(
+                P2,
+                &hands,
+                TrickDrawPolicy::NoProtections,
+                ThrowEvaluationPolicy::All,
+                ThrowTieBreakPolicy::FirstPlayed,
+            )
+            .unwrap();
+        assert_eq!(play.len(), lead.len());
+
+        let p = trick
+            .lead_survival_probability(
+                &hand,
+                hand.len(),
+                TrickDrawPolicy::NoProtections,
+                ThrowEvaluationPolicy::All,
+                ThrowTieBreakPolicy::FirstPlayed,
+                2,
+            )
+            .unwrap();
+        assert!((0.0..=1.0).contains(&p));
+    }
+
+    #[test]
+    fn test_lead_survival_probability() {
+        let survival = |lead: &[Card], unseen: &[Card]| {
+            let mut hands = Hands::new(vec![P1, P2, P3, P4]);
+            hands.add(P1, lead.to_vec()).unwrap();
+            let mut trick = Trick::new(TRUMP, vec![P1, P2, P3, P4]);
+            trick.play_cards(pc!(P1, &mut hands, lead)).unwrap();
+            trick
+                .lead_survival_probability(
+                    unseen,
+                    2,
+                    TrickDrawPolicy::NoProtections,
+                    ThrowEvaluationPolicy::All,
+                    ThrowTieBreakPolicy::FirstPlayed,
+                    100,
+                )
+                .unwrap()
+        };
+
+        // Nothing that's still out there can beat the ace.
+        let p = survival(&[H_A], &[H_K, C_5, C_6, C_7, C_8, C_10, D_K, D_A]);
+        assert!((p - 1.0).abs() < f64::EPSILON);
+
+        // The king loses whenever the ace is dealt to one of the other
+        // players, which happens three quarters of the time.
+        let p = survival(&[H_K], &[H_A, C_5, C_6, C_7, C_8, C_10, D_K, D_A]);
+        assert!(p > 0.0 && p < 1.0);
+
+        // There aren't enough unseen cards for everyone else's hands.
+        let mut hands = Hands::new(vec![P1, P2, P3, P4]);
+        hands.add(P1, vec![H_K]).unwrap();
+        let mut trick = Trick::new(TRUMP, vec![P1, P2, P3, P4]);
+        assert!(trick
+            .lead_survival_probability(
+                &[H_A],
+                1,
+                TrickDrawPolicy::NoProtections,
+                ThrowEvaluationPolicy::All,
+                ThrowTieBreakPolicy::FirstPlayed,
+                100,
+            )
+            .is_err());
+        trick.play_cards(pc!(P1, &mut hands, &[H_K])).unwrap();
+        assert!(trick
+            .lead_survival_probability(
+                &[H_A],
+                1,
+                TrickDrawPolicy::NoProtections,
+                ThrowEvaluationPolicy::All,
+                ThrowTieBreakPolicy::FirstPlayed,
+                100,
+            )
+            .is_err());
+    }
 }
@@ -1,6 +1,7 @@
 use std::cmp::Ordering;
 use std::collections::HashMap;
 use std::fmt;
+use std::str::FromStr;
 
 use serde::de::Error;
 use serde::{Deserialize, Serialize};
@@ -11,15 +12,46 @@ pub struct PlayerID(pub usize);
 
 #[derive(Debug, Copy, Clone, Serialize, Deserialize, Hash, Eq, PartialEq)]
 pub enum Trump {
-    Standard { suit: Suit, number: Number },
-    NoTrump { number: Number },
+    Standard {
+        suit: Suit,
+        number: Number,
+        #[serde(default, skip_serializing_if = "PermanentTrumps::is_empty")]
+        permanent: PermanentTrumps,
+    },
+    NoTrump {
+        number: Number,
+        #[serde(default, skip_serializing_if = "PermanentTrumps::is_empty")]
+        permanent: PermanentTrumps,
+    },
 }
 
 impl Trump {
     pub fn number(self) -> Number {
         match self {
             Trump::Standard { number, .. } => number,
-            Trump::NoTrump { number } => number,
+            Trump::NoTrump { number, .. } => number,
+        }
+    }
+
+    /// The numbers which are trump in every suit besides the trump number.
+    pub fn permanent(self) -> PermanentTrumps {
+        match self {
+            Trump::Standard { permanent, .. } | Trump::NoTrump { permanent, .. } => permanent,
+        }
+    }
+
+    pub fn with_permanent(self, numbers: PermanentTrumps) -> Trump {
+        let numbers = numbers.without(self.number());
+        match self {
+            Trump::Standard { suit, number, .. } => Trump::Standard {
+                suit,
+                number,
+                permanent: numbers,
+            },
+            Trump::NoTrump { number, .. } => Trump::NoTrump {
+                number,
+                permanent: numbers,
+            },
         }
     }
 
@@ -43,12 +75,15 @@ impl Trump {
                 },
             )
             | (
-                Trump::NoTrump { number },
+                Trump::NoTrump { number, .. },
                 Card::Suited {
                     number: card_number,
                     ..
                 },
             ) if number == card_number => EffectiveSuit::Trump,
+            (_, Card::Suited { number, .. }) if self.permanent().contains(number) => {
+                EffectiveSuit::Trump
+            }
 
             (
                 Trump::Standard {
@@ -71,7 +106,7 @@ impl Trump {
         }
     }
 
-    pub fn suit_ordinal(self, card: Card) -> impl Ord {
+    pub fn suit_ordinal(self, card: Card) -> usize {
         let effective_suit = self.effective_suit(card);
         match self {
             Trump::Standard {
@@ -126,7 +161,49 @@ impl Trump {
         }
     }
 
+    /// The rank of `card` within its effective suit. In the trump suit, the
+    /// plain trump-suit cards come first, then the permanent trump numbers in
+    /// order, the trump number and the jokers. Of the cards of a trump number,
+    /// the one in the trump suit ranks above the others, which are equal.
+    fn rank(self, card: Card) -> u8 {
+        let permanent = self.permanent();
+        match card {
+            Card::Unknown => 0,
+            Card::Suited { suit, number } => {
+                let in_trump_suit = if Some(suit) == self.suit() { 1 } else { 0 };
+                if number == self.number() {
+                    14 + 2 * permanent.len() + in_trump_suit
+                } else if permanent.contains(number) {
+                    14 + 2 * permanent.position(number) + in_trump_suit
+                } else {
+                    1 + number as u8
+                }
+            }
+            Card::SmallJoker => 16 + 2 * permanent.len(),
+            Card::BigJoker => 17 + 2 * permanent.len(),
+        }
+    }
+
     pub fn successor(self, card: Card) -> Vec<Card> {
+        if !self.permanent().is_empty() {
+            // The next rank up in the same effective suit, skipping over any
+            // numbers which have been pulled into the trump suit.
+            let suit = self.effective_suit(card);
+            let rank = self.rank(card);
+            let in_suit_above =
+                |c: &&Card| self.effective_suit(**c) == suit && self.rank(**c) > rank;
+            let next = FULL_DECK
+                .iter()
+                .filter(in_suit_above)
+                .map(|c| self.rank(*c))
+                .min();
+            return FULL_DECK
+                .iter()
+                .filter(in_suit_above)
+                .filter(|c| Some(self.rank(**c)) == next)
+                .copied()
+                .collect();
+        }
         match card {
             Card::Unknown => vec![],
             Card::BigJoker => vec![],
@@ -135,6 +212,7 @@ impl Trump {
                 Trump::Standard {
                     suit: trump_suit,
                     number: trump_number,
+                    ..
                 } => {
                     if suit == trump_suit {
                         vec![Card::SmallJoker]
@@ -186,6 +264,12 @@ impl Trump {
         }
     }
 
+    /// Precomputes the ordering of every card under this trump, for use when
+    /// comparing many cards at once.
+    pub fn comparator(self) -> TrumpComparator {
+        TrumpComparator::new(self)
+    }
+
     pub fn compare(self, card1: Card, card2: Card) -> Ordering {
         self.compare_effective(card1, card2)
             .then(card1.as_char().cmp(&card2.as_char()))
@@ -198,50 +282,115 @@ impl Trump {
 
         self.suit_ordinal(card1)
             .cmp(&self.suit_ordinal(card2))
-            .then(match (card1, card2) {
-                (Card::Unknown, _) => Ordering::Less,
-                (_, Card::Unknown) => Ordering::Greater,
-                (Card::BigJoker, _) => Ordering::Greater,
-                (_, Card::BigJoker) => Ordering::Less,
-                (Card::SmallJoker, _) => Ordering::Greater,
-                (_, Card::SmallJoker) => Ordering::Less,
-                (
-                    Card::Suited {
-                        number: number_1,
-                        suit: suit_1,
-                    },
-                    Card::Suited {
-                        number: number_2,
-                        suit: suit_2,
-                    },
-                ) => {
-                    let trump_number = self.number();
-                    if number_1 == trump_number && number_2 == trump_number {
-                        if let Trump::Standard {
-                            suit: trump_suit, ..
-                        } = self
-                        {
-                            if suit_1 == trump_suit && suit_2 == trump_suit {
-                                Ordering::Equal
-                            } else if suit_1 == trump_suit {
-                                Ordering::Greater
-                            } else if suit_2 == trump_suit {
-                                Ordering::Less
-                            } else {
-                                Ordering::Equal
-                            }
-                        } else {
-                            Ordering::Equal
-                        }
-                    } else if number_1 == trump_number {
-                        Ordering::Greater
-                    } else if number_2 == trump_number {
-                        Ordering::Less
-                    } else {
-                        number_1.cmp(&number_2)
-                    }
-                }
-            })
+            .then(self.rank(card1).cmp(&self.rank(card2)))
+    }
+}
+
+/// A lookup table of `Trump::effective_suit` and `Trump::compare` for a single
+/// trump, indexed by card.
+#[derive(Debug, Clone)]
+pub struct TrumpComparator {
+    trump: Trump,
+    suits: [EffectiveSuit; 55],
+    effective_ranks: [u16; 55],
+    chars: [char; 55],
+}
+
+impl TrumpComparator {
+    fn new(trump: Trump) -> Self {
+        let mut comparator = TrumpComparator {
+            trump,
+            suits: [EffectiveSuit::Unknown; 55],
+            effective_ranks: [0; 55],
+            chars: [Card::Unknown.as_char(); 55],
+        };
+        for card in FULL_DECK.iter() {
+            let idx = Self::index(*card);
+            comparator.suits[idx] = trump.effective_suit(*card);
+            comparator.effective_ranks[idx] =
+                (trump.suit_ordinal(*card) as u16) << 8 | trump.rank(*card) as u16;
+            comparator.chars[idx] = card.as_char();
+        }
+        comparator
+    }
+
+    fn index(card: Card) -> usize {
+        match card {
+            Card::Unknown => 0,
+            Card::Suited { suit, number } => 1 + suit as usize * 13 + number as usize,
+            Card::SmallJoker => 53,
+            Card::BigJoker => 54,
+        }
+    }
+
+    pub fn trump(&self) -> Trump {
+        self.trump
+    }
+
+    pub fn effective_suit(&self, card: Card) -> EffectiveSuit {
+        self.suits[Self::index(card)]
+    }
+
+    pub fn compare_effective(&self, card1: Card, card2: Card) -> Ordering {
+        self.effective_ranks[Self::index(card1)].cmp(&self.effective_ranks[Self::index(card2)])
+    }
+
+    pub fn compare(&self, card1: Card, card2: Card) -> Ordering {
+        let (idx1, idx2) = (Self::index(card1), Self::index(card2));
+        self.effective_ranks[idx1]
+            .cmp(&self.effective_ranks[idx2])
+            .then(self.chars[idx1].cmp(&self.chars[idx2]))
+    }
+}
+
+/// A set of numbers which are trump in every suit, e.g. the 2s in variants
+/// where they are always trump regardless of what's being played.
+#[derive(Debug, Copy, Clone, Default, Hash, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(from = "Vec<Number>", into = "Vec<Number>")]
+pub struct PermanentTrumps(u16);
+
+impl PermanentTrumps {
+    pub const NONE: PermanentTrumps = PermanentTrumps(0);
+
+    pub fn is_empty(&self) -> bool {
+        self.0 == 0
+    }
+
+    pub fn len(&self) -> u8 {
+        self.0.count_ones() as u8
+    }
+
+    pub fn contains(self, number: Number) -> bool {
+        self.0 & (1 << number as u16) != 0
+    }
+
+    pub fn without(self, number: Number) -> PermanentTrumps {
+        PermanentTrumps(self.0 & !(1 << number as u16))
+    }
+
+    /// The number of permanent trumps lower than `number`.
+    fn position(self, number: Number) -> u8 {
+        (self.0 & ((1 << number as u16) - 1)).count_ones() as u8
+    }
+}
+
+impl From<Vec<Number>> for PermanentTrumps {
+    fn from(numbers: Vec<Number>) -> Self {
+        PermanentTrumps(numbers.iter().fold(0, |acc, n| acc | 1 << *n as u16))
+    }
+}
+
+impl From<PermanentTrumps> for Vec<Number> {
+    fn from(permanent: PermanentTrumps) -> Self {
+        let mut numbers = vec![];
+        let mut number = Some(Number::Two);
+        while let Some(n) = number {
+            if permanent.contains(n) {
+                numbers.push(n);
+            }
+            number = n.successor();
+        }
+        numbers
     }
 }
 
@@ -255,12 +404,57 @@ pub enum EffectiveSuit {
     Trump,
 }
 
+/// The language that card names are shown in. Only the names of the ranks
+/// change; the card faces and suit symbols are glyphs rather than words, so
+/// they are the same in every locale.
+#[derive(Debug, Copy, Clone, Default, Serialize, Deserialize, PartialEq, Eq, Hash)]
+pub enum Locale {
+    #[default]
+    En,
+    ZhHans,
+    ZhHant,
+}
+
+impl Locale {
+    /// The name of the card's rank in this locale, e.g. "K" or "凯". English
+    /// has no rank names for the jokers.
+    pub fn rank_name(self, card: Card) -> Option<&'static str> {
+        match card {
+            Card::Unknown => None,
+            Card::Suited { number, .. } => Some(self.number_name(number)),
+            Card::SmallJoker => match self {
+                Locale::En => None,
+                Locale::ZhHans | Locale::ZhHant => Some("小王"),
+            },
+            Card::BigJoker => match self {
+                Locale::En => None,
+                Locale::ZhHans | Locale::ZhHant => Some("大王"),
+            },
+        }
+    }
+
+    fn number_name(self, number: Number) -> &'static str {
+        match (self, number) {
+            (Locale::ZhHans, Number::Jack) => "钩",
+            (Locale::ZhHant, Number::Jack) => "鉤",
+            (Locale::ZhHans, Number::Queen) | (Locale::ZhHant, Number::Queen) => "圈",
+            (Locale::ZhHans, Number::King) => "凯",
+            (Locale::ZhHant, Number::King) => "凱",
+            (Locale::ZhHans, Number::Ace) | (Locale::ZhHant, Number::Ace) => "尖",
+            (_, number) => number.as_str(),
+        }
+    }
+}
+
 #[derive(Copy, Clone, Serialize, Deserialize, PartialEq, Eq, Hash)]
 pub struct CardInfo {
     value: char,
     display_value: char,
     typ: char,
+    /// The rank, as used to compare cards; this is never localized.
     number: Option<&'static str>,
+    /// The name of the rank to show players.
+    rank_name: Option<&'static str>,
     points: usize,
 }
 
@@ -301,6 +495,11 @@ impl Card {
     }
 
     pub fn as_info(self) -> CardInfo {
+        self.as_localized_info(Locale::En)
+    }
+
+    /// Like `as_info`, but with the rank named in `locale`.
+    pub fn as_localized_info(self, locale: Locale) -> CardInfo {
         let value = self.as_char();
         CardInfo {
             value,
@@ -310,6 +509,7 @@ impl Card {
                 value
             },
             number: self.number().map(|n| n.as_str()),
+            rank_name: locale.rank_name(self),
             typ: self.suit().map(|s| s.as_char()).unwrap_or(value),
             points: self.points().unwrap_or(0),
         }
@@ -507,7 +707,8 @@ impl Serialize for Number {
 impl<'d> Deserialize<'d> for Number {
     fn deserialize<D: serde::Deserializer<'d>>(deserializer: D) -> Result<Self, D::Error> {
         let s = String::deserialize(deserializer)?;
-        Number::from_str(&s).ok_or_else(|| D::Error::custom(format!("Unexpected string '{}'", s)))
+        s.parse()
+            .map_err(|_| D::Error::custom(format!("Unexpected string '{}'", s)))
     }
 }
 
@@ -603,22 +804,22 @@ impl Number {
         }
     }
 
-    pub fn from_str(s: &str) -> Option<Self> {
-        match s {
-            "2" => Some(Number::Two),
-            "3" => Some(Number::Three),
-            "4" => Some(Number::Four),
-            "5" => Some(Number::Five),
-            "6" => Some(Number::Six),
-            "7" => Some(Number::Seven),
-            "8" => Some(Number::Eight),
-            "9" => Some(Number::Nine),
-            "10" => Some(Number::Ten),
-            "J" => Some(Number::Jack),
-            "Q" => Some(Number::Queen),
-            "K" => Some(Number::King),
-            "A" => Some(Number::Ace),
-            _ => None,
+    /// The English name of the rank, e.g. "King".
+    pub fn name(self) -> &'static str {
+        match self {
+            Number::Two => "Two",
+            Number::Three => "Three",
+            Number::Four => "Four",
+            Number::Five => "Five",
+            Number::Six => "Six",
+            Number::Seven => "Seven",
+            Number::Eight => "Eight",
+            Number::Nine => "Nine",
+            Number::Ten => "Ten",
+            Number::Jack => "Jack",
+            Number::Queen => "Queen",
+            Number::King => "King",
+            Number::Ace => "Ace",
         }
     }
 
@@ -637,6 +838,40 @@ impl fmt::Debug for Number {
     }
 }
 
+impl fmt::Display for Number {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+#[derive(thiserror::Error, Debug, Clone, PartialEq, Eq)]
+#[error("{:?} is not a rank", _0)]
+pub struct ParseNumberError(String);
+
+impl FromStr for Number {
+    type Err = ParseNumberError;
+
+    /// Parses the rank as written by `as_str`, so that the two round-trip.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "2" => Ok(Number::Two),
+            "3" => Ok(Number::Three),
+            "4" => Ok(Number::Four),
+            "5" => Ok(Number::Five),
+            "6" => Ok(Number::Six),
+            "7" => Ok(Number::Seven),
+            "8" => Ok(Number::Eight),
+            "9" => Ok(Number::Nine),
+            "10" => Ok(Number::Ten),
+            "J" => Ok(Number::Jack),
+            "Q" => Ok(Number::Queen),
+            "K" => Ok(Number::King),
+            "A" => Ok(Number::Ace),
+            _ => Err(ParseNumberError(s.to_string())),
+        }
+    }
+}
+
 #[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub enum Suit {
     Diamonds,
@@ -678,6 +913,16 @@ impl Suit {
         }
     }
 
+    /// The English name of the suit, e.g. "clubs".
+    pub fn name(self) -> &'static str {
+        match self {
+            Suit::Hearts => "hearts",
+            Suit::Diamonds => "diamonds",
+            Suit::Spades => "spades",
+            Suit::Clubs => "clubs",
+        }
+    }
+
     pub fn from_char(c: char) -> Option<Self> {
         match c {
             '♡' => Some(Suit::Hearts),
@@ -966,7 +1211,10 @@ pub mod cards {
 
 #[cfg(test)]
 mod tests {
-    use super::{cards, Card, Number, Suit, Trump, FULL_DECK};
+    use super::{
+        cards, Card, EffectiveSuit, Locale, Number, ParseNumberError, PermanentTrumps, Suit, Trump,
+        ALL_SUITS, FULL_DECK,
+    };
 
     #[test]
     fn test_char_roundtrip() {
@@ -975,6 +1223,67 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_number_roundtrip() {
+        let mut numbers = vec![];
+        let mut number = Some(Number::Two);
+        while let Some(n) = number {
+            assert_eq!(n.to_string().parse::<Number>(), Ok(n));
+            assert_eq!(n.as_str().parse::<Number>(), Ok(n));
+            numbers.push(n);
+            number = n.successor();
+        }
+        assert_eq!(numbers.len(), 13);
+        assert_eq!(Number::Ten.to_string(), "10");
+
+        for s in &["", "1", "11", "T", "j", "10 ", "Ace"] {
+            assert_eq!(
+                s.parse::<Number>(),
+                Err(ParseNumberError(s.to_string())),
+                "{:?}",
+                s
+            );
+        }
+    }
+
+    #[test]
+    fn test_localized_rank_names() {
+        let rank = |card: Card, locale| card.as_localized_info(locale).rank_name;
+
+        assert_eq!(rank(Card::SmallJoker, Locale::En), None);
+        assert_eq!(rank(Card::BigJoker, Locale::En), None);
+        assert_eq!(rank(cards::S_K, Locale::En), Some("K"));
+        assert_eq!(rank(cards::S_10, Locale::En), Some("10"));
+
+        assert_eq!(rank(Card::SmallJoker, Locale::ZhHans), Some("小王"));
+        assert_eq!(rank(Card::BigJoker, Locale::ZhHans), Some("大王"));
+        assert_eq!(rank(cards::S_J, Locale::ZhHans), Some("钩"));
+        assert_eq!(rank(cards::S_Q, Locale::ZhHans), Some("圈"));
+        assert_eq!(rank(cards::S_K, Locale::ZhHans), Some("凯"));
+        assert_eq!(rank(cards::S_A, Locale::ZhHans), Some("尖"));
+        assert_eq!(rank(cards::S_10, Locale::ZhHans), Some("10"));
+
+        assert_eq!(rank(Card::BigJoker, Locale::ZhHant), Some("大王"));
+        assert_eq!(rank(cards::H_J, Locale::ZhHant), Some("鉤"));
+        assert_eq!(rank(cards::H_K, Locale::ZhHant), Some("凱"));
+
+        // The card faces, suit symbols and ranks used for comparisons aren't
+        // translated.
+        for card in FULL_DECK.iter() {
+            let en = card.as_info();
+            let zh = card.as_localized_info(Locale::ZhHans);
+            assert_eq!(
+                (en.value, en.display_value, en.typ, en.number, en.points),
+                (zh.value, zh.display_value, zh.typ, zh.number, zh.points)
+            );
+        }
+        assert_eq!(
+            cards::S_K.as_localized_info(Locale::ZhHans).number,
+            Some("K")
+        );
+        assert!(Card::BigJoker.as_info() == Card::BigJoker.as_localized_info(Locale::En));
+    }
+
     #[test]
     fn test_deck_completeness() {
         assert_eq!(
@@ -1003,6 +1312,7 @@ mod tests {
         let trump = Trump::Standard {
             number: Number::Two,
             suit: Suit::Spades,
+            permanent: PermanentTrumps::NONE,
         };
         hand.sort_by(|a, b| trump.compare(*a, *b));
         assert_eq!(
@@ -1016,6 +1326,7 @@ mod tests {
         let trump = Trump::Standard {
             number: Number::Four,
             suit: Suit::Spades,
+            permanent: PermanentTrumps::NONE,
         };
 
         let s = |c| trump.successor(c).into_iter().collect::<Vec<_>>();
@@ -1027,6 +1338,7 @@ mod tests {
 
         let no_trump = Trump::NoTrump {
             number: Number::Four,
+            permanent: PermanentTrumps::NONE,
         };
         let s = |c| no_trump.successor(c).into_iter().collect::<Vec<_>>();
         assert_eq!(s(cards::S_3), vec![cards::S_5]);
@@ -1038,6 +1350,7 @@ mod tests {
         let trump_ace = Trump::Standard {
             number: Number::Ace,
             suit: Suit::Spades,
+            permanent: PermanentTrumps::NONE,
         };
         let s = |c| trump_ace.successor(c).into_iter().collect::<Vec<_>>();
         assert_eq!(s(cards::S_3), vec![cards::S_4]);
@@ -1048,6 +1361,7 @@ mod tests {
 
         let no_trump_ace = Trump::NoTrump {
             number: Number::Ace,
+            permanent: PermanentTrumps::NONE,
         };
         let s = |c| no_trump_ace.successor(c).into_iter().collect::<Vec<_>>();
         assert_eq!(s(cards::S_3), vec![cards::S_4]);
@@ -1056,4 +1370,102 @@ mod tests {
         assert!(s(cards::S_K).is_empty());
         assert!(s(cards::H_K).is_empty());
     }
+
+    #[test]
+    fn test_permanent_trumps() {
+        let trump = Trump::Standard {
+            number: Number::Seven,
+            suit: Suit::Hearts,
+            permanent: PermanentTrumps::NONE,
+        }
+        .with_permanent(vec![Number::Two, Number::Seven].into());
+        assert_eq!(Vec::from(trump.permanent()), vec![Number::Two]);
+
+        assert_eq!(trump.effective_suit(cards::C_2), EffectiveSuit::Trump);
+        assert_eq!(trump.effective_suit(cards::C_3), EffectiveSuit::Clubs);
+        let mut hand = [
+            cards::H_7,
+            cards::C_7,
+            cards::H_2,
+            cards::D_2,
+            cards::H_A,
+            cards::C_A,
+            cards::C_3,
+        ];
+        hand.sort_by(|a, b| trump.compare(*a, *b));
+        assert_eq!(
+            hand.iter().map(|c| format!("{:?}", c)).collect::<String>(),
+            "3♧A♧A♡2♢2♡7♧7♡"
+        );
+
+        let s = |c| trump.successor(c);
+        assert_eq!(s(cards::C_A), vec![]);
+        assert_eq!(s(cards::H_A), vec![cards::D_2, cards::C_2, cards::S_2]);
+        assert_eq!(s(cards::D_2), vec![cards::H_2]);
+        assert_eq!(s(cards::H_2), vec![cards::D_7, cards::C_7, cards::S_7]);
+        assert_eq!(s(cards::H_6), vec![cards::H_8]);
+
+        // The permanent trumps are left out when there aren't any, so the
+        // serialized form of an ordinary trump doesn't change.
+        let json = serde_json::to_string(&trump).unwrap();
+        assert!(json.contains(r#""permanent":["2"]"#));
+        assert_eq!(serde_json::from_str::<Trump>(&json).unwrap(), trump);
+        assert_eq!(
+            serde_json::to_string(&Trump::NoTrump {
+                number: Number::Two,
+                permanent: PermanentTrumps::NONE,
+            })
+            .unwrap(),
+            r#"{"NoTrump":{"number":"2"}}"#
+        );
+    }
+
+    #[test]
+    fn test_comparator_matches_compare() {
+        let numbers = (1..=13).map(|n| Number::from_u32(n).unwrap());
+        let trumps = numbers.flat_map(|number| {
+            ALL_SUITS
+                .iter()
+                .map(move |suit| Trump::Standard {
+                    suit: *suit,
+                    number,
+                    permanent: PermanentTrumps::NONE,
+                })
+                .chain(std::iter::once(Trump::NoTrump {
+                    number,
+                    permanent: PermanentTrumps::NONE,
+                }))
+                .flat_map(|t| [t, t.with_permanent(vec![Number::Two, Number::Ace].into())])
+        });
+        let mut all_cards = FULL_DECK.to_vec();
+        all_cards.push(Card::Unknown);
+
+        for trump in trumps {
+            let comparator = trump.comparator();
+            for card1 in &all_cards {
+                assert_eq!(
+                    comparator.effective_suit(*card1),
+                    trump.effective_suit(*card1)
+                );
+                for card2 in &all_cards {
+                    assert_eq!(
+                        comparator.compare(*card1, *card2),
+                        trump.compare(*card1, *card2),
+                        "{:?} {:?} {:?}",
+                        trump,
+                        card1,
+                        card2
+                    );
+                    assert_eq!(
+                        comparator.compare_effective(*card1, *card2),
+                        trump.compare_effective(*card1, *card2),
+                        "{:?} {:?} {:?}",
+                        trump,
+                        card1,
+                        card2
+                    );
+                }
+            }
+        }
+    }
 }
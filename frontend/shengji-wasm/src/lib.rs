@@ -1,3 +1,4 @@
+use std::collections::HashMap;
 use std::io::{Cursor, Read};
 use std::sync::Mutex;
 
@@ -7,13 +8,20 @@ use serde::{Deserialize, Serialize};
 use shengji_core::{
     bidding::{Bid, BidPolicy, BidReinforcementPolicy, JokerBidPolicy},
     deck::Deck,
-    hands::Hands,
+    game_state::{
+        kitty_arrangement, GameState, InitializePhase, KittyArrangement, PlayPhase, ScoreBreakdown,
+    },
+    hands::{self, group_cards, Hands, SuitGroup, UnknownCardPlacement},
     player::Player,
     scoring::{
         self, compute_level_deltas, explain_level_deltas, GameScoreResult, GameScoringParameters,
     },
-    trick::{TractorRequirements, Trick, TrickDrawPolicy, TrickFormat, TrickUnit, UnitLike},
-    types::{Card, EffectiveSuit, PlayerID, Trump},
+    settings::RemainderPolicy,
+    trick::{
+        ThrowEvaluationPolicy, ThrowTieBreakPolicy, TractorRequirements, Trick, TrickDrawPolicy,
+        TrickFormat, TrickUnit, UnitLike,
+    },
+    types::{Card, CardInfo, Locale, PlayerID, Trump},
 };
 use shengji_types::ZSTD_ZSTD_DICT;
 use wasm_bindgen::prelude::*;
@@ -78,6 +86,106 @@ pub fn find_viable_plays(req: JsValue) -> Result<JsValue, JsValue> {
     Ok(JsValue::from_serde(&FindViablePlaysResult { results }).map_err(|e| e.to_string())?)
 }
 
+#[derive(Deserialize)]
+struct IsValidLeadRequest {
+    trump: Trump,
+    tractor_requirements: TractorRequirements,
+    cards: Vec<Card>,
+    #[serde(default)]
+    format_hint: Option<Vec<TrickUnit>>,
+}
+
+#[derive(Serialize)]
+struct IsValidLeadResponse {
+    reason: Option<String>,
+}
+
+#[wasm_bindgen]
+pub fn is_valid_lead(req: JsValue) -> Result<JsValue, JsValue> {
+    #[cfg(debug_assertions)]
+    console_error_panic_hook::set_once();
+
+    let IsValidLeadRequest {
+        trump,
+        tractor_requirements,
+        cards,
+        format_hint,
+    } = req.into_serde().map_err(|e| e.to_string())?;
+    let reason = Trick::is_valid_lead(&cards, trump, tractor_requirements, format_hint.as_deref())
+        .err()
+        .map(|e| e.to_string());
+    Ok(JsValue::from_serde(&IsValidLeadResponse { reason }).map_err(|e| e.to_string())?)
+}
+
+#[derive(Deserialize)]
+struct SingleUnitRequest {
+    trump: Trump,
+    tractor_requirements: TractorRequirements,
+    cards: Vec<Card>,
+}
+
+#[derive(Serialize)]
+struct SingleUnitResponse {
+    unit: Option<TrickUnit>,
+}
+
+#[wasm_bindgen]
+pub fn single_unit(req: JsValue) -> Result<JsValue, JsValue> {
+    #[cfg(debug_assertions)]
+    console_error_panic_hook::set_once();
+
+    let SingleUnitRequest {
+        trump,
+        tractor_requirements,
+        cards,
+    } = req.into_serde().map_err(|e| e.to_string())?;
+    let unit = TrickUnit::from_single_unit(trump, tractor_requirements, cards);
+    Ok(JsValue::from_serde(&SingleUnitResponse { unit }).map_err(|e| e.to_string())?)
+}
+
+#[derive(Deserialize)]
+struct ValidateGroupingRequest {
+    trump: Trump,
+    tractor_requirements: TractorRequirements,
+    hand: Vec<Card>,
+    grouping: Vec<TrickUnit>,
+    #[serde(default)]
+    trick_format: Option<TrickFormat>,
+}
+
+#[derive(Serialize)]
+struct ValidateGroupingResponse {
+    reason: Option<String>,
+}
+
+#[wasm_bindgen]
+pub fn validate_grouping(req: JsValue) -> Result<JsValue, JsValue> {
+    #[cfg(debug_assertions)]
+    console_error_panic_hook::set_once();
+
+    let ValidateGroupingRequest {
+        trump,
+        tractor_requirements,
+        hand,
+        grouping,
+        trick_format,
+    } = req.into_serde().map_err(|e| e.to_string())?;
+    let mut counts = HashMap::new();
+    for card in hand {
+        *counts.entry(card).or_insert(0) += 1;
+    }
+    let reason = TrickFormat::validate_grouping(
+        trump,
+        tractor_requirements,
+        &counts,
+        &grouping,
+        trick_format.as_ref(),
+    )
+    .err()
+    .map(|e| e.to_string());
+    Ok(JsValue::from_serde(&ValidateGroupingResponse { reason }).map_err(|e| e.to_string())?)
+}
+
 #[derive(Deserialize)]
 struct DecomposeTrickFormatRequest {
     trick_format: TrickFormat,
@@ -152,6 +260,36 @@ pub fn decompose_trick_format(req: JsValue) -> Result<JsValue, JsValue> {
     )
 }
 
+#[derive(Deserialize)]
+struct ProtectedUnitsRequest {
+    trick_format: TrickFormat,
+    hands: Hands,
+    player_id: PlayerID,
+}
+
+#[derive(Serialize)]
+struct ProtectedUnitsResponse {
+    results: Vec<UnitLike>,
+}
+
+#[wasm_bindgen]
+pub fn protected_units(req: JsValue) -> Result<JsValue, JsValue> {
+    #[cfg(debug_assertions)]
+    console_error_panic_hook::set_once();
+
+    let ProtectedUnitsRequest {
+        trick_format,
+        hands,
+        player_id,
+    } = req.into_serde().map_err(|e| e.to_string())?;
+
+    let hand = hands.get(player_id).map_err(|e| e.to_string())?;
+    Ok(JsValue::from_serde(&ProtectedUnitsResponse {
+        results: trick_format.protected_units(hand),
+    })
+    .map_err(|e| e.to_string())?)
+}
+
 #[derive(Deserialize)]
 struct CanPlayCardsRequest {
     trick: Trick,
@@ -186,6 +324,173 @@ pub fn can_play_cards(req: JsValue) -> Result<JsValue, JsValue> {
     .map_err(|e| e.to_string())?)
 }
 
+#[derive(Deserialize)]
+struct FindOptimalPlayRequest {
+    trick: Trick,
+    id: PlayerID,
+    hands: Hands,
+    trick_draw_policy: TrickDrawPolicy,
+    throw_evaluation_policy: ThrowEvaluationPolicy,
+    #[serde(default)]
+    throw_tie_break_policy: ThrowTieBreakPolicy,
+}
+
+#[derive(Serialize)]
+struct FindOptimalPlayResponse {
+    cards: Vec<Card>,
+}
+
+#[wasm_bindgen]
+pub fn find_optimal_play(req: JsValue) -> Result<JsValue, JsValue> {
+    #[cfg(debug_assertions)]
+    console_error_panic_hook::set_once();
+
+    let FindOptimalPlayRequest {
+        trick,
+        id,
+        hands,
+        trick_draw_policy,
+        throw_evaluation_policy,
+        throw_tie_break_policy,
+    } = req.into_serde().map_err(|e| e.to_string())?;
+    Ok(JsValue::from_serde(&FindOptimalPlayResponse {
+        cards: trick
+            .find_optimal_play(
+                id,
+                &hands,
+                trick_draw_policy,
+                throw_evaluation_policy,
+                throw_tie_break_policy,
+            )
+            .map_err(|e| e.to_string())?,
+    })
+    .map_err(|e| e.to_string())?)
+}
+
+#[derive(Deserialize)]
+struct AnalyzeHandDifficultyRequest {
+    hand: Vec<Card>,
+    trump: Trump,
+}
+
+#[derive(Serialize)]
+struct AnalyzeHandDifficultyResponse {
+    difficulty: f32,
+}
+
+#[wasm_bindgen]
+pub fn analyze_hand_difficulty(req: JsValue) -> Result<JsValue, JsValue> {
+    #[cfg(debug_assertions)]
+    console_error_panic_hook::set_once();
+
+    let AnalyzeHandDifficultyRequest { hand, trump } =
+        req.into_serde().map_err(|e| e.to_string())?;
+    Ok(JsValue::from_serde(&AnalyzeHandDifficultyResponse {
+        difficulty: hands::analyze_hand_difficulty(&Card::count(hand), trump),
+    })
+    .map_err(|e| e.to_string())?)
+}
+
+#[derive(Deserialize)]
+struct AnalyzeLeadPriorityRequest {
+    hand: Vec<Card>,
+    trump: Trump,
+    unseen: Vec<Card>,
+}
+
+#[derive(Serialize)]
+struct AnalyzeLeadPriorityResponse {
+    priorities: Vec<(Card, f32)>,
+}
+
+#[wasm_bindgen]
+pub fn analyze_lead_priority(req: JsValue) -> Result<JsValue, JsValue> {
+    #[cfg(debug_assertions)]
+    console_error_panic_hook::set_once();
+
+    let AnalyzeLeadPriorityRequest {
+        hand,
+        trump,
+        unseen,
+    } = req.into_serde().map_err(|e| e.to_string())?;
+    Ok(JsValue::from_serde(&AnalyzeLeadPriorityResponse {
+        priorities: hands::analyze_lead_priority(&Card::count(hand), trump, &Card::count(unseen)),
+    })
+    .map_err(|e| e.to_string())?)
+}
+
+#[derive(Deserialize)]
+struct LeadSurvivalProbabilityRequest {
+    trick: Trick,
+    unseen_cards: Vec<Card>,
+    hand_size: usize,
+    trick_draw_policy: TrickDrawPolicy,
+    throw_evaluation_policy: ThrowEvaluationPolicy,
+    #[serde(default)]
+    throw_tie_break_policy: ThrowTieBreakPolicy,
+    num_samples: usize,
+}
+
+#[derive(Serialize)]
+struct LeadSurvivalProbabilityResponse {
+    probability: f64,
+}
+
+#[wasm_bindgen]
+pub fn lead_survival_probability(req: JsValue) -> Result<JsValue, JsValue> {
+    #[cfg(debug_assertions)]
+    console_error_panic_hook::set_once();
+
+    let LeadSurvivalProbabilityRequest {
+        trick,
+        unseen_cards,
+        hand_size,
+        trick_draw_policy,
+        throw_evaluation_policy,
+        throw_tie_break_policy,
+        num_samples,
+    } = req.into_serde().map_err(|e| e.to_string())?;
+    Ok(JsValue::from_serde(&LeadSurvivalProbabilityResponse {
+        probability: trick
+            .lead_survival_probability(
+                &unseen_cards,
+                hand_size,
+                trick_draw_policy,
+                throw_evaluation_policy,
+                throw_tie_break_policy,
+                num_samples,
+            )
+            .map_err(|e| e.to_string())?,
+    })
+    .map_err(|e| e.to_string())?)
+}
+
+#[derive(Deserialize)]
+struct ExplainTrickWinnerRequest {
+    trick: Trick,
+    throw_evaluation_policy: ThrowEvaluationPolicy,
+    #[serde(default)]
+    throw_tie_break_policy: ThrowTieBreakPolicy,
+}
+
+#[wasm_bindgen]
+pub fn explain_trick_winner(req: JsValue) -> Result<JsValue, JsValue> {
+    #[cfg(debug_assertions)]
+    console_error_panic_hook::set_once();
+
+    let ExplainTrickWinnerRequest {
+        trick,
+        throw_evaluation_policy,
+        throw_tie_break_policy,
+    } = req.into_serde().map_err(|e| e.to_string())?;
+    Ok(JsValue::from_serde(&trick.explain_winner(
+        trick.trump(),
+        throw_evaluation_policy,
+        throw_tie_break_policy,
+    ))
+    .map_err(|e| e.to_string())?)
+}
+
 #[derive(Deserialize)]
 struct FindValidBidsRequest {
     id: PlayerID,
@@ -235,6 +540,8 @@ pub fn find_valid_bids(req: JsValue) -> Result<JsValue, JsValue> {
 struct SortAndGroupCardsRequest {
     trump: Trump,
     cards: Vec<Card>,
+    #[serde(default)]
+    unknown_placement: UnknownCardPlacement,
 }
 
 #[derive(Serialize)]
@@ -242,38 +549,67 @@ struct SortAndGroupCardsResponse {
     results: Vec<SuitGroup>,
 }
 
-#[derive(Serialize)]
-struct SuitGroup {
-    suit: EffectiveSuit,
-    cards: Vec<Card>,
-}
-
 #[wasm_bindgen]
 pub fn sort_and_group_cards(req: JsValue) -> Result<JsValue, JsValue> {
     #[cfg(debug_assertions)]
     console_error_panic_hook::set_once();
 
-    let SortAndGroupCardsRequest { trump, mut cards } =
-        req.into_serde().map_err(|e| e.to_string())?;
+    let SortAndGroupCardsRequest {
+        trump,
+        cards,
+        unknown_placement,
+    } = req.into_serde().map_err(|e| e.to_string())?;
 
-    cards.sort_by(|a, b| trump.compare(*a, *b));
+    Ok(JsValue::from_serde(&SortAndGroupCardsResponse {
+        results: group_cards(trump, cards, unknown_placement),
+    })
+    .map_err(|e| e.to_string())?)
+}
 
-    let mut results: Vec<SuitGroup> = vec![];
-    for card in cards {
-        let suit = trump.effective_suit(card);
-        if let Some(group) = results.last_mut() {
-            if group.suit == suit {
-                group.cards.push(card);
-                continue;
-            }
-        }
-        results.push(SuitGroup {
-            suit,
-            cards: vec![card],
-        })
-    }
+#[derive(Deserialize)]
+struct PrepareHandRequest {
+    trump: Trump,
+    cards: Vec<Card>,
+    #[serde(default)]
+    unknown_placement: UnknownCardPlacement,
+    #[serde(default)]
+    locale: Locale,
+}
 
-    Ok(JsValue::from_serde(&SortAndGroupCardsResponse { results }).map_err(|e| e.to_string())?)
+#[derive(Serialize)]
+struct PrepareHandResponse {
+    results: Vec<SuitGroup<CardInfo>>,
+}
+
+/// Sorts and groups the cards like `sort_and_group_cards`, and attaches the
+/// rendering information for each card, so that the hand can be displayed
+/// with a single call.
+#[wasm_bindgen]
+pub fn prepare_hand(req: JsValue) -> Result<JsValue, JsValue> {
+    #[cfg(debug_assertions)]
+    console_error_panic_hook::set_once();
+
+    let PrepareHandRequest {
+        trump,
+        cards,
+        unknown_placement,
+        locale,
+    } = req.into_serde().map_err(|e| e.to_string())?;
+
+    Ok(JsValue::from_serde(&PrepareHandResponse {
+        results: group_cards(trump, cards, unknown_placement)
+            .into_iter()
+            .map(|group| SuitGroup {
+                suit: group.suit,
+                cards: group
+                    .cards
+                    .into_iter()
+                    .map(|c| c.as_localized_info(locale))
+                    .collect(),
+            })
+            .collect(),
+    })
+    .map_err(|e| e.to_string())?)
 }
 
 #[derive(Deserialize)]
@@ -301,6 +637,44 @@ pub fn next_threshold_reachable(req: JsValue) -> Result<bool, JsValue> {
     )
 }
 
+#[derive(Deserialize)]
+struct PointsNeededRequest {
+    decks: Vec<Deck>,
+    params: GameScoringParameters,
+    non_landlord_points: isize,
+    observed_points: isize,
+    is_defending: bool,
+}
+
+#[derive(Serialize)]
+struct PointsNeededResponse {
+    points_needed: Option<usize>,
+}
+
+#[wasm_bindgen]
+pub fn points_needed_for_next_level(req: JsValue) -> Result<JsValue, JsValue> {
+    #[cfg(debug_assertions)]
+    console_error_panic_hook::set_once();
+
+    let PointsNeededRequest {
+        decks,
+        params,
+        non_landlord_points,
+        observed_points,
+        is_defending,
+    } = req.into_serde().map_err(|e| e.to_string())?;
+    let points_needed = scoring::points_needed_for_next_level(
+        &params,
+        &decks,
+        non_landlord_points,
+        observed_points,
+        is_defending,
+    )
+    .map_err(|e| format!("Failed to compute points needed: {:?}", e))?;
+
+    Ok(JsValue::from_serde(&PointsNeededResponse { points_needed }).map_err(|e| e.to_string())?)
+}
+
 #[derive(Deserialize)]
 struct ExplainScoringRequest {
     decks: Vec<Deck>,
@@ -313,6 +687,8 @@ struct ExplainScoringResponse {
     results: Vec<ScoreSegment>,
     total_points: isize,
     step_size: usize,
+    landlord_step_size: usize,
+    non_landlord_step_size: usize,
 }
 
 #[derive(Serialize)]
@@ -345,6 +721,12 @@ pub fn explain_scoring(req: JsValue) -> Result<JsValue, JsValue> {
         step_size: params
             .step_size(&decks)
             .map_err(|e| format!("Failed to compute step size: {:?}", e))?,
+        landlord_step_size: params
+            .landlord_step_size(&decks)
+            .map_err(|e| format!("Failed to compute step size: {:?}", e))?,
+        non_landlord_step_size: params
+            .non_landlord_step_size(&decks)
+            .map_err(|e| format!("Failed to compute step size: {:?}", e))?,
         total_points: decks.iter().map(|d| d.points() as isize).sum::<isize>(),
     })
     .map_err(|e| e.to_string())?)
@@ -357,6 +739,96 @@ pub fn compute_deck_len(req: JsValue) -> Result<usize, JsValue> {
     Ok(decks.iter().map(|d| d.len() as usize).sum::<usize>())
 }
 
+#[derive(Deserialize)]
+struct DeckSeatingRequest {
+    decks: Vec<Deck>,
+    player_range: (usize, usize),
+    #[serde(default)]
+    kitty_size: Option<usize>,
+    #[serde(default)]
+    remainder_policy: RemainderPolicy,
+}
+
+#[derive(Serialize)]
+struct DeckSeatingResponse {
+    results: Vec<DeckSeating>,
+}
+
+#[derive(Serialize)]
+struct DeckSeating {
+    num_players: usize,
+    arrangement: Option<KittyArrangement>,
+}
+
+#[wasm_bindgen]
+pub fn deck_seating(req: JsValue) -> Result<JsValue, JsValue> {
+    #[cfg(debug_assertions)]
+    console_error_panic_hook::set_once();
+
+    let DeckSeatingRequest {
+        decks,
+        player_range: (min_players, max_players),
+        kitty_size,
+        remainder_policy,
+    } = req.into_serde().map_err(|e| e.to_string())?;
+
+    Ok(JsValue::from_serde(&DeckSeatingResponse {
+        results: (min_players..=max_players)
+            .map(|num_players| DeckSeating {
+                num_players,
+                arrangement: kitty_arrangement(&decks, num_players, kitty_size, remainder_policy)
+                    .ok(),
+            })
+            .collect(),
+    })
+    .map_err(|e| e.to_string())?)
+}
+
+#[derive(Deserialize)]
+struct ValidKittySizesRequest {
+    state: InitializePhase,
+}
+
+#[derive(Serialize)]
+struct ValidKittySizesResponse {
+    sizes: Option<Vec<usize>>,
+}
+
+#[wasm_bindgen]
+pub fn valid_kitty_sizes(req: JsValue) -> Result<JsValue, JsValue> {
+    #[cfg(debug_assertions)]
+    console_error_panic_hook::set_once();
+
+    let ValidKittySizesRequest { state } = req.into_serde().map_err(|e| e.to_string())?;
+    Ok(JsValue::from_serde(&ValidKittySizesResponse {
+        sizes: state.valid_kitty_sizes().ok(),
+    })
+    .map_err(|e| e.to_string())?)
+}
+
+#[derive(Deserialize)]
+struct CanTakeBackBidRequest {
+    state: GameState,
+    id: PlayerID,
+}
+
+#[derive(Serialize)]
+struct CanTakeBackBidResponse {
+    allowed: bool,
+}
+
+#[wasm_bindgen]
+pub fn can_take_back_bid(req: JsValue) -> Result<JsValue, JsValue> {
+    #[cfg(debug_assertions)]
+    console_error_panic_hook::set_once();
+
+    let CanTakeBackBidRequest { state, id } = req.into_serde().map_err(|e| e.to_string())?;
+    Ok(JsValue::from_serde(&CanTakeBackBidResponse {
+        allowed: state.can_take_back_bid(id),
+    })
+    .map_err(|e| e.to_string())?)
+}
+
 #[derive(Deserialize)]
 struct ComputeScoreRequest {
     decks: Vec<Deck>,
@@ -402,6 +874,51 @@ pub fn compute_score(req: JsValue) -> Result<JsValue, JsValue> {
     .map_err(|e| e.to_string())?)
 }
 
+#[derive(Deserialize)]
+struct ComputeScoreBreakdownRequest {
+    state: PlayPhase,
+}
+
+#[derive(Serialize)]
+struct ComputeScoreBreakdownResponse {
+    breakdown: ScoreBreakdown,
+    non_landlord_points: isize,
+    score: GameScoreResult,
+}
+
+#[wasm_bindgen]
+pub fn compute_score_breakdown(req: JsValue) -> Result<JsValue, JsValue> {
+    #[cfg(debug_assertions)]
+    console_error_panic_hook::set_once();
+
+    let ComputeScoreBreakdownRequest { state } = req.into_serde().map_err(|e| e.to_string())?;
+    let (non_landlord_points, score) =
+        state.final_score().map_err(|_| "Failed to compute score")?;
+
+    Ok(JsValue::from_serde(&ComputeScoreBreakdownResponse {
+        breakdown: state.score_breakdown(),
+        non_landlord_points,
+        score,
+    })
+    .map_err(|e| e.to_string())?)
+}
+
+#[derive(Deserialize)]
+struct ProgressToNextThresholdRequest {
+    state: PlayPhase,
+}
+
+#[wasm_bindgen]
+pub fn progress_to_next_threshold(req: JsValue) -> Result<f32, JsValue> {
+    #[cfg(debug_assertions)]
+    console_error_panic_hook::set_once();
+
+    let ProgressToNextThresholdRequest { state } = req.into_serde().map_err(|e| e.to_string())?;
+    Ok(state
+        .progress_to_next_threshold()
+        .map_err(|e| format!("Failed to compute progress: {:?}", e))?)
+}
+
 #[wasm_bindgen]
 pub fn zstd_decompress(req: &[u8]) -> Result<String, JsValue> {
     let mut reader = Cursor::new(req);
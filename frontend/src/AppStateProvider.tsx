@@ -7,6 +7,7 @@ import { State, combineState, noPersistence } from "./State";
 import {
   stringLocalStorageState,
   numberLocalStorageState,
+  JSONLocalStorageState,
 } from "./localStorageState";
 
 export interface AppState {
@@ -16,6 +17,8 @@ export interface AppState {
   everConnected: boolean;
   roomName: string;
   name: string;
+  // Keyed by `${roomName}/${name}`.
+  reconnectTokens: { [roomAndName: string]: string };
   gameState: IGameState | null;
   headerMessages: string[];
   errors: string[];
@@ -31,6 +34,7 @@ const appState: State<AppState> = combineState({
   everConnected: noPersistence(() => false),
   roomName: noPersistence(() => window.location.hash.slice(1, 17)),
   name: stringLocalStorageState("name"),
+  reconnectTokens: JSONLocalStorageState("reconnect_tokens", {}),
   changeLogLastViewed: numberLocalStorageState("change_log_last_viewed"),
   gameState: noPersistence(() => null),
   headerMessages: noPersistence(() => []),
@@ -11,7 +11,7 @@ import {
 } from "./types";
 import { WebsocketContext } from "./WebsocketProvider";
 import LabeledPlay from "./LabeledPlay";
-import WasmContext from "./WasmContext";
+import WasmContext, { BiddingPhase } from "./WasmContext";
 
 interface IBidAreaProps {
   bids: IBid[];
@@ -25,6 +25,7 @@ interface IBidAreaProps {
   prefixButtons?: JSX.Element | JSX.Element[];
   suffixButtons?: JSX.Element | JSX.Element[];
   bidTakeBacksEnabled: boolean;
+  phase: BiddingPhase;
   bidPolicy: BidPolicy;
   bidReinforcementPolicy: BidReinforcementPolicy;
   jokerBidPolicy: JokerBidPolicy;
@@ -34,7 +35,7 @@ interface IBidAreaProps {
 
 const BidArea = (props: IBidAreaProps): JSX.Element => {
   const { send } = React.useContext(WebsocketContext);
-  const { findValidBids } = React.useContext(WasmContext);
+  const { findValidBids, canTakeBackBid } = React.useContext(WasmContext);
 
   const takeBackBid = (evt: React.SyntheticEvent): void => {
     evt.preventDefault();
@@ -145,11 +146,7 @@ const BidArea = (props: IBidAreaProps): JSX.Element => {
         {props.bidTakeBacksEnabled ? (
           <button
             onClick={takeBackBid}
-            disabled={
-              props.bids.length === 0 ||
-              props.bids[props.bids.length - 1].id !== playerId ||
-              props.bids[props.bids.length - 1].epoch !== props.epoch
-            }
+            disabled={!canTakeBackBid({ state: props.phase, id: playerId })}
           >
             Take back bid
           </button>
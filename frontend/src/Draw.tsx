@@ -127,6 +127,7 @@ class Draw extends React.Component<IDrawProps, IDrawState> {
           landlord={this.props.state.propagated.landlord}
           next={next}
           name={this.props.name}
+          vacatedSeats={this.props.state.propagated.vacated_seats}
         />
         <BidArea
           bids={this.props.state.bids}
@@ -222,6 +223,7 @@ class Draw extends React.Component<IDrawProps, IDrawState> {
             this.props.state.propagated.bid_takeback_policy ===
             "AllowBidTakeback"
           }
+          phase={{ Draw: this.props.state }}
         />
         <LabeledPlay
           className="kitty"
@@ -93,6 +93,10 @@ class Exchange extends React.Component<IExchangeProps, IExchangeState> {
     (window as any).send({ Action: "PickUpKitty" });
   }
 
+  setTrumpSuit(suit: string): void {
+    (window as any).send({ Action: { SetTrumpSuit: suit } });
+  }
+
   putDownKitty(evt: React.SyntheticEvent): void {
     evt.preventDefault();
     (window as any).send({ Action: "PutDownKitty" });
@@ -170,11 +174,23 @@ class Exchange extends React.Component<IExchangeProps, IExchangeState> {
               <Card key={idx} onClick={() => this.moveCardToHand(c)} card={c} />
             ))}
           </div>
+          {this.props.state.trump_suit_pending === true ? (
+            <div>
+              <h2>Choose the trump suit</h2>
+              {["♤", "♡", "♧", "♢"].map((suit) => (
+                <button key={suit} onClick={() => this.setTrumpSuit(suit)}>
+                  {suit}
+                </button>
+              ))}
+            </div>
+          ) : null}
           {kittyTheftEnabled ? (
             <button
               onClick={this.putDownKitty}
               disabled={
-                this.props.state.kitty.length !== this.props.state.kitty_size
+                this.props.state.kitty.length !==
+                  this.props.state.kitty_size ||
+                this.props.state.trump_suit_pending === true
               }
             >
               Finalize exchanged cards
@@ -189,6 +205,7 @@ class Exchange extends React.Component<IExchangeProps, IExchangeState> {
         onClick={this.startGame}
         disabled={
           this.props.state.kitty.length !== this.props.state.kitty_size ||
+          this.props.state.trump_suit_pending === true ||
           (kittyTheftEnabled &&
             !this.props.state.finalized &&
             this.props.state.autobid === null)
@@ -238,6 +255,7 @@ class Exchange extends React.Component<IExchangeProps, IExchangeState> {
               this.props.state.propagated.bid_takeback_policy ===
               "AllowBidTakeback"
             }
+            phase={{ Exchange: this.props.state }}
           />
           <LabeledPlay
             className="kitty"
@@ -286,6 +304,7 @@ class Exchange extends React.Component<IExchangeProps, IExchangeState> {
           landlord={this.props.state.landlord}
           next={this.props.state.landlord}
           name={this.props.name}
+          vacatedSeats={this.props.state.propagated.vacated_seats}
         />
         <Trump trump={this.props.state.trump} />
         {this.props.state.removed_cards.length > 0 ? (
@@ -16,6 +16,8 @@ import {
   IPropagatedState,
   IDeck,
   ITractorRequirements,
+  GameModifier,
+  KittyPenalty,
 } from "./types";
 import { WebsocketContext } from "./WebsocketProvider";
 
@@ -29,11 +31,15 @@ interface IDifficultyProps {
   state: IInitializePhase;
   setFriendSelectionPolicy: (v: React.ChangeEvent<HTMLSelectElement>) => void;
   setMultipleJoinPolicy: (v: React.ChangeEvent<HTMLSelectElement>) => void;
+  setFriendPointTransferPolicy: (
+    v: React.ChangeEvent<HTMLSelectElement>
+  ) => void;
   setAdvancementPolicy: (v: React.ChangeEvent<HTMLSelectElement>) => void;
   setHideLandlordsPoints: (v: React.ChangeEvent<HTMLSelectElement>) => void;
   setHidePlayedCards: (v: React.ChangeEvent<HTMLSelectElement>) => void;
-  setKittyPenalty: (v: React.ChangeEvent<HTMLSelectElement>) => void;
+  setKittyPenalty: (v: KittyPenalty) => void;
   setThrowPenalty: (v: React.ChangeEvent<HTMLSelectElement>) => void;
+  setOutOfTurnPolicy: (v: React.ChangeEvent<HTMLSelectElement>) => void;
   setPlayTakebackPolicy: (v: React.ChangeEvent<HTMLSelectElement>) => void;
   setBidTakebackPolicy: (v: React.ChangeEvent<HTMLSelectElement>) => void;
 }
@@ -83,11 +89,34 @@ const DifficultySettings = (props: IDifficultyProps): JSX.Element => {
           </select>
         </label>
       </div>
+      <div>
+        <label>
+          Points captured before joining:{" "}
+          <select
+            value={
+              props.state.propagated.friend_point_transfer_policy ||
+              "MoveToLandlordTeam"
+            }
+            onChange={props.setFriendPointTransferPolicy}
+          >
+            <option value="MoveToLandlordTeam">
+              Move to the defending team with the friend
+            </option>
+            <option value="KeepWithCaptor">
+              Stay with the attacking team
+            </option>
+          </select>
+        </label>
+      </div>
       <div>
         <label>
           Rank advancement policy:{" "}
           <select
-            value={props.state.propagated.advancement_policy}
+            value={
+              typeof props.state.propagated.advancement_policy === "object"
+                ? `FreezeAt:${props.state.propagated.advancement_policy.DefendPointsThenFreeze.ceiling}`
+                : props.state.propagated.advancement_policy
+            }
             onChange={props.setAdvancementPolicy}
           >
             <option value="Unrestricted">A must be defended</option>
@@ -95,6 +124,15 @@ const DifficultySettings = (props: IDifficultyProps): JSX.Element => {
             <option value="DefendPoints">
               Points (5, 10, K) and A must be defended
             </option>
+            <option value="FreezeAt:10">
+              Points must be defended, and nobody advances past 10
+            </option>
+            <option value="FreezeAt:K">
+              Points must be defended, and nobody advances past K
+            </option>
+            <option value="FreezeAt:A">
+              Points must be defended, and nobody advances past A
+            </option>
           </select>
         </label>
       </div>
@@ -124,30 +162,38 @@ const DifficultySettings = (props: IDifficultyProps): JSX.Element => {
           </select>
         </label>
       </div>
+      <KittyPenaltySelector
+        kittyPenalty={props.state.propagated.kitty_penalty}
+        onChange={props.setKittyPenalty}
+      />
       <div>
         <label>
-          Penalty for points left in the bottom:{" "}
+          Penalty for incorrect throws:{" "}
           <select
-            value={props.state.propagated.kitty_penalty}
-            onChange={props.setKittyPenalty}
+            value={props.state.propagated.throw_penalty}
+            onChange={props.setThrowPenalty}
           >
-            <option value="Times">Twice the size of the last trick</option>
-            <option value="Power">
-              Two to the power of the size of the last trick
+            <option value="None">No penalty</option>
+            <option value="TenPointsPerAttempt">
+              Ten points per bad throw
             </option>
           </select>
         </label>
       </div>
       <div>
         <label>
-          Penalty for incorrect throws:{" "}
+          Penalty for playing out of turn:{" "}
           <select
-            value={props.state.propagated.throw_penalty}
-            onChange={props.setThrowPenalty}
+            value={
+              typeof props.state.propagated.out_of_turn_policy === "object"
+                ? "WarnThenPenalize"
+                : "Ignore"
+            }
+            onChange={props.setOutOfTurnPolicy}
           >
-            <option value="None">No penalty</option>
-            <option value="TenPointsPerAttempt">
-              Ten points per bad throw
+            <option value="Ignore">No penalty</option>
+            <option value="WarnThenPenalize">
+              One warning, then ten points per attempt
             </option>
           </select>
         </label>
@@ -161,6 +207,9 @@ const DifficultySettings = (props: IDifficultyProps): JSX.Element => {
           >
             <option value="AllowPlayTakeback">Allow taking back plays</option>
             <option value="NoPlayTakeback">Disallow taking back plays</option>
+            <option value="RequiresApproval">
+              Allow taking back plays with the other team&apos;s approval
+            </option>
           </select>
         </label>
       </div>
@@ -213,8 +262,12 @@ interface IDeckSettings {
 
 const DeckSettings = (props: IDeckSettings): JSX.Element => {
   const [modalOpen, setModalOpen] = React.useState<boolean>(false);
+  const numBigJokers = (d: IDeck): number =>
+    d.exclude_big_joker ? 0 : d.num_big_jokers;
+  const numSmallJokers = (d: IDeck): number =>
+    d.exclude_small_joker ? 0 : d.num_small_jokers;
   const isNotDefault = (d: IDeck): boolean =>
-    !(d.min === "2" && !d.exclude_big_joker && !d.exclude_small_joker);
+    !(d.min === "2" && numBigJokers(d) === 1 && numSmallJokers(d) === 1);
   const onChange = (decks: IDeck[]): void => {
     // exclude the decks that are the same as default
     const filtered = decks.filter((d) => isNotDefault(d));
@@ -241,6 +294,7 @@ const DeckSettings = (props: IDeckSettings): JSX.Element => {
     "K",
     "A",
   ];
+  const jokerCounts = [0, 1, 2, 3, 4];
 
   const s = (
     <>
@@ -258,30 +312,48 @@ const DeckSettings = (props: IDeckSettings): JSX.Element => {
           {isNotDefault(d) ? " (modified)" : " (standard)"}
           <form>
             <label style={{ display: "block" }}>
-              Include HJ (大王){" "}
-              <input
-                type="checkbox"
-                checked={!d.exclude_big_joker}
+              Number of HJ (大王){" "}
+              <select
+                value={numBigJokers(d)}
                 onChange={(evt) =>
                   setDeckAtIndex(
-                    { ...d, exclude_big_joker: !evt.target.checked },
+                    {
+                      ...d,
+                      exclude_big_joker: false,
+                      num_big_jokers: parseInt(evt.target.value, 10),
+                    },
                     i
                   )
                 }
-              />
+              >
+                {jokerCounts.map((n) => (
+                  <option key={n} value={n}>
+                    {n}
+                  </option>
+                ))}
+              </select>
             </label>
             <label style={{ display: "block" }}>
-              Include LJ (小王){" "}
-              <input
-                type="checkbox"
-                checked={!d.exclude_small_joker}
+              Number of LJ (小王){" "}
+              <select
+                value={numSmallJokers(d)}
                 onChange={(evt) =>
                   setDeckAtIndex(
-                    { ...d, exclude_small_joker: !evt.target.checked },
+                    {
+                      ...d,
+                      exclude_small_joker: false,
+                      num_small_jokers: parseInt(evt.target.value, 10),
+                    },
                     i
                   )
                 }
-              />
+              >
+                {jokerCounts.map((n) => (
+                  <option key={n} value={n}>
+                    {n}
+                  </option>
+                ))}
+              </select>
             </label>
             <label>
               Minimum card:{" "}
@@ -332,6 +404,95 @@ const DeckSettings = (props: IDeckSettings): JSX.Element => {
   );
 };
 
+interface IKittyPenaltySelectorProps {
+  kittyPenalty: KittyPenalty;
+  onChange: (penalty: KittyPenalty) => void;
+}
+
+const KittyPenaltySelector = (
+  props: IKittyPenaltySelectorProps
+): JSX.Element => {
+  const penalty = props.kittyPenalty;
+  const variant =
+    typeof penalty === "object" ? Object.keys(penalty)[0] : penalty;
+  const onSelect = (evt: React.ChangeEvent<HTMLSelectElement>): void => {
+    evt.preventDefault();
+    switch (evt.target.value) {
+      case "Fixed":
+        props.onChange({ Fixed: 2 });
+        break;
+      case "ByUnitType":
+        props.onChange({ ByUnitType: { single: 2, pair: 4, tractor: 8 } });
+        break;
+      case "Power":
+      case "None":
+        props.onChange(evt.target.value);
+        break;
+      default:
+        props.onChange("Times");
+    }
+  };
+  const multiplierInput = (
+    value: number,
+    onChange: (value: number) => void
+  ): JSX.Element => (
+    <input
+      type="number"
+      style={{ width: "3em" }}
+      onChange={(v) => {
+        if (!isNaN(v.target.valueAsNumber) && v.target.valueAsNumber >= 1) {
+          onChange(v.target.valueAsNumber);
+        }
+      }}
+      value={value}
+      min="1"
+    />
+  );
+  return (
+    <div>
+      <label>
+        Penalty for points left in the bottom:{" "}
+        <select value={variant} onChange={onSelect}>
+          <option value="Times">Twice the size of the last trick</option>
+          <option value="Power">
+            Two to the power of the size of the last trick
+          </option>
+          <option value="None">No penalty, count the points once</option>
+          <option value="Fixed">A fixed multiple of the points</option>
+          <option value="ByUnitType">
+            A multiple depending on how the last trick was led
+          </option>
+        </select>
+      </label>
+      {typeof penalty === "object" && "Fixed" in penalty && (
+        <div>
+          <label>Multiply the points by </label>
+          {multiplierInput(penalty.Fixed, (Fixed) =>
+            props.onChange({ Fixed })
+          )}
+        </div>
+      )}
+      {typeof penalty === "object" && "ByUnitType" in penalty && (
+        <div>
+          <label>Multiply the points by </label>
+          {multiplierInput(penalty.ByUnitType.single, (single) =>
+            props.onChange({ ByUnitType: { ...penalty.ByUnitType, single } })
+          )}
+          <label> for singles, </label>
+          {multiplierInput(penalty.ByUnitType.pair, (pair) =>
+            props.onChange({ ByUnitType: { ...penalty.ByUnitType, pair } })
+          )}
+          <label> for tuples, and </label>
+          {multiplierInput(penalty.ByUnitType.tractor, (tractor) =>
+            props.onChange({ ByUnitType: { ...penalty.ByUnitType, tractor } })
+          )}
+          <label> for tractors</label>
+        </div>
+      )}
+    </div>
+  );
+};
+
 interface ITractorRequirementsProps {
   tractorRequirements: ITractorRequirements;
   numDecks: number;
@@ -410,22 +571,33 @@ const ScoringSettings = (props: IScoringSettings): JSX.Element => {
   );
 };
 
+const gameModifiers: Array<[GameModifier, string]> = [
+  ["DoublePoints", "Double points"],
+  ["NoThrows", "No throws"],
+  ["NoKittyMultiplier", "No kitty multiplier"],
+];
+
 interface IUncommonSettings {
   state: IInitializePhase;
   numDecksEffective: number;
   setBidPolicy: (v: React.ChangeEvent<HTMLSelectElement>) => void;
   setBidReinforcementPolicy: (v: React.ChangeEvent<HTMLSelectElement>) => void;
   setJokerBidPolicy: (v: React.ChangeEvent<HTMLSelectElement>) => void;
-  setShouldRevealKittyAtEndOfGame: (
-    v: React.ChangeEvent<HTMLSelectElement>
-  ) => void;
+  setJokerBidSuitPolicy: (v: React.ChangeEvent<HTMLSelectElement>) => void;
+  setKittyRevealPolicy: (v: React.ChangeEvent<HTMLSelectElement>) => void;
   setFirstLandlordSelectionPolicy: (
     v: React.ChangeEvent<HTMLSelectElement>
   ) => void;
+  setFirstLeadPolicy: (v: React.ChangeEvent<HTMLSelectElement>) => void;
   setGameStartPolicy: (v: React.ChangeEvent<HTMLSelectElement>) => void;
+  setAbandonedGamePolicy: (v: React.ChangeEvent<HTMLSelectElement>) => void;
+  setAbsentLandlordPolicy: (v: React.ChangeEvent<HTMLSelectElement>) => void;
   setGameShadowingPolicy: (v: React.ChangeEvent<HTMLSelectElement>) => void;
   setKittyBidPolicy: (v: React.ChangeEvent<HTMLSelectElement>) => void;
   setHideThrowHaltingPlayer: (v: React.ChangeEvent<HTMLSelectElement>) => void;
+  setRevealKittyPointCount: (v: React.ChangeEvent<HTMLSelectElement>) => void;
+  setSealedKitty: (v: React.ChangeEvent<HTMLSelectElement>) => void;
+  setGameModifier: (modifier: GameModifier, enabled: boolean) => void;
   setTractorRequirements: (v: ITractorRequirements) => void;
 }
 
@@ -465,6 +637,39 @@ const UncommonSettings = (props: IUncommonSettings): JSX.Element => {
           </select>
         </label>
       </div>
+      <div>
+        <label>
+          Reset games:{" "}
+          <select
+            value={
+              props.state.propagated.abandoned_game_policy || "IgnoreResets"
+            }
+            onChange={props.setAbandonedGamePolicy}
+          >
+            <option value="IgnoreResets">Don&apos;t track reset games</option>
+            <option value="CountResetsAsAbandoned">
+              Count reset games as abandoned
+            </option>
+          </select>
+        </label>
+      </div>
+      <div>
+        <label>
+          Absent landlord:{" "}
+          <select
+            value={props.state.propagated.absent_landlord_policy || "Wait"}
+            onChange={props.setAbsentLandlordPolicy}
+          >
+            <option value="Wait">
+              Wait for the landlord to finish exchanging
+            </option>
+            <option value="AutoFinalizeExchange">
+              Finish the exchange for a disconnected landlord after the move
+              deadline
+            </option>
+          </select>
+        </label>
+      </div>
       <div>
         <label>
           Landlord selection from bid:{" "}
@@ -481,6 +686,21 @@ const UncommonSettings = (props: IUncommonSettings): JSX.Element => {
           </select>
         </label>
       </div>
+      <div>
+        <label>
+          First trick leader:{" "}
+          <select
+            value={props.state.propagated.first_lead_policy}
+            onChange={props.setFirstLeadPolicy}
+          >
+            <option value="Landlord">Landlord leads</option>
+            <option value="LeftOfLandlord">
+              Player after the landlord leads
+            </option>
+            <option value="BidWinner">Winning bidder leads</option>
+          </select>
+        </label>
+      </div>
       <div>
         <label>
           Trump policy for cards revealed from the bottom:{" "}
@@ -512,6 +732,10 @@ const UncommonSettings = (props: IUncommonSettings): JSX.Element => {
             <option value="GreaterLength">
               All bids must have more cards than the previous bids
             </option>
+            <option value="StrictlyIncreasing">
+              All bids, including reinforcements, must have more cards than
+              the winning bid
+            </option>
           </select>
         </label>
       </div>
@@ -553,6 +777,20 @@ const UncommonSettings = (props: IUncommonSettings): JSX.Element => {
           </select>
         </label>
       </div>
+      <div>
+        <label>
+          Trump suit after a joker bid:{" "}
+          <select
+            value={props.state.propagated.joker_bid_suit_policy}
+            onChange={props.setJokerBidSuitPolicy}
+          >
+            <option value="ForceNoTrump">No trump suit</option>
+            <option value="LandlordChoosesSuit">
+              The leader chooses the trump suit
+            </option>
+          </select>
+        </label>
+      </div>
       <TractorRequirements
         tractorRequirements={props.state.propagated.tractor_requirements}
         numDecks={props.numDecksEffective}
@@ -563,18 +801,25 @@ const UncommonSettings = (props: IUncommonSettings): JSX.Element => {
           Should reveal kitty at end of game:{" "}
           <select
             value={
-              props.state.propagated.should_reveal_kitty_at_end_of_game
-                ? "show"
-                : "hide"
+              props.state.propagated.kitty_reveal_policy !== undefined &&
+              props.state.propagated.kitty_reveal_policy !== "Never"
+                ? props.state.propagated.kitty_reveal_policy
+                : props.state.propagated.should_reveal_kitty_at_end_of_game
+                ? "Always"
+                : "Never"
             }
-            onChange={props.setShouldRevealKittyAtEndOfGame}
+            onChange={props.setKittyRevealPolicy}
           >
-            <option value="hide">
+            <option value="Never">
               Do not reveal contents of the kitty at the end of the game in chat
             </option>
-            <option value="show">
+            <option value="Always">
               Reveal contents of the kitty at the end of the game in chat
             </option>
+            <option value="OnlyIfContestedPoints">
+              Reveal contents of the kitty in chat only if the attackers won
+              its points
+            </option>
           </select>
         </label>
       </div>
@@ -596,6 +841,67 @@ const UncommonSettings = (props: IUncommonSettings): JSX.Element => {
           </select>
         </label>
       </div>
+      <div>
+        <label>
+          Point cards in the bottom:{" "}
+          <select
+            value={
+              props.state.propagated.reveal_kitty_point_count ? "show" : "hide"
+            }
+            onChange={props.setRevealKittyPointCount}
+          >
+            <option value="hide">
+              Hide how many point cards are in the bottom
+            </option>
+            <option value="show">
+              Show how many point cards are in the bottom
+            </option>
+          </select>
+        </label>
+      </div>
+      <div>
+        <label>
+          Sealed bottom (experimental):{" "}
+          <select
+            value={
+              (props.state.propagated.experimental_features ?? []).includes(
+                "sealed_kitty"
+              )
+                ? "enabled"
+                : "disabled"
+            }
+            onChange={props.setSealedKitty}
+          >
+            <option value="disabled">
+              The landlord exchanges cards with the bottom
+            </option>
+            <option value="enabled">
+              The landlord keeps the bottom cards as they were dealt
+            </option>
+          </select>
+        </label>
+      </div>
+      {gameModifiers.map(([modifier, description]) => (
+        <div key={modifier}>
+          <label>
+            {description} (next game only):{" "}
+            <select
+              value={
+                (props.state.propagated.game_modifiers ?? []).includes(modifier)
+                  ? "enabled"
+                  : "disabled"
+              }
+              onChange={(evt) => {
+                evt.preventDefault();
+                props.setGameModifier(modifier, evt.target.value === "enabled");
+              }}
+            >
+              <option value="disabled">Off</option>
+              <option value="enabled">On</option>
+            </select>
+          </label>
+        </div>
+      ))}
     </>
   );
   return (
@@ -701,52 +1007,113 @@ const Initialize = (props: IProps): JSX.Element => {
 
   const setFriendSelectionPolicy = onSelectString("SetFriendSelectionPolicy");
   const setMultipleJoinPolicy = onSelectString("SetMultipleJoinPolicy");
+  const setFriendPointTransferPolicy = onSelectString(
+    "SetFriendPointTransferPolicy"
+  );
   const setFirstLandlordSelectionPolicy = onSelectString(
     "SetFirstLandlordSelectionPolicy"
   );
+  const setFirstLeadPolicy = onSelectString("SetFirstLeadPolicy");
   const setBidPolicy = onSelectString("SetBidPolicy");
   const setBidReinforcementPolicy = onSelectString("SetBidReinforcementPolicy");
   const setJokerBidPolicy = onSelectString("SetJokerBidPolicy");
+  const setJokerBidSuitPolicy = onSelectString("SetJokerBidSuitPolicy");
   const setKittyTheftPolicy = onSelectString("SetKittyTheftPolicy");
+  const setKittyTheftRevealPolicy = onSelectString("SetKittyTheftRevealPolicy");
+  const setKittyTheftBidPolicy = onSelectString("SetKittyTheftBidPolicy");
   const setKittyBidPolicy = onSelectString("SetKittyBidPolicy");
+  const setKittyOnLossPolicy = onSelectString("SetKittyOnLossPolicy");
   const setTrickDrawPolicy = onSelectString("SetTrickDrawPolicy");
   const setThrowEvaluationPolicy = onSelectString("SetThrowEvaluationPolicy");
+  const setThrowTieBreakPolicy = onSelectString("SetThrowTieBreakPolicy");
   const setPlayTakebackPolicy = onSelectString("SetPlayTakebackPolicy");
   const setGameShadowingPolicy = onSelectString("SetGameShadowingPolicy");
   const setGameStartPolicy = onSelectString("SetGameStartPolicy");
+  const setAbandonedGamePolicy = onSelectString("SetAbandonedGamePolicy");
+  const setAbsentLandlordPolicy = onSelectString("SetAbsentLandlordPolicy");
   const setBidTakebackPolicy = onSelectString("SetBidTakebackPolicy");
 
-  const setShouldRevealKittyAtEndOfGame = (
+  const setKittyRevealPolicy = onSelectString("SetKittyRevealPolicy");
+  const setHideThrowHaltingPlayer = (
     evt: React.ChangeEvent<HTMLSelectElement>
   ): void => {
     evt.preventDefault();
     if (evt.target.value !== "") {
       send({
         Action: {
-          SetShouldRevealKittyAtEndOfGame: evt.target.value === "show",
+          SetHideThrowHaltingPlayer: evt.target.value === "hide",
         },
       });
     }
   };
-  const setHideThrowHaltingPlayer = (
+
+  const setSealedKitty = (evt: React.ChangeEvent<HTMLSelectElement>): void => {
+    evt.preventDefault();
+    const features = (
+      props.state.propagated.experimental_features ?? []
+    ).filter((f) => f !== "sealed_kitty");
+    if (evt.target.value === "enabled") {
+      features.push("sealed_kitty");
+    }
+    send({ Action: { SetExperimentalFeatures: features } });
+  };
+
+  const setGameModifier = (modifier: GameModifier, enabled: boolean): void => {
+    const modifiers = (props.state.propagated.game_modifiers ?? []).filter(
+      (m) => m !== modifier
+    );
+    if (enabled) {
+      modifiers.push(modifier);
+    }
+    send({ Action: { SetGameModifiers: modifiers } });
+  };
+
+  const setRevealKittyPointCount = (
     evt: React.ChangeEvent<HTMLSelectElement>
   ): void => {
     evt.preventDefault();
     if (evt.target.value !== "") {
       send({
         Action: {
-          SetHideThrowHaltingPlayer: evt.target.value === "hide",
+          SetRevealKittyPointCount: evt.target.value === "show",
         },
       });
     }
   };
 
-  const setKittyPenalty = onSelectStringDefault("SetKittyPenalty", null);
-  const setAdvancementPolicy = onSelectStringDefault(
-    "SetAdvancementPolicy",
-    "Unrestricted"
-  );
+  const setKittyPenalty = (penalty: KittyPenalty): void =>
+    send({ Action: { SetKittyPenalty: penalty } });
+  const setAdvancementPolicy = (
+    evt: React.ChangeEvent<HTMLSelectElement>
+  ): void => {
+    evt.preventDefault();
+    const value = evt.target.value;
+    if (value.startsWith("FreezeAt:")) {
+      send({
+        Action: {
+          SetAdvancementPolicy: {
+            DefendPointsThenFreeze: { ceiling: value.slice("FreezeAt:".length) },
+          },
+        },
+      });
+    } else {
+      send({ Action: { SetAdvancementPolicy: value || "Unrestricted" } });
+    }
+  };
   const setThrowPenalty = onSelectStringDefault("SetThrowPenalty", null);
+  const setOutOfTurnPolicy = (
+    evt: React.ChangeEvent<HTMLSelectElement>
+  ): void => {
+    evt.preventDefault();
+    send({
+      Action: {
+        SetOutOfTurnPolicy:
+          evt.target.value === "WarnThenPenalize"
+            ? { WarnThenPenalize: { warnings: 1, points: 10 } }
+            : "Ignore",
+      },
+    });
+  };
 
   const setHideLandlordsPoints = (
     evt: React.ChangeEvent<HTMLSelectElement>
@@ -767,6 +1134,21 @@ const Initialize = (props: IProps): JSX.Element => {
     send({ Action: "StartGame" });
   };
 
+  const addBot = (evt: React.SyntheticEvent): void => {
+    evt.preventDefault();
+    send({ Action: "AddBot" });
+  };
+
+  const startNewMatch = (evt: React.SyntheticEvent): void => {
+    evt.preventDefault();
+    send({ Action: "StartNewMatch" });
+  };
+
+  const randomizeSettings = (evt: React.SyntheticEvent): void => {
+    evt.preventDefault();
+    send({ Action: "RandomizeSettings" });
+  };
+
   const setEmoji = (
     evt: React.MouseEvent,
     emojiObject: IEmojiData | null
@@ -802,6 +1184,8 @@ const Initialize = (props: IProps): JSX.Element => {
     decks.push({
       exclude_big_joker: false,
       exclude_small_joker: false,
+      num_small_jokers: 1,
+      num_big_jokers: 1,
       min: "2",
     });
   }
@@ -893,6 +1277,13 @@ const Initialize = (props: IProps): JSX.Element => {
               },
             });
             break;
+          case "friend_point_transfer_policy":
+            send({
+              Action: {
+                SetFriendPointTransferPolicy: value,
+              },
+            });
+            break;
           case "first_landlord_selection_policy":
             send({
               Action: {
@@ -900,6 +1291,13 @@ const Initialize = (props: IProps): JSX.Element => {
               },
             });
             break;
+          case "first_lead_policy":
+            send({
+              Action: {
+                SetFirstLeadPolicy: value,
+              },
+            });
+            break;
           case "hide_landlord_points":
             send({
               Action: {
@@ -938,6 +1336,27 @@ const Initialize = (props: IProps): JSX.Element => {
               },
             });
             break;
+          case "kitty_theft_reveal_policy":
+            send({
+              Action: {
+                SetKittyTheftRevealPolicy: value,
+              },
+            });
+            break;
+          case "kitty_on_loss_policy":
+            send({
+              Action: {
+                SetKittyOnLossPolicy: value,
+              },
+            });
+            break;
+          case "kitty_theft_bid_policy":
+            send({
+              Action: {
+                SetKittyTheftBidPolicy: value,
+              },
+            });
+            break;
           case "throw_penalty":
             send({
               Action: {
@@ -945,6 +1364,13 @@ const Initialize = (props: IProps): JSX.Element => {
               },
             });
             break;
+          case "out_of_turn_policy":
+            send({
+              Action: {
+                SetOutOfTurnPolicy: value,
+              },
+            });
+            break;
           case "trick_draw_policy":
             send({
               Action: {
@@ -959,6 +1385,13 @@ const Initialize = (props: IProps): JSX.Element => {
               },
             });
             break;
+          case "throw_tie_break_policy":
+            send({
+              Action: {
+                SetThrowTieBreakPolicy: value,
+              },
+            });
+            break;
           case "landlord_emoji":
             send({
               Action: {
@@ -980,6 +1413,13 @@ const Initialize = (props: IProps): JSX.Element => {
               },
             });
             break;
+          case "joker_bid_suit_policy":
+            send({
+              Action: {
+                SetJokerBidSuitPolicy: value,
+              },
+            });
+            break;
           case "joker_bid_policy":
             send({
               Action: {
@@ -994,9 +1434,22 @@ const Initialize = (props: IProps): JSX.Element => {
               },
             });
             break;
+          case "kitty_reveal_policy":
+            send({
+              Action: {
+                SetKittyRevealPolicy: value,
+              },
+            });
+            break;
           case "hide_throw_halting_player":
             send({ Action: { SetHideThrowHaltingPlayer: value } });
             break;
+          case "reveal_kitty_point_count":
+            send({ Action: { SetRevealKittyPointCount: value } });
+            break;
+          case "experimental_features":
+            send({ Action: { SetExperimentalFeatures: value } });
+            break;
           case "game_scoring_parameters":
             send({
               Action: {
@@ -1032,6 +1485,20 @@ const Initialize = (props: IProps): JSX.Element => {
               },
             });
             break;
+          case "abandoned_game_policy":
+            send({
+              Action: {
+                SetAbandonedGamePolicy: value,
+              },
+            });
+            break;
+          case "absent_landlord_policy":
+            send({
+              Action: {
+                SetAbsentLandlordPolicy: value,
+              },
+            });
+            break;
           case "tractor_requirements":
             send({
               Action: {
@@ -1116,7 +1583,9 @@ const Initialize = (props: IProps): JSX.Element => {
           <code>{window.location.href}</code>
         </a>
       </p>
-      {props.state.propagated.players.length >= 4 ? (
+      {(props.state.propagated.match_winners ?? []).length > 0 ? (
+        <button onClick={startNewMatch}>Start a new match</button>
+      ) : props.state.propagated.players.length >= 4 ? (
         <>
           <button
             disabled={
@@ -1129,11 +1598,13 @@ const Initialize = (props: IProps): JSX.Element => {
           >
             Start game
           </button>
+          <button onClick={randomizeSettings}>Dealer&apos;s choice</button>
           <ReadyCheck />
         </>
       ) : (
         <h2>Waiting for players...</h2>
       )}
+      <button onClick={addBot}>Add a bot</button>
       <RandomizePlayersButton players={props.state.propagated.players}>
         Randomize player order
       </RandomizePlayersButton>
@@ -1185,6 +1656,7 @@ const Initialize = (props: IProps): JSX.Element => {
           setSpecialDecks={(d) => send({ Action: { SetSpecialDecks: d } })}
         />
         <KittySizeSelector
+          state={props.state}
           numPlayers={props.state.propagated.players.length}
           decks={decks}
           kittySize={props.state.propagated.kitty_size}
@@ -1204,6 +1676,61 @@ const Initialize = (props: IProps): JSX.Element => {
             </select>
           </label>
         </div>
+        {props.state.propagated.kitty_theft_policy === "AllowKittyTheft" && (
+          <div>
+            <label>
+              Stolen bottom cards:{" "}
+              <select
+                value={
+                  props.state.propagated.kitty_theft_reveal_policy ||
+                  "RevealPreviousDiscards"
+                }
+                onChange={setKittyTheftRevealPolicy}
+              >
+                <option value="RevealPreviousDiscards">
+                  Include the previous exchanger&apos;s discards
+                </option>
+                <option value="ResetToOriginalKitty">
+                  Reset to the original bottom cards
+                </option>
+              </select>
+            </label>
+          </div>
+        )}
+        {props.state.propagated.kitty_theft_policy === "AllowKittyTheft" && (
+          <div>
+            <label>
+              Bid needed to steal the bottom cards:{" "}
+              <select
+                value={
+                  props.state.propagated.kitty_theft_bid_policy || "MustExceed"
+                }
+                onChange={setKittyTheftBidPolicy}
+              >
+                <option value="MustExceed">A higher bid</option>
+                <option value="MustMatch">An equal or higher bid</option>
+              </select>
+            </label>
+          </div>
+        )}
+        <div>
+          <label>
+            Points in the bottom when the landlord team loses:{" "}
+            <select
+              value={
+                props.state.propagated.kitty_on_loss_policy || "LastTrickWinner"
+              }
+              onChange={setKittyOnLossPolicy}
+            >
+              <option value="LastTrickWinner">
+                Go to the winner of the last trick
+              </option>
+              <option value="AttackersOnLoss">
+                Go to the opposing team
+              </option>
+            </select>
+          </label>
+        </div>
         <div>
           <label>
             Card protection policy:{" "}
@@ -1218,6 +1745,9 @@ const Initialize = (props: IProps): JSX.Element => {
               <option value="OnlyDrawTractorOnTractor">
                 Only tractors can draw tractors
               </option>
+              <option value="OnlyDrawTractorOnTractorStrict">
+                Only tractors can draw tractors, and only down to pairs
+              </option>
               <option value="NoFormatBasedDraw">
                 No format-based requirements (pairs do not draw pairs)
               </option>
@@ -1240,6 +1770,21 @@ const Initialize = (props: IProps): JSX.Element => {
               <option value="TrickUnitLength">
                 Subsequent throw must beat largest component to win
               </option>
+              <option value="Cumulative">
+                Subsequent throw must have higher combined rank to win
+              </option>
+            </select>
+          </label>
+        </div>
+        <div>
+          <label>
+            Equally strong throws:{" "}
+            <select
+              value={props.state.propagated.throw_tie_break_policy}
+              onChange={setThrowTieBreakPolicy}
+            >
+              <option value="FirstPlayed">First one played wins</option>
+              <option value="LastPlayed">Last one played wins</option>
             </select>
           </label>
         </div>
@@ -1250,10 +1795,17 @@ const Initialize = (props: IProps): JSX.Element => {
           setBidPolicy={setBidPolicy}
           setBidReinforcementPolicy={setBidReinforcementPolicy}
           setJokerBidPolicy={setJokerBidPolicy}
-          setShouldRevealKittyAtEndOfGame={setShouldRevealKittyAtEndOfGame}
+          setJokerBidSuitPolicy={setJokerBidSuitPolicy}
+          setKittyRevealPolicy={setKittyRevealPolicy}
           setHideThrowHaltingPlayer={setHideThrowHaltingPlayer}
+          setRevealKittyPointCount={setRevealKittyPointCount}
+          setSealedKitty={setSealedKitty}
+          setGameModifier={setGameModifier}
           setFirstLandlordSelectionPolicy={setFirstLandlordSelectionPolicy}
+          setFirstLeadPolicy={setFirstLeadPolicy}
           setGameStartPolicy={setGameStartPolicy}
+          setAbandonedGamePolicy={setAbandonedGamePolicy}
+          setAbsentLandlordPolicy={setAbsentLandlordPolicy}
           setGameShadowingPolicy={setGameShadowingPolicy}
           setKittyBidPolicy={setKittyBidPolicy}
           setTractorRequirements={(requirements) =>
@@ -1264,11 +1816,13 @@ const Initialize = (props: IProps): JSX.Element => {
           state={props.state}
           setFriendSelectionPolicy={setFriendSelectionPolicy}
           setMultipleJoinPolicy={setMultipleJoinPolicy}
+          setFriendPointTransferPolicy={setFriendPointTransferPolicy}
           setAdvancementPolicy={setAdvancementPolicy}
           setHideLandlordsPoints={setHideLandlordsPoints}
           setHidePlayedCards={setHidePlayedCards}
           setKittyPenalty={setKittyPenalty}
           setThrowPenalty={setThrowPenalty}
+          setOutOfTurnPolicy={setOutOfTurnPolicy}
           setPlayTakebackPolicy={setPlayTakebackPolicy}
           setBidTakebackPolicy={setBidTakebackPolicy}
         />
@@ -1280,6 +1834,38 @@ const Initialize = (props: IProps): JSX.Element => {
             send({ Action: { SetLandlord: newLandlord } })
           }
         />
+        <div>
+          <label>
+            Director:{" "}
+            <select
+              value={
+                props.state.propagated.director === null ||
+                props.state.propagated.director === undefined
+                  ? ""
+                  : props.state.propagated.director
+              }
+              onChange={(e: React.ChangeEvent<HTMLSelectElement>) =>
+                send({
+                  Action: {
+                    SetDirector:
+                      e.target.value === ""
+                        ? null
+                        : parseInt(e.target.value, 10),
+                  },
+                })
+              }
+            >
+              <option value="">nobody</option>
+              {props.state.propagated.players
+                .concat(props.state.propagated.observers)
+                .map((player) => (
+                  <option value={player.id} key={player.id}>
+                    {player.name}
+                  </option>
+                ))}
+            </select>
+          </label>
+        </div>
         <RankSelector
           rank={currentPlayer.level}
           onChangeRank={(newRank: string) =>
@@ -6,6 +6,7 @@ import LabeledPlay from "./LabeledPlay";
 interface IProps {
   name: string;
   room_name: string;
+  reconnect_token?: string;
   setName: (name: string) => void;
   setRoomName: (name: string) => void;
 }
@@ -30,6 +31,7 @@ const JoinRoom = (props: IProps): JSX.Element => {
       send({
         room_name: props.room_name,
         name: props.name,
+        reconnect_token: props.reconnect_token,
       });
     }
   };
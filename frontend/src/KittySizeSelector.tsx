@@ -1,9 +1,10 @@
 import * as React from "react";
-import { IDeck } from "./types";
+import { IDeck, IInitializePhase } from "./types";
 import ArrayUtils from "./util/array";
 import WasmContext from "./WasmContext";
 
 interface IProps {
+  state: IInitializePhase;
   numPlayers: number;
   decks: IDeck[];
   kittySize: number | null;
@@ -11,13 +12,16 @@ interface IProps {
 }
 
 const KittySizeSelector = (props: IProps): JSX.Element => {
-  const { computeDeckLen } = React.useContext(WasmContext);
+  const { computeDeckLen, validKittySizes } = React.useContext(WasmContext);
   const handleChange = (e: React.ChangeEvent<HTMLSelectElement>): void => {
     const newKittySize =
       e.target.value === "" ? null : parseInt(e.target.value, 10);
     props.onChange(newKittySize);
   };
   const deckLen = computeDeckLen(props.decks);
+  const validSizes = validKittySizes({ state: props.state });
+  const isValid = (v: number): boolean =>
+    validSizes === null || validSizes.includes(v);
   const kittyOffset = deckLen % props.numPlayers;
   const defaultOptions = [
     kittyOffset,
@@ -34,6 +38,7 @@ const KittySizeSelector = (props: IProps): JSX.Element => {
   const options = potentialOptions.filter(
     (v) =>
       !defaultOptions.includes(v) &&
+      isValid(v) &&
       v < deckLen - props.numPlayers &&
       // Note: this isn't quite right, but it seems fine for the common case of no short decks.
       (deckLen - v) % props.numPlayers <= props.decks.length * 4
@@ -54,7 +59,7 @@ const KittySizeSelector = (props: IProps): JSX.Element => {
           <optgroup label="Standard">
             <option value="">default</option>
             {defaultOptions
-              .filter((v) => v < deckLen - props.numPlayers)
+              .filter((v) => v < deckLen - props.numPlayers && isValid(v))
               .map((v) => (
                 <option value={v} key={v}>
                   {v} card{v === 1 ? "" : "s"}
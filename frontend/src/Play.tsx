@@ -43,6 +43,7 @@ const Play = (props: IProps): JSX.Element => {
   const {
     findViablePlays,
     canPlayCards,
+    findOptimalPlay,
     nextThresholdReachable,
     sortAndGroupCards,
   } = React.useContext(WasmContext);
@@ -55,6 +56,8 @@ const Play = (props: IProps): JSX.Element => {
 
   const sendEvent = (event: {}) => () => send(event);
   const takeBackCards = sendEvent({ Action: "TakeBackCards" });
+  const approveTakeBack = sendEvent({ Action: "ApproveTakeBack" });
+  const cancelTakeBack = sendEvent({ Action: "CancelTakeBack" });
   const endTrick = sendEvent({ Action: "EndTrick" });
   const endGameEarly = sendEvent({ Action: "EndGameEarly" });
   const startNewGame = sendEvent({ Action: "StartNewGame" });
@@ -141,6 +144,25 @@ const Play = (props: IProps): JSX.Element => {
     currentPlayer.id === lastPlay.id &&
     !playPhase.game_ended_early;
 
+  const suggestPlay = (): void => {
+    const suggested = findOptimalPlay({
+      trick: playPhase.trick,
+      id: currentPlayer.id,
+      hands: playPhase.hands,
+      trick_draw_policy: playPhase.propagated.trick_draw_policy,
+      throw_evaluation_policy: playPhase.propagated.throw_evaluation_policy,
+      throw_tie_break_policy: playPhase.propagated.throw_tie_break_policy,
+    });
+    setSelected(suggested);
+    setGrouping(
+      findViablePlays(
+        playPhase.trump,
+        playPhase.propagated.tractor_requirements,
+        suggested
+      )
+    );
+  };
+
   const shouldBeBeeping =
     props.beepOnTurn && isCurrentPlayerTurn && !playPhase.game_ended_early;
 
@@ -215,6 +237,7 @@ const Play = (props: IProps): JSX.Element => {
         landlord={playPhase.landlord}
         landlords_team={playPhase.landlords_team}
         name={props.name}
+        vacatedSeats={playPhase.propagated.vacated_seats}
         next={nextPlayer}
       />
       <Trump trump={playPhase.trump} />
@@ -250,11 +273,32 @@ const Play = (props: IProps): JSX.Element => {
         unsetAutoPlayWhenWinnerChanges={props.unsetAutoPlayWhenWinnerChanges}
         isCurrentPlayerTurn={isCurrentPlayerTurn}
       />
+      {!isSpectator && isCurrentPlayerTurn && lastPlay !== undefined && (
+        <button onClick={suggestPlay}>Suggest a play</button>
+      )}
       {playPhase.propagated.play_takeback_policy === "AllowPlayTakeback" && (
         <button onClick={takeBackCards} disabled={!canTakeBack}>
           Take back last play
         </button>
       )}
+      {playPhase.propagated.play_takeback_policy === "RequiresApproval" &&
+        (playPhase.pending_take_back === null ? (
+          <button onClick={takeBackCards} disabled={!canTakeBack}>
+            Ask to take back last play
+          </button>
+        ) : (
+          !isSpectator && (
+            <>
+              {playPhase.pending_take_back.requester !== currentPlayer.id &&
+                !playPhase.pending_take_back.approvals.includes(
+                  currentPlayer.id
+                ) && (
+                  <button onClick={approveTakeBack}>Approve takeback</button>
+                )}
+              <button onClick={cancelTakeBack}>Cancel takeback</button>
+            </>
+          )
+        ))}
       <button
         onClick={endTrick}
         disabled={
@@ -369,7 +413,16 @@ const Play = (props: IProps): JSX.Element => {
         gameScoringParameters={playPhase.propagated.game_scoring_parameters}
         smallerTeamSize={smallerTeamSize}
       />
-      <LabeledPlay className="kitty" cards={playPhase.kitty} label="底牌" />
+      <LabeledPlay
+        className="kitty"
+        cards={playPhase.kitty}
+        label={
+          playPhase.kitty_point_count !== null &&
+          playPhase.kitty_point_count !== undefined
+            ? `底牌 (${playPhase.kitty_point_count} point cards)`
+            : "底牌"
+        }
+      />
     </div>
   );
 };
@@ -13,6 +13,7 @@ interface IProps {
   movable?: boolean;
   next?: number | null;
   name: string;
+  vacatedSeats?: number[];
 }
 
 const Players = (props: IProps): JSX.Element => {
@@ -25,8 +26,10 @@ const Players = (props: IProps): JSX.Element => {
     movable,
     next,
     name,
+    vacatedSeats,
   } = props;
   const { send } = React.useContext(WebsocketContext);
+  const me = observers.find((p) => p.name === name);
 
   const makeDescriptor = (p: IPlayer): Array<JSX.Element | string> => {
     if (p.metalevel <= 1) {
@@ -44,7 +47,8 @@ const Players = (props: IProps): JSX.Element => {
     <table className="players">
       <tbody>
         <tr>
-          {players.map((player) => {
+          {players.map((player, seatIndex) => {
+            const vacant = vacatedSeats?.includes(player.id);
             const className = classNames("player", {
               landlord:
                 player.id === landlord || landlords_team?.includes(player.id),
@@ -60,10 +64,25 @@ const Players = (props: IProps): JSX.Element => {
             if (player.name === name) {
               descriptor.push(" (You!)");
             }
+            if (vacant) {
+              descriptor.push(" (vacant)");
+            }
 
             return (
               <td key={player.id} className={className}>
                 {descriptor}
+                {vacant && me !== undefined && (
+                  <button
+                    style={{ display: "block" }}
+                    onClick={() =>
+                      send({
+                        Action: { PromoteObserver: [me.id, seatIndex] },
+                      })
+                    }
+                  >
+                    Take this seat
+                  </button>
+                )}
                 {movable && (
                   <span
                     style={{
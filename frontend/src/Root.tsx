@@ -74,6 +74,9 @@ const Root = (): JSX.Element => {
             <JoinRoom
               name={state.name}
               room_name={state.roomName}
+              reconnect_token={
+                state.reconnectTokens[`${state.roomName}/${state.name}`]
+              }
               setName={(name: string) => updateState({ name })}
               setRoomName={(roomName: string) => {
                 updateState({ roomName });
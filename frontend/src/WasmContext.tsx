@@ -12,9 +12,16 @@ import {
   IDeck,
   ITrick,
   TrickDrawPolicy,
+  ThrowEvaluationPolicy,
+  ThrowTieBreakPolicy,
   IGameScoringParameters,
   JokerBidPolicy,
   ITractorRequirements,
+  ICardInfo,
+  IInitializePhase,
+  IDrawPhase,
+  IExchangePhase,
+  IPlayPhase,
 } from "./types";
 
 interface Context {
@@ -27,14 +34,34 @@ interface Context {
   sortAndGroupCards: (
     req: ISortAndGroupCardsRequest
   ) => ISortedAndGroupedCards[];
+  prepareHand: (req: IPrepareHandRequest) => IPreparedSuitGroup[];
   decomposeTrickFormat: (
     req: IDecomposeTrickFormatRequest
   ) => IDecomposedTrickFormat[];
+  protectedUnits: (req: IProtectedUnitsRequest) => IUnitLike[];
   canPlayCards: (req: ICanPlayCardsRequest) => boolean;
+  findOptimalPlay: (req: IFindOptimalPlayRequest) => string[];
+  leadSurvivalProbability: (req: ILeadSurvivalProbabilityRequest) => number;
+  analyzeHandDifficulty: (req: IAnalyzeHandDifficultyRequest) => number;
+  analyzeLeadPriority: (
+    req: IAnalyzeLeadPriorityRequest
+  ) => Array<[string, number]>;
+  isValidLead: (req: IIsValidLeadRequest) => string | null;
+  singleUnit: (req: ISingleUnitRequest) => ITrickUnit | null;
+  validateGrouping: (req: IValidateGroupingRequest) => string | null;
+  explainTrickWinner: (req: IExplainTrickWinnerRequest) => IWinnerExplanation;
   explainScoring: (req: IExplainScoringRequest) => IExplainScoringResponse;
   nextThresholdReachable: (req: INextThresholdReachableRequest) => boolean;
+  pointsNeededForNextLevel: (req: IPointsNeededRequest) => number | null;
   computeScore: (req: IComputeScoreRequest) => IComputeScoreResponse;
+  computeScoreBreakdown: (
+    req: IComputeScoreBreakdownRequest
+  ) => IComputeScoreBreakdownResponse;
+  progressToNextThreshold: (req: IProgressToNextThresholdRequest) => number;
   computeDeckLen: (req: IDeck[]) => number;
+  deckSeating: (req: IDeckSeatingRequest) => IDeckSeating[];
+  validKittySizes: (req: IValidKittySizesRequest) => number[] | null;
+  canTakeBackBid: (req: ICanTakeBackBidRequest) => boolean;
   decodeWireFormat: (req: any) => any;
 }
 
@@ -56,9 +83,12 @@ interface IFindValidBidsRequest {
   num_decks: number;
 }
 
+type UnknownCardPlacement = "First" | "Last";
+
 interface ISortAndGroupCardsRequest {
   trump: ITrump | null;
   cards: string[];
+  unknown_placement?: UnknownCardPlacement;
 }
 
 export interface ISortedAndGroupedCards {
@@ -66,6 +96,20 @@ export interface ISortedAndGroupedCards {
   cards: string[];
 }
 
+export type Locale = "En" | "ZhHans" | "ZhHant";
+
+interface IPrepareHandRequest {
+  trump: ITrump;
+  cards: string[];
+  unknown_placement?: UnknownCardPlacement;
+  locale?: Locale;
+}
+
+export interface IPreparedSuitGroup {
+  suit: string;
+  cards: ICardInfo[];
+}
+
 interface IDecomposedTrickFormat {
   description: string;
   format: IUnitLike[];
@@ -79,6 +123,37 @@ interface IDecomposeTrickFormatRequest {
   trick_draw_policy: TrickDrawPolicy;
 }
 
+interface IProtectedUnitsRequest {
+  trick_format: ITrickFormat;
+  hands: IHands;
+  player_id: number;
+}
+
+interface IDeckSeatingRequest {
+  decks: IDeck[];
+  player_range: [number, number];
+  kitty_size?: number | null;
+  remainder_policy?: "GrowKitty" | "RemoveCards" | "SmallerLastRound";
+}
+
+interface IValidKittySizesRequest {
+  state: IInitializePhase;
+}
+
+export type BiddingPhase =
+  | { Draw: IDrawPhase }
+  | { Exchange: IExchangePhase };
+
+interface ICanTakeBackBidRequest {
+  state: BiddingPhase;
+  id: number;
+}
+
+export interface IDeckSeating {
+  num_players: number;
+  arrangement: { kitty_size: number; num_removed_cards: number } | null;
+}
+
 interface ICanPlayCardsRequest {
   trick: ITrick;
   id: number;
@@ -87,6 +162,75 @@ interface ICanPlayCardsRequest {
   trick_draw_policy: TrickDrawPolicy;
 }
 
+interface IFindOptimalPlayRequest {
+  trick: ITrick;
+  id: number;
+  hands: IHands;
+  trick_draw_policy: TrickDrawPolicy;
+  throw_evaluation_policy: ThrowEvaluationPolicy;
+  throw_tie_break_policy?: ThrowTieBreakPolicy;
+}
+
+interface ILeadSurvivalProbabilityRequest {
+  trick: ITrick;
+  unseen_cards: string[];
+  hand_size: number;
+  trick_draw_policy: TrickDrawPolicy;
+  throw_evaluation_policy: ThrowEvaluationPolicy;
+  throw_tie_break_policy?: ThrowTieBreakPolicy;
+  num_samples: number;
+}
+
+interface IAnalyzeHandDifficultyRequest {
+  hand: string[];
+  trump: ITrump;
+}
+
+interface IAnalyzeLeadPriorityRequest {
+  hand: string[];
+  trump: ITrump;
+  unseen: string[];
+}
+
+interface IIsValidLeadRequest {
+  trump: ITrump;
+  tractor_requirements: ITractorRequirements;
+  cards: string[];
+  format_hint?: ITrickUnit[];
+}
+
+interface ISingleUnitRequest {
+  trump: ITrump;
+  tractor_requirements: ITractorRequirements;
+  cards: string[];
+}
+
+interface IValidateGroupingRequest {
+  trump: ITrump;
+  tractor_requirements: ITractorRequirements;
+  hand: string[];
+  grouping: ITrickUnit[];
+  trick_format?: ITrickFormat | null;
+}
+
+interface IExplainTrickWinnerRequest {
+  trick: ITrick;
+  throw_evaluation_policy: ThrowEvaluationPolicy;
+  throw_tie_break_policy?: ThrowTieBreakPolicy;
+}
+
+export interface IPlayExplanation {
+  id: number;
+  matches_format: boolean;
+  compared_against: number | null;
+  beats_winner: boolean;
+}
+
+export interface IWinnerExplanation {
+  plays: IPlayExplanation[];
+  winner: number | null;
+}
+
 interface IExplainScoringRequest {
   decks: IDeck[];
   params: IGameScoringParameters;
@@ -100,6 +244,14 @@ interface INextThresholdReachableRequest {
   observed_points: number;
 }
 
+interface IPointsNeededRequest {
+  decks: IDeck[];
+  params: IGameScoringParameters;
+  non_landlord_points: number;
+  observed_points: number;
+  is_defending: boolean;
+}
+
 export interface IScoreSegment {
   point_threshold: number;
   results: IGameScoreResult;
@@ -124,9 +276,34 @@ interface IComputeScoreResponse {
   next_threshold: number;
 }
 
+interface IComputeScoreBreakdownRequest {
+  state: IPlayPhase;
+}
+
+interface IProgressToNextThresholdRequest {
+  state: IPlayPhase;
+}
+
+export interface IScoreBreakdown {
+  trick_points: { [player_id: number]: number };
+  transferred_points: number;
+  kitty_points: number;
+  kitty_multiplier: number;
+  penalty_points: number;
+  double_points: boolean;
+}
+
+interface IComputeScoreBreakdownResponse {
+  breakdown: IScoreBreakdown;
+  non_landlord_points: number;
+  score: IGameScoreResult;
+}
+
 interface IExplainScoringResponse {
   results: IScoreSegment[];
   step_size: number;
+  landlord_step_size: number;
+  non_landlord_step_size: number;
   total_points: number;
 }
 
@@ -134,10 +311,27 @@ export const WasmContext = React.createContext<Context>({
   findViablePlays: (_, __) => [],
   findValidBids: (_) => [],
   sortAndGroupCards: (_) => [],
+  prepareHand: (_) => [],
   decomposeTrickFormat: (_) => [],
+  protectedUnits: (_) => [],
   canPlayCards: (_) => false,
-  explainScoring: (_) => ({ results: [], step_size: 0, total_points: 0 }),
+  findOptimalPlay: (_) => [],
+  leadSurvivalProbability: (_) => 1,
+  analyzeHandDifficulty: (_) => 0,
+  analyzeLeadPriority: (_) => [],
+  isValidLead: (_) => null,
+  singleUnit: (_) => null,
+  validateGrouping: (_) => null,
+  explainTrickWinner: (_) => ({ plays: [], winner: null }),
+  explainScoring: (_) => ({
+    results: [],
+    step_size: 0,
+    landlord_step_size: 0,
+    non_landlord_step_size: 0,
+    total_points: 0,
+  }),
   nextThresholdReachable: (_) => true,
+  pointsNeededForNextLevel: (_) => null,
   computeScore: (_) => ({
     score: {
       landlord_won: true,
@@ -147,7 +341,28 @@ export const WasmContext = React.createContext<Context>({
     },
     next_threshold: 0,
   }),
+  computeScoreBreakdown: (_) => ({
+    breakdown: {
+      trick_points: {},
+      transferred_points: 0,
+      kitty_points: 0,
+      kitty_multiplier: 0,
+      penalty_points: 0,
+      double_points: false,
+    },
+    non_landlord_points: 0,
+    score: {
+      landlord_won: true,
+      landlord_bonus: false,
+      landlord_delta: 0,
+      non_landlord_delta: 0,
+    },
+  }),
+  progressToNextThreshold: (_) => 0,
   computeDeckLen: (_) => 0,
+  deckSeating: (_) => [],
+  validKittySizes: (_) => null,
+  canTakeBackBid: (_) => false,
   decodeWireFormat: (_) => {},
 });
 
@@ -28,24 +28,72 @@ const ShengjiProvider = (props: IProps): JSX.Element => {
         sortAndGroupCards: (req) => {
           return Shengji.sort_and_group_cards(req).results;
         },
+        prepareHand: (req) => {
+          return Shengji.prepare_hand(req).results;
+        },
         decomposeTrickFormat: (req) => {
           return Shengji.decompose_trick_format(req).results;
         },
+        protectedUnits: (req) => {
+          return Shengji.protected_units(req).results;
+        },
+        isValidLead: (req) => {
+          return Shengji.is_valid_lead(req).reason;
+        },
+        singleUnit: (req) => {
+          return Shengji.single_unit(req).unit;
+        },
+        validateGrouping: (req) => {
+          return Shengji.validate_grouping(req).reason;
+        },
         canPlayCards: (req) => {
           return Shengji.can_play_cards(req).playable;
         },
+        findOptimalPlay: (req) => {
+          return Shengji.find_optimal_play(req).cards;
+        },
+        leadSurvivalProbability: (req) => {
+          return Shengji.lead_survival_probability(req).probability;
+        },
+        analyzeHandDifficulty: (req) => {
+          return Shengji.analyze_hand_difficulty(req).difficulty;
+        },
+        analyzeLeadPriority: (req) => {
+          return Shengji.analyze_lead_priority(req).priorities;
+        },
+        explainTrickWinner: (req) => {
+          return Shengji.explain_trick_winner(req);
+        },
         explainScoring: (req) => {
           return Shengji.explain_scoring(req);
         },
         nextThresholdReachable: (req) => {
           return Shengji.next_threshold_reachable(req);
         },
+        pointsNeededForNextLevel: (req) => {
+          return Shengji.points_needed_for_next_level(req).points_needed;
+        },
         computeScore: (req) => {
           return Shengji.compute_score(req);
         },
+        computeScoreBreakdown: (req) => {
+          return Shengji.compute_score_breakdown(req);
+        },
+        progressToNextThreshold: (req) => {
+          return Shengji.progress_to_next_threshold(req);
+        },
         computeDeckLen: (req) => {
           return Shengji.compute_deck_len(req);
         },
+        deckSeating: (req) => {
+          return Shengji.deck_seating(req).results;
+        },
+        validKittySizes: (req) => {
+          return Shengji.valid_kitty_sizes(req).sizes;
+        },
+        canTakeBackBid: (req) => {
+          return Shengji.can_take_back_bid(req).allowed;
+        },
         decodeWireFormat: (req) => {
           return JSON.parse(Shengji.zstd_decompress(req));
         },
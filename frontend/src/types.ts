@@ -12,6 +12,33 @@ export interface IGameMessage {
   Header?: {
     messages: string[];
   };
+  History?: {
+    entries: IHistoryEntry[];
+  };
+  Summary?: {
+    summary: IGameSummary;
+  };
+  ChatBacklog?: {
+    messages: IGameMessageMessage[];
+  };
+}
+
+export interface IGameSummary {
+  landlord: number;
+  landlord_won: boolean;
+  non_landlord_points: number;
+  bonus_level: boolean;
+  ranks_before: { [player_id: number]: string };
+  ranks_after: { [player_id: number]: string };
+  results: {
+    [player_name: string]: {
+      won_game: boolean;
+      is_defending: boolean;
+      is_landlord: boolean;
+      ranks_up: number;
+      confetti: boolean;
+    };
+  };
 }
 
 export interface IGameMessageMessage {
@@ -25,6 +52,11 @@ export interface IGameMessageBroadcast {
 export interface IGameMessageState {
   state: IGameState;
 }
+export interface IHistoryEntry {
+  seq: number;
+  actor: number;
+  message: MessageVariant;
+}
 export interface IBroadcastMessage {
   actor: number;
   actor_name: string;
@@ -33,10 +65,40 @@ export interface IBroadcastMessage {
 export type MessageVariant =
   | { type: "GameModeSet"; game_mode: IGameMode }
   | { type: "JoinedGame"; player: number }
-  | { type: "JoinedTeam"; player: number }
+  | {
+      type: "JoinedTeam";
+      player: number;
+      already_joined: boolean;
+      via_card: string;
+      remaining_friends: number;
+    }
   | { type: "KittySizeSet"; size: number | null }
   | { type: "LeftGame"; name: string }
   | { type: "MadeBid"; card: string; count: number }
+  | { type: "MadeBlindBid"; card: string; count: number }
+  | { type: "GamePaused" }
+  | { type: "GameResumed" }
+  | { type: "WeakHandRedealSet"; rule: IWeakHandRedeal | null }
+  | { type: "DeclaredWeakHand"; player: number }
+  | { type: "QuickRematchSet"; set: boolean }
+  | { type: "SkipExchangeSet"; set: boolean }
+  | {
+      type: "FriendBoundsSet";
+      min_friends: number | null;
+      max_friends: number | null;
+    }
+  | {
+      type: "TeamLevelSpread";
+      team: number;
+      min_level: string;
+      max_level: string;
+    }
+  | {
+      type: "CaptureBonusEarned";
+      player: number;
+      cards: string[];
+      bonus: number;
+    }
   | { type: "NewLandlordForNextGame"; landlord: number }
   | { type: "NumDecksSet"; num_decks: number | null }
   | { type: "NumFriendsSet"; num_friends: number | null }
@@ -50,6 +112,10 @@ export type MessageVariant =
   | { type: "SetRank"; rank: string }
   | { type: "StartingGame" }
   | { type: "TookBackPlay" }
+  | { type: "TakeBackRequested" }
+  | { type: "TakeBackApproved" }
+  | { type: "TakeBackCancelled" }
+  | { type: "TakeBackCompleted"; player: number }
   | { type: "TrickWon"; winner: number; points: number }
   | {
       type: "GameFinished";
@@ -74,6 +140,7 @@ export interface IPlayer {
   name: string;
   level: string;
   metalevel: number;
+  reconnect_token?: string;
 }
 
 export interface IGameState {
@@ -92,6 +159,7 @@ export interface IBid {
   card: string;
   count: number;
   epoch: number;
+  blind: boolean;
 }
 
 export interface IDrawPhase {
@@ -108,6 +176,7 @@ export interface IDrawPhase {
   level: number;
   removed_cards: string[];
   decks: IDeck[];
+  history: IHistoryEntry[];
 }
 
 export interface IExchangePhase {
@@ -126,6 +195,9 @@ export interface IExchangePhase {
   exchanger: number | null;
   removed_cards: string[];
   decks: IDeck[];
+  history: IHistoryEntry[];
+  kitty_point_count?: number | null;
+  trump_suit_pending?: boolean;
 }
 
 export interface IPlayPhase {
@@ -144,9 +216,32 @@ export interface IPlayPhase {
   game_ended_early: boolean;
   removed_cards: string[];
   decks: IDeck[];
+  blind_bidder: number | null;
+  capture_bonuses: { [id: number]: number };
+  winning_bid: IBid | null;
+  trick_points: [number, number][];
+  pending_take_back: IPendingTakeBack | null;
+  history: IHistoryEntry[];
+  kitty_point_count?: number | null;
 }
 
-export type BidPolicy = "JokerOrGreaterLength" | "GreaterLength";
+export interface IPendingTakeBack {
+  requester: number;
+  approvals: number[];
+}
+
+export type GameModifier = "DoublePoints" | "NoThrows" | "NoKittyMultiplier";
+export type ThrowEvaluationPolicy =
+  | "All"
+  | "Highest"
+  | "TrickUnitLength"
+  | "Cumulative";
+export type ThrowTieBreakPolicy = "FirstPlayed" | "LastPlayed";
+export type BidPolicy =
+  | "JokerOrHigherSuit"
+  | "JokerOrGreaterLength"
+  | "GreaterLength"
+  | "StrictlyIncreasing";
 export type BidReinforcementPolicy =
   | "ReinforceWhileWinning"
   | "ReinforceWhileEquivalent"
@@ -155,11 +250,20 @@ export type JokerBidPolicy =
   | "BothTwoOrMore"
   | "BothNumDecks"
   | "LJNumDecksHJNumDecksLessOne";
+export type JokerBidSuitPolicy = "ForceNoTrump" | "LandlordChoosesSuit";
 export type TrickDrawPolicy =
   | "NoProtections"
   | "LongerTuplesProtected"
   | "NoFormatBasedDraw"
-  | "OnlyDrawTractorOnTractor";
+  | "OnlyDrawTractorOnTractor"
+  | "OnlyDrawTractorOnTractorStrict";
+
+export type KittyPenalty =
+  | "Times"
+  | "Power"
+  | "None"
+  | { Fixed: number }
+  | { ByUnitType: { single: number; pair: number; tractor: number } };
 
 export interface IPropagatedState {
   game_mode: IGameModeSettings;
@@ -171,10 +275,13 @@ export interface IPropagatedState {
     | "HighestCardNotAllowed"
     | "PointCardNotAllowed";
   multiple_join_policy: "Unrestricted" | "NoDoubleJoin";
+  friend_point_transfer_policy?: "MoveToLandlordTeam" | "KeepWithCaptor";
   first_landlord_selection_policy: "ByWinningBid" | "ByFirstBid";
+  first_lead_policy: "Landlord" | "LeftOfLandlord" | "BidWinner";
   bid_policy: BidPolicy;
   bid_reinforcement_policy: BidReinforcementPolicy;
   joker_bid_policy: JokerBidPolicy;
+  joker_bid_suit_policy: JokerBidSuitPolicy;
   num_decks: number | null;
   special_decks: IDeck[];
   max_player_id: number;
@@ -182,23 +289,74 @@ export interface IPropagatedState {
   observers: IPlayer[];
   landlord: number | null;
   chat_link: string | null;
-  advancement_policy: "Unrestricted" | "DefendPoints";
-  kitty_penalty: "Times" | "Power";
+  advancement_policy:
+    | "Unrestricted"
+    | "FullyUnrestricted"
+    | "DefendPoints"
+    | { DefendPointsThenFreeze: { ceiling: string } };
+  kitty_penalty: KittyPenalty;
   kitty_bid_policy: "FirstCard" | "FirstCardOfLevelOrHighest";
   throw_penalty: "None" | "TenPointsPerAttempt";
+  out_of_turn_policy?:
+    | "Ignore"
+    | { WarnThenPenalize: { warnings: number; points: number } };
   trick_draw_policy: TrickDrawPolicy;
-  throw_evaluation_policy: "All" | "Highest" | "TrickUnitLength";
+  throw_evaluation_policy: ThrowEvaluationPolicy;
+  throw_tie_break_policy: ThrowTieBreakPolicy;
   hide_played_cards: boolean;
   landlord_emoji: string | null;
-  play_takeback_policy: "AllowPlayTakeback" | "NoPlayTakeback";
+  director?: number | null;
+  play_takeback_policy:
+    | "AllowPlayTakeback"
+    | "NoPlayTakeback"
+    | "RequiresApproval";
   bid_takeback_policy: "AllowBidTakeback" | "NoBidTakeback";
   kitty_theft_policy: "AllowKittyTheft" | "NoKittyTheft";
+  kitty_theft_reveal_policy?: "RevealPreviousDiscards" | "ResetToOriginalKitty";
+  kitty_theft_bid_policy?: "MustExceed" | "MustMatch";
+  kitty_on_loss_policy?: "LastTrickWinner" | "AttackersOnLoss";
   game_shadowing_policy: "AllowMultipleSessions" | "SingleSessionOnly";
   game_start_policy: "AllowAnyPlayer" | "AllowLandlordOnly";
+  abandoned_game_policy?: "IgnoreResets" | "CountResetsAsAbandoned";
+  absent_landlord_policy?: "Wait" | "AutoFinalizeExchange";
   game_scoring_parameters: IGameScoringParameters;
   should_reveal_kitty_at_end_of_game: boolean;
+  kitty_reveal_policy?: "Never" | "Always" | "OnlyIfContestedPoints";
   hide_throw_halting_player: boolean;
+  reveal_kitty_point_count?: boolean;
   tractor_requirements: ITractorRequirements;
+  blind_bid_bonus: number | null;
+  max_metalevel?: number | null;
+  match_winners?: number[];
+  remainder_policy: "GrowKitty" | "RemoveCards" | "SmallerLastRound";
+  capture_bonus_rules: [string[], number][];
+  pause_policy: "NoPausing" | "AllowPausing";
+  paused: boolean;
+  past_games: IPastGame[];
+  weak_hand_redeal: IWeakHandRedeal | null;
+  quick_rematch: boolean;
+  skip_exchange: boolean;
+  min_friends: number | null;
+  max_friends: number | null;
+  spectator_card_visibility: "Hidden" | "AfterGameEnd" | "AlwaysVisible";
+  vacated_seat_policy?: "LeaveVacant" | "AllowObserverTakeover";
+  vacated_seats?: number[];
+  bots?: number[];
+  move_deadline_secs: number | null;
+  game_modifiers: GameModifier[];
+  permanent_trump_numbers?: string[];
+  excluded_cards?: string[];
+  experimental_features?: string[];
+}
+
+export interface IWeakHandRedeal {
+  point_threshold: number;
+  trump_threshold: number;
+}
+
+export interface IPastGame {
+  winning_bid: IBid;
+  trump: ITrump;
 }
 
 export interface IGameScoringParameters {
@@ -208,6 +366,9 @@ export interface IGameScoringParameters {
   deadzone_size: number;
   truncate_zero_crossing_window: boolean;
   bonus_level_policy: "NoBonusLevel" | "BonusLevelForSmallerLandlordTeam";
+  win_margin?: number;
+  landlord_step_size_per_deck?: number | null;
+  non_landlord_step_size_per_deck?: number | null;
 }
 
 export interface IHands {
@@ -238,6 +399,7 @@ export interface ICardInfo {
   display_value: string;
   typ: string;
   number: string | null;
+  rank_name: string | null;
   points: number;
 }
 
@@ -293,18 +455,21 @@ export interface IOrderedCard {
 
 export type ITrump =
   | {
-      Standard: { suit: string; number: string };
+      Standard: { suit: string; number: string; permanent?: string[] };
       NoTrump?: null;
     }
   | {
       Standard?: null;
-      NoTrump: { number: string };
+      NoTrump: { number: string; permanent?: string[] };
     };
 
 export interface IDeck {
   exclude_small_joker: boolean;
   exclude_big_joker: boolean;
+  num_small_jokers: number;
+  num_big_jokers: number;
   min: string;
+  excluded?: string[];
 }
 
 export interface ITractorRequirements {
@@ -29,6 +29,26 @@ const messageHandler: WebsocketHandler = (
   }
 };
 
+const chatBacklogHandler: WebsocketHandler = (
+  state: AppState,
+  message: IGameMessage
+) => {
+  // Skip the backlog when reconnecting, since we've already seen the chat.
+  if (
+    message.ChatBacklog !== undefined &&
+    state.messages.every((m) => m.from_game === true)
+  ) {
+    return {
+      messages: truncateMessages([
+        ...message.ChatBacklog.messages,
+        ...state.messages,
+      ]),
+    };
+  } else {
+    return null;
+  }
+};
+
 const broadcastHandler: WebsocketHandler = (
   state: AppState,
   message: IGameMessage
@@ -65,6 +85,38 @@ const stateHandler: WebsocketHandler = (_: AppState, message: IGameMessage) => {
   }
 };
 
+const reconnectTokenHandler: WebsocketHandler = (
+  state: AppState,
+  message: IGameMessage
+) => {
+  if (message.State === undefined) {
+    return null;
+  }
+  const s = message.State.state;
+  const phase = s.Initialize ?? s.Draw ?? s.Exchange ?? s.Play;
+  if (phase === null) {
+    return null;
+  }
+  const self = [
+    ...phase.propagated.players,
+    ...phase.propagated.observers,
+  ].find((p) => p.name === state.name);
+  const key = `${state.roomName}/${state.name}`;
+  if (
+    self?.reconnect_token !== undefined &&
+    self.reconnect_token !== "" &&
+    state.reconnectTokens[key] !== self.reconnect_token
+  ) {
+    return {
+      reconnectTokens: {
+        ...state.reconnectTokens,
+        [key]: self.reconnect_token,
+      },
+    };
+  }
+  return null;
+};
+
 const headerMessageHandler: WebsocketHandler = (
   _: AppState,
   message: IGameMessage
@@ -165,9 +217,11 @@ const gameFinishedHandler: WebsocketHandler = (
 
 const allHandlers: WebsocketHandler[] = [
   messageHandler,
+  chatBacklogHandler,
   broadcastHandler,
   errorHandler,
   stateHandler,
+  reconnectTokenHandler,
   headerMessageHandler,
   gameFinishedHandler,
 ];
@@ -77,17 +77,13 @@ impl<S: State> Storage<S, ()> for HashMapStorage<S> {
 
     async fn put_cas(self, expected_version: u64, state: S) -> Result<(), ()> {
         let mut m = self.state_map.lock().await;
-        if !m.contains_key(state.key()) {
-            *self.num_games_created.lock().await += 1;
-            info!(self.logger, "Initializing state"; "key" => stringify(state.key()));
-        }
         if m.get(state.key()).map(|s| s.0.version()).unwrap_or(0) == expected_version {
             if state.version() != expected_version {
-                m.insert(state.key().to_vec(), (state, Instant::now()));
-                if expected_version == 0 {
-                    let mut n = self.num_games_created.lock().await;
-                    *n += 1;
+                if !m.contains_key(state.key()) {
+                    *self.num_games_created.lock().await += 1;
+                    info!(self.logger, "Initializing state"; "key" => stringify(state.key()));
                 }
+                m.insert(state.key().to_vec(), (state, Instant::now()));
             }
             Ok(())
         } else {
@@ -94,6 +94,75 @@ async fn test_basic_kv() {
     assert_eq!(s.clone().stats().await.unwrap(), (1, 0));
 }
 
+#[tokio::test]
+async fn test_put_cas_new_key() {
+    let s: HashMapStorage<VersionedState> = HashMapStorage::new(make_logger());
+
+    // A state which doesn't exist yet has version zero.
+    s.clone().put_cas(1, vs!("test", 2)).await.unwrap_err();
+    assert_eq!(s.clone().get_states_created().await.unwrap(), 0);
+    s.clone().put_cas(0, vs!("test", 1)).await.unwrap();
+    assert_eq!(
+        s.clone().get(b"test".to_vec()).await.unwrap(),
+        vs!("test", 1)
+    );
+    assert_eq!(s.clone().get_states_created().await.unwrap(), 1);
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn test_concurrent_writers() {
+    const NUM_WRITES: u64 = 200;
+    let s: HashMapStorage<VersionedState> = HashMapStorage::new(make_logger());
+
+    // Writers which read, modify, and compare-and-set, retrying on conflict.
+    let cas_writer = |s: HashMapStorage<VersionedState>| async move {
+        for _ in 0..NUM_WRITES {
+            loop {
+                let state = s.clone().get(b"cas".to_vec()).await.unwrap();
+                let next = vs!("cas", state.version + 1);
+                if s.clone().put_cas(state.version, next).await.is_ok() {
+                    break;
+                }
+                task::yield_now().await;
+            }
+        }
+    };
+    let (a, b) = tokio::join!(
+        task::spawn(cas_writer(s.clone())),
+        task::spawn(cas_writer(s.clone()))
+    );
+    a.unwrap();
+    b.unwrap();
+    assert_eq!(
+        s.clone().get(b"cas".to_vec()).await.unwrap(),
+        vs!("cas", 2 * NUM_WRITES)
+    );
+
+    // Operations are applied atomically, so they never need to be retried.
+    let op_writer = |s: HashMapStorage<VersionedState>| async move {
+        for _ in 0..NUM_WRITES {
+            s.clone()
+                .execute_operation_with_messages::<(), _>(b"op".to_vec(), |state| {
+                    Ok((vs!("op", state.version + 1), vec![]))
+                })
+                .await
+                .unwrap();
+            task::yield_now().await;
+        }
+    };
+    let (a, b) = tokio::join!(
+        task::spawn(op_writer(s.clone())),
+        task::spawn(op_writer(s.clone()))
+    );
+    a.unwrap();
+    b.unwrap();
+    assert_eq!(
+        s.clone().get(b"op".to_vec()).await.unwrap(),
+        vs!("op", 2 * NUM_WRITES)
+    );
+    assert_eq!(s.clone().get_states_created().await.unwrap(), 2);
+}
+
 #[tokio::test]
 async fn test_basic_pubsub() {
     let s: HashMapStorage<VersionedState> = HashMapStorage::new(make_logger());